@@ -21,6 +21,12 @@ RUST_LIB_FLAGS_ST0 += -W warnings
 RUST_LIB_FLAGS_ST1 += -D warnings
 RUST_LIB_FLAGS_ST2 += -D warnings
 
+# The boxed closure types are deprecated but still used throughout the
+# libraries; don't let the deprecation warning fail the build until they have
+# been migrated. (stage0 doesn't know about this lint.)
+RUST_LIB_FLAGS_ST1 += -A boxed_closures
+RUST_LIB_FLAGS_ST2 += -A boxed_closures
+
 # Macro that generates the full list of dependencies for a crate at a particular
 # stage/target/host tuple.
 #
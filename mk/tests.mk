@@ -96,6 +96,12 @@ ifdef TEST_SHARD
   CRATE_TEST_EXTRA_ARGS += --test-shard=$(TEST_SHARD)
 endif
 
+# Set the number of compiletest tests that run at once, which otherwise
+# defaults to RUST_TEST_TASKS or the number of CPUs.
+ifdef CTEST_JOBS
+  CTEST_TESTARGS += --jobs=$(CTEST_JOBS)
+endif
+
 define DEF_TARGET_COMMANDS
 
 ifdef CFG_UNIXY_$(1)
@@ -492,6 +498,7 @@ $(foreach host,$(CFG_HOST), \
 ######################################################################
 
 RPASS_RS := $(wildcard $(S)src/test/run-pass/*.rs)
+RPASS_STDOUT := $(wildcard $(S)src/test/run-pass/*.stdout)
 RPASS_VALGRIND_RS := $(wildcard $(S)src/test/run-pass-valgrind/*.rs)
 RPASS_FULL_RS := $(wildcard $(S)src/test/run-pass-fulldeps/*.rs)
 CFAIL_FULL_RS := $(wildcard $(S)src/test/compile-fail-fulldeps/*.rs)
@@ -508,7 +515,7 @@ CODEGEN_CC := $(wildcard $(S)src/test/codegen/*.cc)
 # a performance monitor.
 PERF_RS := $(wildcard $(S)src/test/bench/*.rs)
 
-RPASS_TESTS := $(RPASS_RS)
+RPASS_TESTS := $(RPASS_RS) $(RPASS_STDOUT)
 RPASS_VALGRIND_TESTS := $(RPASS_VALGRIND_RS)
 RPASS_FULL_TESTS := $(RPASS_FULL_RS)
 CFAIL_FULL_TESTS := $(CFAIL_FULL_RS)
@@ -634,6 +641,10 @@ ifndef CFG_DISABLE_OPTIMIZE_TESTS
 CTEST_RUSTC_FLAGS += -O
 endif
 
+# Many tests still use the deprecated boxed closure types; the lint itself is
+# exercised by the lint-boxed-closures tests, which opt back in.
+CTEST_RUSTC_FLAGS += -A boxed_closures
+
 
 CTEST_COMMON_ARGS$(1)-T-$(2)-H-$(3) := \
 		--compile-lib-path $$(HLIB$(1)_H_$(3)) \
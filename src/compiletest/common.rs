@@ -121,6 +121,10 @@ pub struct Config {
     // positional order equal to a mod b to run.
     pub test_shard: Option<(uint,uint)>,
 
+    // The number of tests to run at once; by default, libtest decides
+    // from RUST_TEST_TASKS or the number of CPUs
+    pub jobs: Option<uint>,
+
     // A command line to prefix program execution with,
     // for running under valgrind
     pub runtool: Option<String>,
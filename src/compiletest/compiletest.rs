@@ -85,6 +85,7 @@ pub fn parse_config(args: Vec<String> ) -> Config {
           optopt("", "adb-test-dir", "path to tests for the android debugger", "PATH"),
           optopt("", "lldb-python-dir", "directory containing LLDB's python module", "PATH"),
           optopt("", "test-shard", "run shard A, of B shards, worth of the testsuite", "A.B"),
+          optopt("", "jobs", "the number of tests to run concurrently", "N"),
           optflag("h", "help", "show this message"));
 
     assert!(!args.is_empty());
@@ -171,6 +172,12 @@ pub fn parse_config(args: Vec<String> ) -> Config {
             !opt_str2(matches.opt_str("adb-test-dir")).is_empty(),
         lldb_python_dir: matches.opt_str("lldb-python-dir"),
         test_shard: test::opt_shard(matches.opt_str("test-shard")),
+        jobs: matches.opt_str("jobs").map(|s| {
+            match from_str::<uint>(s.as_slice()) {
+                Some(n) if n > 0 => n,
+                _ => panic!("--jobs is `{}`, should be a positive integer", s),
+            }
+        }),
         verbose: matches.opt_present("verbose"),
     }
 }
@@ -210,6 +217,7 @@ pub fn log_config(config: &Config) {
         None => logv(c, "test_shard: (all)".to_string()),
         Some((a,b)) => logv(c, format!("test_shard: {}.{}", a, b))
     }
+    logv(c, format!("jobs: {}", config.jobs));
     logv(c, format!("verbose: {}", config.verbose));
     logv(c, format!("\n"));
 }
@@ -229,6 +237,13 @@ pub fn opt_str2(maybestr: Option<String>) -> String {
 }
 
 pub fn run_tests(config: &Config) {
+    // libtest runs as many tests at once as RUST_TEST_TASKS says; the
+    // debugger modes below may still insist on one at a time.
+    match config.jobs {
+        Some(jobs) => os::setenv("RUST_TEST_TASKS", jobs.to_string().as_slice()),
+        None => {}
+    }
+
     if config.target.as_slice() == "arm-linux-androideabi" {
         match config.mode {
             DebugInfoGdb => {
@@ -343,7 +358,7 @@ pub fn make_test(config: &Config, testfile: &Path, f: || -> test::TestFn)
         desc: test::TestDesc {
             name: make_test_name(config, testfile),
             ignore: header::is_test_ignored(config, testfile),
-            should_fail: false
+            should_fail: header::is_test_should_fail(testfile)
         },
         testfn: f(),
     }
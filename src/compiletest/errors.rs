@@ -8,35 +8,139 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::fmt;
 use std::io::{BufferedReader, File};
 use regex::Regex;
 
 pub struct ExpectedError {
     pub line: uint,
+    // The column the message must start at, if the annotation gave one
+    pub col: Option<uint>,
     pub kind: String,
-    pub msg: String,
+    pub msg: MessagePattern,
 }
 
-pub static EXPECTED_PATTERN : &'static str = r"//~(?P<adjusts>\^*)\s*(?P<kind>\S*)\s*(?P<msg>.*)";
+/// What an expected error or error pattern looks for in a message: a
+/// substring, or a regular expression when written as "re: PATTERN".
+pub enum MessagePattern {
+    Substring(String),
+    Pattern(Regex),
+}
+
+impl MessagePattern {
+    pub fn new(pattern: &str) -> Result<MessagePattern, String> {
+        let pattern = pattern.trim();
+        if pattern.starts_with("re:") {
+            let re = pattern.slice_from(3).trim();
+            match Regex::new(re) {
+                Ok(re) => Ok(Pattern(re)),
+                Err(e) => Err(format!("invalid regular expression `{}`: {}", re, e)),
+            }
+        } else {
+            Ok(Substring(pattern.to_string()))
+        }
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        match *self {
+            Substring(ref s) => text.contains(s.as_slice()),
+            Pattern(ref re) => re.is_match(text),
+        }
+    }
+}
+
+impl fmt::Show for MessagePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Substring(ref s) => write!(f, "{}", s),
+            Pattern(ref re) => write!(f, "re: {}", re),
+        }
+    }
+}
 
-// Load any test directives embedded in the file
-pub fn load_errors(re: &Regex, testfile: &Path) -> Vec<ExpectedError> {
+/// Looks for either "//~| KIND MESSAGE" or "//~^^... KIND MESSAGE".
+/// The former is a "follow" that targets the same line as the preceding
+/// annotation; the latter is an "adjusts" that goes that many lines up.
+/// Either may give the column the message starts at, as in
+/// "//~ KIND[col=17] MESSAGE", and either may be scoped to some of the
+/// test's revisions, as in "//[a,b]~ KIND MESSAGE".
+pub static EXPECTED_PATTERN : &'static str =
+    concat!(r"//(\[(?P<revs>[\w,]+)\])?~(?P<follow>\|)?(?P<adjusts>\^*)\s*",
+            r"(?P<kind>[^\s\[]*)(\[col=(?P<col>\d+)\])?\s*(?P<msg>.*)");
+
+// The levels of compiler message that an expected error can name as its
+// kind. An expected error of one of these kinds only matches a message
+// of that level; any other kind is just looked for in the message. A
+// `label` is a label under a secondary span of another message.
+pub static MESSAGE_LEVELS: &'static [&'static str] =
+    &["error", "warning", "note", "help", "label"];
+
+// Load the errors expected of the given revision of the test, which
+// are those without a revision scope and those scoped to it
+pub fn load_errors(re: &Regex, testfile: &Path, revision: Option<&str>)
+                   -> Vec<ExpectedError> {
     let mut rdr = BufferedReader::new(File::open(testfile).unwrap());
 
+    // The line targeted by the most recent annotation, which a following
+    // `//~|` annotation targets as well.
+    let mut last_target = None;
+
     rdr.lines().enumerate().filter_map(|(line_no, ln)| {
-        parse_expected(line_no + 1, ln.unwrap().as_slice(), re)
+        let error = parse_expected(last_target, line_no + 1, ln.unwrap().as_slice(),
+                                   re, revision);
+        for error in error.iter() {
+            last_target = Some(error.line);
+        }
+        error
     }).collect()
 }
 
-fn parse_expected(line_num: uint, line: &str, re: &Regex) -> Option<ExpectedError> {
+fn parse_expected(last_target: Option<uint>,
+                  line_num: uint,
+                  line: &str,
+                  re: &Regex,
+                  revision: Option<&str>) -> Option<ExpectedError> {
     re.captures(line).and_then(|caps| {
+        let revs = caps.name("revs");
+        if !revs.is_empty() {
+            let applies = match revision {
+                Some(rev) => revs.split(',').any(|r| r == rev),
+                None => false,
+            };
+            if !applies {
+                return None;
+            }
+        }
+
         let adjusts = caps.name("adjusts").len();
         let kind = caps.name("kind").to_ascii().to_lowercase().into_string();
-        let msg = caps.name("msg").trim().to_string();
+        let kind = match kind.as_slice().trim_right_chars(':') {
+            "warn" => "warning".to_string(),
+            kind => kind.to_string(),
+        };
+        let col = from_str::<uint>(caps.name("col"));
+        let msg = match MessagePattern::new(caps.name("msg")) {
+            Ok(msg) => msg,
+            Err(e) => panic!("line {}: {}", line_num, e),
+        };
+
+        let line = if caps.name("follow").is_empty() {
+            line_num - adjusts
+        } else {
+            if adjusts > 0 {
+                panic!("line {}: `//~|` cannot be combined with `^`", line_num);
+            }
+            match last_target {
+                Some(line) => line,
+                None => panic!("line {}: `//~|` must follow another expected error",
+                               line_num),
+            }
+        };
 
-        debug!("line={} kind={} msg={}", line_num, kind, msg);
+        debug!("line={} col={} kind={} msg={}", line, col, kind, msg);
         Some(ExpectedError {
-            line: line_num - adjusts,
+            line: line,
+            col: col,
             kind: kind,
             msg: msg,
         })
@@ -32,7 +32,8 @@ pub struct TestProps {
     pub check_lines: Vec<String> ,
     // Flag to force a crate to be built with the host architecture
     pub force_host: bool,
-    // Check stdout for error-pattern output as well as stderr
+    // Check stdout for error-pattern output as well as stderr; for run-pass
+    // tests, compare stdout against the test's `.stdout` file
     pub check_stdout: bool,
     // Don't force a --crate-type=dylib flag on the command line
     pub no_prefer_dynamic: bool,
@@ -44,10 +45,23 @@ pub struct TestProps {
     pub pretty_compare_only: bool,
     // Patterns which must not appear in the output of a cfail test.
     pub forbid_output: Vec<String>,
+    // Fail a cfail test on any error or warning it does not account for,
+    // including those reported without a span
+    pub deny_unexpected_errors: bool,
+    // Don't run at the same time as other tests that set this, e.g.
+    // because they need a lot of some system resource
+    pub no_parallel: bool,
+    // Names of the revisions to compile the test under, each with
+    // `--cfg <revision>`; empty if the test has no revisions
+    pub revisions: Vec<String>,
+    // The revision these props were loaded for, if any
+    pub revision: Option<String>,
 }
 
-// Load any test directives embedded in the file
-pub fn load_props(testfile: &Path) -> TestProps {
+// Load any test directives embedded in the file. Given a revision,
+// directives scoped to it as `//[rev] directive` are loaded as well,
+// and `--cfg rev` is added to the compile flags.
+pub fn load_props(testfile: &Path, revision: Option<&str>) -> TestProps {
     let mut error_patterns = Vec::new();
     let mut aux_builds = Vec::new();
     let mut exec_env = Vec::new();
@@ -62,16 +76,31 @@ pub fn load_props(testfile: &Path) -> TestProps {
     let mut pretty_mode = None;
     let mut pretty_compare_only = false;
     let mut forbid_output = Vec::new();
-    iter_header(testfile, |ln| {
+    let mut deny_unexpected_errors = false;
+    let mut no_parallel = false;
+    let mut revisions = Vec::new();
+    let mut revision_flags = Vec::new();
+    iter_header(testfile, revision, |ln| {
         match parse_error_pattern(ln) {
           Some(ep) => error_patterns.push(ep),
           None => ()
         };
 
-        if compile_flags.is_none() {
+        if is_revision_scoped(ln) {
+            // Flags for the current revision add to the test's own
+            // flags rather than replacing them
+            match parse_compile_flags(ln) {
+                Some(flags) => revision_flags.push(flags),
+                None => {}
+            }
+        } else if compile_flags.is_none() {
             compile_flags = parse_compile_flags(ln);
         }
 
+        if revisions.is_empty() && !is_revision_scoped(ln) {
+            revisions = parse_revisions(ln);
+        }
+
         if run_flags.is_none() {
             run_flags = parse_run_flags(ln);
         }
@@ -104,6 +133,14 @@ pub fn load_props(testfile: &Path) -> TestProps {
             pretty_compare_only = parse_pretty_compare_only(ln);
         }
 
+        if !deny_unexpected_errors {
+            deny_unexpected_errors = parse_deny_unexpected_errors(ln);
+        }
+
+        if !no_parallel {
+            no_parallel = parse_no_parallel(ln);
+        }
+
         match parse_aux_build(ln) {
             Some(ab) => { aux_builds.push(ab); }
             None => {}
@@ -127,6 +164,23 @@ pub fn load_props(testfile: &Path) -> TestProps {
         true
     });
 
+    match revision {
+        Some(rev) => {
+            if !revisions.iter().any(|r| r.as_slice() == rev) {
+                panic!("{}: revision `{}` is not listed in `revisions:`",
+                       testfile.display(), rev);
+            }
+            let mut flags = compile_flags.unwrap_or(String::new());
+            flags.push_str(format!(" --cfg {}", rev).as_slice());
+            for extra in revision_flags.iter() {
+                flags.push_str(" ");
+                flags.push_str(extra.as_slice());
+            }
+            compile_flags = Some(flags);
+        }
+        None => {}
+    }
+
     TestProps {
         error_patterns: error_patterns,
         compile_flags: compile_flags,
@@ -142,6 +196,10 @@ pub fn load_props(testfile: &Path) -> TestProps {
         pretty_mode: pretty_mode.unwrap_or("normal".to_string()),
         pretty_compare_only: pretty_compare_only,
         forbid_output: forbid_output,
+        deny_unexpected_errors: deny_unexpected_errors,
+        no_parallel: no_parallel,
+        revisions: revisions,
+        revision: revision.map(|r| r.to_string()),
     }
 }
 
@@ -209,7 +267,7 @@ pub fn is_test_ignored(config: &Config, testfile: &Path) -> bool {
         }
     }
 
-    let val = iter_header(testfile, |ln| {
+    let val = iter_header(testfile, None, |ln| {
         !parse_name_directive(ln, "ignore-test") &&
         !parse_name_directive(ln, ignore_target(config).as_slice()) &&
         !parse_name_directive(ln, ignore_stage(config).as_slice()) &&
@@ -222,7 +280,17 @@ pub fn is_test_ignored(config: &Config, testfile: &Path) -> bool {
     !val
 }
 
-fn iter_header(testfile: &Path, it: |&str| -> bool) -> bool {
+// Tests marked `should-fail` pass only if compiletest rejects them, which
+// lets the suite check compiletest's own checks.
+pub fn is_test_should_fail(testfile: &Path) -> bool {
+    !iter_header(testfile, None, |ln| !parse_name_directive(ln, "should-fail"))
+}
+
+// Calls `it` on each header line. A line scoped to revisions, as in
+// `//[a,b] compile-flags: ...`, is only seen when loading one of those
+// revisions, and then with the scope still in place so callers can
+// tell it apart with `is_revision_scoped`.
+fn iter_header(testfile: &Path, revision: Option<&str>, it: |&str| -> bool) -> bool {
     use std::io::{BufferedReader, File};
 
     let mut rdr = BufferedReader::new(File::open(testfile).unwrap());
@@ -235,7 +303,20 @@ fn iter_header(testfile: &Path, it: |&str| -> bool) -> bool {
                 ln.as_slice().starts_with("mod") {
             return true;
         } else {
-            if !(it(ln.as_slice().trim())) {
+            let ln = ln.as_slice().trim();
+            match line_revisions(ln) {
+                Some(revs) => {
+                    let applies = match revision {
+                        Some(rev) => revs.iter().any(|r| *r == rev),
+                        None => false,
+                    };
+                    if !applies {
+                        continue;
+                    }
+                }
+                None => {}
+            }
+            if !(it(ln)) {
                 return false;
             }
         }
@@ -243,6 +324,28 @@ fn iter_header(testfile: &Path, it: |&str| -> bool) -> bool {
     return true;
 }
 
+// The revisions a `//[a,b] ...` line is scoped to, or None if it applies
+// to every revision.
+pub fn line_revisions<'a>(line: &'a str) -> Option<Vec<&'a str>> {
+    if !line.starts_with("//[") {
+        return None;
+    }
+    line.find(']').map(|close| {
+        line.slice(3, close).split(',').map(|r| r.trim()).collect()
+    })
+}
+
+fn is_revision_scoped(line: &str) -> bool {
+    line_revisions(line).is_some()
+}
+
+fn parse_revisions(line: &str) -> Vec<String> {
+    match parse_name_value_directive(line, "revisions") {
+        Some(revs) => revs.as_slice().words().map(|r| r.to_string()).collect(),
+        None => Vec::new(),
+    }
+}
+
 fn parse_error_pattern(line: &str) -> Option<String> {
     parse_name_value_directive(line, "error-pattern")
 }
@@ -291,6 +394,14 @@ fn parse_pretty_compare_only(line: &str) -> bool {
     parse_name_directive(line, "pretty-compare-only")
 }
 
+fn parse_deny_unexpected_errors(line: &str) -> bool {
+    parse_name_directive(line, "deny-unexpected-errors")
+}
+
+fn parse_no_parallel(line: &str) -> bool {
+    parse_name_directive(line, "no-parallel")
+}
+
 fn parse_exec_env(line: &str) -> Option<(String, String)> {
     parse_name_value_directive(line, "exec-env").map(|nv| {
         // nv is either FOO or FOO=BAR
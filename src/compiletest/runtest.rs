@@ -19,6 +19,7 @@ use util::logv;
 #[cfg(target_os = "windows")]
 use util;
 
+use std::cmp;
 use std::io::File;
 use std::io::fs::PathExtensions;
 use std::io::fs;
@@ -28,12 +29,15 @@ use std::io::process;
 use std::io::timer;
 use std::io;
 use std::os;
+use std::rt::mutex::{StaticNativeMutex, NATIVE_MUTEX_INIT};
 use std::str;
 use std::string::String;
 use std::task;
 use std::time::Duration;
 use test::MetricMap;
 
+static NO_PARALLEL_LOCK: StaticNativeMutex = NATIVE_MUTEX_INIT;
+
 pub fn run(config: Config, testfile: String) {
     match config.target.as_slice() {
 
@@ -57,17 +61,48 @@ pub fn run_metrics(config: Config, testfile: String, mm: &mut MetricMap) {
     }
     let testfile = Path::new(testfile);
     debug!("running {}", testfile.display());
-    let props = header::load_props(&testfile);
+    let props = header::load_props(&testfile, None);
     debug!("loaded props");
+
+    // Tests marked `no-parallel` take turns with each other, though not
+    // with the rest of the suite. compiletest runs on native threads, so
+    // blocking on an OS mutex here is fine.
+    let _no_parallel_guard = if props.no_parallel {
+        Some(unsafe { NO_PARALLEL_LOCK.lock() })
+    } else {
+        None
+    };
+
+    if props.revisions.is_empty() {
+        run_props(&config, &props, &testfile, mm);
+        return;
+    }
+
+    // Each revision is a separate compilation, so give each its own
+    // build directory to keep their outputs apart
+    for revision in props.revisions.iter() {
+        let rev_props = header::load_props(&testfile, Some(revision.as_slice()));
+        let mut rev_config = config.clone();
+        rev_config.build_base = config.build_base.join(revision.as_slice());
+        ensure_dir(&rev_config.build_base);
+
+        // Printed so that a failure's captured output names the revision
+        // that failed
+        println!("\nrevision `{}`", revision);
+        run_props(&rev_config, &rev_props, &testfile, mm);
+    }
+}
+
+fn run_props(config: &Config, props: &TestProps, testfile: &Path, mm: &mut MetricMap) {
     match config.mode {
-      CompileFail => run_cfail_test(&config, &props, &testfile),
-      RunFail => run_rfail_test(&config, &props, &testfile),
-      RunPass => run_rpass_test(&config, &props, &testfile),
-      RunPassValgrind => run_valgrind_test(&config, &props, &testfile),
-      Pretty => run_pretty_test(&config, &props, &testfile),
-      DebugInfoGdb => run_debuginfo_gdb_test(&config, &props, &testfile),
-      DebugInfoLldb => run_debuginfo_lldb_test(&config, &props, &testfile),
-      Codegen => run_codegen_test(&config, &props, &testfile, mm),
+      CompileFail => run_cfail_test(config, props, testfile),
+      RunFail => run_rfail_test(config, props, testfile),
+      RunPass => run_rpass_test(config, props, testfile),
+      RunPassValgrind => run_valgrind_test(config, props, testfile),
+      Pretty => run_pretty_test(config, props, testfile),
+      DebugInfoGdb => run_debuginfo_gdb_test(config, props, testfile),
+      DebugInfoLldb => run_debuginfo_lldb_test(config, props, testfile),
+      Codegen => run_codegen_test(config, props, testfile, mm),
     }
 }
 
@@ -94,14 +129,17 @@ fn run_cfail_test(config: &Config, props: &TestProps, testfile: &Path) {
     }
 
     let output_to_check = get_output(props, &proc_res);
-    let expected_errors = errors::load_errors(&config.cfail_regex, testfile);
+    let expected_errors = errors::load_errors(&config.cfail_regex,
+                                              testfile,
+                                              props.revision.as_ref().map(|r| r.as_slice()));
     if !expected_errors.is_empty() {
         if !props.error_patterns.is_empty() {
             fatal("both error pattern and expected errors specified");
         }
-        check_expected_errors(expected_errors, testfile, &proc_res);
+        check_expected_errors(expected_errors, testfile, props, &proc_res);
     } else {
         check_error_patterns(props, testfile, output_to_check.as_slice(), &proc_res);
+        check_no_unexpected_errors(props, &proc_res);
     }
     check_no_compiler_crash(&proc_res);
     check_forbid_output(props, output_to_check.as_slice(), &proc_res);
@@ -155,6 +193,10 @@ fn run_rpass_test(config: &Config, props: &TestProps, testfile: &Path) {
         if !proc_res.status.success() {
             fatal_proc_rec("test run failed!", &proc_res);
         }
+
+        if props.check_stdout {
+            check_expected_stdout(testfile, &proc_res);
+        }
     } else {
         let proc_res = jit_test(config, props, testfile);
 
@@ -164,6 +206,81 @@ fn run_rpass_test(config: &Config, props: &TestProps, testfile: &Path) {
     }
 }
 
+fn check_expected_stdout(testfile: &Path, proc_res: &ProcRes) {
+    /*!
+     * Compares the standard output of a run-pass test against the
+     * `.stdout` file next to it, ignoring differences in line endings.
+     */
+
+    let expected_path = testfile.with_extension("stdout");
+    let expected = match File::open(&expected_path).read_to_string() {
+        Ok(expected) => expected,
+        Err(e) => {
+            fatal(format!("check-stdout is set but {} could not be read: {}",
+                          expected_path.display(), e).as_slice());
+        }
+    };
+
+    let expected = normalize_line_endings(expected.as_slice());
+    let actual = normalize_line_endings(proc_res.stdout.as_slice());
+    if expected == actual {
+        return;
+    }
+
+    let expected_lines: Vec<&str> = expected.as_slice().lines().collect();
+    let actual_lines: Vec<&str> = actual.as_slice().lines().collect();
+    println!("\n--- {}\n+++ actual stdout", expected_path.display());
+    for line in diff_lines(expected_lines.as_slice(), actual_lines.as_slice()).iter() {
+        println!("{}", line);
+    }
+    fatal_proc_rec("stdout did not match the expected output", proc_res);
+}
+
+fn normalize_line_endings(s: &str) -> String {
+    s.replace("\r\n", "\n")
+}
+
+fn diff_lines(expected: &[&str], actual: &[&str]) -> Vec<String> {
+    /*!
+     * A line diff of `expected` against `actual`, with each line
+     * prefixed by `-` if it is only expected, `+` if it only occurred,
+     * and a space if it is common to both. It is built from a longest
+     * common subsequence, which is plenty fast for test output.
+     */
+
+    let (n, m) = (expected.len(), actual.len());
+    // lcs[i][j] is the length of the longest common subsequence of
+    // expected[i..] and actual[j..]
+    let mut lcs = Vec::from_elem(n + 1, Vec::from_elem(m + 1, 0u));
+    for i in range(0, n).rev() {
+        for j in range(0, m).rev() {
+            let len = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+            lcs[i][j] = len;
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            lines.push(format!("  {}", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(format!("- {}", expected[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", actual[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
 fn run_valgrind_test(config: &Config, props: &TestProps, testfile: &Path) {
     if config.valgrind_path.is_none() {
         assert!(!config.force_valgrind);
@@ -276,7 +393,9 @@ fn run_pretty_test(config: &Config, props: &TestProps, testfile: &Path) {
                                      props,
                                      testfile,
                                      pretty_type.to_string()),
-                        props.exec_env.clone(),
+                        // `exec-env` is for running the test, not for
+                        // the compiler
+                        Vec::new(),
                         config.compile_lib_path.as_slice(),
                         Some(aux_dir.as_str().unwrap()),
                         Some(src))
@@ -916,25 +1035,26 @@ fn check_error_patterns(props: &TestProps,
         fatal(format!("no error pattern specified in {}",
                       testfile.display()).as_slice());
     }
+    let error_patterns = load_error_patterns(props);
     let mut next_err_idx = 0u;
-    let mut next_err_pat = &props.error_patterns[next_err_idx];
+    let mut next_err_pat = &error_patterns[next_err_idx];
     let mut done = false;
     for line in output_to_check.as_slice().lines() {
-        if line.contains(next_err_pat.as_slice()) {
+        if next_err_pat.matches(line) {
             debug!("found error pattern {}", next_err_pat);
             next_err_idx += 1u;
-            if next_err_idx == props.error_patterns.len() {
+            if next_err_idx == error_patterns.len() {
                 debug!("found all error patterns");
                 done = true;
                 break;
             }
-            next_err_pat = &props.error_patterns[next_err_idx];
+            next_err_pat = &error_patterns[next_err_idx];
         }
     }
     if done { return; }
 
     let missing_patterns =
-        props.error_patterns[next_err_idx..];
+        error_patterns[next_err_idx..];
     if missing_patterns.len() == 1u {
         fatal_proc_rec(format!("error pattern '{}' not found!",
                               missing_patterns[0]).as_slice(),
@@ -948,6 +1068,15 @@ fn check_error_patterns(props: &TestProps,
     }
 }
 
+fn load_error_patterns(props: &TestProps) -> Vec<errors::MessagePattern> {
+    props.error_patterns.iter().map(|pattern| {
+        match errors::MessagePattern::new(pattern.as_slice()) {
+            Ok(pattern) => pattern,
+            Err(e) => fatal(format!("error pattern '{}': {}", *pattern, e).as_slice()),
+        }
+    }).collect()
+}
+
 fn check_no_compiler_crash(proc_res: &ProcRes) {
     for line in proc_res.stderr.as_slice().lines() {
         if line.starts_with("error: internal compiler error:") {
@@ -969,6 +1098,7 @@ fn check_forbid_output(props: &TestProps,
 
 fn check_expected_errors(expected_errors: Vec<errors::ExpectedError> ,
                          testfile: &Path,
+                         props: &TestProps,
                          proc_res: &ProcRes) {
 
     // true if we found the error in question
@@ -979,8 +1109,14 @@ fn check_expected_errors(expected_errors: Vec<errors::ExpectedError> ,
         fatal("process did not return an error status");
     }
 
+    // Messages are rendered as `filename:line:col: ...`, where the column
+    // is that of the start of the span, so an expected column is checked
+    // by making it part of the prefix.
     let prefixes = expected_errors.iter().map(|ee| {
-        format!("{}:{}:", testfile.display(), ee.line)
+        match ee.col {
+            Some(col) => format!("{}:{}:{}:", testfile.display(), ee.line, col),
+            None => format!("{}:{}:", testfile.display(), ee.line),
+        }
     }).collect::<Vec<String> >();
 
     #[cfg(target_os = "windows")]
@@ -1012,7 +1148,11 @@ fn check_expected_errors(expected_errors: Vec<errors::ExpectedError> ,
     //    filename:line1:col1: line2:col2: *warning:* msg
     // where line1:col1: is the starting point, line2:col2:
     // is the ending point, and * represents ANSI color codes.
-    for line in proc_res.stderr.as_slice().lines() {
+    // Labels printed in a snippet are taken to be messages of the
+    // same form, with the level `label`.
+    let messages = expand_labels(proc_res.stderr.as_slice());
+    let mut unexpected = Vec::new();
+    for line in messages.iter().map(|line| line.as_slice()) {
         let mut was_expected = false;
         for (i, ee) in expected_errors.iter().enumerate() {
             if !found_flags[i] {
@@ -1022,8 +1162,8 @@ fn check_expected_errors(expected_errors: Vec<errors::ExpectedError> ,
                        ee.msg,
                        line);
                 if prefix_matches(line, prefixes[i].as_slice()) &&
-                    line.contains(ee.kind.as_slice()) &&
-                    line.contains(ee.msg.as_slice()) {
+                    kind_matches(line, ee.kind.as_slice()) &&
+                    msg_matches(line, &ee.msg) {
                     found_flags[i] = true;
                     was_expected = true;
                     break;
@@ -1036,26 +1176,172 @@ fn check_expected_errors(expected_errors: Vec<errors::ExpectedError> ,
             was_expected = true;
         }
 
-        if !was_expected && is_compiler_error_or_warning(line) {
-            fatal_proc_rec(format!("unexpected compiler error or warning: '{}'",
-                                  line).as_slice(),
-                          proc_res);
+        // Unexpected notes and help messages are tolerated, so that
+        // a test only needs to annotate the ones it cares about.
+        if !was_expected && must_be_expected(props, line) {
+            unexpected.push(line);
         }
     }
 
-    for (i, &flag) in found_flags.iter().enumerate() {
-        if !flag {
-            let ee = &expected_errors[i];
-            fatal_proc_rec(format!("expected {} on line {} not found: {}",
-                                  ee.kind, ee.line, ee.msg).as_slice(),
-                          proc_res);
+    // For each expectation that was not met, list the messages reported
+    // at the line it targets, which are the likely near misses.
+    let missing: Vec<String> = found_flags.iter().zip(expected_errors.iter()).enumerate()
+        .filter(|&(_, (&flag, _))| !flag)
+        .map(|(i, (_, ee))| {
+            let col = match ee.col {
+                Some(col) => format!(", column {}", col),
+                None => String::new(),
+            };
+            let mut msg = format!("expected {} on line {}{} not found: {}",
+                                  ee.kind, ee.line, col, ee.msg);
+            let line_prefix = format!("{}:{}:", testfile.display(), ee.line);
+            for line in messages.iter().map(|line| line.as_slice()) {
+                if prefix_matches(line, line_prefix.as_slice()) {
+                    msg.push_str(format!("\n    candidate: '{}'", line).as_slice());
+                }
+            }
+            msg
+        })
+        .collect();
+
+    // Report every mismatch between the expected and the actual messages
+    // at once, rather than just the first.
+    let mut mismatches: Vec<String> = unexpected.iter().map(|line| {
+        format!("unexpected compiler error or warning: '{}'", line)
+    }).collect();
+    mismatches.extend(missing.into_iter());
+    match mismatches.as_slice() {
+        [] => {}
+        [ref mismatch] => fatal_proc_rec(mismatch.as_slice(), proc_res),
+        _ => {
+            for mismatch in mismatches.iter() {
+                error(mismatch.as_slice());
+            }
+            fatal_proc_rec("expected errors did not match the compiler's output", proc_res);
         }
     }
 }
 
+fn check_no_unexpected_errors(props: &TestProps, proc_res: &ProcRes) {
+    /*!
+     * Under `deny-unexpected-errors`, fails a test checked with error
+     * patterns if the compiler reported an error or warning that none
+     * of the patterns accounts for.
+     */
+
+    if !props.deny_unexpected_errors {
+        return;
+    }
+    let error_patterns = load_error_patterns(props);
+    let unexpected: Vec<&str> = proc_res.stderr.as_slice().lines().filter(|line| {
+        !line.contains("aborting due to") &&
+            must_be_expected(props, *line) &&
+            !error_patterns.iter().any(|pat| pat.matches(*line))
+    }).collect();
+    if unexpected.is_empty() {
+        return;
+    }
+    for line in unexpected.iter() {
+        error(format!("unexpected compiler error or warning: '{}'", *line).as_slice());
+    }
+    fatal_proc_rec("compiler reported errors not matched by any error pattern", proc_res);
+}
+
+// Rewrites each label that the compiler prints under a span in a snippet
+// of source, as in
+//    filename:line source
+//                  ^~~~~~ label
+// into a message of the form
+//    filename:line:col: line:col label: label
+// so that it can be expected like any other message.
+fn expand_labels(output: &str) -> Vec<String> {
+    let mut snippet: Option<(&str, &str, uint)> = None;
+    let mut messages = Vec::new();
+    for line in output.lines() {
+        let mark = line.trim_left_chars(|c: char| c == ' ' || c == '\t');
+        if !mark.starts_with("^") {
+            snippet = snippet_line(line);
+            messages.push(line.to_string());
+            continue;
+        }
+        let indent = line.len() - mark.len();
+        let mark_len = mark.chars().take_while(|&c| c == '^' || c == '~').count();
+        match snippet {
+            Some((file, line_num, prefix_len))
+                    if indent >= prefix_len && mark.slice_from(mark_len).starts_with(" ") => {
+                let col = indent - prefix_len + 1;
+                messages.push(format!("{}:{}:{}: {}:{} label: {}",
+                                      file, line_num, col, line_num, col + mark_len,
+                                      mark.slice_from(mark_len + 1)));
+            }
+            _ => messages.push(line.to_string()),
+        }
+    }
+    messages
+}
+
+// Splits a line of a snippet of source, `filename:line source`, into the
+// file name, the line number and the length of the `filename:line ` prefix.
+fn snippet_line<'a>(line: &'a str) -> Option<(&'a str, &'a str, uint)> {
+    for (i, c) in line.char_indices() {
+        if c != ':' {
+            continue;
+        }
+        let rest = line.slice_from(i + 1);
+        let digits = rest.chars().take_while(|c| c.is_digit()).count();
+        if digits > 0 && rest.slice_from(digits).starts_with(" ") {
+            return Some((line.slice_to(i), rest.slice_to(digits), i + digits + 2));
+        }
+    }
+    None
+}
+
+// Whether a test has to account for this line of the compiler's output.
+// Errors and warnings with a span always count; those without one only
+// count under `deny-unexpected-errors`.
+fn must_be_expected(props: &TestProps, line: &str) -> bool {
+    is_compiler_error_or_warning(line) ||
+        (props.deny_unexpected_errors &&
+         (line.starts_with("error: ") || line.starts_with("error[") ||
+          line.starts_with("warning: ") || line.starts_with("warning[")))
+}
+
+// Substrings are looked for in the whole line, as they always have been;
+// regular expressions are matched against the text of the message alone,
+// so that they can be anchored to its start.
+fn msg_matches(line: &str, msg: &errors::MessagePattern) -> bool {
+    match *msg {
+        errors::Substring(_) => msg.matches(line),
+        errors::Pattern(_) => {
+            msg.matches(compiler_message(line).map_or(line, |(_, text)| text))
+        }
+    }
+}
+
+fn kind_matches(line: &str, kind: &str) -> bool {
+    if errors::MESSAGE_LEVELS.iter().any(|level| *level == kind) {
+        compiler_message(line).map_or(false, |(level, _)| level == kind)
+    } else {
+        line.contains(kind)
+    }
+}
+
 fn is_compiler_error_or_warning(line: &str) -> bool {
+    match compiler_message(line) {
+        Some(("error", _)) | Some(("warning", _)) => true,
+        _ => false,
+    }
+}
+
+// Splits a compiler message into its level, such as "error" or "note",
+// and its text, if the line is one of the form
+//    filename:line1:col1: line2:col2: level: msg
+// or, for a message with an error code,
+//    filename:line1:col1: line2:col2: level[E0123]: msg
+// The code is not part of the text, so annotations need not mention it.
+fn compiler_message<'a>(line: &'a str) -> Option<(&'static str, &'a str)> {
     let mut i = 0u;
-    return
+    let has_span =
         scan_until_char(line, ':', &mut i) &&
         scan_char(line, ':', &mut i) &&
         scan_integer(line, &mut i) &&
@@ -1066,9 +1352,22 @@ fn is_compiler_error_or_warning(line: &str) -> bool {
         scan_integer(line, &mut i) &&
         scan_char(line, ':', &mut i) &&
         scan_integer(line, &mut i) &&
-        scan_char(line, ' ', &mut i) &&
-        (scan_string(line, "error", &mut i) ||
-         scan_string(line, "warning", &mut i));
+        scan_char(line, ' ', &mut i);
+    if !has_span {
+        return None;
+    }
+    let level = errors::MESSAGE_LEVELS.iter()
+        .find(|level| scan_string(line, **level, &mut i))
+        .map(|level| *level);
+    level.map(|level| {
+        if scan_char(line, '[', &mut i) {
+            scan_until_char(line, ']', &mut i);
+            scan_char(line, ']', &mut i);
+        }
+        scan_char(line, ':', &mut i);
+        scan_char(line, ' ', &mut i);
+        (level, line.slice_from(i))
+    })
 }
 
 fn scan_until_char(haystack: &str, needle: char, idx: &mut uint) -> bool {
@@ -1079,7 +1378,7 @@ fn scan_until_char(haystack: &str, needle: char, idx: &mut uint) -> bool {
     if opt.is_none() {
         return false;
     }
-    *idx = opt.unwrap();
+    *idx += opt.unwrap();
     return true;
 }
 
@@ -1204,7 +1503,7 @@ fn compose_and_run_compiler(
 
     for rel_ab in props.aux_builds.iter() {
         let abs_ab = config.aux_base.join(rel_ab.as_slice());
-        let aux_props = header::load_props(&abs_ab);
+        let aux_props = header::load_props(&abs_ab, None);
         let mut crate_type = if aux_props.no_prefer_dynamic {
             Vec::new()
         } else {
@@ -24,6 +24,7 @@ the `clone` method.
 #![unstable]
 
 /// A common trait for cloning an object.
+#[lang="clone"]
 pub trait Clone {
     /// Returns a copy of the value.
     fn clone(&self) -> Self;
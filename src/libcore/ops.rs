@@ -878,6 +878,12 @@ pub trait FnOnce<Args,Result> {
     extern "rust-call" fn call_once(self, args: Args) -> Result;
 }
 
+/// The `Result` of a function trait whose output is written `!`, as in
+/// `FnOnce() -> !`. It has no values, so a call through such a bound
+/// never returns.
+#[lang="diverging_output"]
+pub enum DivergingOutput {}
+
 impl<F,A,R> FnMut<A,R> for F
     where F : Fn<A,R>
 {
@@ -894,7 +900,10 @@ impl<F,A,R> FnOnce<A,R> for F
     }
 }
 
-
+// The snapshot compiler has no builtin `Fn` candidate for bare fn types, so
+// the library still provides these impls when built with it.
+// NOTE(stage0): remove impls after a snapshot
+#[cfg(stage0)]
 impl<Result> Fn<(),Result> for extern "Rust" fn() -> Result {
     #[allow(non_snake_case)]
     extern "rust-call" fn call(&self, _args: ()) -> Result {
@@ -902,6 +911,7 @@ impl<Result> Fn<(),Result> for extern "Rust" fn() -> Result {
     }
 }
 
+#[cfg(stage0)]
 impl<Result,A0> Fn<(A0,),Result> for extern "Rust" fn(A0) -> Result {
     #[allow(non_snake_case)]
     extern "rust-call" fn call(&self, args: (A0,)) -> Result {
@@ -912,6 +922,7 @@ impl<Result,A0> Fn<(A0,),Result> for extern "Rust" fn(A0) -> Result {
 
 macro_rules! def_fn(
     ($($args:ident)*) => (
+        #[cfg(stage0)]
         impl<Result$(,$args)*>
         Fn<($($args,)*),Result>
         for extern "Rust" fn($($args: $args,)*) -> Result {
@@ -531,7 +531,8 @@ fn exec<'t>(which: ::regex::native::MatchKind, input: &'t str,
                 end = end.to_uppercase();
             }
             let pat = self.cx.pat(self.sp, ast::PatRange(quote_expr!(self.cx, $start),
-                                                         quote_expr!(self.cx, $end)));
+                                                         quote_expr!(self.cx, $end),
+                                                         ast::RangeIncluded));
             self.cx.arm(self.sp, vec!(pat), quote_expr!(self.cx, true))
         }).collect::<Vec<ast::Arm>>();
 
@@ -19,6 +19,186 @@ register_diagnostic!(E0001, r##"
     one is too specific or the ordering is incorrect.
 "##)
 
+register_diagnostic!(E0046, r##"
+    An implementation of a trait must provide every method, associated type and
+    constant that the trait declares without a default. For example:
+
+        trait Foo {
+            fn foo(&self);
+            fn bar(&self) {}
+        }
+
+        struct Baz;
+
+        impl Foo for Baz {}
+
+    is rejected because `Baz` does not implement `foo`. `bar` has a default in the
+    trait, so the implementation may leave it out.
+"##)
+
+register_diagnostic!(E0050, r##"
+    A method in a trait implementation takes a different number of parameters from
+    the declaration of the method in the trait. For example:
+
+        trait Foo {
+            fn foo(&self, x: u8) -> bool;
+        }
+
+        struct Bar;
+
+        impl Foo for Bar {
+            fn foo(&self) -> bool { true }
+        }
+
+    is rejected because `Bar::foo` leaves out the `x: u8` parameter. The parameters
+    of an implementing method must line up with those of the trait's method.
+"##)
+
+register_diagnostic!(E0053, r##"
+    A method in a trait implementation has a signature that does not match the
+    declaration of the method in the trait, for example because it takes `&mut self`
+    where the trait takes `&self`, or a parameter of a different type:
+
+        trait Foo {
+            fn foo(&self, x: u8) -> bool;
+        }
+
+        struct Bar;
+
+        impl Foo for Bar {
+            fn foo(&self, x: i16) -> bool { x > 0 }
+        }
+
+    Write the method with exactly the argument and return types declared in the
+    trait, after substituting the implementation's types for `Self` and the
+    trait's type parameters.
+"##)
+
+register_diagnostic!(E0054, r##"
+    Casting a value to `bool` with `as` is not allowed, because it is not obvious
+    which values should be `true`. Compare the value explicitly instead:
+
+        let x = 5i;
+        let x_is_nonzero = x != 0; // rather than `x as bool`
+"##)
+
+register_diagnostic!(E0061, r##"
+    A function or method was called with the wrong number of arguments. For
+    example, given:
+
+        fn f(a: u16, b: &str) {}
+
+    the calls `f(2)` and `f(2, "test", 3)` are both rejected; `f` must be called
+    with exactly two arguments, such as `f(2, "test")`.
+
+    A method called with method-call syntax takes its receiver from the left of
+    the `.`, so the receiver does not count as one of the arguments in the
+    parentheses.
+"##)
+
+register_diagnostic!(E0062, r##"
+    A field was given a value more than once in a struct expression:
+
+        struct Foo {
+            x: int
+        }
+
+        let f = Foo { x: 0, x: 1 };
+
+    Each field of a struct may only be initialized once; remove the duplicate.
+"##)
+
+register_diagnostic!(E0063, r##"
+    A struct expression did not give a value to every field of the struct:
+
+        struct Foo {
+            x: int,
+            y: int
+        }
+
+        let f = Foo { x: 0 };
+
+    Provide a value for each field, or use functional update syntax to take the
+    remaining fields from another value of the same type:
+
+        let g = Foo { x: 0, ..f };
+"##)
+
+register_diagnostic!(E0069, r##"
+    A `return` statement without a value was used in a function whose return type
+    is not `()`:
+
+        fn foo() -> u8 {
+            return;
+        }
+
+    Either return a value of the function's return type, as in `return 0;`, or
+    change the return type to `()`.
+"##)
+
+register_diagnostic!(E0106, r##"
+    A reference or a type with lifetime parameters was written without a lifetime,
+    in a place where none can be inferred. Lifetimes may be elided in the
+    arguments and return types of functions, but must be written out in the
+    fields of structs and enums, among other places:
+
+        struct Foo {
+            x: &bool, // should be `x: &'a bool` in a `struct Foo<'a>`
+        }
+
+    In a function signature, the lifetime of a returned reference can only be
+    elided if there is exactly one reference among the arguments, or if one of
+    them is `&self` or `&mut self`:
+
+        fn foo(a: &str, b: &str) -> &str { a } // error: write `<'a>` and use it
+"##)
+
+register_diagnostic!(E0116, r##"
+    An inherent implementation, that is an `impl` block without a trait, was
+    written for a type that is defined in another crate:
+
+        impl Vec<u8> {
+            fn len_in_bits(&self) -> uint { self.len() * 8 }
+        }
+
+    Only the crate that defines a type may add inherent methods to it. Instead,
+    declare a trait with the methods you need and implement it for the type, or
+    wrap the type in a new struct defined in this crate.
+"##)
+
+register_diagnostic!(E0117, r##"
+    A trait was implemented for a type when neither the trait nor the type is
+    defined in the current crate:
+
+        impl Iterator<u8> for Option<u8> { ... }
+
+    This restriction keeps two crates from providing conflicting implementations
+    of the same trait for the same type. Define a new trait, or wrap the type in a
+    struct defined in this crate and implement the trait for that.
+"##)
+
+register_diagnostic!(E0119, r##"
+    Two implementations of a trait apply to the same type, so the compiler cannot
+    tell which one to use:
+
+        trait MyTrait {
+            fn get(&self) -> uint;
+        }
+
+        impl<T> MyTrait for T {
+            fn get(&self) -> uint { 0 }
+        }
+
+        struct Foo;
+
+        impl MyTrait for Foo { // conflicts with the blanket impl above
+            fn get(&self) -> uint { 1 }
+        }
+
+    Remove one of the implementations, or narrow the blanket implementation with a
+    bound that the overlapping types do not satisfy.
+"##)
+
 register_diagnostics!(
     E0002,
     E0003,
@@ -56,26 +236,18 @@ register_diagnostics!(
     E0040,
     E0044,
     E0045,
-    E0046,
     E0049,
-    E0050,
     E0051,
     E0052,
-    E0053,
-    E0054,
     E0055,
     E0056,
     E0057,
     E0058,
     E0059,
     E0060,
-    E0061,
-    E0062,
-    E0063,
     E0066,
     E0067,
     E0068,
-    E0069,
     E0070,
     E0071,
     E0072,
@@ -105,15 +277,11 @@ register_diagnostics!(
     E0102,
     E0103,
     E0104,
-    E0106,
     E0107,
     E0108,
     E0109,
     E0110,
-    E0116,
-    E0117,
     E0118,
-    E0119,
     E0120,
     E0121,
     E0122,
@@ -144,5 +312,17 @@ register_diagnostics!(
     E0164,
     E0165,
     E0166,
-    E0167
+    E0167,
+    E0168,
+    E0169,
+    E0170,
+    E0171,
+    E0172,
+    E0173,
+    E0174,
+    E0175,
+    E0176,
+    E0177,
+    E0178,
+    E0179
 )
@@ -26,6 +26,7 @@ use syntax::ast::{IntTy, UintTy};
 use syntax::attr;
 use syntax::attr::AttrMetaMethods;
 use syntax::diagnostic::{ColorConfig, Auto, Always, Never, SpanHandler};
+use syntax::diagnostic::{ErrorFormat, HumanReadable, Json};
 use syntax::parse;
 use syntax::parse::token::InternedString;
 
@@ -94,6 +95,7 @@ pub struct Options {
     pub print_metas: (bool, bool),
     pub cg: CodegenOptions,
     pub color: ColorConfig,
+    pub error_format: ErrorFormat,
     pub externs: HashMap<String, Vec<String>>,
     pub crate_name: Option<String>,
     /// An optional name to use as the crate for std during std injection,
@@ -125,6 +127,7 @@ pub fn basic_options() -> Options {
         print_metas: (false, false),
         cg: basic_codegen_options(),
         color: Auto,
+        error_format: HumanReadable,
         externs: HashMap::new(),
         crate_name: None,
         alt_std_name: None,
@@ -186,7 +189,10 @@ debugging_opts!(
         FLOWGRAPH_PRINT_LOANS,
         FLOWGRAPH_PRINT_MOVES,
         FLOWGRAPH_PRINT_ASSIGNS,
-        FLOWGRAPH_PRINT_ALL
+        FLOWGRAPH_PRINT_ALL,
+        NO_DEDUP_DIAGNOSTICS,
+        FOLD_STATS,
+        VERIFY_FOLD
     ]
     0
 )
@@ -228,7 +234,12 @@ pub fn debugging_opts_map() -> Vec<(&'static str, &'static str, u64)> {
      ("flowgraph-print-assigns", "Include assignment analysis data in \
                        --pretty flowgraph output", FLOWGRAPH_PRINT_ASSIGNS),
      ("flowgraph-print-all", "Include all dataflow analysis data in \
-                       --pretty flowgraph output", FLOWGRAPH_PRINT_ALL))
+                       --pretty flowgraph output", FLOWGRAPH_PRINT_ALL),
+     ("no-dedup-diagnostics", "Report every diagnostic, even exact repeats \
+                       of one already reported", NO_DEDUP_DIAGNOSTICS),
+     ("fold-stats", "count the calls made to type folders in each pass", FOLD_STATS),
+     ("verify-fold", "check that type folders return types interned in \
+                      their own type context", VERIFY_FOLD))
 }
 
 #[deriving(Clone)]
@@ -581,6 +592,9 @@ pub fn optgroups() -> Vec<getopts::OptGroup> {
             auto   = colorize, if output goes to a tty (default);
             always = always colorize output;
             never  = never colorize output", "auto|always|never"),
+        optopt("", "error-format", "Configure the format of errors and warnings:
+            human = text for people to read (default);
+            json  = one JSON object per message, for tools", "human|json"),
         optmulti("", "extern", "Specify where an external rust library is located",
                  "NAME=PATH"),
     )
@@ -771,6 +785,17 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         }
     };
 
+    let error_format = match matches.opt_str("error-format").as_ref().map(|s| s.as_slice()) {
+        Some("human") | None => HumanReadable,
+        Some("json") => Json,
+
+        Some(arg) => {
+            early_error(format!("argument for --error-format must be human or json \
+                                 (instead was `{}`)",
+                                arg).as_slice())
+        }
+    };
+
     let mut externs = HashMap::new();
     for arg in matches.opt_strs("extern").iter() {
         let mut parts = arg.as_slice().splitn(1, '=');
@@ -812,6 +837,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         print_metas: print_metas,
         cg: cg,
         color: color,
+        error_format: error_format,
         externs: externs,
         crate_name: crate_name,
         alt_std_name: None,
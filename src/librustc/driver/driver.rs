@@ -17,7 +17,7 @@ use lint;
 use llvm::{ContextRef, ModuleRef};
 use metadata::common::LinkMeta;
 use metadata::creader;
-use middle::{trans, stability, ty, typeck, reachable};
+use middle::{trans, stability, ty, ty_fold, typeck, reachable};
 use middle::dependency_format;
 use middle;
 use plugin::load::Plugins;
@@ -430,6 +430,7 @@ pub fn phase_3_run_analysis_passes<'tcx>(sess: Session,
 
     // passes are timed inside typeck
     typeck::check_crate(&ty_cx, trait_map);
+    ty_fold::dump_fold_stats(&ty_cx, "type checking");
 
     time(time_passes, "check static items", (), |_|
          middle::check_static::check_crate(&ty_cx));
@@ -484,6 +485,8 @@ pub fn phase_3_run_analysis_passes<'tcx>(sess: Session,
     time(time_passes, "lint checking", (), |_|
          lint::check_crate(&ty_cx, &exported_items));
 
+    ty_fold::dump_fold_stats(&ty_cx, "analysis after type checking");
+
     CrateAnalysis {
         exp_map2: exp_map2,
         ty_cx: ty_cx,
@@ -530,8 +533,10 @@ pub fn phase_4_translate_to_llvm<'tcx>(analysis: CrateAnalysis<'tcx>)
          dependency_format::calculate(&analysis.ty_cx));
 
     // Option dance to work around the lack of stack once closures.
-    time(time_passes, "translation", analysis, |analysis|
-         trans::base::trans_crate(analysis))
+    let (tcx, trans) = time(time_passes, "translation", analysis, |analysis|
+                            trans::base::trans_crate(analysis));
+    ty_fold::dump_fold_stats(&tcx, "translation");
+    (tcx, trans)
 }
 
 /// Run LLVM itself, producing a bitcode file, assembly file or object file
@@ -69,6 +69,13 @@ impl Session {
     pub fn span_err_with_code(&self, sp: Span, msg: &str, code: &str) {
         self.diagnostic().span_err_with_code(sp, msg, code)
     }
+    pub fn span_err_with_labels(&self, sp: Span, msg: &str, labels: &[(Span, String)]) {
+        self.diagnostic().span_err_with_labels(sp, msg, labels)
+    }
+    pub fn span_err_with_labels_and_code(&self, sp: Span, msg: &str,
+                                         labels: &[(Span, String)], code: &str) {
+        self.diagnostic().span_err_with_labels_and_code(sp, msg, labels, code)
+    }
     pub fn err(&self, msg: &str) {
         self.diagnostic().handler().err(msg)
     }
@@ -99,6 +106,9 @@ impl Session {
     pub fn span_help(&self, sp: Span, msg: &str) {
         self.diagnostic().span_help(sp, msg)
     }
+    pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String) {
+        self.diagnostic().span_suggestion(sp, msg, suggestion)
+    }
     pub fn fileline_note(&self, sp: Span, msg: &str) {
         self.diagnostic().fileline_note(sp, msg)
     }
@@ -170,6 +180,8 @@ impl Session {
     pub fn asm_comments(&self) -> bool { self.debugging_opt(config::ASM_COMMENTS) }
     pub fn no_verify(&self) -> bool { self.debugging_opt(config::NO_VERIFY) }
     pub fn borrowck_stats(&self) -> bool { self.debugging_opt(config::BORROWCK_STATS) }
+    pub fn fold_stats(&self) -> bool { self.debugging_opt(config::FOLD_STATS) }
+    pub fn verify_fold(&self) -> bool { self.debugging_opt(config::VERIFY_FOLD) }
     pub fn print_llvm_passes(&self) -> bool {
         self.debugging_opt(config::PRINT_LLVM_PASSES)
     }
@@ -208,7 +220,7 @@ pub fn build_session(sopts: config::Options,
                      -> Session {
     let codemap = codemap::CodeMap::new();
     let diagnostic_handler =
-        diagnostic::default_handler(sopts.color, Some(registry));
+        diagnostic::handler_for_format(sopts.error_format, sopts.color, Some(registry));
     let span_diagnostic_handler =
         diagnostic::mk_span_handler(diagnostic_handler, codemap);
 
@@ -219,6 +231,9 @@ pub fn build_session_(sopts: config::Options,
                       local_crate_source_file: Option<Path>,
                       span_diagnostic: diagnostic::SpanHandler)
                       -> Session {
+    if sopts.debugging_opts & config::NO_DEDUP_DIAGNOSTICS != 0 {
+        span_diagnostic.handler().set_dedup(false);
+    }
     let target_cfg = config::build_target_config(&sopts, &span_diagnostic);
     let p_s = parse::new_parse_sess_special_handler(span_diagnostic);
     let default_sysroot = match sopts.maybe_sysroot {
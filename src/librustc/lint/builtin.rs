@@ -45,8 +45,9 @@ use syntax::ast_map;
 use syntax::ast_util::is_shift_binop;
 use syntax::attr::AttrMetaMethods;
 use syntax::attr;
-use syntax::codemap::{Span, DUMMY_SP};
+use syntax::codemap::{Span, DUMMY_SP, NO_EXPANSION};
 use syntax::parse::token;
+use syntax::print::pprust;
 use syntax::{ast, ast_util, visit};
 use syntax::ast::{TyI, TyU, TyI8, TyU8, TyI16, TyU16, TyI32, TyU32, TyI64, TyU64};
 use syntax::ptr::P;
@@ -529,6 +530,59 @@ impl LintPass for BoxPointers {
     }
 }
 
+declare_lint!(BOXED_CLOSURES, Warn,
+              "use of deprecated boxed closure types (`|A| -> R` and `proc(A) -> R`)")
+
+pub struct BoxedClosures;
+
+impl BoxedClosures {
+    fn suggestion(trait_name: &str, decl: &ast::FnDecl) -> String {
+        let inputs = decl.inputs.iter()
+                                .map(|arg| pprust::ty_to_string(&*arg.ty))
+                                .collect::<Vec<String>>();
+        let mut s = format!("{}({})", trait_name, inputs.connect(", "));
+        match decl.output.node {
+            ast::TyNil => {}
+            _ => {
+                s.push_str(" -> ");
+                s.push_str(pprust::ty_to_string(&*decl.output).as_slice());
+            }
+        }
+        s
+    }
+}
+
+impl LintPass for BoxedClosures {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(BOXED_CLOSURES)
+    }
+
+    fn check_ty(&mut self, cx: &Context, ty: &ast::Ty) {
+        // Types produced by macro expansion (including `#[deriving]` and
+        // other compiler-generated code) were not written by the user.
+        if ty.span.expn_id != NO_EXPANSION {
+            return
+        }
+
+        let msg = match ty.node {
+            ast::TyClosure(ref c) => {
+                let trait_name = match c.onceness {
+                    ast::Once => "FnOnce",
+                    ast::Many => "FnMut",
+                };
+                format!("boxed closure types are deprecated, use the unboxed closure \
+                         trait `{}` instead", BoxedClosures::suggestion(trait_name, &*c.decl))
+            }
+            ast::TyProc(ref c) => {
+                format!("`proc` types are deprecated, use the unboxed closure trait \
+                         `{} + Send` instead", BoxedClosures::suggestion("FnOnce", &*c.decl))
+            }
+            _ => return
+        };
+        cx.span_lint(BOXED_CLOSURES, ty.span, msg.as_slice());
+    }
+}
+
 declare_lint!(RAW_POINTER_DERIVING, Warn,
               "uses of #[deriving] with raw pointers are rarely correct")
 
@@ -194,6 +194,7 @@ impl LintStore {
                      UnusedCasts,
                      ImproperCTypes,
                      BoxPointers,
+                     BoxedClosures,
                      UnusedAttributes,
                      PathStatements,
                      UnusedResults,
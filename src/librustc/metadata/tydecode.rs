@@ -354,8 +354,10 @@ fn parse_str(st: &mut PState, term: char) -> String {
 
 fn parse_trait_ref(st: &mut PState, conv: conv_did) -> ty::TraitRef {
     let def = parse_def(st, NominalType, |x,y| conv(x,y));
+    let binder_id = parse_uint(st) as ast::NodeId;
+    assert_eq!(next(st), '|');
     let substs = parse_substs(st, |x,y| conv(x,y));
-    ty::TraitRef {def_id: def, substs: substs}
+    ty::TraitRef {def_id: def, substs: substs, binder_id: binder_id}
 }
 
 fn parse_ty(st: &mut PState, conv: conv_did) -> ty::t {
@@ -1000,6 +1000,12 @@ impl<'a> rbml_writer_helpers for Encoder<'a> {
                             |this| Ok(this.emit_auto_deref_ref(ecx, auto_deref_ref)))
                     })
                 }
+
+                ty::AdjustUnboxedClosureToFnPointer(fn_ptr_ty) => {
+                    this.emit_enum_variant("AutoUnboxedClosureToFnPointer", 2, 1, |this| {
+                        this.emit_enum_variant_arg(0, |this| Ok(this.emit_ty(ecx, fn_ptr_ty)))
+                    })
+                }
             }
         });
     }
@@ -1190,16 +1196,27 @@ fn encode_side_tables_for_id(ecx: &e::EncodeContext,
         });
 
         for freevar in fv.iter() {
-            match tcx.capture_mode(id) {
+            let var_id = freevar.def.def_id().node;
+            let upvar_id = ty::UpvarId {
+                var_id: var_id,
+                closure_expr_id: id
+            };
+
+            for &mode in tcx.upvar_capture_modes.borrow().get(&upvar_id).iter() {
+                rbml_w.tag(c::tag_table_upvar_capture_modes, |rbml_w| {
+                    rbml_w.id(id);
+                    rbml_w.tag(c::tag_table_val, |rbml_w| {
+                        var_id.encode(rbml_w);
+                        encode_capture_mode(rbml_w, *mode);
+                    })
+                })
+            }
+
+            match tcx.upvar_capture_mode(upvar_id) {
                 ast::CaptureByRef => {
                     rbml_w.tag(c::tag_table_upvar_borrow_map, |rbml_w| {
                         rbml_w.id(id);
                         rbml_w.tag(c::tag_table_val, |rbml_w| {
-                            let var_id = freevar.def.def_id().node;
-                            let upvar_id = ty::UpvarId {
-                                var_id: var_id,
-                                closure_expr_id: id
-                            };
                             let upvar_borrow = tcx.upvar_borrow_map.borrow()
                                                   .get_copy(&upvar_id);
                             var_id.encode(rbml_w);
@@ -1576,7 +1593,7 @@ impl<'a> rbml_decoder_decoder_helpers for reader::Decoder<'a> {
 
     fn read_auto_adjustment(&mut self, dcx: &DecodeContext) -> ty::AutoAdjustment {
         self.read_enum("AutoAdjustment", |this| {
-            let variants = ["AutoAddEnv", "AutoDerefRef"];
+            let variants = ["AutoAddEnv", "AutoDerefRef", "AutoUnboxedClosureToFnPointer"];
             this.read_enum_variant(variants, |this, i| {
                 Ok(match i {
                     0 => {
@@ -1592,6 +1609,12 @@ impl<'a> rbml_decoder_decoder_helpers for reader::Decoder<'a> {
 
                         ty::AdjustDerefRef(auto_deref_ref)
                     }
+                    2 => {
+                        let fn_ptr_ty: ty::t =
+                            this.read_enum_variant_arg(0, |this| Ok(this.read_ty(dcx))).unwrap();
+
+                        ty::AdjustUnboxedClosureToFnPointer(fn_ptr_ty)
+                    }
                     _ => panic!("bad enum variant for ty::AutoAdjustment")
                 })
             })
@@ -1856,6 +1879,18 @@ fn decode_side_tables(dcx: &DecodeContext,
                            .borrow_mut()
                            .insert(id, capture_mode);
                     }
+                    c::tag_table_upvar_capture_modes => {
+                        let var_id: ast::NodeId = Decodable::decode(val_dsr).unwrap();
+                        let upvar_id = ty::UpvarId {
+                            var_id: dcx.tr_id(var_id),
+                            closure_expr_id: id
+                        };
+                        let capture_mode = val_dsr.read_capture_mode();
+                        dcx.tcx
+                           .upvar_capture_modes
+                           .borrow_mut()
+                           .insert(upvar_id, capture_mode);
+                    }
                     c::tag_table_tcache => {
                         let pty = val_dsr.read_polytype(dcx);
                         let lid = ast::DefId { krate: ast::LOCAL_CRATE, node: id };
@@ -21,9 +21,12 @@
 use middle::borrowck::*;
 use middle::expr_use_visitor as euv;
 use middle::mem_categorization as mc;
+use middle::pat_util;
 use middle::ty;
 use syntax::ast;
+use syntax::ast_map;
 use syntax::codemap::Span;
+use syntax::parse::token;
 use util::ppaux::Repr;
 
 use std::rc::Rc;
@@ -107,6 +110,10 @@ impl<'a, 'tcx> euv::Delegate for CheckLoanCtxt<'a, 'tcx> {
                cmt.repr(self.tcx()),
                mode);
 
+        if self.check_for_conflicting_at_bindings(consume_pat) {
+            return;
+        }
+
         self.consume_common(consume_pat.id, consume_pat.span, cmt, mode);
     }
 
@@ -123,6 +130,18 @@ impl<'a, 'tcx> euv::Delegate for CheckLoanCtxt<'a, 'tcx> {
                borrow_id, cmt.repr(self.tcx()), loan_region,
                bk, loan_cause);
 
+        if loan_cause == euv::RefBinding {
+            let conflicting = match self.tcx().map.find(borrow_id) {
+                Some(ast_map::NodeLocal(pat)) | Some(ast_map::NodeArg(pat)) => {
+                    self.check_for_conflicting_at_bindings(pat)
+                }
+                _ => false
+            };
+            if conflicting {
+                return;
+            }
+        }
+
         match opt_loan_path(&cmt) {
             Some(lp) => {
                 let moved_value_use_kind = match loan_cause {
@@ -261,7 +280,9 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
         //     let x = &mut a.b.c; // Restricts a, a.b, and a.b.c
         //     let y = a;          // Conflicts with restriction
 
-        let loan_path = owned_ptr_base_path(loan_path);
+        // Loan paths do not record the offsets of vector elements.
+        let erased_path = loan_path.erase_element_offsets();
+        let loan_path = owned_ptr_base_path(&*erased_path);
         let cont = self.each_in_scope_loan(scope_id, |loan| {
             let mut ret = true;
             for restr_path in loan.restricted_paths.iter() {
@@ -552,6 +573,67 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
         }
     }
 
+    fn check_for_conflicting_at_bindings(&self, whole: &ast::Pat) -> bool {
+        /*!
+         * Checks that the bindings in the sub-pattern of `whole @ pat`
+         * can coexist with `whole`: both cannot take ownership of the
+         * matched value, a binding cannot borrow it while `whole` takes
+         * ownership of it, and neither can borrow it mutably while the
+         * other has access to it. This does not depend on the matched value
+         * having a loan path, so it also covers matches on rvalues.
+         * Returns true if an error was reported.
+         */
+
+        let tcx = self.tcx();
+        let (whole_mode, whole_ident, sub) = match whole.node {
+            ast::PatIdent(binding_mode, ref path1, Some(ref sub)) => {
+                (binding_mode, path1.node, sub)
+            }
+            _ => return false
+        };
+        fn moves(tcx: &ty::ctxt, id: ast::NodeId) -> bool {
+            ty::type_moves_by_default(tcx, ty::node_id_to_type(tcx, id))
+        }
+
+        let mut reported = false;
+        pat_util::pat_bindings(&tcx.def_map, &**sub, |mode, id, span, path1| {
+            let msg = match (whole_mode, mode) {
+                (ast::BindByValue(_), ast::BindByValue(_))
+                        if moves(tcx, whole.id) && moves(tcx, id) => {
+                    format!("cannot move into both `{}` and `{}`, \
+                             which is part of the same value",
+                            token::get_ident(whole_ident),
+                            token::get_ident(path1.node))
+                }
+                (ast::BindByValue(_), ast::BindByRef(_)) if moves(tcx, whole.id) => {
+                    format!("cannot borrow `{}` while `{}` takes \
+                             ownership of the value containing it",
+                            token::get_ident(path1.node),
+                            token::get_ident(whole_ident))
+                }
+                (ast::BindByRef(ast::MutMutable), ast::BindByRef(_)) => {
+                    format!("cannot borrow `{}` while `{}` borrows \
+                             the value containing it as mutable",
+                            token::get_ident(path1.node),
+                            token::get_ident(whole_ident))
+                }
+                (ast::BindByRef(ast::MutImmutable), ast::BindByRef(ast::MutMutable)) => {
+                    format!("cannot borrow `{}` as mutable while `{}` \
+                             borrows the value containing it",
+                            token::get_ident(path1.node),
+                            token::get_ident(whole_ident))
+                }
+                _ => return
+            };
+            self.bccx.span_err(span, msg.as_slice());
+            self.bccx.span_note(whole.span,
+                                format!("`{}` is bound here",
+                                        token::get_ident(whole_ident)).as_slice());
+            reported = true;
+        });
+        reported
+    }
+
     fn consume_common(&self,
                       id: ast::NodeId,
                       span: Span,
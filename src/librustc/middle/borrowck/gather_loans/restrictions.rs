@@ -91,9 +91,10 @@ impl<'a, 'tcx> RestrictionsContext<'a, 'tcx> {
                 //
                 // Overwriting the base would not change the type of
                 // the memory, so no additional restrictions are
-                // needed.
+                // needed. Loans do not distinguish the offsets of
+                // vector elements, so those are erased here.
                 let result = self.restrict(cmt_base);
-                self.extend(result, cmt.mutbl, LpInterior(i))
+                self.extend(result, cmt.mutbl, LpInterior(erase_element_offset(i)))
             }
 
 
@@ -344,6 +344,54 @@ impl LoanPath {
             _ => None,
         }
     }
+
+    pub fn erase_element_offsets(&self) -> Rc<LoanPath> {
+        /*!
+         * Returns a copy of this path in which every element at a
+         * statically known offset (`a[1]`) is replaced by an element
+         * at an unknown one (`a[..]`). Only moves are tracked per
+         * offset; loans compare paths in this erased form.
+         */
+
+        match *self {
+            LpVar(id) => Rc::new(LpVar(id)),
+            LpUpvar(id) => Rc::new(LpUpvar(id)),
+            LpExtend(ref base, mutbl, LpInterior(ik)) => {
+                Rc::new(LpExtend(base.erase_element_offsets(), mutbl,
+                                 LpInterior(erase_element_offset(ik))))
+            }
+            LpExtend(ref base, mutbl, LpDeref(pk)) => {
+                Rc::new(LpExtend(base.erase_element_offsets(), mutbl, LpDeref(pk)))
+            }
+        }
+    }
+
+    pub fn has_element_of_unknown_offset(&self) -> bool {
+        match *self {
+            LpVar(_) | LpUpvar(_) => false,
+            LpExtend(_, _, LpInterior(mc::InteriorElement(_))) => true,
+            LpExtend(ref base, _, _) => base.has_element_of_unknown_offset(),
+        }
+    }
+
+    pub fn has_base_path(&self, base_path: &LoanPath) -> bool {
+        //! True if `base_path` is this path or one of its base paths.
+
+        if *self == *base_path {
+            return true;
+        }
+        match *self {
+            LpVar(_) | LpUpvar(_) => false,
+            LpExtend(ref base, _, _) => base.has_base_path(base_path),
+        }
+    }
+}
+
+pub fn erase_element_offset(ik: mc::InteriorKind) -> mc::InteriorKind {
+    match ik {
+        mc::InteriorElementAt(_) => mc::InteriorElement(mc::VecElement),
+        _ => ik
+    }
 }
 
 pub fn opt_loan_path(cmt: &mc::cmt) -> Option<Rc<LoanPath>> {
@@ -565,6 +613,13 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
                                                   r).as_slice())
                     }
                 };
+                // The move is recorded against the closure expression, but
+                // it is the captured variable's type that is moved.
+                let expr_ty = match *moved_lp {
+                    LpVar(id) => ty::node_id_to_type(self.tcx, id),
+                    LpUpvar(upvar_id) => ty::node_id_to_type(self.tcx, upvar_id.var_id),
+                    LpExtend(..) => expr_ty,
+                };
                 let (suggestion, help) = move_suggestion(self.tcx, expr_ty,
                         ("moved by default", "make a copy and \
                          capture that instead to override"));
@@ -625,6 +680,10 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
         self.tcx.sess.span_help(s, m);
     }
 
+    pub fn span_suggestion(&self, s: Span, m: &str, suggestion: String) {
+        self.tcx.sess.span_suggestion(s, m, suggestion);
+    }
+
     pub fn bckerr_to_string(&self, err: &BckError) -> String {
         match err.code {
             err_mutbl => {
@@ -857,6 +916,13 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
                 out.push_str("[..]");
             }
 
+            LpExtend(ref lp_base, _, LpInterior(mc::InteriorElementAt(i))) => {
+                self.append_autoderefd_loan_path_to_string(&**lp_base, out);
+                out.push('[');
+                out.push_str(i.to_string().as_slice());
+                out.push(']');
+            }
+
             LpExtend(ref lp_base, _, LpDeref(_)) => {
                 out.push('*');
                 self.append_loan_path_to_string(&**lp_base, out);
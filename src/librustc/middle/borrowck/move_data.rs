@@ -179,6 +179,21 @@ fn loan_path_is_precise(loan_path: &LoanPath) -> bool {
     }
 }
 
+fn element_paths_overlap(lp1: &LoanPath, lp2: &LoanPath) -> bool {
+    /*!
+     * True if `lp1` and `lp2` are not otherwise related, but one of
+     * them names an element at an unknown offset that may be (or
+     * contain) the other.
+     */
+
+    if !lp1.has_element_of_unknown_offset() && !lp2.has_element_of_unknown_offset() {
+        return false;
+    }
+    let lp1 = lp1.erase_element_offsets();
+    let lp2 = lp2.erase_element_offsets();
+    lp1.has_base_path(&*lp2) || lp2.has_base_path(&*lp1)
+}
+
 impl MoveData {
     pub fn new() -> MoveData {
         MoveData {
@@ -613,6 +628,12 @@ impl<'a, 'tcx> FlowedMoveData<'a, 'tcx> {
         // OK scenario:
         //
         // 4. move of `a.b.c`, use of `a.b.d`
+        //
+        // Elements of a vector at unknown offsets (`a[..]`, from an
+        // indexing expression) may be any of the elements at known
+        // offsets (`a[0]`, `a[1]`, from a vector pattern), so:
+        //
+        // 5. Move of `a[0]`, use of `a[..]` (or vice versa)
 
         let base_indices = self.move_data.existing_base_paths(loan_path);
         if base_indices.is_empty() {
@@ -634,11 +655,13 @@ impl<'a, 'tcx> FlowedMoveData<'a, 'tcx> {
                     ret = false;
                 }
             } else {
+                let mut reported = false;
                 for &loan_path_index in opt_loan_path_index.iter() {
                     let cont = self.move_data.each_base_path(moved_path, |p| {
                         if p == loan_path_index {
                             // Scenario 3: some extension of `loan_path`
                             // was moved
+                            reported = true;
                             f(the_move,
                               &*self.move_data.path_loan_path(moved_path))
                         } else {
@@ -647,6 +670,14 @@ impl<'a, 'tcx> FlowedMoveData<'a, 'tcx> {
                     });
                     if !cont { ret = false; break }
                 }
+
+                let moved_lp = self.move_data.path_loan_path(moved_path);
+                if !reported && element_paths_overlap(&*moved_lp, &**loan_path) {
+                    // Scenario 5
+                    if !f(the_move, &*moved_lp) {
+                        ret = false;
+                    }
+                }
             }
             ret
         })
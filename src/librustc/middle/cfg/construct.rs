@@ -143,6 +143,11 @@ impl<'a, 'tcx> CFGBuilder<'a, 'tcx> {
                 self.add_node(pat.id, [post_exit])
             }
 
+            ast::PatOr(ref alternatives) => {
+                let alts_exit = self.pats_any(alternatives.as_slice(), pred);
+                self.add_node(pat.id, [alts_exit])
+            }
+
             ast::PatMac(_) => {
                 self.tcx.sess.span_bug(pat.span, "unexpanded macro");
             }
@@ -342,13 +347,12 @@ impl<'a, 'tcx> CFGBuilder<'a, 'tcx> {
                 //      /  \
                 //     |    \
                 //     v 3   \
-                //  [pat1]    \
-                //     |       |
-                //     v 4     |
-                //  [guard1]   |
-                //     |       |
-                //     |       |
-                //     v 5     v
+                //  [guard1]  \
+                //     |  \    |
+                //     v 4 \   |
+                //  [pat1]  \  |
+                //     |     \ |
+                //     v 5    vv
                 //  [body1]  [cond2]
                 //     |      /  \
                 //     |    ...  ...
@@ -356,18 +360,25 @@ impl<'a, 'tcx> CFGBuilder<'a, 'tcx> {
                 //     v 6   v    v
                 //  [.....expr.....]
                 //
+                // The guard only sees the bindings of the arm by reference, so
+                // the pattern does not move anything out of the discriminant
+                // until the guard has succeeded. When it fails, control moves
+                // on to the next arm with whatever the guard itself did.
                 let discr_exit = self.expr(&**discr, pred);              // 1
 
                 let expr_exit = self.add_node(expr.id, []);
                 let mut cond_exit = discr_exit;
                 for arm in arms.iter() {
                     cond_exit = self.add_dummy_node([cond_exit]);        // 2
-                    let pats_exit = self.pats_any(arm.pats.as_slice(),
-                                                  cond_exit);            // 3
                     let guard_exit = self.opt_expr(&arm.guard,
-                                                   pats_exit);           // 4
-                    let body_exit = self.expr(&*arm.body, guard_exit);   // 5
+                                                   cond_exit);           // 3
+                    let pats_exit = self.pats_any(arm.pats.as_slice(),
+                                                  guard_exit);           // 4
+                    let body_exit = self.expr(&*arm.body, pats_exit);    // 5
                     self.add_contained_edge(body_exit, expr_exit);       // 6
+                    if arm.guard.is_some() {
+                        cond_exit = self.add_dummy_node([cond_exit, guard_exit]);
+                    }
                 }
                 expr_exit
             }
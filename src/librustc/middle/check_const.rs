@@ -91,7 +91,7 @@ fn check_pat(v: &mut CheckCrateVisitor, p: &Pat) {
     match p.node {
         // Let through plain ~-string literals here
         PatLit(ref a) => if !is_str(&**a) { v.inside_const(|v| v.visit_expr(&**a)); },
-        PatRange(ref a, ref b) => {
+        PatRange(ref a, ref b, _) => {
             if !is_str(&**a) { v.inside_const(|v| v.visit_expr(&**a)); }
             if !is_str(&**b) { v.inside_const(|v| v.visit_expr(&**b)); }
         }
@@ -212,6 +212,13 @@ fn check_expr(v: &mut CheckCrateVisitor, e: &Expr) -> bool {
         ExprRepeat(..) |
         ExprStruct(..) => {}
 
+        ExprFnBlock(..) | ExprProc(..) | ExprUnboxedFn(..) => {
+            span_err!(v.tcx.sess, e.span, E0177,
+                      "closures are not allowed in constant expressions, as \
+                       they construct their environment at runtime");
+            return false;
+        }
+
         ExprAddrOf(_, ref inner) => {
             match inner.node {
                 // Mutable slices are allowed.
@@ -8,13 +8,16 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use middle::const_eval::{compare_const_vals, const_bool, const_float, const_nil, const_val};
-use middle::const_eval::{const_expr_to_pat, eval_const_expr, lookup_const_by_id};
+use middle::const_eval::{compare_const_vals, const_bool, const_float, const_int, const_nil};
+use middle::const_eval::{const_uint, const_val};
+use middle::const_eval::{const_expr_to_pat, eval_const_expr, eval_range_bounds};
+use middle::const_eval::{lookup_const_by_id};
 use middle::def::*;
 use middle::expr_use_visitor::{ConsumeMode, Delegate, ExprUseVisitor, Init};
 use middle::expr_use_visitor::{JustWrite, LoanCause, MutateMode};
 use middle::expr_use_visitor::{WriteAndRead};
-use middle::mem_categorization::cmt;
+use middle::expr_use_visitor as euv;
+use middle::mem_categorization::{cat_local, cmt};
 use middle::pat_util::*;
 use middle::ty::*;
 use middle::ty;
@@ -31,6 +34,7 @@ use syntax::print::pprust::pat_to_string;
 use syntax::parse::token;
 use syntax::ptr::P;
 use syntax::visit::{mod, Visitor, FnKind};
+use util::nodemap::NodeMap;
 use util::ppaux::ty_to_string;
 
 pub const DUMMY_WILD_PAT: &'static Pat = &Pat {
@@ -95,7 +99,11 @@ impl<'a> FromIterator<Vec<&'a Pat>> for Matrix<'a> {
 }
 
 pub struct MatchCheckCtxt<'a, 'tcx: 'a> {
-    pub tcx: &'a ty::ctxt<'tcx>
+    pub tcx: &'a ty::ctxt<'tcx>,
+    /// Whether the enum variants that can't be constructed, because one of
+    /// their arguments is of an uninhabited type, are left out of the
+    /// constructors a match has to cover.
+    pub skip_uninhabited_variants: bool
 }
 
 #[deriving(Clone, PartialEq)]
@@ -111,17 +119,24 @@ pub enum Constructor {
     ConstantRange(const_val, const_val),
     /// Array patterns of length n.
     Slice(uint),
-    /// Array patterns with a subslice.
+    /// Array patterns with a subslice, matched against the given numbers of
+    /// elements at the front and at the back.
     SliceWithSubslice(uint, uint)
 }
 
 #[deriving(Clone, PartialEq)]
 enum Usefulness {
     Useful,
-    UsefulWithWitness(Vec<P<Pat>>),
+    /// Rows of patterns matching values that none of the matrix's rows match.
+    /// At most `MAX_WITNESSES + 1` of them are collected, the extra one only
+    /// telling that there are more.
+    UsefulWithWitnesses(Vec<Vec<P<Pat>>>),
     NotUseful
 }
 
+/// The number of patterns a non-exhaustive match error lists as not covered.
+const MAX_WITNESSES: uint = 3;
+
 enum WitnessPreference {
     ConstructWitness,
     LeaveOutWitness
@@ -141,7 +156,8 @@ impl<'a, 'tcx, 'v> Visitor<'v> for MatchCheckCtxt<'a, 'tcx> {
 }
 
 pub fn check_crate(tcx: &ty::ctxt) {
-    visit::walk_crate(&mut MatchCheckCtxt { tcx: tcx }, tcx.map.krate());
+    let mut cx = MatchCheckCtxt { tcx: tcx, skip_uninhabited_variants: true };
+    visit::walk_crate(&mut cx, tcx.map.krate());
     tcx.sess.abort_if_errors();
 }
 
@@ -151,27 +167,25 @@ fn check_expr(cx: &mut MatchCheckCtxt, ex: &Expr) {
         ExprMatch(ref scrut, ref arms, source) => {
             // First, check legality of move bindings.
             for arm in arms.iter() {
-                check_legality_of_move_bindings(cx,
-                                                arm.guard.is_some(),
-                                                arm.pats.as_slice());
-                for pat in arm.pats.iter() {
-                    check_legality_of_bindings_in_at_patterns(cx, &**pat);
-                }
+                check_legality_of_move_bindings(cx, arm.pats.as_slice());
             }
 
             // Second, if there is a guard on each arm, make sure it isn't
-            // assigning or borrowing anything mutably.
+            // assigning, borrowing anything mutably or moving out of the
+            // bindings of its arm.
             for arm in arms.iter() {
                 match arm.guard {
-                    Some(ref guard) => check_for_mutation_in_guard(cx, &**guard),
+                    Some(ref guard) => {
+                        check_for_mutation_in_guard(cx, arm.pats.as_slice(), &**guard)
+                    }
                     None => {}
                 }
             }
 
             let mut static_inliner = StaticInliner::new(cx.tcx);
             let inlined_arms = arms.iter().map(|arm| {
-                (arm.pats.iter().map(|pat| {
-                    static_inliner.fold_pat((*pat).clone())
+                (arm.pats.iter().flat_map(|pat| {
+                    expand_or_pats(&static_inliner.fold_pat((*pat).clone())).into_iter()
                 }).collect(), arm.guard.as_ref().map(|e| &**e))
             }).collect::<Vec<(Vec<P<Pat>>, Option<&Expr>)>>();
 
@@ -188,10 +202,10 @@ fn check_expr(cx: &mut MatchCheckCtxt, ex: &Expr) {
             check_arms(cx, inlined_arms.as_slice(), source);
 
             // Finally, check if the whole match expression is exhaustive.
-            // Check for empty enum, because is_useful only works on inhabited types.
+            // Check for uninhabited types, because is_useful only works on inhabited types.
             let pat_ty = node_id_to_type(cx.tcx, scrut.id);
             if inlined_arms.is_empty() {
-                if !type_is_empty(cx.tcx, pat_ty) {
+                if !type_is_uninhabited(cx.tcx, pat_ty) {
                     // We know the type is inhabited, so this must be wrong
                     span_err!(cx.tcx.sess, ex.span, E0002,
                         "non-exhaustive patterns: type {} is non-empty",
@@ -211,6 +225,9 @@ fn check_expr(cx: &mut MatchCheckCtxt, ex: &Expr) {
             check_exhaustive(cx, ex.span, &matrix);
         },
         ExprForLoop(ref pat, _, _, _) => {
+            if !check_no_or_pats(cx, &**pat) {
+                return;
+            }
             let mut static_inliner = StaticInliner::new(cx.tcx);
             is_refutable(cx, &*static_inliner.fold_pat((*pat).clone()), |uncovered_pat| {
                 cx.tcx.sess.span_err(
@@ -221,8 +238,7 @@ fn check_expr(cx: &mut MatchCheckCtxt, ex: &Expr) {
             });
 
             // Check legality of move bindings.
-            check_legality_of_move_bindings(cx, false, slice::ref_slice(pat));
-            check_legality_of_bindings_in_at_patterns(cx, &**pat);
+            check_legality_of_move_bindings(cx, slice::ref_slice(pat));
         }
         _ => ()
     }
@@ -261,6 +277,16 @@ fn check_arms(cx: &MatchCheckCtxt, arms: &[(Vec<P<Pat>>, Option<&Expr>)], source
             let v = vec![&**pat];
 
             match is_useful(cx, &seen, v.as_slice(), LeaveOutWitness) {
+                NotUseful if is_useful_with_uninhabited_variants(cx, &seen, v.as_slice()) => {
+                    // The pattern only covers variants that can't be
+                    // constructed, which used to be required.
+                    match source {
+                        MatchNormal => {
+                            cx.tcx.sess.span_warn(pat.span, "unreachable pattern")
+                        }
+                        MatchIfLetDesugar | MatchWhileLetDesugar => {}
+                    }
+                }
                 NotUseful => {
                     match source {
                         MatchIfLetDesugar => {
@@ -290,8 +316,16 @@ fn check_arms(cx: &MatchCheckCtxt, arms: &[(Vec<P<Pat>>, Option<&Expr>)], source
                         },
                     }
                 }
-                Useful => (),
-                UsefulWithWitness(_) => unreachable!()
+                Useful => match uninhabited_subpat_ty(cx, &**pat) {
+                    Some(ty) => {
+                        cx.tcx.sess.span_warn(pat.span, "unreachable pattern");
+                        cx.tcx.sess.span_note(pat.span,
+                            format!("no value of type `{}` can be constructed",
+                                    ty_to_string(cx.tcx, ty)).as_slice());
+                    }
+                    None => {}
+                },
+                UsefulWithWitnesses(_) => unreachable!()
             }
             if guard.is_none() {
                 let Matrix(mut rows) = seen;
@@ -302,6 +336,40 @@ fn check_arms(cx: &MatchCheckCtxt, arms: &[(Vec<P<Pat>>, Option<&Expr>)], source
     }
 }
 
+fn is_useful_with_uninhabited_variants(cx: &MatchCheckCtxt, matrix: &Matrix,
+                                       v: &[&Pat]) -> bool {
+    let cx = MatchCheckCtxt { tcx: cx.tcx, skip_uninhabited_variants: false };
+    is_useful(&cx, matrix, v, LeaveOutWitness) != NotUseful
+}
+
+/// Finds an uninhabited type among the arguments of the variants, structs,
+/// tuples and arrays that make up `pat`, which then can't match any value.
+/// Pointers are not looked through.
+fn uninhabited_subpat_ty(cx: &MatchCheckCtxt, pat: &Pat) -> Option<ty::t> {
+    let subpats: Vec<&Pat> = match pat.node {
+        PatEnum(_, Some(ref args)) | PatTup(ref args) => args.iter().map(|p| &**p).collect(),
+        PatStruct(_, ref fields, _) => fields.iter().map(|f| &*f.node.pat).collect(),
+        PatVec(ref before, _, ref after) =>
+            before.iter().chain(after.iter()).map(|p| &**p).collect(),
+        PatIdent(_, _, Some(ref sub)) => return uninhabited_subpat_ty(cx, &**sub),
+        _ => vec![]
+    };
+    for &subpat in subpats.iter() {
+        if subpat.node == PatWild(PatWildMulti) {
+            continue;
+        }
+        match node_id_to_type_opt(cx.tcx, subpat.id) {
+            Some(ty) if type_is_uninhabited(cx.tcx, ty) => return Some(ty),
+            _ => {}
+        }
+        match uninhabited_subpat_ty(cx, subpat) {
+            Some(ty) => return Some(ty),
+            None => {}
+        }
+    }
+    None
+}
+
 fn raw_pat<'a>(p: &'a Pat) -> &'a Pat {
     match p.node {
         PatIdent(_, _, Some(ref s)) => raw_pat(&**s),
@@ -311,15 +379,18 @@ fn raw_pat<'a>(p: &'a Pat) -> &'a Pat {
 
 fn check_exhaustive(cx: &MatchCheckCtxt, sp: Span, matrix: &Matrix) {
     match is_useful(cx, matrix, &[DUMMY_WILD_PAT], ConstructWitness) {
-        UsefulWithWitness(pats) => {
-            let witness = match pats.as_slice() {
-                [ref witness] => &**witness,
-                [] => DUMMY_WILD_PAT,
-                _ => unreachable!()
-            };
+        UsefulWithWitnesses(witnesses) => {
+            let witnesses: Vec<String> = witnesses.iter().map(|pats| {
+                let witness = match pats.as_slice() {
+                    [ref witness] => &**witness,
+                    [] => DUMMY_WILD_PAT,
+                    _ => unreachable!()
+                };
+                format!("`{}`", pat_to_string(witness))
+            }).collect();
             span_err!(cx.tcx.sess, sp, E0004,
-                "non-exhaustive patterns: `{}` not covered",
-                pat_to_string(witness)
+                "non-exhaustive patterns: {} not covered",
+                join_witnesses(witnesses.as_slice())
             );
         }
         NotUseful => {
@@ -329,6 +400,18 @@ fn check_exhaustive(cx: &MatchCheckCtxt, sp: Span, matrix: &Matrix) {
     }
 }
 
+/// Lists the witnesses as "`a`, `b` and `c`", ending the list with "and more"
+/// when there are more than `MAX_WITNESSES` of them.
+fn join_witnesses(witnesses: &[String]) -> String {
+    if witnesses.len() > MAX_WITNESSES {
+        format!("{} and more", witnesses.slice_to(MAX_WITNESSES).connect(", "))
+    } else if witnesses.len() == 1 {
+        witnesses[0].clone()
+    } else {
+        format!("{} and {}", witnesses.init().connect(", "), witnesses.last().unwrap())
+    }
+}
+
 fn const_val_to_expr(value: &const_val) -> P<Expr> {
     let node = match value {
         &const_bool(b) => LitBool(b),
@@ -379,11 +462,73 @@ impl<'a, 'tcx> Folder for StaticInliner<'a, 'tcx> {
                     _ => noop_fold_pat(pat, self)
                 }
             }
+            PatVec(..) if vec_pat_elems_are_bytes(self.tcx, pat.id) => {
+                let tcx = self.tcx;
+                noop_fold_pat(pat, self).map(|Pat { id, node, span }| {
+                    let node = match node {
+                        PatVec(before, slice, after) => {
+                            PatVec(expand_byte_str_pats(tcx, before),
+                                   slice,
+                                   expand_byte_str_pats(tcx, after))
+                        }
+                        _ => unreachable!()
+                    };
+                    Pat { id: id, node: node, span: span }
+                })
+            }
             _ => noop_fold_pat(pat, self)
         }
     }
 }
 
+fn vec_pat_elems_are_bytes(tcx: &ty::ctxt, pat_id: NodeId) -> bool {
+    let elem_ty = match ty::get(node_id_to_type(tcx, pat_id)).sty {
+        ty::ty_vec(elem_ty, Some(_)) => elem_ty,
+        ty::ty_rptr(_, ty::mt { ty, .. }) => match ty::get(ty).sty {
+            ty::ty_vec(elem_ty, None) => elem_ty,
+            _ => return false
+        },
+        _ => return false
+    };
+    match ty::get(elem_ty).sty {
+        ty::ty_uint(TyU8) => true,
+        _ => false
+    }
+}
+
+/// Replaces each byte string literal among the elements of a vector pattern
+/// over `u8`s with one literal pattern per byte. The new patterns are given
+/// fresh ids, typed as `u8` so that they are analyzed and translated like
+/// the literals they stand for.
+fn expand_byte_str_pats(tcx: &ty::ctxt, pats: Vec<P<Pat>>) -> Vec<P<Pat>> {
+    let mut result = vec![];
+    for pat in pats.into_iter() {
+        let bytes = match byte_str_pat_bytes(&*pat) {
+            Some(bytes) => bytes,
+            None => {
+                result.push(pat);
+                continue;
+            }
+        };
+        for &byte in bytes.iter() {
+            let expr_id = tcx.sess.next_node_id();
+            let pat_id = tcx.sess.next_node_id();
+            tcx.node_types.borrow_mut().insert(expr_id, ty::mk_u8());
+            tcx.node_types.borrow_mut().insert(pat_id, ty::mk_u8());
+            result.push(P(Pat {
+                id: pat_id,
+                node: PatLit(P(Expr {
+                    id: expr_id,
+                    node: ExprLit(P(Spanned { node: LitByte(byte), span: pat.span })),
+                    span: pat.span
+                })),
+                span: pat.span
+            }));
+        }
+    }
+    result
+}
+
 /// Constructs a partial witness for a pattern given a list of
 /// patterns expanded by the specialization step.
 ///
@@ -477,14 +622,21 @@ fn construct_witness(cx: &MatchCheckCtxt, ctor: &Constructor,
     })
 }
 
-fn missing_constructor(cx: &MatchCheckCtxt, &Matrix(ref rows): &Matrix,
-                       left_ty: ty::t, max_slice_length: uint) -> Option<Constructor> {
-    let used_constructors: Vec<Constructor> = rows.iter()
-        .flat_map(|row| pat_constructors(cx, row[0], left_ty, max_slice_length).into_iter())
-        .collect();
-    all_constructors(cx, left_ty, max_slice_length)
-        .into_iter()
-        .find(|c| !used_constructors.contains(c))
+/// Whether every value of the constructor `ctor` is also a value of `used`.
+fn constructor_covered_by(ctor: &Constructor, used: &Constructor) -> bool {
+    match *used {
+        ConstantValue(ref value) => match *ctor {
+            ConstantValue(_) | ConstantRange(..) =>
+                range_covered_by_constructor(ctor, value, value) == Some(true),
+            _ => false
+        },
+        ConstantRange(ref from, ref to) => match *ctor {
+            ConstantValue(_) | ConstantRange(..) =>
+                range_covered_by_constructor(ctor, from, to) == Some(true),
+            _ => false
+        },
+        _ => ctor == used
+    }
 }
 
 /// This determines the set of all possible constructors of a pattern matching
@@ -506,17 +658,163 @@ fn all_constructors(cx: &MatchCheckCtxt, left_ty: ty::t,
             _ => vec!(Single)
         },
 
-        ty::ty_enum(eid, _) =>
-            ty::enum_variants(cx.tcx, eid)
+        ty::ty_enum(eid, ref substs) =>
+            ty::substd_enum_variants(cx.tcx, eid, substs)
                 .iter()
+                .filter(|va| {
+                    !cx.skip_uninhabited_variants ||
+                        !va.args.iter().any(|&arg| type_is_uninhabited(cx.tcx, arg))
+                })
                 .map(|va| Variant(va.id))
                 .collect(),
 
+        ty::ty_int(_) | ty::ty_uint(_) | ty::ty_char => {
+            let domain = ScalarDomain::of(cx, left_ty).unwrap();
+            vec!(ConstantRange(domain.value(0), domain.value(domain.max)))
+        }
+
         _ =>
             vec!(Single)
     }
 }
 
+/// The first surrogate code point, which is not a valid `char`.
+const SURROGATE_START: u64 = 0xD800;
+/// The number of surrogate code points, `0xD800...0xDFFF`.
+const SURROGATE_COUNT: u64 = 0x800;
+
+enum ScalarKind {
+    /// A signed integer type, with its smallest value.
+    SignedScalar(i64),
+    UnsignedScalar,
+    /// `char`, whose values are the Unicode scalar values: the code points up
+    /// to `0x10FFFF` other than the surrogates.
+    CharScalar
+}
+
+/// The values of a fixed-size integral type or of `char`, numbered `0...max`
+/// in ascending order so that they can all be split into ranges alike.
+struct ScalarDomain {
+    kind: ScalarKind,
+    max: u64
+}
+
+impl ScalarDomain {
+    fn of(cx: &MatchCheckCtxt, ty: ty::t) -> Option<ScalarDomain> {
+        let (kind, bits) = match ty::get(ty).sty {
+            ty::ty_char => return Some(ScalarDomain {
+                kind: CharScalar,
+                max: 0x10FFFF - SURROGATE_COUNT
+            }),
+            ty::ty_int(t) => match if t == TyI { cx.tcx.sess.target.int_type } else { t } {
+                TyI8 => (SignedScalar(-1 << 7), 8u),
+                TyI16 => (SignedScalar(-1 << 15), 16),
+                TyI32 => (SignedScalar(-1 << 31), 32),
+                _ => (SignedScalar(-1 << 63), 64)
+            },
+            ty::ty_uint(t) => match if t == TyU { cx.tcx.sess.target.uint_type } else { t } {
+                TyU8 => (UnsignedScalar, 8u),
+                TyU16 => (UnsignedScalar, 16),
+                TyU32 => (UnsignedScalar, 32),
+                _ => (UnsignedScalar, 64)
+            },
+            _ => return None
+        };
+        Some(ScalarDomain {
+            kind: kind,
+            max: if bits == 64 { -1u64 } else { (1u64 << bits) - 1 }
+        })
+    }
+
+    /// The position of `value` in the domain, clamped to its bounds.
+    fn offset(&self, value: &const_val) -> Option<u64> {
+        match (&self.kind, value) {
+            (&SignedScalar(min), &const_int(v)) => {
+                let max = (min as u64 + self.max) as i64;
+                Some((if v < min { min } else if v > max { max } else { v }) as u64 - min as u64)
+            }
+            (&UnsignedScalar, &const_int(v)) if v < 0 => Some(0),
+            (&UnsignedScalar, &const_int(v)) =>
+                Some(if v as u64 > self.max { self.max } else { v as u64 }),
+            (&UnsignedScalar, &const_uint(v)) => Some(if v > self.max { self.max } else { v }),
+            (&CharScalar, &const_uint(v)) => Some(if v < SURROGATE_START {
+                v
+            } else if v < SURROGATE_START + SURROGATE_COUNT {
+                SURROGATE_START
+            } else if v - SURROGATE_COUNT > self.max {
+                self.max
+            } else {
+                v - SURROGATE_COUNT
+            }),
+            _ => None
+        }
+    }
+
+    /// The value at position `offset` in the domain.
+    fn value(&self, offset: u64) -> const_val {
+        match self.kind {
+            SignedScalar(min) => const_int((offset + min as u64) as i64),
+            UnsignedScalar => const_uint(offset),
+            CharScalar if offset < SURROGATE_START => const_uint(offset),
+            CharScalar => const_uint(offset + SURROGATE_COUNT)
+        }
+    }
+
+    /// The positions of the first and last values of a literal or range constructor.
+    fn constructor_range(&self, ctor: &Constructor) -> Option<(u64, u64)> {
+        match *ctor {
+            ConstantValue(ref value) => self.offset(value).map(|v| (v, v)),
+            ConstantRange(ref from, ref to) => match (self.offset(from), self.offset(to)) {
+                (Some(from), Some(to)) if from <= to => Some((from, to)),
+                _ => None
+            },
+            _ => None
+        }
+    }
+}
+
+/// Splits the literal and range constructors of an integral or `char` type into
+/// ranges whose borders are those of the literals and ranges in the first column
+/// of `rows`, so that each resulting range is either entirely covered by, or
+/// disjoint from, every pattern in that column. This is what lets a set of
+/// ranges such as `0...127` and `128...255` be found exhaustive for `u8`.
+fn split_constructors(cx: &MatchCheckCtxt, rows: &[Vec<&Pat>], ctors: Vec<Constructor>,
+                      left_ty: ty::t, max_slice_length: uint) -> Vec<Constructor> {
+    let domain = match ScalarDomain::of(cx, left_ty) {
+        Some(domain) => domain,
+        None => return ctors
+    };
+    let column_ranges: Vec<(u64, u64)> = rows.iter()
+        .flat_map(|row| pat_constructors(cx, row[0], left_ty, max_slice_length).into_iter())
+        .filter_map(|c| domain.constructor_range(&c))
+        .collect();
+
+    let mut result = vec![];
+    for ctor in ctors.into_iter() {
+        let (from, to) = match domain.constructor_range(&ctor) {
+            Some(range) => range,
+            None => {
+                result.push(ctor);
+                continue;
+            }
+        };
+        // Each border is the first value of a new range.
+        let mut borders: Vec<u64> = column_ranges.iter().flat_map(|&(lo, hi)| {
+            let after_hi = if hi == domain.max { None } else { Some(hi + 1) };
+            Some(lo).into_iter().chain(after_hi.into_iter())
+        }).filter(|&b| b > from && b <= to).collect();
+        borders.sort();
+        borders.dedup();
+        let mut start = from;
+        for &border in borders.iter() {
+            result.push(ConstantRange(domain.value(start), domain.value(border - 1)));
+            start = border;
+        }
+        result.push(ConstantRange(domain.value(start), domain.value(to)));
+    }
+    result
+}
+
 // Algorithm from http://moscova.inria.fr/~maranget/papers/warn/index.html
 //
 // Whether a vector `v` of patterns is 'useful' in relation to a set of such
@@ -539,7 +837,7 @@ fn is_useful(cx: &MatchCheckCtxt,
     debug!("{:}", matrix);
     if rows.len() == 0u {
         return match witness {
-            ConstructWitness => UsefulWithWitness(vec!()),
+            ConstructWitness => UsefulWithWitnesses(vec![vec![]]),
             LeaveOutWitness => Useful
         };
     }
@@ -557,56 +855,103 @@ fn is_useful(cx: &MatchCheckCtxt,
         ty::pat_ty(cx.tcx, &*real_pat)
     };
 
-    let max_slice_length = rows.iter().filter_map(|row| match row[0].node {
-        PatVec(ref before, _, ref after) => Some(before.len() + after.len()),
-        _ => None
-    }).max().map_or(0, |v| v + 1);
+    // `v` is taken into account too, so that a slice pattern in it always
+    // specializes to at least one length.
+    let max_slice_length = rows.iter().map(|row| row[0]).chain(Some(v[0]).into_iter())
+        .filter_map(|pat| match raw_pat(pat).node {
+            PatVec(ref before, _, ref after) => Some(before.len() + after.len()),
+            _ => None
+        }).max().map_or(0, |v| v + 1);
 
     let constructors = pat_constructors(cx, v[0], left_ty, max_slice_length);
     if constructors.is_empty() {
-        match missing_constructor(cx, matrix, left_ty, max_slice_length) {
-            None => {
-                all_constructors(cx, left_ty, max_slice_length).into_iter().map(|c| {
-                    match is_useful_specialized(cx, matrix, v, c.clone(), left_ty, witness) {
-                        UsefulWithWitness(pats) => UsefulWithWitness({
-                            let arity = constructor_arity(cx, &c, left_ty);
-                            let mut result = {
-                                let pat_slice = pats.as_slice();
-                                let subpats = Vec::from_fn(arity, |i| {
-                                    pat_slice.get(i).map_or(DUMMY_WILD_PAT, |p| &**p)
-                                });
-                                vec![construct_witness(cx, &c, subpats, left_ty)]
-                            };
-                            result.extend(pats.into_iter().skip(arity));
-                            result
-                        }),
-                        result => result
-                    }
-                }).find(|result| result != &NotUseful).unwrap_or(NotUseful)
-            },
+        let used_constructors: Vec<Constructor> = rows.iter()
+            .flat_map(|row| pat_constructors(cx, row[0], left_ty, max_slice_length).into_iter())
+            .collect();
+        let constructors: Vec<(Constructor, bool)> =
+            split_constructors(cx, rows.as_slice(),
+                               all_constructors(cx, left_ty, max_slice_length),
+                               left_ty, max_slice_length).into_iter().map(|c| {
+                let is_missing = !used_constructors.iter()
+                    .any(|used| constructor_covered_by(&c, used));
+                (c, is_missing)
+            }).collect();
+
+        // For the constructors that no row starts with, `v` is useful if its
+        // tail is useful in relation to the rows starting with a wildcard.
+        let default_usefulness = if constructors.iter().any(|&(_, is_missing)| is_missing) {
+            let matrix = rows.iter().filter_map(|r| {
+                if pat_is_binding_or_wild(&cx.tcx.def_map, raw_pat(r[0])) {
+                    Some(r.tail().to_vec())
+                } else {
+                    None
+                }
+            }).collect();
+            Some(is_useful(cx, &matrix, v.tail(), witness))
+        } else {
+            None
+        };
 
-            Some(constructor) => {
-                let matrix = rows.iter().filter_map(|r| {
-                    if pat_is_binding_or_wild(&cx.tcx.def_map, raw_pat(r[0])) {
-                        Some(r.tail().to_vec())
+        match (witness, default_usefulness) {
+            (LeaveOutWitness, Some(usefulness)) => usefulness,
+            (LeaveOutWitness, None) => {
+                constructors.into_iter().map(|(c, _)| {
+                    is_useful_specialized(cx, matrix, v, c, left_ty, witness)
+                }).find(|result| result != &NotUseful).unwrap_or(NotUseful)
+            }
+            (ConstructWitness, default_usefulness) => {
+                let default_witnesses = match default_usefulness {
+                    Some(UsefulWithWitnesses(witnesses)) => witnesses,
+                    _ => vec![]
+                };
+                let mut witnesses: Vec<Vec<P<Pat>>> = vec![];
+                for (c, is_missing) in constructors.into_iter() {
+                    if witnesses.len() > MAX_WITNESSES {
+                        break;
+                    }
+                    let arity = constructor_arity(cx, &c, left_ty);
+                    let new_witnesses = if is_missing {
+                        default_witnesses.iter().map(|pats| {
+                            let wild_pats = Vec::from_elem(arity, DUMMY_WILD_PAT);
+                            let mut result = vec![construct_witness(cx, &c, wild_pats, left_ty)];
+                            result.extend(pats.iter().map(|p| p.clone()));
+                            result
+                        }).collect()
                     } else {
-                        None
+                        match is_useful_specialized(cx, matrix, v, c.clone(), left_ty, witness) {
+                            UsefulWithWitnesses(witnesses) => {
+                                witnesses.into_iter().map(|pats| {
+                                    let mut result = {
+                                        let pat_slice = pats.as_slice();
+                                        let subpats = Vec::from_fn(arity, |i| {
+                                            pat_slice.get(i).map_or(DUMMY_WILD_PAT, |p| &**p)
+                                        });
+                                        vec![construct_witness(cx, &c, subpats, left_ty)]
+                                    };
+                                    result.extend(pats.into_iter().skip(arity));
+                                    result
+                                }).collect()
+                            }
+                            _ => vec![]
+                        }
+                    };
+                    // Witnesses of distinct integer ranges all read `_`.
+                    for pats in new_witnesses.into_iter() {
+                        if witnesses.len() <= MAX_WITNESSES && !witnesses.contains(&pats) {
+                            witnesses.push(pats);
+                        }
                     }
-                }).collect();
-                match is_useful(cx, &matrix, v.tail(), witness) {
-                    UsefulWithWitness(pats) => {
-                        let arity = constructor_arity(cx, &constructor, left_ty);
-                        let wild_pats = Vec::from_elem(arity, DUMMY_WILD_PAT);
-                        let enum_pat = construct_witness(cx, &constructor, wild_pats, left_ty);
-                        let mut new_pats = vec![enum_pat];
-                        new_pats.extend(pats.into_iter());
-                        UsefulWithWitness(new_pats)
-                    },
-                    result => result
+                }
+                if witnesses.is_empty() {
+                    NotUseful
+                } else {
+                    UsefulWithWitnesses(witnesses)
                 }
             }
         }
     } else {
+        let constructors = split_constructors(cx, rows.as_slice(), constructors,
+                                              left_ty, max_slice_length);
         constructors.into_iter().map(|c|
             is_useful_specialized(cx, matrix, v, c.clone(), left_ty, witness)
         ).find(|result| result != &NotUseful).unwrap_or(NotUseful)
@@ -666,8 +1011,10 @@ fn pat_constructors(cx: &MatchCheckCtxt, p: &Pat,
             },
         PatLit(ref expr) =>
             vec!(ConstantValue(eval_const_expr(cx.tcx, &**expr))),
-        PatRange(ref lo, ref hi) =>
-            vec!(ConstantRange(eval_const_expr(cx.tcx, &**lo), eval_const_expr(cx.tcx, &**hi))),
+        PatRange(ref lo, ref hi, end) => {
+            let (lo, hi) = eval_range_bounds(cx.tcx, &**lo, &**hi, end);
+            vec!(ConstantRange(lo, hi))
+        }
         PatVec(ref before, ref slice, ref after) =>
             match ty::get(left_ty).sty {
                 ty::ty_vec(_, Some(_)) => vec!(Single),
@@ -683,6 +1030,8 @@ fn pat_constructors(cx: &MatchCheckCtxt, p: &Pat,
             vec!(Single),
         PatWild(_) =>
             vec!(),
+        PatOr(_) =>
+            cx.tcx.sess.span_bug(pat.span, "or-pattern should have been expanded"),
         PatMac(_) =>
             cx.tcx.sess.bug("unexpanded macro")
     }
@@ -718,6 +1067,16 @@ pub fn constructor_arity(cx: &MatchCheckCtxt, ctor: &Constructor, ty: ty::t) ->
     }
 }
 
+/// Lines up the sub-patterns of a tuple or tuple-struct pattern with the `arity`
+/// elements they match, using wild patterns for the elements covered by `..`.
+fn tuple_subpats_with_wilds<'a>(subpats: &'a [P<Pat>], arity: uint) -> Vec<&'a Pat> {
+    let mut pats: Vec<&'a Pat> = Vec::from_elem(arity, DUMMY_WILD_PAT);
+    for &(i, subpat) in tuple_pat_elements(subpats, arity).iter() {
+        pats[i] = &**subpat;
+    }
+    pats
+}
+
 fn range_covered_by_constructor(ctor: &Constructor,
                                 from: &const_val, to: &const_val) -> Option<bool> {
     let (c_from, c_to) = match *ctor {
@@ -741,7 +1100,8 @@ fn range_covered_by_constructor(ctor: &Constructor,
 /// OTOH, slice patterns with a subslice pattern (..tail) can be expanded into multiple
 /// different patterns.
 /// Structure patterns with a partial wild pattern (Foo { a: 42, .. }) have their missing
-/// fields filled with wild patterns.
+/// fields filled with wild patterns, and so do tuple and tuple-struct patterns with a `..`
+/// (Foo(a, .., z)).
 pub fn specialize<'a>(cx: &MatchCheckCtxt, r: &[&'a Pat],
                       constructor: &Constructor, col: uint, arity: uint) -> Option<Vec<&'a Pat>> {
     let &Pat {
@@ -776,7 +1136,7 @@ pub fn specialize<'a>(cx: &MatchCheckCtxt, r: &[&'a Pat],
                 DefVariant(_, id, _) if *constructor != Variant(id) => None,
                 DefVariant(..) | DefStruct(..) => {
                     Some(match args {
-                        &Some(ref args) => args.iter().map(|p| &**p).collect(),
+                        &Some(ref args) => tuple_subpats_with_wilds(args.as_slice(), arity),
                         &None => Vec::from_elem(arity, DUMMY_WILD_PAT)
                     })
                 }
@@ -821,7 +1181,7 @@ pub fn specialize<'a>(cx: &MatchCheckCtxt, r: &[&'a Pat],
         }
 
         &PatTup(ref args) =>
-            Some(args.iter().map(|p| &**p).collect()),
+            Some(tuple_subpats_with_wilds(args.as_slice(), arity)),
 
         &PatBox(ref inner) | &PatRegion(ref inner) =>
             Some(vec![&**inner]),
@@ -838,9 +1198,8 @@ pub fn specialize<'a>(cx: &MatchCheckCtxt, r: &[&'a Pat],
             }
         }
 
-        &PatRange(ref from, ref to) => {
-            let from_value = eval_const_expr(cx.tcx, &**from);
-            let to_value = eval_const_expr(cx.tcx, &**to);
+        &PatRange(ref from, ref to, end) => {
+            let (from_value, to_value) = eval_range_bounds(cx.tcx, &**from, &**to, end);
             match range_covered_by_constructor(constructor, &from_value, &to_value) {
                 Some(true) => Some(vec![]),
                 Some(false) => None,
@@ -872,10 +1231,12 @@ pub fn specialize<'a>(cx: &MatchCheckCtxt, r: &[&'a Pat],
                     Some(pats)
                 },
                 SliceWithSubslice(prefix, suffix)
-                    if before.len() == prefix
-                        && after.len() == suffix
+                    if before.len() <= prefix
+                        && after.len() <= suffix
                         && slice.is_some() => {
                     let mut pats: Vec<&Pat> = before.iter().map(|p| &**p).collect();
+                    pats.grow_fn(prefix - before.len() + suffix - after.len(),
+                                 |_| DUMMY_WILD_PAT);
                     pats.extend(after.iter().map(|p| &**p));
                     Some(pats)
                 }
@@ -883,6 +1244,9 @@ pub fn specialize<'a>(cx: &MatchCheckCtxt, r: &[&'a Pat],
             }
         }
 
+        &PatOr(_) =>
+            cx.tcx.sess.span_bug(pat_span, "or-pattern should have been expanded"),
+
         &PatMac(_) => {
             cx.tcx.sess.span_err(pat_span, "unexpanded macro");
             None
@@ -903,6 +1267,9 @@ fn check_local(cx: &mut MatchCheckCtxt, loc: &Local) {
         LocalFor => "`for` loop"
     };
 
+    if !check_no_or_pats(cx, &*loc.pat) {
+        return;
+    }
     let mut static_inliner = StaticInliner::new(cx.tcx);
     is_refutable(cx, &*static_inliner.fold_pat(loc.pat.clone()), |pat| {
         span_err!(cx.tcx.sess, loc.pat.span, E0005,
@@ -911,9 +1278,8 @@ fn check_local(cx: &mut MatchCheckCtxt, loc: &Local) {
         );
     });
 
-    // Check legality of move bindings and `@` patterns.
-    check_legality_of_move_bindings(cx, false, slice::ref_slice(&loc.pat));
-    check_legality_of_bindings_in_at_patterns(cx, &*loc.pat);
+    // Check legality of move bindings.
+    check_legality_of_move_bindings(cx, slice::ref_slice(&loc.pat));
 }
 
 fn check_fn(cx: &mut MatchCheckCtxt,
@@ -923,21 +1289,42 @@ fn check_fn(cx: &mut MatchCheckCtxt,
             sp: Span) {
     visit::walk_fn(cx, kind, decl, body, sp);
     for input in decl.inputs.iter() {
+        if !check_no_or_pats(cx, &*input.pat) {
+            continue;
+        }
         is_refutable(cx, &*input.pat, |pat| {
             span_err!(cx.tcx.sess, input.pat.span, E0006,
                 "refutable pattern in function argument: `{}` not covered",
                 pat_to_string(pat)
             );
         });
-        check_legality_of_move_bindings(cx, false, slice::ref_slice(&input.pat));
-        check_legality_of_bindings_in_at_patterns(cx, &*input.pat);
+        check_legality_of_move_bindings(cx, slice::ref_slice(&input.pat));
     }
 }
 
+// Or-patterns are only supported in match arms, where each alternative
+// can be tested in turn.
+fn check_no_or_pats(cx: &MatchCheckCtxt, pat: &Pat) -> bool {
+    let mut ok = true;
+    walk_pat(pat, |p| {
+        match p.node {
+            PatOr(..) => {
+                span_err!(cx.tcx.sess, p.span, E0172,
+                    "or-patterns are only allowed in `match` arms");
+                ok = false;
+                false
+            }
+            _ => true
+        }
+    });
+    ok
+}
+
 fn is_refutable<A>(cx: &MatchCheckCtxt, pat: &Pat, refutable: |&Pat| -> A) -> Option<A> {
     let pats = Matrix(vec!(vec!(pat)));
     match is_useful(cx, &pats, [DUMMY_WILD_PAT], ConstructWitness) {
-        UsefulWithWitness(pats) => {
+        UsefulWithWitnesses(witnesses) => {
+            let pats = &witnesses[0];
             assert_eq!(pats.len(), 1);
             Some(refutable(&*pats[0]))
         },
@@ -948,7 +1335,6 @@ fn is_refutable<A>(cx: &MatchCheckCtxt, pat: &Pat, refutable: |&Pat| -> A) -> Op
 
 // Legality of move bindings checking
 fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
-                                   has_guard: bool,
                                    pats: &[P<Pat>]) {
     let tcx = cx.tcx;
     let def_map = &tcx.def_map;
@@ -965,15 +1351,13 @@ fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
         })
     }
 
-    let check_move: |&Pat, Option<&Pat>| = |p, sub| {
+    let check_move: |&Pat| = |p| {
         // check legality of moving out of the enum
 
-        // x @ Foo(..) is legal, but x @ Foo(y) isn't.
-        if sub.map_or(false, |p| pat_contains_bindings(def_map, &*p)) {
-            span_err!(cx.tcx.sess, p.span, E0007, "cannot bind by-move with sub-bindings");
-        } else if has_guard {
-            span_err!(cx.tcx.sess, p.span, E0008, "cannot bind by-move into a pattern guard");
-        } else if by_ref_span.is_some() {
+        // Moving into both `x` and a binding in `x @ Foo(y)` is caught
+        // by the borrow checker, and moving out of a binding within a
+        // pattern guard by `check_for_mutation_in_guard`.
+        if by_ref_span.is_some() {
             span_err!(cx.tcx.sess, p.span, E0009,
                 "cannot bind by-move and by-ref in the same pattern");
             span_note!(cx.tcx.sess, by_ref_span.unwrap(), "by-ref binding occurs here");
@@ -984,10 +1368,10 @@ fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
         walk_pat(&**pat, |p| {
             if pat_is_binding(def_map, &*p) {
                 match p.node {
-                    PatIdent(BindByValue(_), _, ref sub) => {
+                    PatIdent(BindByValue(_), _, _) => {
                         let pat_ty = ty::node_id_to_type(tcx, p.id);
                         if ty::type_moves_by_default(tcx, pat_ty) {
-                            check_move(p, sub.as_ref().map(|p| &**p));
+                            check_move(p);
                         }
                     }
                     PatIdent(BindByRef(_), _, _) => {
@@ -1007,11 +1391,23 @@ fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
     }
 }
 
-/// Ensures that a pattern guard doesn't borrow by mutable reference or
-/// assign.
-fn check_for_mutation_in_guard<'a, 'tcx>(cx: &'a MatchCheckCtxt<'a, 'tcx>, guard: &Expr) {
+/// Ensures that a pattern guard doesn't borrow by mutable reference,
+/// assign, or move out of the bindings of its arm.
+///
+/// The bindings of an arm only refer to the matched value while its guard
+/// runs; nothing is moved out of the value until the guard has succeeded,
+/// as the value must be left intact for the following arms if it fails.
+fn check_for_mutation_in_guard<'a, 'tcx>(cx: &'a MatchCheckCtxt<'a, 'tcx>,
+                                         pats: &[P<Pat>], guard: &Expr) {
+    let mut bindings = NodeMap::new();
+    for pat in pats.iter() {
+        pat_bindings(&cx.tcx.def_map, &**pat, |_, id, _, path| {
+            bindings.insert(id, path.node);
+        });
+    }
     let mut checker = MutationChecker {
         cx: cx,
+        bindings: bindings,
     };
     let mut visitor = ExprUseVisitor::new(&mut checker, checker.cx.tcx);
     visitor.walk_expr(guard);
@@ -1019,11 +1415,33 @@ fn check_for_mutation_in_guard<'a, 'tcx>(cx: &'a MatchCheckCtxt<'a, 'tcx>, guard
 
 struct MutationChecker<'a, 'tcx: 'a> {
     cx: &'a MatchCheckCtxt<'a, 'tcx>,
+    /// The bindings of the arm the guard belongs to.
+    bindings: NodeMap<Ident>,
 }
 
 impl<'a, 'tcx> Delegate for MutationChecker<'a, 'tcx> {
-    fn consume(&mut self, _: NodeId, _: Span, _: cmt, _: ConsumeMode) {}
-    fn consume_pat(&mut self, _: &Pat, _: cmt, _: ConsumeMode) {}
+    fn consume(&mut self, _: NodeId, span: Span, cmt: cmt, mode: ConsumeMode) {
+        match mode {
+            euv::Move(_) => match cmt.guarantor().cat {
+                cat_local(id) => match self.bindings.get(&id) {
+                    Some(ident) => {
+                        self.cx
+                            .tcx
+                            .sess
+                            .span_err(span,
+                                      format!("cannot move out of `{}` in a pattern guard",
+                                              token::get_ident(*ident)).as_slice())
+                    }
+                    None => {}
+                },
+                _ => {}
+            },
+            euv::Copy => {}
+        }
+    }
+    fn consume_pat(&mut self, pat: &Pat, cmt: cmt, mode: ConsumeMode) {
+        self.consume(pat.id, pat.span, cmt, mode)
+    }
     fn borrow(&mut self,
               _: NodeId,
               span: Span,
@@ -1056,35 +1474,3 @@ impl<'a, 'tcx> Delegate for MutationChecker<'a, 'tcx> {
     }
 }
 
-/// Forbids bindings in `@` patterns. This is necessary for memory safety,
-/// because of the way rvalues are handled in the borrow check. (See issue
-/// #14587.)
-fn check_legality_of_bindings_in_at_patterns(cx: &MatchCheckCtxt, pat: &Pat) {
-    AtBindingPatternVisitor { cx: cx, bindings_allowed: true }.visit_pat(pat);
-}
-
-struct AtBindingPatternVisitor<'a, 'b:'a, 'tcx:'b> {
-    cx: &'a MatchCheckCtxt<'b, 'tcx>,
-    bindings_allowed: bool
-}
-
-impl<'a, 'b, 'tcx, 'v> Visitor<'v> for AtBindingPatternVisitor<'a, 'b, 'tcx> {
-    fn visit_pat(&mut self, pat: &Pat) {
-        if !self.bindings_allowed && pat_is_binding(&self.cx.tcx.def_map, pat) {
-            self.cx.tcx.sess.span_err(pat.span,
-                                      "pattern bindings are not allowed \
-                                       after an `@`");
-        }
-
-        match pat.node {
-            PatIdent(_, _, Some(_)) => {
-                let bindings_were_allowed = self.bindings_allowed;
-                self.bindings_allowed = false;
-                visit::walk_pat(self, pat);
-                self.bindings_allowed = bindings_were_allowed;
-            }
-            _ => visit::walk_pat(self, pat),
-        }
-    }
-}
-
@@ -382,6 +382,14 @@ pub fn eval_const_expr(tcx: &ty::ctxt, e: &Expr) -> const_val {
 
 pub fn eval_const_expr_partial(tcx: &ty::ctxt, e: &Expr) -> Result<const_val, String> {
     fn fromb(b: bool) -> Result<const_val, String> { Ok(const_int(b as i64)) }
+    // Closures, or calls of closures written in place, as in `(|| 1)()`.
+    fn is_closure(e: &Expr) -> bool {
+        match e.node {
+            ExprParen(ref e) | ExprCall(ref e, _) => is_closure(&**e),
+            ExprFnBlock(..) | ExprProc(..) | ExprUnboxedFn(..) => true,
+            _ => false
+        }
+    }
     match e.node {
       ExprUnary(UnNeg, ref inner) => {
         match eval_const_expr_partial(tcx, &**inner) {
@@ -568,6 +576,10 @@ pub fn eval_const_expr_partial(tcx: &ty::ctxt, e: &Expr) -> Result<const_val, St
             None => Ok(const_int(0i64))
         }
       }
+      _ if is_closure(e) => {
+        Err("closures construct their environment at runtime and cannot be \
+             used in constant expressions".to_string())
+      }
       _ => Err("unsupported constant expr".to_string())
     }
 }
@@ -601,6 +613,14 @@ pub fn compare_const_vals(a: &const_val, b: &const_val) -> Option<int> {
     match (a, b) {
         (&const_int(a), &const_int(b)) => compare_vals(a, b),
         (&const_uint(a), &const_uint(b)) => compare_vals(a, b),
+        // Unsuffixed literals evaluate to `const_int` whatever their type,
+        // so they may be compared with unsigned values.
+        (&const_int(a), &const_uint(b)) => {
+            if a < 0 { Some(-1) } else { compare_vals(a as u64, b) }
+        }
+        (&const_uint(a), &const_int(b)) => {
+            if b < 0 { Some(1) } else { compare_vals(a, b as u64) }
+        }
         (&const_float(a), &const_float(b)) => compare_vals(a, b),
         (&const_str(ref a), &const_str(ref b)) => compare_vals(a, b),
         (&const_bool(a), &const_bool(b)) => compare_vals(a, b),
@@ -613,3 +633,18 @@ pub fn compare_const_vals(a: &const_val, b: &const_val) -> Option<int> {
 pub fn compare_lit_exprs(tcx: &ty::ctxt, a: &Expr, b: &Expr) -> Option<int> {
     compare_const_vals(&eval_const_expr(tcx, a), &eval_const_expr(tcx, b))
 }
+
+/// Evaluates the bounds of a range pattern. The upper bound returned is the
+/// last value matched by the range, whether or not the range is inclusive.
+pub fn eval_range_bounds(tcx: &ty::ctxt, from: &Expr, to: &Expr,
+                         end: RangeEnd) -> (const_val, const_val) {
+    let last = match (end, eval_const_expr(tcx, to)) {
+        (RangeIncluded, value) => value,
+        (RangeExcluded, const_int(n)) => const_int(n - 1),
+        (RangeExcluded, const_uint(n)) => const_uint(n - 1),
+        (RangeExcluded, _) => {
+            tcx.sess.span_bug(to.span, "exclusive range bound is not an integer")
+        }
+    };
+    (eval_const_expr(tcx, from), last)
+}
@@ -694,9 +694,11 @@ impl<'d,'t,'tcx,TYPER:mc::Typer<'tcx>> ExprUseVisitor<'d,'t,TYPER> {
             None => { }
             Some(adjustment) => {
                 match *adjustment {
-                    ty::AdjustAddEnv(..) => {
+                    ty::AdjustAddEnv(..) |
+                    ty::AdjustUnboxedClosureToFnPointer(..) => {
                         // Creating a closure consumes the input and stores it
-                        // into the resulting rvalue.
+                        // into the resulting rvalue. Likewise, reifying an
+                        // unboxed closure consumes the (empty) closure.
                         debug!("walk_adjustment(AutoAddEnv)");
                         let cmt_unadjusted =
                             return_if_err!(self.mc.cat_expr_unadjusted(expr));
@@ -881,11 +883,14 @@ impl<'d,'t,'tcx,TYPER:mc::Typer<'tcx>> ExprUseVisitor<'d,'t,TYPER> {
                 }
             } else {
                 match pat.node {
-                    ast::PatVec(_, Some(ref slice_pat), _) => {
+                    ast::PatVec(_, Some(ref slice_pat), _)
+                            if !pat_util::pat_is_by_ref_binding(def_map, &**slice_pat) => {
                         // The `slice_pat` here creates a slice into
                         // the original vector.  This is effectively a
                         // borrow of the elements of the vector being
-                        // matched.
+                        // matched.  (A `ref` binding of the subslice is
+                        // itself that borrow, and is reported as such
+                        // when the binding is visited.)
 
                         let (slice_cmt, slice_mutbl, slice_r) = {
                             match mc.cat_slice_pattern(cmt_pat, &**slice_pat) {
@@ -933,52 +938,49 @@ impl<'d,'t,'tcx,TYPER:mc::Typer<'tcx>> ExprUseVisitor<'d,'t,TYPER> {
 
         let tcx = self.typer.tcx();
         ty::with_freevars(tcx, closure_expr.id, |freevars| {
-            match self.tcx().capture_mode(closure_expr.id) {
-                ast::CaptureByRef => {
-                    self.walk_by_ref_captures(closure_expr, freevars);
-                }
-                ast::CaptureByValue => {
-                    self.walk_by_value_captures(closure_expr, freevars);
+            for freevar in freevars.iter() {
+                let upvar_id = ty::UpvarId { var_id: freevar.def.def_id().node,
+                                             closure_expr_id: closure_expr.id };
+                match self.typer.upvar_capture_mode(upvar_id) {
+                    ast::CaptureByRef => {
+                        self.walk_by_ref_capture(closure_expr, freevar, upvar_id);
+                    }
+                    ast::CaptureByValue => {
+                        self.walk_by_value_capture(closure_expr, freevar);
+                    }
                 }
             }
         });
     }
 
-    fn walk_by_ref_captures(&mut self,
-                            closure_expr: &ast::Expr,
-                            freevars: &[ty::Freevar]) {
-        for freevar in freevars.iter() {
-            let id_var = freevar.def.def_id().node;
-            let cmt_var = return_if_err!(self.cat_captured_var(closure_expr.id,
-                                                               closure_expr.span,
-                                                               freevar.def));
-
-            // Lookup the kind of borrow the callee requires, as
-            // inferred by regionbk
-            let upvar_id = ty::UpvarId { var_id: id_var,
-                                         closure_expr_id: closure_expr.id };
-            let upvar_borrow = self.tcx().upvar_borrow_map.borrow()
-                                   .get_copy(&upvar_id);
-
-            self.delegate.borrow(closure_expr.id,
-                                 closure_expr.span,
-                                 cmt_var,
-                                 upvar_borrow.region,
-                                 upvar_borrow.kind,
-                                 ClosureCapture(freevar.span));
-        }
+    fn walk_by_ref_capture(&mut self,
+                           closure_expr: &ast::Expr,
+                           freevar: &ty::Freevar,
+                           upvar_id: ty::UpvarId) {
+        let cmt_var = return_if_err!(self.cat_captured_var(closure_expr.id,
+                                                           closure_expr.span,
+                                                           freevar.def));
+
+        // Lookup the kind of borrow the callee requires, as
+        // inferred by regionbk
+        let upvar_borrow = self.typer.upvar_borrow(upvar_id);
+
+        self.delegate.borrow(closure_expr.id,
+                             closure_expr.span,
+                             cmt_var,
+                             upvar_borrow.region,
+                             upvar_borrow.kind,
+                             ClosureCapture(freevar.span));
     }
 
-    fn walk_by_value_captures(&mut self,
-                              closure_expr: &ast::Expr,
-                              freevars: &[ty::Freevar]) {
-        for freevar in freevars.iter() {
-            let cmt_var = return_if_err!(self.cat_captured_var(closure_expr.id,
-                                                               closure_expr.span,
-                                                               freevar.def));
-            let mode = copy_or_move(self.tcx(), cmt_var.ty, CaptureMove);
-            self.delegate.consume(closure_expr.id, freevar.span, cmt_var, mode);
-        }
+    fn walk_by_value_capture(&mut self,
+                             closure_expr: &ast::Expr,
+                             freevar: &ty::Freevar) {
+        let cmt_var = return_if_err!(self.cat_captured_var(closure_expr.id,
+                                                           closure_expr.span,
+                                                           freevar.def));
+        let mode = copy_or_move(self.tcx(), cmt_var.ty, CaptureMove);
+        self.delegate.consume(closure_expr.id, freevar.span, cmt_var, mode);
     }
 
     fn cat_captured_var(&mut self,
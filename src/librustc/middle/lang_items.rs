@@ -259,6 +259,9 @@ lets_do_this! {
     FnTraitLangItem,                 "fn",                      fn_trait;
     FnMutTraitLangItem,              "fn_mut",                  fn_mut_trait;
     FnOnceTraitLangItem,             "fn_once",                 fn_once_trait;
+    DivergingOutputLangItem,         "diverging_output",        diverging_output;
+
+    CloneTraitLangItem,              "clone",                   clone_trait;
 
     EqTraitLangItem,                 "eq",                      eq_trait;
     OrdTraitLangItem,                "ord",                     ord_trait;
@@ -114,7 +114,7 @@ use middle::pat_util;
 use middle::typeck;
 use middle::ty;
 use lint;
-use util::nodemap::NodeMap;
+use util::nodemap::{NodeMap, NodeSet};
 
 use std::fmt;
 use std::io;
@@ -625,7 +625,15 @@ impl<'a, 'tcx> Liveness<'a, 'tcx> {
                          f: |&mut Liveness<'a, 'tcx>, LiveNode, Variable, Span, NodeId|) {
         match pat {
             Some(pat) => {
-                self.pat_bindings(pat, f);
+                // the alternatives of a nested or-pattern share the ids of
+                // the bindings in the first one
+                let canonical: NodeSet = pat_util::pat_id_map(&self.ir.tcx.def_map, pat)
+                    .values().map(|&id| id).collect();
+                self.pat_bindings(pat, |this, ln, var, sp, id| {
+                    if canonical.contains(&id) {
+                        f(this, ln, var, sp, id);
+                    }
+                });
             }
             None => {}
         }
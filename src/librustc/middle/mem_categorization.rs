@@ -63,6 +63,7 @@
 #![allow(non_camel_case_types)]
 
 use middle::def;
+use middle::pat_util;
 use middle::ty;
 use middle::typeck;
 use util::nodemap::{DefIdMap, NodeMap};
@@ -76,6 +77,7 @@ use syntax::print::pprust;
 use syntax::parse::token;
 
 use std::cell::RefCell;
+use std::iter::AdditiveIterator;
 use std::rc::Rc;
 
 #[deriving(Clone, PartialEq, Show)]
@@ -116,6 +118,9 @@ pub enum PointerKind {
 pub enum InteriorKind {
     InteriorField(FieldName),
     InteriorElement(ElementKind),
+    // an element of a fixed-length vector at an offset known statically,
+    // as bound by a vector pattern
+    InteriorElementAt(uint),
 }
 
 #[deriving(Clone, PartialEq, Eq, Hash, Show)]
@@ -282,6 +287,7 @@ pub trait Typer<'tcx> {
     fn upvar_borrow(&self, upvar_id: ty::UpvarId) -> ty::UpvarBorrow;
     fn capture_mode(&self, closure_expr_id: ast::NodeId)
                     -> ast::CaptureClause;
+    fn upvar_capture_mode(&self, upvar_id: ty::UpvarId) -> ast::CaptureClause;
     fn unboxed_closures<'a>(&'a self)
                         -> &'a RefCell<DefIdMap<ty::UnboxedClosure>>;
 }
@@ -419,6 +425,15 @@ impl<'t,'tcx,TYPER:Typer<'tcx>> MemCategorizationContext<'t,TYPER> {
                         Ok(self.cat_rvalue_node(expr.id(), expr.span(), expr_ty))
                     }
 
+                    ty::AdjustUnboxedClosureToFnPointer(..) => {
+                        debug!("cat_expr(AdjustUnboxedClosureToFnPointer): {}",
+                               expr.repr(self.tcx()));
+                        // The closure is replaced by a pointer to its body.
+                        // Result is an rvalue.
+                        let expr_ty = if_ok!(self.expr_ty_adjusted(expr));
+                        Ok(self.cat_rvalue_node(expr.id(), expr.span(), expr_ty))
+                    }
+
                     ty::AdjustDerefRef(
                         ty::AutoDerefRef {
                             autoref: Some(_), ..}) => {
@@ -597,7 +612,10 @@ impl<'t,'tcx,TYPER:Typer<'tcx>> MemCategorizationContext<'t,TYPER> {
                   ty::ty_unboxed_closure(closure_id, _, _) => {
                       let unboxed_closures = self.typer.unboxed_closures().borrow();
                       let kind = (*unboxed_closures)[closure_id].kind;
-                      let mode = self.typer.capture_mode(fn_node_id);
+                      let mode = self.typer.upvar_capture_mode(ty::UpvarId {
+                          var_id: var_id,
+                          closure_expr_id: fn_node_id
+                      });
                       self.cat_upvar(id, span, var_id, fn_node_id, kind, mode, true)
                   }
                   _ => {
@@ -1171,7 +1189,7 @@ impl<'t,'tcx,TYPER:Typer<'tcx>> MemCategorizationContext<'t,TYPER> {
           }
           ast::PatEnum(_, Some(ref subpats)) => {
             match self.tcx().def_map.borrow().get(&pat.id) {
-                Some(&def::DefVariant(enum_did, _, _)) => {
+                Some(&def::DefVariant(enum_did, variant_did, _)) => {
                     // variant(x, y, z)
 
                     let downcast_cmt = {
@@ -1182,7 +1200,10 @@ impl<'t,'tcx,TYPER:Typer<'tcx>> MemCategorizationContext<'t,TYPER> {
                         }
                     };
 
-                    for (i, subpat) in subpats.iter().enumerate() {
+                    let arity = ty::enum_variant_with_id(self.tcx(), enum_did,
+                                                         variant_did).args.len();
+                    for &(i, subpat) in pat_util::tuple_pat_elements(subpats.as_slice(),
+                                                                    arity).iter() {
                         let subpat_ty = if_ok!(self.pat_ty(&**subpat)); // see (*2)
 
                         let subcmt =
@@ -1193,8 +1214,10 @@ impl<'t,'tcx,TYPER:Typer<'tcx>> MemCategorizationContext<'t,TYPER> {
                         if_ok!(self.cat_pattern(subcmt, &**subpat, |x,y,z| op(x,y,z)));
                     }
                 }
-                Some(&def::DefStruct(..)) => {
-                    for (i, subpat) in subpats.iter().enumerate() {
+                Some(&def::DefStruct(struct_did)) => {
+                    let arity = ty::lookup_struct_fields(self.tcx(), struct_did).len();
+                    for &(i, subpat) in pat_util::tuple_pat_elements(subpats.as_slice(),
+                                                                    arity).iter() {
                         let subpat_ty = if_ok!(self.pat_ty(&**subpat)); // see (*2)
                         let cmt_field =
                             self.cat_imm_interior(
@@ -1236,7 +1259,12 @@ impl<'t,'tcx,TYPER:Typer<'tcx>> MemCategorizationContext<'t,TYPER> {
 
           ast::PatTup(ref subpats) => {
             // (p1, ..., pN)
-            for (i, subpat) in subpats.iter().enumerate() {
+            let arity = match ty::get(if_ok!(self.pat_ty(pat))).sty {
+                ty::ty_tup(ref elems) => elems.len(),
+                _ => subpats.len()
+            };
+            for &(i, subpat) in pat_util::tuple_pat_elements(subpats.as_slice(),
+                                                            arity).iter() {
                 let subpat_ty = if_ok!(self.pat_ty(&**subpat)); // see (*2)
                 let subcmt =
                     self.cat_imm_interior(
@@ -1253,22 +1281,65 @@ impl<'t,'tcx,TYPER:Typer<'tcx>> MemCategorizationContext<'t,TYPER> {
           }
 
           ast::PatVec(ref before, ref slice, ref after) => {
-              let elt_cmt = self.cat_index(pat, self.deref_vec(pat, cmt));
+              let vec_cmt = self.deref_vec(pat, cmt.clone());
+              let elt_cmt = self.cat_index(pat, vec_cmt.clone());
+
+              // The elements of a fixed-length vector matched by the
+              // pattern sit at offsets known statically, so give each
+              // one its own path; this lets borrowck track moves out
+              // of them separately.
+              let fixed_len = match ty::get(vec_cmt.ty).sty {
+                  ty::ty_vec(_, Some(n)) => Some(n),
+                  _ => None
+              };
+              let elt_cmt_at = |offset: uint| -> cmt {
+                  match fixed_len {
+                      Some(_) => self.cat_imm_interior(pat, vec_cmt.clone(), elt_cmt.ty,
+                                                       InteriorElementAt(offset)),
+                      None => elt_cmt.clone()
+                  }
+              };
+
+              // A byte string literal may stand for several elements.
+              let mut offset = 0;
               for before_pat in before.iter() {
-                  if_ok!(self.cat_pattern(elt_cmt.clone(), &**before_pat,
+                  if_ok!(self.cat_pattern(elt_cmt_at(offset), &**before_pat,
                                           |x,y,z| op(x,y,z)));
+                  offset += pat_util::vec_pat_elem_width(&**before_pat, elt_cmt.ty);
               }
               for slice_pat in slice.iter() {
                   let slice_ty = if_ok!(self.pat_ty(&**slice_pat));
-                  let slice_cmt = self.cat_rvalue_node(pat.id(), pat.span(), slice_ty);
+                  let slice_cmt = match slice_pat.node {
+                      // A `ref` subslice binding borrows the elements of
+                      // the vector directly, not a temporary slice.
+                      ast::PatIdent(ast::BindByRef(_), _, None) => {
+                          let (cmt_slice, _, _) =
+                              if_ok!(self.cat_slice_pattern(cmt.clone(), &**slice_pat));
+                          cmt_slice
+                      }
+                      _ => self.cat_rvalue_node(pat.id(), pat.span(), slice_ty)
+                  };
                   if_ok!(self.cat_pattern(slice_cmt, &**slice_pat, |x,y,z| op(x,y,z)));
               }
+              let after_len = after.iter()
+                  .map(|after_pat| pat_util::vec_pat_elem_width(&**after_pat, elt_cmt.ty))
+                  .sum();
+              let mut offset = fixed_len.map_or(0, |n| n - after_len);
               for after_pat in after.iter() {
-                  if_ok!(self.cat_pattern(elt_cmt.clone(), &**after_pat, |x,y,z| op(x,y,z)));
+                  if_ok!(self.cat_pattern(elt_cmt_at(offset), &**after_pat,
+                                          |x,y,z| op(x,y,z)));
+                  offset += pat_util::vec_pat_elem_width(&**after_pat, elt_cmt.ty);
+              }
+          }
+
+          ast::PatOr(ref alternatives) => {
+              // each alternative matches the whole value
+              for alternative in alternatives.iter() {
+                  if_ok!(self.cat_pattern(cmt.clone(), &**alternative, |x,y,z| op(x,y,z)));
               }
           }
 
-          ast::PatLit(_) | ast::PatRange(_, _) => {
+          ast::PatLit(_) | ast::PatRange(..) => {
               /*always ok*/
           }
 
@@ -1336,7 +1407,8 @@ impl<'t,'tcx,TYPER:Typer<'tcx>> MemCategorizationContext<'t,TYPER> {
           cat_interior(_, InteriorField(PositionalField(_))) => {
               "anonymous field".to_string()
           }
-          cat_interior(_, InteriorElement(VecElement)) => {
+          cat_interior(_, InteriorElement(VecElement)) |
+          cat_interior(_, InteriorElementAt(_)) => {
               "vec content".to_string()
           }
           cat_interior(_, InteriorElement(OtherElement)) => {
@@ -1516,6 +1588,7 @@ impl Repr for InteriorKind {
             }
             InteriorField(PositionalField(i)) => format!("#{}", i),
             InteriorElement(_) => "[]".to_string(),
+            InteriorElementAt(i) => format!("[{}]", i),
         }
     }
 }
@@ -13,25 +13,30 @@ use middle::resolve;
 use middle::ty;
 use util::nodemap::FnvHashMap;
 
+use std::rc::Rc;
 use syntax::ast::*;
 use syntax::ast_util::{walk_pat};
-use syntax::codemap::{Span, DUMMY_SP};
+use syntax::codemap::{Span, Spanned, DUMMY_SP};
+use syntax::ptr::P;
 
 pub type PatIdMap = FnvHashMap<Ident, NodeId>;
 
 // This is used because same-named variables in alternative patterns need to
-// use the NodeId of their namesake in the first pattern.
+// use the NodeId of their namesake in the first pattern. Bindings are visited
+// in order, so for the alternatives of a nested or-pattern the first one wins.
 pub fn pat_id_map(dm: &resolve::DefMap, pat: &Pat) -> PatIdMap {
     let mut map = FnvHashMap::new();
     pat_bindings(dm, pat, |_bm, p_id, _s, path1| {
-        map.insert(path1.node, p_id);
+        if !map.contains_key(&path1.node) {
+            map.insert(path1.node, p_id);
+        }
     });
     map
 }
 
 pub fn pat_is_refutable(dm: &resolve::DefMap, pat: &Pat) -> bool {
     match pat.node {
-        PatLit(_) | PatRange(_, _) => true,
+        PatLit(_) | PatRange(..) => true,
         PatEnum(_, _) | PatIdent(_, _, None) | PatStruct(..) => {
             match dm.borrow().get(&pat.id) {
                 Some(&DefVariant(..)) => true,
@@ -77,6 +82,15 @@ pub fn pat_is_binding(dm: &resolve::DefMap, pat: &Pat) -> bool {
     }
 }
 
+/// Checks whether the pattern is a `ref` or `ref mut` binding with no
+/// sub-pattern.
+pub fn pat_is_by_ref_binding(dm: &resolve::DefMap, pat: &Pat) -> bool {
+    match pat.node {
+        PatIdent(BindByRef(_), _, None) => pat_is_binding(dm, pat),
+        _ => false
+    }
+}
+
 pub fn pat_is_binding_or_wild(dm: &resolve::DefMap, pat: &Pat) -> bool {
     match pat.node {
         PatIdent(..) => pat_is_binding(dm, pat),
@@ -85,6 +99,63 @@ pub fn pat_is_binding_or_wild(dm: &resolve::DefMap, pat: &Pat) -> bool {
     }
 }
 
+/// Returns the position of the `..` among the sub-patterns of a tuple or
+/// tuple-struct pattern like `(a, .., z)`, if there is one.
+pub fn tuple_pat_dotdot_pos(subpats: &[P<Pat>]) -> Option<uint> {
+    subpats.iter().position(|p| {
+        match p.node {
+            PatWild(PatWildMulti) => true,
+            _ => false
+        }
+    })
+}
+
+/// Pairs each sub-pattern of a tuple or tuple-struct pattern with the
+/// index of the element it matches, given the number of elements. The
+/// `..`, if any, is left out: it stands for the elements that are not
+/// matched by any other sub-pattern.
+pub fn tuple_pat_elements<'a>(subpats: &'a [P<Pat>], arity: uint) -> Vec<(uint, &'a P<Pat>)> {
+    let (dotdot_pos, elided) = match tuple_pat_dotdot_pos(subpats) {
+        Some(pos) if arity + 1 >= subpats.len() => (pos, arity + 1 - subpats.len()),
+        Some(pos) => (pos, 0),
+        None => (subpats.len(), 0)
+    };
+    subpats.iter().enumerate().filter_map(|(i, subpat)| {
+        if i < dotdot_pos {
+            Some((i, subpat))
+        } else if i == dotdot_pos {
+            None
+        } else {
+            Some((i - 1 + elided, subpat))
+        }
+    }).collect()
+}
+
+/// Returns the bytes of `pat` if it is a byte string literal. As an element
+/// of a vector pattern over `u8`s, such a literal stands for one literal
+/// pattern per byte, as in `[b"GET ", rest..]`.
+pub fn byte_str_pat_bytes(pat: &Pat) -> Option<Rc<Vec<u8>>> {
+    match pat.node {
+        PatLit(ref expr) => match expr.node {
+            ExprLit(ref lit) => match lit.node {
+                LitBinary(ref bytes) => Some(bytes.clone()),
+                _ => None
+            },
+            _ => None
+        },
+        _ => None
+    }
+}
+
+/// Returns the number of elements matched by `pat` as an element of a
+/// vector pattern whose elements are of type `elem_ty`.
+pub fn vec_pat_elem_width(pat: &Pat, elem_ty: ty::t) -> uint {
+    match (byte_str_pat_bytes(pat), &ty::get(elem_ty).sty) {
+        (Some(bytes), &ty::ty_uint(TyU8)) => bytes.len(),
+        _ => 1
+    }
+}
+
 /// Call `it` on every "binding" in a pattern, e.g., on `a` in
 /// `match foo() { Some(a) => (), None => () }`
 pub fn pat_bindings(dm: &resolve::DefMap,
@@ -116,6 +187,111 @@ pub fn pat_contains_bindings(dm: &resolve::DefMap, pat: &Pat) -> bool {
     contains_bindings
 }
 
+/// Checks if the pattern contains an or-pattern, e.g. `Some(1 | 2)`.
+pub fn pat_contains_or(pat: &Pat) -> bool {
+    let mut contains_or = false;
+    walk_pat(pat, |p| {
+        match p.node {
+            PatOr(..) => {
+                contains_or = true;
+                false
+            }
+            _ => true
+        }
+    });
+    contains_or
+}
+
+/// Expands the or-patterns nested in a pattern, returning one pattern per
+/// combination of alternatives, e.g. `(1 | 2, Some(x) | None)` gives
+/// `(1, Some(x))`, `(1, None)`, `(2, Some(x))` and `(2, None)`. The
+/// resulting patterns keep the node ids and spans of the original ones.
+pub fn expand_or_pats(pat: &P<Pat>) -> Vec<P<Pat>> {
+    fn rebuild(pat: &P<Pat>, node: Pat_) -> P<Pat> {
+        P(Pat {
+            id: pat.id,
+            node: node,
+            span: pat.span,
+        })
+    }
+
+    fn expand_seq(pats: &[P<Pat>]) -> Vec<Vec<P<Pat>>> {
+        let mut result = vec!(Vec::new());
+        for pat in pats.iter() {
+            let expansions = expand_or_pats(pat);
+            let mut next = Vec::with_capacity(result.len() * expansions.len());
+            for prefix in result.iter() {
+                for expansion in expansions.iter() {
+                    let mut seq = prefix.clone();
+                    seq.push(expansion.clone());
+                    next.push(seq);
+                }
+            }
+            result = next;
+        }
+        result
+    }
+
+    if !pat_contains_or(&**pat) {
+        return vec!(pat.clone());
+    }
+
+    match pat.node {
+        PatOr(ref alternatives) => {
+            alternatives.iter().flat_map(|p| expand_or_pats(p).into_iter()).collect()
+        }
+        PatIdent(binding_mode, ref path1, Some(ref sub)) => {
+            expand_or_pats(sub).into_iter().map(|sub| {
+                rebuild(pat, PatIdent(binding_mode, path1.clone(), Some(sub)))
+            }).collect()
+        }
+        PatEnum(ref path, Some(ref subpats)) => {
+            expand_seq(subpats.as_slice()).into_iter().map(|subpats| {
+                rebuild(pat, PatEnum(path.clone(), Some(subpats)))
+            }).collect()
+        }
+        PatStruct(ref path, ref fields, etc) => {
+            let subpats: Vec<P<Pat>> = fields.iter().map(|f| f.node.pat.clone()).collect();
+            expand_seq(subpats.as_slice()).into_iter().map(|subpats| {
+                let fields = fields.iter().zip(subpats.into_iter()).map(|(f, subpat)| {
+                    Spanned {
+                        span: f.span,
+                        node: FieldPat {
+                            ident: f.node.ident,
+                            pat: subpat,
+                            is_shorthand: f.node.is_shorthand,
+                        }
+                    }
+                }).collect();
+                rebuild(pat, PatStruct(path.clone(), fields, etc))
+            }).collect()
+        }
+        PatTup(ref subpats) => {
+            expand_seq(subpats.as_slice()).into_iter().map(|subpats| {
+                rebuild(pat, PatTup(subpats))
+            }).collect()
+        }
+        PatBox(ref inner) => {
+            expand_or_pats(inner).into_iter().map(|inner| rebuild(pat, PatBox(inner))).collect()
+        }
+        PatRegion(ref inner) => {
+            expand_or_pats(inner).into_iter().map(|inner| rebuild(pat, PatRegion(inner))).collect()
+        }
+        PatVec(ref before, ref slice, ref after) => {
+            let mut subpats = before.clone();
+            subpats.extend(slice.iter().map(|p| p.clone()));
+            subpats.extend(after.iter().map(|p| p.clone()));
+            expand_seq(subpats.as_slice()).into_iter().map(|subpats| {
+                let mut subpats = subpats.into_iter();
+                let new_before = subpats.by_ref().take(before.len()).collect();
+                let new_slice = if slice.is_some() { subpats.next() } else { None };
+                rebuild(pat, PatVec(new_before, new_slice, subpats.collect()))
+            }).collect()
+        }
+        _ => vec!(pat.clone())
+    }
+}
+
 pub fn simple_identifier<'a>(pat: &'a Pat) -> Option<&'a Ident> {
     match pat.node {
         PatIdent(BindByValue(_), ref path1, None) => {
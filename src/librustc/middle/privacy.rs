@@ -16,6 +16,7 @@ use std::mem::replace;
 
 use metadata::csearch;
 use middle::def;
+use middle::pat_util;
 use middle::resolve;
 use middle::ty;
 use middle::typeck::{MethodCall, MethodMap, MethodOrigin, MethodParam, MethodTypeParam};
@@ -1007,7 +1008,9 @@ impl<'a, 'tcx, 'v> Visitor<'v> for PrivacyVisitor<'a, 'tcx> {
             ast::PatEnum(_, Some(ref fields)) => {
                 match ty::get(ty::pat_ty(self.tcx, pattern)).sty {
                     ty::ty_struct(id, _) => {
-                        for (i, field) in fields.iter().enumerate() {
+                        let arity = ty::lookup_struct_fields(self.tcx, id).len();
+                        for &(i, field) in pat_util::tuple_pat_elements(fields.as_slice(),
+                                                                       arity).iter() {
                             match field.node {
                                 ast::PatWild(..) => continue,
                                 _ => {}
@@ -31,7 +31,7 @@ use syntax::ast::{Ident, ImplItem, Item, ItemEnum, ItemFn, ItemForeignMod};
 use syntax::ast::{ItemImpl, ItemMac, ItemMod, ItemStatic, ItemStruct};
 use syntax::ast::{ItemTrait, ItemTy, LOCAL_CRATE, Local, ItemConst};
 use syntax::ast::{MethodImplItem, Mod, Name, NamedField, NodeId};
-use syntax::ast::{Pat, PatEnum, PatIdent, PatLit};
+use syntax::ast::{Pat, PatEnum, PatIdent, PatLit, PatOr};
 use syntax::ast::{PatRange, PatStruct, Path, PathListIdent, PathListMod};
 use syntax::ast::{PolyTraitRef, PrimTy, Public, SelfExplicit, SelfStatic};
 use syntax::ast::{RegionTyParamBound, StmtDecl, StructField};
@@ -54,6 +54,7 @@ use syntax::parse::token::special_idents;
 use syntax::parse::token;
 use syntax::codemap::{Span, DUMMY_SP, Pos};
 use syntax::owned_slice::OwnedSlice;
+use syntax::ptr::P;
 use syntax::visit;
 use syntax::visit::Visitor;
 
@@ -4559,10 +4560,23 @@ impl<'a> Resolver<'a> {
                         self.record_def(trait_reference.ref_id, def);
                     }
                     (def, _) => {
-                        self.resolve_error(trait_reference.path.span,
-                                           format!("`{}` is not a trait",
-                                                   self.path_names_to_string(
-                                                       &trait_reference.path)));
+                        let path_str = self.path_names_to_string(&trait_reference.path);
+
+                        // The parenthesized sugar `Foo(A) -> B` only makes
+                        // sense for traits, so say so when it was used.
+                        match trait_reference.path.segments.last().unwrap().parameters {
+                            ast::ParenthesizedParameters(..) => {
+                                self.resolve_error(trait_reference.path.span,
+                                                   format!("`{}` is not a trait; parenthesized \
+                                                            parameters may only be used with \
+                                                            a trait",
+                                                           path_str));
+                            }
+                            ast::AngleBracketedParameters(..) => {
+                                self.resolve_error(trait_reference.path.span,
+                                                   format!("`{}` is not a trait", path_str));
+                            }
+                        }
 
                         // If it's a typedef, give a note
                         match def {
@@ -5074,8 +5088,27 @@ impl<'a> Resolver<'a> {
                        // pattern that binds them
                        bindings_list: &mut HashMap<Name, NodeId>) {
         let pat_id = pattern.id;
+        // The sub-patterns of or-patterns are resolved by
+        // `resolve_or_pattern`, so the walk has to skip them.
+        let mut or_subpats = NodeSet::new();
         walk_pat(pattern, |pattern| {
+            if or_subpats.contains(&pattern.id) {
+                return true;
+            }
             match pattern.node {
+                PatOr(ref alternatives) => {
+                    for alternative in alternatives.iter() {
+                        walk_pat(&**alternative, |p| {
+                            or_subpats.insert(p.id);
+                            true
+                        });
+                    }
+                    self.resolve_or_pattern(alternatives.as_slice(),
+                                            pat_id,
+                                            mode,
+                                            bindings_list);
+                }
+
                 PatIdent(binding_mode, ref path1, _) => {
 
                     // The meaning of pat_ident with no type parameters
@@ -5224,7 +5257,7 @@ impl<'a> Resolver<'a> {
                     self.resolve_expr(&**expr);
                 }
 
-                PatRange(ref first_expr, ref last_expr) => {
+                PatRange(ref first_expr, ref last_expr, _) => {
                     self.resolve_expr(&**first_expr);
                     self.resolve_expr(&**last_expr);
                 }
@@ -5252,6 +5285,98 @@ impl<'a> Resolver<'a> {
         });
     }
 
+    /// Resolves the alternatives of an or-pattern nested in the pattern
+    /// `pat_id`. Each alternative may rebind the names bound by the others,
+    /// but not the names bound elsewhere in the enclosing pattern, and all
+    /// of them must bind the same names in the same way.
+    fn resolve_or_pattern(&mut self,
+                          alternatives: &[P<Pat>],
+                          pat_id: NodeId,
+                          mode: PatternBindingMode,
+                          bindings_list: &mut HashMap<Name, NodeId>) {
+        let first_id = alternatives[0].id;
+        let mut new_bindings = Vec::new();
+        for (i, alternative) in alternatives.iter().enumerate() {
+            // Names already bound by the enclosing pattern (or, for
+            // arguments, by the whole parameter list) are duplicates when
+            // bound again by this alternative.
+            let mut alt_bindings_list = HashMap::new();
+            for (&name, &id) in bindings_list.iter() {
+                let id = if id == pat_id || mode == ArgumentIrrefutableMode {
+                    alternative.id
+                } else {
+                    id
+                };
+                alt_bindings_list.insert(name, id);
+            }
+            // The names bound by the first alternative are already in the
+            // ribs, the other alternatives only rebind them.
+            for &name in new_bindings.iter() {
+                alt_bindings_list.insert(name, first_id);
+            }
+
+            let alt_mode = match mode {
+                ArgumentIrrefutableMode => LocalIrrefutableMode,
+                mode => mode
+            };
+            self.resolve_pattern(&**alternative, alt_mode, &mut alt_bindings_list);
+
+            if i == 0 {
+                for (&name, &id) in alt_bindings_list.iter() {
+                    if id == first_id && !bindings_list.contains_key(&name) {
+                        new_bindings.push(name);
+                    }
+                }
+            }
+        }
+        for &name in new_bindings.iter() {
+            bindings_list.insert(name, pat_id);
+        }
+
+        self.check_consistent_or_pattern(alternatives);
+    }
+
+    // check that all of the alternatives of a nested or-pattern bind
+    // exactly the same set of names, with the same binding modes.
+    fn check_consistent_or_pattern(&mut self, alternatives: &[P<Pat>]) {
+        let map_0 = self.binding_mode_map(&*alternatives[0]);
+        for p in alternatives.iter().skip(1) {
+            let map_i = self.binding_mode_map(&**p);
+
+            for (&key, &binding_0) in map_0.iter() {
+                match map_i.get(&key) {
+                    None => {
+                        self.resolve_error(
+                            p.span,
+                            format!("variable `{}` is not bound in all \
+                                     alternatives of this or-pattern",
+                                    token::get_name(key)).as_slice());
+                    }
+                    Some(binding_i) => {
+                        if binding_0.binding_mode != binding_i.binding_mode {
+                            self.resolve_error(
+                                binding_i.span,
+                                format!("variable `{}` is bound with different \
+                                         modes in the alternatives of this \
+                                         or-pattern",
+                                        token::get_name(key)).as_slice());
+                        }
+                    }
+                }
+            }
+
+            for (&key, &binding) in map_i.iter() {
+                if !map_0.contains_key(&key) {
+                    self.resolve_error(
+                        binding.span,
+                        format!("variable `{}` is not bound in all \
+                                 alternatives of this or-pattern",
+                                token::get_name(key)).as_slice());
+                }
+            }
+        }
+    }
+
     fn resolve_bare_identifier_pattern(&mut self, name: Name, span: Span)
                                        -> BareIdentifierPatternResolution {
         let module = self.current_module.clone();
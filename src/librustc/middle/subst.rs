@@ -12,12 +12,13 @@
 
 use middle::ty;
 use middle::ty_fold;
-use middle::ty_fold::{TypeFoldable, TypeFolder};
-use util::ppaux::Repr;
+use middle::ty_fold::{CountingFolder, FallibleTypeFolder, TypeFoldable};
+use util::ppaux::{Repr, UserString};
 
 use std::fmt;
 use std::slice::Items;
 use std::vec::Vec;
+use syntax::ast;
 use syntax::codemap::{Span, DUMMY_SP};
 
 ///////////////////////////////////////////////////////////////////////////
@@ -98,6 +99,18 @@ impl Substs {
         regions_is_noop && self.types.is_empty()
     }
 
+    /// True if there is nothing to substitute: no types in any space, and
+    /// regions that are erased or empty. Unlike `is_noop`, erased regions
+    /// count as empty. Folding such substs always gives them back.
+    pub fn is_empty(&self) -> bool {
+        let regions_is_empty = match self.regions {
+            ErasedRegions => true,
+            NonerasedRegions(ref regions) => regions.as_slice().is_empty(),
+        };
+
+        regions_is_empty && self.types.as_slice().is_empty()
+    }
+
     pub fn self_ty(&self) -> Option<ty::t> {
         self.types.get_self().map(|&t| t)
     }
@@ -109,9 +122,11 @@ impl Substs {
         s
     }
 
-    pub fn erase_regions(self) -> Substs {
-        let Substs { types, regions: _ } = self;
-        Substs { types: types, regions: ErasedRegions }
+    /// The same substs with `ErasedRegions` in place of the region
+    /// values. Unlike `ty_fold::erase_regions`, this leaves the regions
+    /// within the types alone.
+    pub fn erase_regions(&self) -> Substs {
+        Substs { types: self.types.clone(), regions: ErasedRegions }
     }
 
     pub fn regions<'a>(&'a self) -> &'a VecPerParamSpace<ty::Region> {
@@ -140,6 +155,46 @@ impl Substs {
         }
     }
 
+    /// The type given for the type parameter `def`. Panics, naming the
+    /// parameter, if there is none.
+    pub fn type_for_def(&self, def: &ty::TypeParameterDef) -> ty::t {
+        match self.opt_type_for_def(def) {
+            Some(t) => t,
+            None => {
+                panic!("no type for type parameter `{}` (index {} in {}): \
+                        the substs have {} {} types",
+                       def.name.as_str(), def.index, def.space,
+                       self.types.len(def.space), def.space)
+            }
+        }
+    }
+
+    pub fn opt_type_for_def(&self, def: &ty::TypeParameterDef) -> Option<ty::t> {
+        self.types.opt_get(def.space, def.index).map(|&t| t)
+    }
+
+    /// The region given for the region parameter `def`, or `'static` if
+    /// the regions are erased. Panics, naming the parameter, if there is
+    /// none.
+    pub fn region_for_def(&self, def: &ty::RegionParameterDef) -> ty::Region {
+        match self.opt_region_for_def(def) {
+            Some(r) => r,
+            None => {
+                panic!("no region for region parameter `{}` (index {} in {}): \
+                        the substs have {} {} regions",
+                       def.name.as_str(), def.index, def.space,
+                       self.regions().len(def.space), def.space)
+            }
+        }
+    }
+
+    pub fn opt_region_for_def(&self, def: &ty::RegionParameterDef) -> Option<ty::Region> {
+        match self.regions {
+            ErasedRegions => Some(ty::ReStatic),
+            NonerasedRegions(ref regions) => regions.opt_get(def.space, def.index).map(|&r| r),
+        }
+    }
+
     pub fn with_method(self,
                        m_types: Vec<ty::t>,
                        m_regions: Vec<ty::Region>)
@@ -341,6 +396,33 @@ impl<T> VecPerParamSpace<T> {
         }
     }
 
+    /// Removes the elements of `space` for which `pred` returns false,
+    /// keeping the others in order. The other spaces are untouched.
+    pub fn retain(&mut self, space: ParamSpace, pred: |&T| -> bool) {
+        let (start, limit) = self.limits(space);
+        let mut removed = 0;
+        let mut i = start;
+        while i < limit - removed {
+            if pred(&self.content[i]) {
+                i += 1;
+            } else {
+                self.content.remove(i);
+                removed += 1;
+            }
+        }
+
+        match space {
+            TypeSpace => {
+                self.type_limit -= removed;
+                self.self_limit -= removed;
+                self.assoc_limit -= removed;
+            }
+            SelfSpace => { self.self_limit -= removed; self.assoc_limit -= removed; }
+            AssocSpace => { self.assoc_limit -= removed; }
+            FnSpace => {}
+        }
+    }
+
     pub fn replace(&mut self, space: ParamSpace, elems: Vec<T>) {
         // FIXME (#15435): slow; O(n^2); could enhance vec to make it O(n).
         self.truncate(space, 0);
@@ -385,14 +467,28 @@ impl<T> VecPerParamSpace<T> {
         &self.get_slice(space)[index]
     }
 
+    pub fn get_mut<'a>(&'a mut self, space: ParamSpace, index: uint) -> &'a mut T {
+        &mut self.get_mut_slice(space)[index]
+    }
+
     pub fn iter<'a>(&'a self) -> Items<'a,T> {
         self.content.iter()
     }
 
+    /// Iterates over the elements along with the space each is in and
+    /// its index within that space.
+    pub fn iter_enumerated<'a>(&'a self) -> EnumeratedItems<'a,T> {
+        EnumeratedItems::new(self)
+    }
+
     pub fn as_slice(&self) -> &[T] {
         self.content.as_slice()
     }
 
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.content.as_mut_slice()
+    }
+
     pub fn all_vecs(&self, pred: |&[T]| -> bool) -> bool {
         let spaces = [TypeSpace, SelfSpace, FnSpace];
         spaces.iter().all(|&space| { pred(self.get_slice(space)) })
@@ -418,6 +514,25 @@ impl<T> VecPerParamSpace<T> {
                                        self.assoc_limit)
     }
 
+    pub fn map_enumerated<U>(&self, pred: |ParamSpace, uint, &T| -> U) -> VecPerParamSpace<U> {
+        let result = self.iter_enumerated().map(|(space, index, t)| pred(space, index, t))
+                                           .collect();
+        VecPerParamSpace::new_internal(result,
+                                       self.type_limit,
+                                       self.self_limit,
+                                       self.assoc_limit)
+    }
+
+    /// Gives `content` the same division into spaces as `self`: it must
+    /// have as many elements as `self` has.
+    pub fn with_content<U>(&self, content: Vec<U>) -> VecPerParamSpace<U> {
+        assert_eq!(content.len(), self.content.len());
+        VecPerParamSpace::new_internal(content,
+                                       self.type_limit,
+                                       self.self_limit,
+                                       self.assoc_limit)
+    }
+
     pub fn map_move<U>(self, pred: |T| -> U) -> VecPerParamSpace<U> {
         let SeparateVecsPerParamSpace {
             types: t,
@@ -454,12 +569,56 @@ impl<T> VecPerParamSpace<T> {
     }
 }
 
+pub struct EnumeratedItems<'a,T:'a> {
+    vec: &'a VecPerParamSpace<T>,
+    space_index: uint,
+    elem_index: uint
+}
+
+impl<'a,T> EnumeratedItems<'a,T> {
+    fn new(v: &'a VecPerParamSpace<T>) -> EnumeratedItems<'a,T> {
+        let mut result = EnumeratedItems { vec: v, space_index: 0, elem_index: 0 };
+        result.adjust_space();
+        result
+    }
+
+    // Skips over any spaces that have no elements left.
+    fn adjust_space(&mut self) {
+        let spaces = ParamSpace::all();
+        while self.space_index < spaces.len() &&
+              self.elem_index >= self.vec.len(spaces[self.space_index]) {
+            self.space_index += 1;
+            self.elem_index = 0;
+        }
+    }
+}
+
+impl<'a,T> Iterator<(ParamSpace, uint, &'a T)> for EnumeratedItems<'a,T> {
+    fn next(&mut self) -> Option<(ParamSpace, uint, &'a T)> {
+        let spaces = ParamSpace::all();
+        if self.space_index < spaces.len() {
+            let space = spaces[self.space_index];
+            let index = self.elem_index;
+            let item = self.vec.get(space, index);
+
+            self.elem_index += 1;
+            self.adjust_space();
+
+            Some((space, index, item))
+        } else {
+            None
+        }
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////
 // Public trait `Subst`
 //
 // Just call `foo.subst(tcx, substs)` to perform a substitution across
 // `foo`. Or use `foo.subst_spanned(tcx, substs, Some(span))` when
-// there is more information available (for better errors).
+// there is more information available (for better errors). Use
+// `foo.try_subst(tcx, substs)` when `substs` may be missing some of
+// the parameters that `foo` mentions.
 
 pub trait Subst {
     fn subst(&self, tcx: &ty::ctxt, substs: &Substs) -> Self {
@@ -470,6 +629,16 @@ pub trait Subst {
                      substs: &Substs,
                      span: Option<Span>)
                      -> Self;
+
+    /// As `subst_spanned`, but names `item`, the item that `substs` were
+    /// built for, if one of its parameters turns out to be missing.
+    fn subst_for_item(&self, tcx: &ty::ctxt,
+                      substs: &Substs,
+                      item: ast::DefId,
+                      span: Option<Span>)
+                      -> Self;
+
+    fn try_subst(&self, tcx: &ty::ctxt, substs: &Substs) -> Result<Self, SubstError>;
 }
 
 impl<T:TypeFoldable> Subst for T {
@@ -479,12 +648,94 @@ impl<T:TypeFoldable> Subst for T {
                      span: Option<Span>)
                      -> T
     {
-        let mut folder = SubstFolder { tcx: tcx,
-                                       substs: substs,
-                                       span: span,
-                                       root_ty: None,
-                                       ty_stack_depth: 0 };
-        (*self).fold_with(&mut folder)
+        subst_or_bug(tcx, self, substs, None, span)
+    }
+
+    fn subst_for_item(&self,
+                      tcx: &ty::ctxt,
+                      substs: &Substs,
+                      item: ast::DefId,
+                      span: Option<Span>)
+                      -> T
+    {
+        subst_or_bug(tcx, self, substs, Some(item), span)
+    }
+
+    fn try_subst(&self, tcx: &ty::ctxt, substs: &Substs) -> Result<T, SubstError> {
+        SubstFolder::new(tcx, substs, None).subst(self)
+    }
+}
+
+fn subst_or_bug<T:TypeFoldable>(tcx: &ty::ctxt,
+                                value: &T,
+                                substs: &Substs,
+                                item: Option<ast::DefId>,
+                                span: Option<Span>)
+                                -> T {
+    match SubstFolder::new(tcx, substs, item).subst(value) {
+        Ok(t) => t,
+        Err(err) => {
+            tcx.sess.span_bug(
+                span.unwrap_or(DUMMY_SP),
+                format!("{} substs={}",
+                        err.repr(tcx),
+                        substs.repr(tcx)).as_slice());
+        }
+    }
+}
+
+/// A parameter for which the substitution has no entry in its `Substs`,
+/// with what is known of the substitution that needed it.
+#[deriving(Clone)]
+pub struct SubstError {
+    pub param: OutOfRangeParam,
+
+    /// The number of parameters that the substs had in the space of
+    /// the missing one.
+    pub len: uint,
+
+    /// The root type that was being substituted, if there was one.
+    pub root_ty: Option<ty::t>,
+
+    /// The item that the substs were built for, if the caller said.
+    pub item: Option<ast::DefId>,
+}
+
+#[deriving(Clone)]
+pub enum OutOfRangeParam {
+    TypeParamOutOfRange(ty::ParamTy),
+
+    /// The region parameter's name, space and index.
+    RegionParamOutOfRange(ast::Name, ParamSpace, uint),
+}
+
+impl Repr for SubstError {
+    fn repr(&self, tcx: &ty::ctxt) -> String {
+        let (kind, name, space, index) = match self.param {
+            TypeParamOutOfRange(p) => ("type", p.user_string(tcx), p.space, p.idx),
+            RegionParamOutOfRange(name, space, index) => {
+                ("region", name.as_str().to_string(), space, index)
+            }
+        };
+
+        let mut msg = format!("{} parameter `{}` (index {} in {}) out of range \
+                               when substituting",
+                              kind, name, index, space);
+        match self.item {
+            Some(did) => {
+                msg.push_str(format!(" substs for `{}`",
+                                     ty::item_path_str(tcx, did)).as_slice());
+            }
+            None => {}
+        }
+        match self.root_ty {
+            Some(t) => msg.push_str(format!(" into `{}`", t.repr(tcx)).as_slice()),
+            None => {}
+        }
+        msg.push_str(format!(" (had {} {} parameter{})",
+                             self.len, space,
+                             if self.len == 1 { "" } else { "s" }).as_slice());
+        msg
     }
 }
 
@@ -495,8 +746,8 @@ struct SubstFolder<'a, 'tcx: 'a> {
     tcx: &'a ty::ctxt<'tcx>,
     substs: &'a Substs,
 
-    // The location for which the substitution is performed, if available.
-    span: Option<Span>,
+    // The item that the substs were built for, if known.
+    item: Option<ast::DefId>,
 
     // The root type that is being substituted, if available.
     root_ty: Option<ty::t>,
@@ -505,10 +756,38 @@ struct SubstFolder<'a, 'tcx: 'a> {
     ty_stack_depth: uint,
 }
 
-impl<'a, 'tcx> TypeFolder<'tcx> for SubstFolder<'a, 'tcx> {
+impl<'a, 'tcx> SubstFolder<'a, 'tcx> {
+    fn new(tcx: &'a ty::ctxt<'tcx>,
+           substs: &'a Substs,
+           item: Option<ast::DefId>)
+           -> SubstFolder<'a, 'tcx> {
+        SubstFolder { tcx: tcx,
+                      substs: substs,
+                      item: item,
+                      root_ty: None,
+                      ty_stack_depth: 0 }
+    }
+
+    fn subst<T:TypeFoldable>(&mut self, value: &T) -> Result<T, SubstError> {
+        if self.tcx.sess.fold_stats() {
+            return value.try_fold_with(&mut CountingFolder::new("SubstFolder", self));
+        }
+        value.try_fold_with(self)
+    }
+
+    fn out_of_range(&self, param: OutOfRangeParam, len: uint) -> SubstError {
+        SubstError { param: param, len: len, root_ty: self.root_ty, item: self.item }
+    }
+}
+
+impl<'a, 'tcx> FallibleTypeFolder<'tcx, SubstError> for SubstFolder<'a, 'tcx> {
     fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
 
-    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+    fn skip_ty(&self, t: ty::t) -> bool {
+        !ty::type_needs_subst(t)
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> Result<ty::Region, SubstError> {
         // Note: This routine only handles regions that are bound on
         // type declarations and other outer declarations, not those
         // bound in *fn types*. Region substitution of the bound
@@ -518,31 +797,24 @@ impl<'a, 'tcx> TypeFolder<'tcx> for SubstFolder<'a, 'tcx> {
         match r {
             ty::ReEarlyBound(_, space, i, region_name) => {
                 match self.substs.regions {
-                    ErasedRegions => ty::ReStatic,
+                    ErasedRegions => Ok(ty::ReStatic),
                     NonerasedRegions(ref regions) =>
                         match regions.opt_get(space, i) {
-                            Some(t) => *t,
+                            Some(t) => Ok(*t),
                             None => {
-                                let span = self.span.unwrap_or(DUMMY_SP);
-                                self.tcx().sess.span_bug(
-                                    span,
-                                    format!("Type parameter out of range \
-                                     when substituting in region {} (root type={}) \
-                                     (space={}, index={})",
-                                    region_name.as_str(),
-                                    self.root_ty.repr(self.tcx()),
-                                    space, i).as_slice());
+                                let param = RegionParamOutOfRange(region_name, space, i);
+                                Err(self.out_of_range(param, regions.len(space)))
                             }
                         }
                 }
             }
-            _ => r
+            _ => Ok(r)
         }
     }
 
-    fn fold_ty(&mut self, t: ty::t) -> ty::t {
-        if !ty::type_needs_subst(t) {
-            return t;
+    fn fold_ty(&mut self, t: ty::t) -> Result<ty::t, SubstError> {
+        if self.skip_ty(t) {
+            return Ok(t);
         }
 
         // track the root type we were asked to substitute
@@ -554,15 +826,16 @@ impl<'a, 'tcx> TypeFolder<'tcx> for SubstFolder<'a, 'tcx> {
 
         let t1 = match ty::get(t).sty {
             ty::ty_param(p) => {
-                check(self,
-                      p,
-                      t,
-                      self.substs.types.opt_get(p.space, p.idx),
-                      p.space,
-                      p.idx)
+                match self.substs.types.opt_get(p.space, p.idx) {
+                    Some(t) => *t,
+                    None => {
+                        let len = self.substs.types.len(p.space);
+                        return Err(self.out_of_range(TypeParamOutOfRange(p), len));
+                    }
+                }
             }
             _ => {
-                ty_fold::super_fold_ty(self, t)
+                try!(ty_fold::try_super_fold_ty(self, t))
             }
         };
 
@@ -572,31 +845,6 @@ impl<'a, 'tcx> TypeFolder<'tcx> for SubstFolder<'a, 'tcx> {
             self.root_ty = None;
         }
 
-        return t1;
-
-        fn check(this: &SubstFolder,
-                 p: ty::ParamTy,
-                 source_ty: ty::t,
-                 opt_ty: Option<&ty::t>,
-                 space: ParamSpace,
-                 index: uint)
-                 -> ty::t {
-            match opt_ty {
-                Some(t) => *t,
-                None => {
-                    let span = this.span.unwrap_or(DUMMY_SP);
-                    this.tcx().sess.span_bug(
-                        span,
-                        format!("Type parameter `{}` ({}/{}/{}) out of range \
-                                 when substituting (root type={}) substs={}",
-                                p.repr(this.tcx()),
-                                source_ty.repr(this.tcx()),
-                                space,
-                                index,
-                                this.root_ty.repr(this.tcx()),
-                                this.substs.repr(this.tcx())).as_slice());
-                }
-            }
-        }
+        return Ok(t1);
     }
 }
@@ -178,6 +178,16 @@ pub enum Vtable<N> {
     /// not appear in the source.
     VtableUnboxedClosure(ast::DefId, subst::Substs),
 
+    /// Vtable automatically generated for a bare fn type that implements
+    /// `Fn`. The type is the bare fn type itself; the generated method
+    /// simply calls through the fn pointer.
+    VtableFnPointer(ty::t),
+
+    /// Vtable automatically generated for cloning an unboxed closure
+    /// that holds its upvars by value. The nested obligations require
+    /// each upvar to be `Clone`.
+    VtableUnboxedClosureClone(ast::DefId, subst::Substs, VtableBuiltinData<N>),
+
     /// Successful resolution to an obligation provided by the caller
     /// for some type parameter.
     VtableParam(VtableParamData),
@@ -339,6 +349,8 @@ impl<N> Vtable<N> {
         match *self {
             VtableImpl(ref i) => i.iter_nested(),
             VtableUnboxedClosure(..) => (&[]).iter(),
+            VtableFnPointer(..) => (&[]).iter(),
+            VtableUnboxedClosureClone(_, _, ref i) => i.iter_nested(),
             VtableParam(_) => (&[]).iter(),
             VtableBuiltin(ref i) => i.iter_nested(),
         }
@@ -348,6 +360,10 @@ impl<N> Vtable<N> {
         match *self {
             VtableImpl(ref i) => VtableImpl(i.map_nested(op)),
             VtableUnboxedClosure(d, ref s) => VtableUnboxedClosure(d, s.clone()),
+            VtableFnPointer(t) => VtableFnPointer(t),
+            VtableUnboxedClosureClone(d, ref s, ref i) => {
+                VtableUnboxedClosureClone(d, s.clone(), i.map_nested(op))
+            }
             VtableParam(ref p) => VtableParam((*p).clone()),
             VtableBuiltin(ref i) => VtableBuiltin(i.map_nested(op)),
         }
@@ -357,6 +373,10 @@ impl<N> Vtable<N> {
         match self {
             VtableImpl(i) => VtableImpl(i.map_move_nested(op)),
             VtableUnboxedClosure(d, s) => VtableUnboxedClosure(d, s),
+            VtableFnPointer(t) => VtableFnPointer(t),
+            VtableUnboxedClosureClone(d, s, i) => {
+                VtableUnboxedClosureClone(d, s, i.map_move_nested(op))
+            }
             VtableParam(p) => VtableParam(p),
             VtableBuiltin(i) => VtableBuiltin(i.map_move_nested(op)),
         }
@@ -17,20 +17,22 @@ use super::{SelectionError, Unimplemented, Overflow,
             OutputTypeParameterMismatch};
 use super::{Selection};
 use super::{SelectionResult};
-use super::{VtableBuiltin, VtableImpl, VtableParam, VtableUnboxedClosure};
+use super::{VtableBuiltin, VtableImpl, VtableParam, VtableUnboxedClosure, VtableFnPointer};
+use super::{VtableUnboxedClosureClone, ClosureCapture};
 use super::{VtableImplData, VtableParamData, VtableBuiltinData};
 use super::{util};
 
 use middle::mem_categorization::Typer;
 use middle::subst::{Subst, Substs, VecPerParamSpace};
 use middle::ty;
-use middle::typeck::check::regionmanip;
+use middle::ty_fold;
 use middle::typeck::infer;
 use middle::typeck::infer::{InferCtxt, TypeSkolemizer};
 use middle::ty_fold::TypeFoldable;
 use std::cell::RefCell;
 use std::collections::hash_map::HashMap;
 use std::rc::Rc;
+use syntax::abi;
 use syntax::ast;
 use util::ppaux::Repr;
 
@@ -127,6 +129,8 @@ enum Candidate {
     ParamCandidate(VtableParamData),
     ImplCandidate(ast::DefId),
     UnboxedClosureCandidate(/* closure */ ast::DefId, Substs),
+    UnboxedClosureCloneCandidate(/* closure */ ast::DefId, Substs),
+    FnPointerCandidate,
     ErrorCandidate,
 }
 
@@ -943,6 +947,9 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 // For the time being, we ignore user-defined impls for builtin-bounds.
                 // (And unboxed candidates only apply to the Fn/FnMut/etc traits.)
                 try!(self.assemble_unboxed_candidates(obligation, &mut candidates));
+                try!(self.assemble_unboxed_closure_clone_candidates(obligation,
+                                                                     &mut candidates));
+                try!(self.assemble_fn_pointer_candidates(obligation, &mut candidates));
                 try!(self.assemble_candidates_from_impls(obligation, &mut candidates));
             }
         }
@@ -1049,6 +1056,78 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         Ok(())
     }
 
+    fn assemble_unboxed_closure_clone_candidates(&mut self,
+                                                 obligation: &Obligation,
+                                                 candidates: &mut CandidateSet)
+                                                 -> Result<(),SelectionError>
+    {
+        /*!
+         * Check for the artificial impl of `Clone` that the compiler
+         * provides for an unboxed closure that holds its upvars by
+         * value. Whether the upvars are `Clone` is checked when the
+         * candidate is confirmed, so that an error names the upvar
+         * that is not.
+         */
+
+        if Some(obligation.trait_ref.def_id) != self.tcx().lang_items.clone_trait() {
+            return Ok(());
+        }
+
+        let self_ty = self.infcx.shallow_resolve(obligation.self_ty());
+        match ty::get(self_ty).sty {
+            ty::ty_unboxed_closure(closure_def_id, _, ref substs) => {
+                if ty::unboxed_closure_captures_by_value(self.tcx(), closure_def_id) {
+                    candidates.vec.push(UnboxedClosureCloneCandidate(closure_def_id,
+                                                                     substs.clone()));
+                }
+            }
+            ty::ty_infer(ty::TyVar(_)) => {
+                candidates.ambiguous = true;
+            }
+            _ => { }
+        }
+
+        Ok(())
+    }
+
+    fn assemble_fn_pointer_candidates(&mut self,
+                                      obligation: &Obligation,
+                                      candidates: &mut CandidateSet)
+                                      -> Result<(),SelectionError>
+    {
+        /*!
+         * Check for the artificial impl of `Fn` that the compiler
+         * provides for bare fn types. `FnMut` and `FnOnce` need no
+         * candidate of their own, because they follow from the blanket
+         * impls in libcore.
+         */
+
+        if Some(obligation.trait_ref.def_id) != self.tcx().lang_items.fn_trait() {
+            return Ok(());
+        }
+
+        let self_ty = self.infcx.shallow_resolve(obligation.self_ty());
+        match ty::get(self_ty).sty {
+            ty::ty_infer(ty::TyVar(_)) => {
+                // Could wind up being a bare fn type.
+                candidates.ambiguous = true;
+            }
+
+            // Only safe, non-variadic Rust fns can be called through `Fn`.
+            ty::ty_bare_fn(ty::BareFnTy {
+                fn_style: ast::NormalFn,
+                abi: abi::Rust,
+                sig: ty::FnSig { variadic: false, .. }
+            }) => {
+                candidates.vec.push(FnPointerCandidate);
+            }
+
+            _ => { }
+        }
+
+        Ok(())
+    }
+
     fn assemble_candidates_from_impls(&mut self,
                                       obligation: &Obligation,
                                       candidates: &mut CandidateSet)
@@ -1427,26 +1506,19 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 // captures are by value. Really what we ought to do
                 // is reserve judgement and then intertwine this
                 // analysis with closure inference.
+                //
+                // `Copy` is the exception: a closure is only `Copy` if
+                // its upvars are known to be held by value.
                 assert_eq!(def_id.krate, ast::LOCAL_CRATE);
-                match self.tcx().freevars.borrow().get(&def_id.node) {
-                    None => {
-                        // No upvars.
-                        Ok(If(Vec::new()))
-                    }
-
-                    Some(freevars) => {
-                        let tys: Vec<ty::t> =
-                            freevars
-                            .iter()
-                            .map(|freevar| {
-                                let freevar_def_id = freevar.def.def_id();
-                                self.typer.node_ty(freevar_def_id.node)
-                                    .unwrap_or(ty::mk_err()).subst(self.tcx(), substs)
-                            })
-                            .collect();
-                        Ok(If(tys))
-                    }
+                if bound == ty::BoundCopy &&
+                        !ty::unboxed_closure_captures_by_value(self.tcx(), def_id) {
+                    return Err(Unimplemented);
                 }
+                let tys = self.unboxed_closure_upvar_types(def_id, substs)
+                              .into_iter()
+                              .map(|(_, ty)| ty)
+                              .collect();
+                Ok(If(tys))
             }
 
             ty::ty_struct(def_id, ref substs) => {
@@ -1548,6 +1620,26 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         }
     }
 
+    fn unboxed_closure_upvar_types(&self,
+                                   closure_def_id: ast::DefId,
+                                   substs: &Substs)
+                                   -> Vec<(ty::Freevar, ty::t)>
+    {
+        /*!
+         * Returns the upvars of the given unboxed closure along with
+         * their types, assuming that they are captured by value.
+         */
+
+        ty::with_freevars(self.tcx(), closure_def_id.node, |freevars| {
+            freevars.iter().map(|freevar| {
+                let freevar_def_id = freevar.def.def_id();
+                let ty = self.typer.node_ty(freevar_def_id.node)
+                             .unwrap_or(ty::mk_err()).subst(self.tcx(), substs);
+                (*freevar, ty)
+            }).collect()
+        })
+    }
+
     ///////////////////////////////////////////////////////////////////////////
     // CONFIRMATION
     //
@@ -1586,6 +1678,18 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 try!(self.confirm_unboxed_closure_candidate(obligation, closure_def_id, &substs));
                 Ok(VtableUnboxedClosure(closure_def_id, substs))
             }
+
+            UnboxedClosureCloneCandidate(closure_def_id, substs) => {
+                let data = self.confirm_unboxed_closure_clone_candidate(obligation,
+                                                                        closure_def_id,
+                                                                        &substs);
+                Ok(VtableUnboxedClosureClone(closure_def_id, substs, data))
+            }
+
+            FnPointerCandidate => {
+                let fn_type = try!(self.confirm_fn_pointer_candidate(obligation));
+                Ok(VtableFnPointer(fn_type))
+            }
         }
     }
 
@@ -1700,11 +1804,12 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             }
         };
 
-        // FIXME(pcwalton): This is a bogus thing to do, but
-        // it'll do for now until we get the new trait-bound
-        // region skolemization working.
-        let (_, new_signature) =
-            regionmanip::replace_late_bound_regions(
+        // The closure satisfies the obligation if its signature can be
+        // instantiated to match it. Any regions that the obligation
+        // itself binds (as in `F: Fn(&int)`) are skolemized when the
+        // two trait references are related in `confirm()`.
+        let (new_signature, _) =
+            ty_fold::replace_late_bound_regions(
                 self.tcx(),
                 closure_type.sig.binder_id,
                 &closure_type.sig,
@@ -1712,14 +1817,19 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                          infer::LateBoundRegion(obligation.cause.span, br)));
 
         let arguments_tuple = new_signature.inputs[0];
+
+        // A diverging closure satisfies a bound such as `FnOnce() -> !`.
+        let output = ty::fn_output_as_ty(self.tcx(), new_signature.output);
+
         let trait_ref = Rc::new(ty::TraitRef {
             def_id: obligation.trait_ref.def_id,
             substs: Substs::new_trait(
                 vec![arguments_tuple.subst(self.tcx(), substs),
-                     new_signature.output.unwrap().subst(self.tcx(), substs)],
+                     output.subst(self.tcx(), substs)],
                 vec![],
                 vec![],
-                obligation.self_ty())
+                obligation.self_ty()),
+            binder_id: ast::DUMMY_NODE_ID
         });
 
         self.confirm(obligation.cause,
@@ -1727,6 +1837,90 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                      trait_ref)
     }
 
+    fn confirm_unboxed_closure_clone_candidate(&mut self,
+                                               obligation: &Obligation,
+                                               closure_def_id: ast::DefId,
+                                               substs: &Substs)
+                                               -> VtableBuiltinData<Obligation>
+    {
+        debug!("confirm_unboxed_closure_clone_candidate({},{},{})",
+               obligation.repr(self.tcx()),
+               closure_def_id.repr(self.tcx()),
+               substs.repr(self.tcx()));
+
+        // Each upvar must be `Clone` in turn. The obligations are
+        // attributed to the captures, so that an error names the
+        // upvar that cannot be cloned.
+        let closure_span = self.tcx().map.span(closure_def_id.node);
+        let obligations = self.unboxed_closure_upvar_types(closure_def_id, substs)
+            .into_iter()
+            .map(|(freevar, ty)| {
+                let code = ClosureCapture(freevar.def.def_id().node, closure_span);
+                Obligation {
+                    cause: ObligationCause::new(freevar.span, code),
+                    recursion_depth: obligation.recursion_depth + 1,
+                    trait_ref: Rc::new(ty::TraitRef {
+                        def_id: obligation.trait_ref.def_id,
+                        substs: Substs::new_trait(vec![], vec![], vec![], ty),
+                        binder_id: ast::DUMMY_NODE_ID
+                    })
+                }
+            })
+            .collect();
+        VtableBuiltinData {
+            nested: VecPerParamSpace::new(obligations, Vec::new(), Vec::new(), Vec::new())
+        }
+    }
+
+    fn confirm_fn_pointer_candidate(&mut self,
+                                    obligation: &Obligation)
+                                    -> Result<ty::t,SelectionError>
+    {
+        debug!("confirm_fn_pointer_candidate({})",
+               obligation.repr(self.tcx()));
+
+        let self_ty = self.infcx.shallow_resolve(obligation.self_ty());
+        let sig = match ty::get(self_ty).sty {
+            ty::ty_bare_fn(ty::BareFnTy { ref sig, .. }) => sig,
+            _ => {
+                self.tcx().sess.span_bug(
+                    obligation.cause.span,
+                    format!("Fn pointer candidate for inappropriate self type: {}",
+                            self_ty.repr(self.tcx())).as_slice());
+            }
+        };
+
+        // As with unboxed closures, the late-bound regions of the fn
+        // type are instantiated with fresh variables, so that a
+        // higher-ranked fn such as `for<'a> fn(&'a int)` can satisfy a
+        // higher-ranked bound such as `F: Fn(&int)`.
+        let (new_signature, _) =
+            ty_fold::replace_late_bound_regions(
+                self.tcx(),
+                sig.binder_id,
+                sig,
+                |br| self.infcx.next_region_var(
+                         infer::LateBoundRegion(obligation.cause.span, br)));
+
+        let arguments_tuple = ty::mk_tup_or_nil(self.tcx(), new_signature.inputs.clone());
+        let output = ty::fn_output_as_ty(self.tcx(), new_signature.output);
+
+        let trait_ref = Rc::new(ty::TraitRef {
+            def_id: obligation.trait_ref.def_id,
+            substs: Substs::new_trait(
+                vec![arguments_tuple, output],
+                vec![],
+                vec![],
+                self_ty),
+            binder_id: ast::DUMMY_NODE_ID
+        });
+
+        try!(self.confirm(obligation.cause,
+                          obligation.trait_ref.clone(),
+                          trait_ref));
+        Ok(self_ty)
+    }
+
     ///////////////////////////////////////////////////////////////////////////
     // Matching
     //
@@ -2001,6 +2195,10 @@ impl Repr for Candidate {
             UnboxedClosureCandidate(c, ref s) => {
                 format!("MatchedUnboxedClosureCandidate({},{})", c, s.repr(tcx))
             }
+            UnboxedClosureCloneCandidate(c, ref s) => {
+                format!("UnboxedClosureCloneCandidate({},{})", c, s.repr(tcx))
+            }
+            FnPointerCandidate => format!("FnPointerCandidate"),
             ParamCandidate(ref a) => format!("ParamCandidate({})", a.repr(tcx)),
             ImplCandidate(a) => format!("ImplCandidate({})", a.repr(tcx)),
         }
@@ -244,7 +244,8 @@ pub fn trait_ref_for_builtin_bound(
         Ok(def_id) => {
             Some(Rc::new(ty::TraitRef {
                 def_id: def_id,
-                substs: Substs::empty().with_self_ty(param_ty)
+                substs: Substs::empty().with_self_ty(param_ty),
+                binder_id: ast::DUMMY_NODE_ID
             }))
         }
         Err(e) => {
@@ -316,6 +317,15 @@ impl<N:Repr> Repr for super::Vtable<N> {
                         d.repr(tcx),
                         s.repr(tcx)),
 
+            super::VtableFnPointer(ref t) =>
+                format!("VtableFnPointer({})", t.repr(tcx)),
+
+            super::VtableUnboxedClosureClone(ref d, ref s, ref n) =>
+                format!("VtableUnboxedClosureClone({},{},{})",
+                        d.repr(tcx),
+                        s.repr(tcx),
+                        n.repr(tcx)),
+
             super::VtableParam(ref v) =>
                 format!("VtableParam({})", v.repr(tcx)),
 
@@ -327,8 +337,10 @@ impl<N:Repr> Repr for super::Vtable<N> {
 
 impl<N:Repr> Repr for super::VtableImplData<N> {
     fn repr(&self, tcx: &ty::ctxt) -> String {
-        format!("VtableImpl(impl_def_id={}, substs={}, nested={})",
-                self.impl_def_id.repr(tcx),
+        // Impls are anonymous, so print the path of the impl item,
+        // which names its trait and type, rather than its def-id
+        format!("VtableImpl(impl={}, substs={}, nested={})",
+                ty::item_path_str(tcx, self.impl_def_id),
                 self.substs.repr(tcx),
                 self.nested.repr(tcx))
     }
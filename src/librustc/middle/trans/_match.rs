@@ -147,27 +147,15 @@
  * can be done on subparts of it.
  *
  * The way that vector pattern matches are dealt with, then, is as
- * follows. First, we make the actual condition associated with a
- * vector pattern simply a vector length comparison. So the pattern
- * [1, .. x] gets the condition "vec len >= 1", and the pattern
- * [.. x] gets the condition "vec len >= 0". The problem here is that
- * having the condition "vec len >= 1" hold clearly does not mean that
- * only a pattern that has exactly that condition will match. This
- * means that it may well be the case that a condition holds, but none
- * of the patterns matching that condition match; to deal with this,
- * when doing vector length matches, we have match failures proceed to
- * the next condition to check.
- *
- * There are a couple more subtleties to deal with. While the "actual"
- * condition associated with vector length tests is simply a test on
- * the vector length, the actual vec_len Opt entry contains more
- * information used to restrict which matches are associated with it.
- * So that all matches in a submatch are matching against the same
- * values from inside the vector, they are split up by how many
- * elements they match at the front and at the back of the vector. In
- * order to make sure that arms are properly checked in order, even
- * with the overmatching conditions, each vec_len Opt entry is
- * associated with a range of matches.
+ * follows. All the vector patterns in a column are compiled into a
+ * single switch on the vector length. Each length up to that of the
+ * longest pattern gets its own case, in which exactly that many
+ * elements are extracted, and every pattern that can match a vector of
+ * that length is specialized to them: the pattern [1, .. x] is tried
+ * for lengths 1, 2 and so on as [1], [1, _] and so on, the subslice
+ * being bound to whatever is left in the middle. The default case
+ * takes the longer vectors, which only the patterns with a subslice
+ * (and the wildcards) can match.
  * Consider the following:
  *
  *   match &[1, 2, 3] {
@@ -177,12 +165,12 @@
  *       [1, 2, .. _] => 3,
  *       _ => 4
  *   }
- * The proper arm to match is arm 2, but arms 0 and 3 both have the
- * condition "len >= 2". If arm 3 was lumped in with arm 0, then the
- * wrong branch would be taken. Instead, vec_len Opts are associated
- * with a contiguous range of matches that have the same "shape".
- * This is sort of ugly and requires a bunch of special handling of
- * vec_len options.
+ *
+ * Lengths 0 and 1 only leave arm 4. Length 2 leaves arms 0, 3 and 4,
+ * and lengths 3 and up all five arms, in their original order, so arm
+ * 2 is the one matched here. As every length has its own case, the
+ * arms for a given length are only tested once, and their guards are
+ * tried in order as they would be anyway.
  *
  */
 
@@ -244,7 +232,7 @@ impl<'a> ConstantExpr<'a> {
 #[deriving(Show)]
 enum Opt<'a> {
     ConstantValue(ConstantExpr<'a>),
-    ConstantRange(ConstantExpr<'a>, ConstantExpr<'a>),
+    ConstantRange(ConstantExpr<'a>, ConstantExpr<'a>, ast::RangeEnd),
     Variant(ty::Disr, Rc<adt::Repr>, ast::DefId),
     SliceLengthEqual(uint),
     SliceLengthGreaterOrEqual(/* prefix length */ uint, /* suffix length */ uint),
@@ -254,8 +242,8 @@ impl<'a> Opt<'a> {
     fn eq(&self, other: &Opt<'a>, tcx: &ty::ctxt) -> bool {
         match (self, other) {
             (&ConstantValue(a), &ConstantValue(b)) => a.eq(b, tcx),
-            (&ConstantRange(a1, a2), &ConstantRange(b1, b2)) => {
-                a1.eq(b1, tcx) && a2.eq(b2, tcx)
+            (&ConstantRange(a1, a2, a_end), &ConstantRange(b1, b2, b_end)) => {
+                a1.eq(b1, tcx) && a2.eq(b2, tcx) && a_end == b_end
             }
             (&Variant(a_disr, ref a_repr, a_def), &Variant(b_disr, ref b_repr, b_def)) => {
                 a_disr == b_disr && *a_repr == *b_repr && a_def == b_def
@@ -279,10 +267,10 @@ impl<'a> Opt<'a> {
                 let lit_datum = unpack_datum!(bcx, lit_datum.to_appropriate_datum(bcx));
                 SingleResult(Result::new(bcx, lit_datum.val))
             }
-            ConstantRange(ConstantExpr(ref l1), ConstantExpr(ref l2)) => {
+            ConstantRange(ConstantExpr(ref l1), ConstantExpr(ref l2), end) => {
                 let (l1, _) = consts::const_expr(ccx, &**l1);
                 let (l2, _) = consts::const_expr(ccx, &**l2);
-                RangeResult(Result::new(bcx, l1), Result::new(bcx, l2))
+                RangeResult(Result::new(bcx, l1), Result::new(bcx, l2), end)
             }
             Variant(disr_val, ref repr, _) => {
                 adt::trans_case(bcx, &**repr, disr_val)
@@ -290,8 +278,8 @@ impl<'a> Opt<'a> {
             SliceLengthEqual(length) => {
                 SingleResult(Result::new(bcx, C_uint(ccx, length)))
             }
-            SliceLengthGreaterOrEqual(prefix, suffix) => {
-                LowerBound(Result::new(bcx, C_uint(ccx, prefix + suffix)))
+            SliceLengthGreaterOrEqual(..) => {
+                bcx.sess().bug("slice length options with a subslice have no value to compare")
             }
         }
     }
@@ -302,14 +290,12 @@ pub enum BranchKind {
     NoBranch,
     Single,
     Switch,
-    Compare,
-    CompareSliceLength
+    Compare
 }
 
 pub enum OptResult<'blk, 'tcx: 'blk> {
     SingleResult(Result<'blk, 'tcx>),
-    RangeResult(Result<'blk, 'tcx>, Result<'blk, 'tcx>),
-    LowerBound(Result<'blk, 'tcx>)
+    RangeResult(Result<'blk, 'tcx>, Result<'blk, 'tcx>, ast::RangeEnd)
 }
 
 #[deriving(Clone)]
@@ -444,6 +430,8 @@ fn enter_match<'a, 'b, 'p, 'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                             let subslice_val = bind_subslice_pat(
                                 bcx, this.id, val,
                                 before.len(), after.len());
+                            let subslice_val = subslice_binding_val(bcx, &**slice,
+                                                                    subslice_val);
                             bound_ptrs.push((path.node, subslice_val));
                         }
                         _ => {}
@@ -535,10 +523,10 @@ fn enter_opt<'a, 'p, 'blk, 'tcx>(
         &ConstantValue(ConstantExpr(expr)) => check_match::ConstantValue(
             const_eval::eval_const_expr(bcx.tcx(), &*expr)
         ),
-        &ConstantRange(ConstantExpr(lo), ConstantExpr(hi)) => check_match::ConstantRange(
-            const_eval::eval_const_expr(bcx.tcx(), &*lo),
-            const_eval::eval_const_expr(bcx.tcx(), &*hi)
-        ),
+        &ConstantRange(ConstantExpr(lo), ConstantExpr(hi), end) => {
+            let (lo, hi) = const_eval::eval_range_bounds(bcx.tcx(), &*lo, &*hi, end);
+            check_match::ConstantRange(lo, hi)
+        }
         &SliceLengthEqual(n) =>
             check_match::Slice(n),
         &SliceLengthGreaterOrEqual(before, after) =>
@@ -547,7 +535,10 @@ fn enter_opt<'a, 'p, 'blk, 'tcx>(
             check_match::Variant(def_id)
     };
 
-    let mcx = check_match::MatchCheckCtxt { tcx: bcx.tcx() };
+    let mcx = check_match::MatchCheckCtxt {
+        tcx: bcx.tcx(),
+        skip_uninhabited_variants: true
+    };
     enter_match(bcx, dm, m, col, val, |pats|
         check_match::specialize(&mcx, pats.as_slice(), &ctor, col, variant_size)
     )
@@ -577,8 +568,8 @@ fn get_branches<'a, 'p, 'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                     _ => continue
                 }
             }
-            ast::PatRange(ref l1, ref l2) => {
-                ConstantRange(ConstantExpr(&**l1), ConstantExpr(&**l2))
+            ast::PatRange(ref l1, ref l2, end) => {
+                ConstantRange(ConstantExpr(&**l1), ConstantExpr(&**l2), end)
             }
             ast::PatVec(ref before, None, ref after) => {
                 SliceLengthEqual(before.len() + after.len())
@@ -648,6 +639,18 @@ fn bind_subslice_pat(bcx: Block,
     scratch.val
 }
 
+fn subslice_binding_val(bcx: Block, slice_pat: &ast::Pat, subslice_val: ValueRef) -> ValueRef {
+    /*!
+     * A `ref` binding of a subslice has the slice type itself (the
+     * subslice already borrows from the vector), so it is bound to the
+     * slice value rather than to a pointer to the temporary holding it.
+     */
+    match slice_pat.node {
+        ast::PatIdent(ast::BindByRef(_), _, None) => Load(bcx, subslice_val),
+        _ => subslice_val
+    }
+}
+
 fn extract_vec_elems<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                                  left_ty: ty::t,
                                  before: uint,
@@ -834,7 +837,14 @@ fn insert_lllocals<'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
      * the `fcx.lllocals` map
      */
 
-    for (&ident, &binding_info) in bindings_map.iter() {
+    // Bind the pieces of a value before the value as a whole, as in
+    // `whole @ Some(n)`: moving `whole` out may zero the matched memory.
+    let tcx = bcx.tcx();
+    let (moved, copied) = bindings_map.iter().collect::<Vec<_>>().partition(|&(_, info)| {
+        ty::type_moves_by_default(tcx, info.ty)
+    });
+
+    for (&ident, &binding_info) in copied.into_iter().chain(moved.into_iter()) {
         let llval = match binding_info.trmode {
             // By value mut binding for a copy type: load from the ptr
             // into the matched value and copy to our alloca
@@ -1017,7 +1027,10 @@ fn compile_submatch_continue<'a, 'p, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
         node_id_type(bcx, pat_id)
     };
 
-    let mcx = check_match::MatchCheckCtxt { tcx: bcx.tcx() };
+    let mcx = check_match::MatchCheckCtxt {
+        tcx: bcx.tcx(),
+        skip_uninhabited_variants: true
+    };
     let adt_vals = if any_irrefutable_adt_pat(bcx.tcx(), m, col) {
         let repr = adt::represent_type(bcx.ccx(), left_ty);
         let arg_count = adt::num_args(&*repr, 0);
@@ -1055,12 +1068,20 @@ fn compile_submatch_continue<'a, 'p, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
     // Decide what kind of branch we need
     let opts = get_branches(bcx, m, col);
     debug!("options={}", opts);
+    match opts.as_slice().head() {
+        Some(&SliceLengthEqual(_)) | Some(&SliceLengthGreaterOrEqual(..)) => {
+            compile_slice_length_switch(bcx, m, vals_left.as_slice(), chk, col, val, left_ty,
+                                        pat_id, opts.as_slice(), has_genuine_default);
+            return;
+        }
+        _ => ()
+    }
     let mut kind = NoBranch;
     let mut test_val = val;
     debug!("test_val={}", bcx.val_to_string(test_val));
     if opts.len() > 0u {
         match opts[0] {
-            ConstantValue(_) | ConstantRange(_, _) => {
+            ConstantValue(_) | ConstantRange(..) => {
                 test_val = load_if_immediate(bcx, val, left_ty);
                 kind = if ty::type_is_integral(left_ty) {
                     Switch
@@ -1073,17 +1094,12 @@ fn compile_submatch_continue<'a, 'p, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                 kind = the_kind;
                 for &tval in val_opt.iter() { test_val = tval; }
             }
-            SliceLengthEqual(_) | SliceLengthGreaterOrEqual(_, _) => {
-                let (_, len) = tvec::get_base_and_len(bcx, val, left_ty);
-                test_val = len;
-                kind = Switch;
-            }
+            SliceLengthEqual(_) | SliceLengthGreaterOrEqual(_, _) => unreachable!()
         }
     }
     for o in opts.iter() {
         match *o {
-            ConstantRange(_, _) => { kind = Compare; break },
-            SliceLengthGreaterOrEqual(_, _) => { kind = CompareSliceLength; break },
+            ConstantRange(..) => { kind = Compare; break },
             _ => ()
         }
     }
@@ -1126,32 +1142,29 @@ fn compile_submatch_continue<'a, 'p, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                         }
                     }
                 }
-                Compare | CompareSliceLength => {
-                    let t = if kind == Compare {
-                        left_ty
-                    } else {
-                        ty::mk_uint() // vector length
-                    };
+                Compare => {
+                    let t = left_ty;
                     let Result { bcx: after_cx, val: matches } = {
                         match opt.trans(bcx) {
                             SingleResult(Result { bcx, val }) => {
                                 compare_values(bcx, test_val, val, t)
                             }
                             RangeResult(Result { val: vbegin, .. },
-                                        Result { bcx, val: vend }) => {
+                                        Result { bcx, val: vend }, end) => {
                                 let Result { bcx, val: llge } =
                                     compare_scalar_types(
                                     bcx, test_val,
                                     vbegin, t, ast::BiGe);
+                                let upper_op = match end {
+                                    ast::RangeIncluded => ast::BiLe,
+                                    ast::RangeExcluded => ast::BiLt
+                                };
                                 let Result { bcx, val: llle } =
                                     compare_scalar_types(
                                     bcx, test_val, vend,
-                                    t, ast::BiLe);
+                                    t, upper_op);
                                 Result::new(bcx, And(bcx, llge, llle))
                             }
-                            LowerBound(Result { bcx, val }) => {
-                                compare_scalar_types(bcx, test_val, val, t, ast::BiGe)
-                            }
                         }
                     };
                     bcx = fcx.new_temp_block("compare_next");
@@ -1162,14 +1175,14 @@ fn compile_submatch_continue<'a, 'p, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                     // the default.
                     let guarded = m[i].data.arm.guard.is_some();
                     let multi_pats = m[i].pats.len() > 1;
-                    if i + 1 < len && (guarded || multi_pats || kind == CompareSliceLength) {
+                    if i + 1 < len && (guarded || multi_pats) {
                         branch_chk = Some(JumpToBasicBlock(bcx.llbb));
                     }
                     CondBr(after_cx, matches, opt_cx.llbb, bcx.llbb);
                 }
                 _ => ()
             }
-        } else if kind == Compare || kind == CompareSliceLength {
+        } else if kind == Compare {
             Br(bcx, else_cx.llbb);
         }
 
@@ -1183,19 +1196,8 @@ fn compile_submatch_continue<'a, 'p, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                 unpacked = argvals;
                 opt_cx = new_bcx;
             }
-            SliceLengthEqual(len) => {
-                let args = extract_vec_elems(opt_cx, left_ty, len, 0, val);
-                size = args.vals.len();
-                unpacked = args.vals.clone();
-                opt_cx = args.bcx;
-            }
-            SliceLengthGreaterOrEqual(before, after) => {
-                let args = extract_vec_elems(opt_cx, left_ty, before, after, val);
-                size = args.vals.len();
-                unpacked = args.vals.clone();
-                opt_cx = args.bcx;
-            }
-            ConstantValue(_) | ConstantRange(_, _) => ()
+            ConstantValue(_) | ConstantRange(..) => (),
+            SliceLengthEqual(_) | SliceLengthGreaterOrEqual(_, _) => unreachable!()
         }
         let opt_ms = enter_opt(opt_cx, pat_id, dm, m, opt, col, size, val);
         let mut opt_vals = unpacked;
@@ -1209,7 +1211,7 @@ fn compile_submatch_continue<'a, 'p, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
 
     // Compile the fall-through case, if any
     if !exhaustive && kind != Single {
-        if kind == Compare || kind == CompareSliceLength {
+        if kind == Compare {
             Br(bcx, else_cx.llbb);
         }
         match chk {
@@ -1230,6 +1232,109 @@ fn compile_submatch_continue<'a, 'p, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
     }
 }
 
+/// Compiles the slice patterns in column `col` of `m` into a single switch on
+/// the length of the slice. Each length up to that of the longest pattern gets
+/// its own case, which extracts that many elements and goes on with all the
+/// rows that can match a slice of that length, in their original order so that
+/// the guards are tried as they would be otherwise. Longer slices go to the
+/// default case, which extracts as many elements from the front and the back
+/// as the patterns with a subslice need and goes on with those rows. The
+/// lengths that no pattern in the column matches only leave the rows with a
+/// wildcard there.
+fn compile_slice_length_switch<'a, 'p, 'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
+                                                   m: &[Match<'a, 'p, 'blk, 'tcx>],
+                                                   vals_left: &[ValueRef],
+                                                   chk: &FailureHandler,
+                                                   col: uint,
+                                                   val: ValueRef,
+                                                   left_ty: ty::t,
+                                                   pat_id: ast::NodeId,
+                                                   opts: &[Opt],
+                                                   has_genuine_default: bool) {
+    let _icx = push_ctxt("match::compile_slice_length_switch");
+    let fcx = bcx.fcx;
+    let ccx = bcx.ccx();
+    let dm = &bcx.tcx().def_map;
+
+    let mut max_len = 0u;
+    let (mut prefix, mut suffix) = (0u, 0u);
+    let mut has_subslice = false;
+    for opt in opts.iter() {
+        match *opt {
+            SliceLengthEqual(len) => max_len = std::cmp::max(max_len, len),
+            SliceLengthGreaterOrEqual(before, after) => {
+                max_len = std::cmp::max(max_len, before + after);
+                prefix = std::cmp::max(prefix, before);
+                suffix = std::cmp::max(suffix, after);
+                has_subslice = true;
+            }
+            _ => bcx.sess().bug("in compile_slice_length_switch, expected \
+                                 only slice length options")
+        }
+    }
+
+    let (_, len) = tvec::get_base_and_len(bcx, val, left_ty);
+    let else_cx = fcx.new_temp_block("match_else");
+    let longer_cx = if has_subslice {
+        fcx.new_temp_block("match_case")
+    } else {
+        else_cx
+    };
+    let sw = build::Switch(bcx, len, longer_cx.llbb, max_len + 1);
+
+    let compile_case = |opt_cx: Block<'blk, 'tcx>, opt: &Opt, before: uint, after: uint| {
+        let args = extract_vec_elems(opt_cx, left_ty, before, after, val);
+        let opt_ms = enter_opt(args.bcx, pat_id, dm, m, opt, col, before + after, val);
+        let mut opt_vals = args.vals;
+        opt_vals.push_all(vals_left);
+        compile_submatch(args.bcx, opt_ms.as_slice(), opt_vals.as_slice(), chk,
+                         has_genuine_default);
+    };
+
+    let mut else_reachable = !has_subslice;
+    for length in std::iter::range_inclusive(0, max_len) {
+        let matched = opts.iter().any(|opt| match *opt {
+            SliceLengthEqual(len) => len == length,
+            SliceLengthGreaterOrEqual(before, after) => before + after <= length,
+            _ => false
+        });
+        if matched {
+            let opt_cx = fcx.new_temp_block("match_case");
+            AddCase(sw, C_uint(ccx, length), opt_cx.llbb);
+            compile_case(opt_cx, &SliceLengthEqual(length), length, 0);
+        } else if has_subslice {
+            AddCase(sw, C_uint(ccx, length), else_cx.llbb);
+            else_reachable = true;
+        }
+    }
+    if has_subslice {
+        compile_case(longer_cx, &SliceLengthGreaterOrEqual(prefix, suffix), prefix, suffix);
+    }
+
+    let defaults = enter_default(else_cx, dm, m, col, val);
+    if !else_reachable || (chk.is_infallible() && defaults.len() == 0u) {
+        // Either no length is left over, or the match wouldn't have been
+        // found exhaustive.
+        build::Unreachable(else_cx);
+    } else {
+        match chk {
+            // If there is only one default arm left, move on to the next
+            // condition explicitly rather than (eventually) falling back to
+            // the last default arm.
+            &JumpToBasicBlock(_) if defaults.len() == 1 && has_genuine_default => {
+                chk.handle_fail(else_cx);
+            }
+            _ => {
+                compile_submatch(else_cx,
+                                 defaults.as_slice(),
+                                 vals_left,
+                                 chk,
+                                 has_genuine_default);
+            }
+        }
+    }
+}
+
 pub fn trans_match<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                                match_expr: &ast::Expr,
                                discr_expr: &ast::Expr,
@@ -1287,13 +1392,17 @@ fn create_bindings_map(bcx: Block, pat: &ast::Pat,
     // Create the bindings map, which is a mapping from each binding name
     // to an alloca() that will be the value for that local variable.
     // Note that we use the names because each binding will have many ids
-    // from the various alternatives.
+    // from the various alternatives. The body refers to the bindings of the
+    // first alternative of any nested or-pattern, which are visited first.
     let ccx = bcx.ccx();
     let tcx = bcx.tcx();
     let reassigned = is_discr_reassigned(bcx, discr, body);
     let mut bindings_map = FnvHashMap::new();
     pat_bindings(&tcx.def_map, &*pat, |bm, p_id, span, path1| {
         let ident = path1.node;
+        if bindings_map.contains_key(&ident) {
+            return;
+        }
         let variable_ty = node_id_type(bcx, p_id);
         let llvariable_ty = type_of::type_of(ccx, variable_ty);
         let tcx = bcx.tcx();
@@ -1354,7 +1463,7 @@ fn trans_match_inner<'blk, 'tcx>(scope_cx: Block<'blk, 'tcx>,
     }
 
     let t = node_id_type(bcx, discr_expr.id);
-    let chk = if ty::type_is_empty(tcx, t) {
+    let chk = if ty::type_is_uninhabited(tcx, t) {
         Unreachable
     } else {
         Infallible
@@ -1368,7 +1477,9 @@ fn trans_match_inner<'blk, 'tcx>(scope_cx: Block<'blk, 'tcx>,
 
     let mut static_inliner = StaticInliner::new(scope_cx.tcx());
     let arm_pats: Vec<Vec<P<ast::Pat>>> = arm_datas.iter().map(|arm_data| {
-        arm_data.arm.pats.iter().map(|p| static_inliner.fold_pat((*p).clone())).collect()
+        arm_data.arm.pats.iter().flat_map(|p| {
+            expand_or_pats(&static_inliner.fold_pat((*p).clone())).into_iter()
+        }).collect()
     }).collect();
     let mut matches = Vec::new();
     for (arm_data, pats) in arm_datas.iter().zip(arm_pats.iter()) {
@@ -1612,6 +1723,12 @@ fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     let ccx = bcx.ccx();
     match pat.node {
         ast::PatIdent(pat_binding_mode, ref path1, ref inner) => {
+            // Bind the sub-pattern of `x @ ...` first, since moving the
+            // whole value into `x` zeroes the memory `val` points at.
+            for inner_pat in inner.iter() {
+                bcx = bind_irrefutable_pat(bcx, &**inner_pat, val, cleanup_scope);
+            }
+
             if pat_is_binding(&tcx.def_map, &*pat) {
                 // Allocate the stack slot where the value of this
                 // binding will live and place it into the appropriate
@@ -1636,10 +1753,6 @@ fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                         }
                     });
             }
-
-            for inner_pat in inner.iter() {
-                bcx = bind_irrefutable_pat(bcx, &**inner_pat, val, cleanup_scope);
-            }
         }
         ast::PatEnum(_, ref sub_pats) => {
             let opt_def = bcx.tcx().def_map.borrow().find_copy(&pat.id);
@@ -1654,13 +1767,15 @@ fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                                                     vinfo.disr_val,
                                                     val);
                     for sub_pat in sub_pats.iter() {
-                        for (i, &argval) in args.vals.iter().enumerate() {
-                            bcx = bind_irrefutable_pat(bcx, &*sub_pat[i],
-                                                       argval, cleanup_scope);
+                        let arity = args.vals.len();
+                        for &(i, elem) in tuple_pat_elements(sub_pat.as_slice(),
+                                                             arity).iter() {
+                            bcx = bind_irrefutable_pat(bcx, &**elem,
+                                                       args.vals[i], cleanup_scope);
                         }
                     }
                 }
-                Some(def::DefStruct(..)) => {
+                Some(def::DefStruct(struct_did)) => {
                     match *sub_pats {
                         None => {
                             // This is a unit-like struct. Nothing to do here.
@@ -1668,7 +1783,9 @@ fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                         Some(ref elems) => {
                             // This is the tuple struct case.
                             let repr = adt::represent_node(bcx, pat.id);
-                            for (i, elem) in elems.iter().enumerate() {
+                            let arity = ty::lookup_struct_fields(ccx.tcx(), struct_did).len();
+                            for &(i, elem) in tuple_pat_elements(elems.as_slice(),
+                                                                 arity).iter() {
                                 let fldptr = adt::trans_field_ptr(bcx, &*repr,
                                                                   val, 0, i);
                                 bcx = bind_irrefutable_pat(bcx, &**elem,
@@ -1697,7 +1814,11 @@ fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         }
         ast::PatTup(ref elems) => {
             let repr = adt::represent_node(bcx, pat.id);
-            for (i, elem) in elems.iter().enumerate() {
+            let arity = match ty::get(node_id_type(bcx, pat.id)).sty {
+                ty::ty_tup(ref tys) => tys.len(),
+                _ => elems.len()
+            };
+            for &(i, elem) in tuple_pat_elements(elems.as_slice(), arity).iter() {
                 let fldptr = adt::trans_field_ptr(bcx, &*repr, val, 0, i);
                 bcx = bind_irrefutable_pat(bcx, &**elem, fldptr, cleanup_scope);
             }
@@ -1714,10 +1835,12 @@ fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
             let pat_ty = node_id_type(bcx, pat.id);
             let mut extracted = extract_vec_elems(bcx, pat_ty, before.len(), after.len(), val);
             match slice {
-                &Some(_) => {
+                &Some(ref slice_pat) => {
+                    let subslice_val = bind_subslice_pat(bcx, pat.id, val,
+                                                         before.len(), after.len());
                     extracted.vals.insert(
                         before.len(),
-                        bind_subslice_pat(bcx, pat.id, val, before.len(), after.len())
+                        subslice_binding_val(bcx, &**slice_pat, subslice_val)
                     );
                 }
                 &None => ()
@@ -1731,10 +1854,13 @@ fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                     bind_irrefutable_pat(bcx, &**inner, elem, cleanup_scope)
                 );
         }
+        ast::PatOr(..) => {
+            bcx.sess().span_bug(pat.span, "or-pattern in irrefutable pattern");
+        }
         ast::PatMac(..) => {
             bcx.sess().span_bug(pat.span, "unexpanded macro");
         }
-        ast::PatWild(_) | ast::PatLit(_) | ast::PatRange(_, _) => ()
+        ast::PatWild(_) | ast::PatLit(_) | ast::PatRange(..) => ()
     }
     return bcx;
 }
@@ -75,6 +75,7 @@ use middle::trans::type_of;
 use middle::trans::type_of::*;
 use middle::trans::value::Value;
 use middle::ty;
+use middle::ty_fold;
 use util::common::indenter;
 use util::ppaux::{Repr, ty_to_string};
 use util::sha2::Sha256;
@@ -504,7 +505,7 @@ pub fn get_res_dtor(ccx: &CrateContext,
         assert_eq!(did.krate, ast::LOCAL_CRATE);
 
         // Since we're in trans we don't care for any region parameters
-        let ref substs = subst::Substs::erased(substs.types.clone());
+        let ref substs = substs.erase_regions();
 
         let (val, _) = monomorphize::monomorphic_fn(ccx, did, substs, None);
 
@@ -722,7 +723,7 @@ pub fn iter_structural_ty<'a, 'blk, 'tcx>(cx: Block<'blk, 'tcx>,
           })
       }
       ty::ty_unboxed_closure(def_id, _, ref substs) => {
-          let repr = adt::represent_type(cx.ccx(), t);
+          let repr = adt::represent_type(cx.ccx(), ty_fold::erase_regions(cx.tcx(), t));
           let upvars = ty::unboxed_closure_upvars(cx.tcx(), def_id, substs);
           for (i, upvar) in upvars.iter().enumerate() {
               let llupvar = adt::trans_field_ptr(cx, &*repr, data_ptr, 0, i);
@@ -3084,6 +3085,8 @@ pub fn trans_crate<'tcx>(analysis: CrateAnalysis<'tcx>)
         println!("n_monos: {}", stats.n_monos.get());
         println!("n_inlines: {}", stats.n_inlines.get());
         println!("n_closures: {}", stats.n_closures.get());
+        println!("n_erase_regions_hits: {}", shared_ccx.tcx().erased_regions_hits.get());
+        println!("n_erase_regions_misses: {}", shared_ccx.tcx().erased_regions_misses.get());
         println!("fn stats:");
         stats.fn_stats.borrow_mut().sort_by(|&(_, insns_a), &(_, insns_b)| {
             insns_b.cmp(&insns_a)
@@ -397,6 +397,101 @@ pub fn trans_unboxing_shim(bcx: Block,
     llfn
 }
 
+/// Translates an adapter that implements the `Fn` trait for a bare fn
+/// type. This is basically the equivalent of
+///
+/// ```ignore
+/// impl<'a> Fn<(&'a int,), &'a int> for fn(&int) -> &int {
+///     extern "rust-call" fn call(&self, args: (&'a int,)) -> &'a int {
+///         (*self)(args.0)
+///     }
+/// }
+/// ```
+///
+/// but for the bare fn type given. The shim loads the fn pointer out of
+/// `self` and calls it with the untupled arguments.
+pub fn trans_fn_pointer_shim(ccx: &CrateContext, bare_fn_ty: ty::t) -> ValueRef {
+    let _icx = push_ctxt("trans_fn_pointer_shim");
+    let tcx = ccx.tcx();
+
+    match ccx.fn_pointer_shims().borrow().get(&bare_fn_ty) {
+        Some(&llval) => return llval,
+        None => {}
+    }
+
+    debug!("trans_fn_pointer_shim(bare_fn_ty={})", bare_fn_ty.repr(tcx));
+
+    let sig = match ty::get(bare_fn_ty).sty {
+        ty::ty_bare_fn(ty::BareFnTy { abi: synabi::Rust, ref sig, .. }) => sig.clone(),
+        _ => {
+            tcx.sess.bug(format!("trans_fn_pointer_shim invoked on invalid type: {}",
+                                 bare_fn_ty.repr(tcx)).as_slice());
+        }
+    };
+
+    // The shim implements `Fn`, so its receiver is `&self`, a pointer to
+    // the fn pointer. The remaining arguments arrive as a tuple.
+    let self_ty = ty::mk_imm_rptr(tcx, ty::ReStatic, bare_fn_ty);
+    let tuple_input_ty = ty::mk_tup_or_nil(tcx, sig.inputs.clone());
+    let tuple_fn_ty = ty::mk_bare_fn(tcx, ty::BareFnTy {
+        fn_style: ast::NormalFn,
+        abi: synabi::RustCall,
+        sig: ty::FnSig {
+            binder_id: sig.binder_id,
+            inputs: vec![self_ty, tuple_input_ty],
+            output: sig.output,
+            variadic: false,
+        },
+    });
+    debug!("trans_fn_pointer_shim: tuple_fn_ty={}", tuple_fn_ty.repr(tcx));
+
+    let function_name = link::mangle_internal_name_by_type_and_seq(ccx,
+                                                                   bare_fn_ty,
+                                                                   "fn_pointer_shim");
+    let llfn = decl_internal_rust_fn(ccx, tuple_fn_ty, function_name.as_slice());
+
+    let block_arena = TypedArena::new();
+    let empty_param_substs = param_substs::empty();
+    let fcx = new_fn_ctxt(ccx,
+                          llfn,
+                          ast::DUMMY_NODE_ID,
+                          false,
+                          sig.output,
+                          &empty_param_substs,
+                          None,
+                          &block_arena);
+    let mut bcx = init_function(&fcx, false, sig.output);
+
+    // The first argument points at the fn pointer; the rest are the
+    // untupled arguments, which are passed through verbatim.
+    let llfnpointer = Load(bcx, get_param(fcx.llfn, fcx.arg_pos(0) as u32));
+    let llargs: Vec<ValueRef> = range(0, sig.inputs.len()).map(|i| {
+        get_param(fcx.llfn, fcx.arg_pos(i + 1) as u32)
+    }).collect();
+
+    assert!(!fcx.needs_ret_allocas);
+    let dest = fcx.llretslotptr.get().map(|_|
+        expr::SaveIn(fcx.get_ret_slot(bcx, sig.output, "ret_slot"))
+    );
+    bcx = trans_call_inner(bcx,
+                           None,
+                           bare_fn_ty,
+                           |bcx, _| {
+                               Callee {
+                                   bcx: bcx,
+                                   data: Fn(llfnpointer),
+                               }
+                           },
+                           ArgVals(llargs.as_slice()),
+                           dest).bcx;
+
+    finish_fn(&fcx, bcx, sig.output);
+
+    ccx.fn_pointer_shims().borrow_mut().insert(bare_fn_ty, llfn);
+
+    llfn
+}
+
 pub fn trans_fn_ref_with_substs(
     bcx: Block,                  //
     def_id: ast::DefId,          // def id of fn
@@ -879,7 +974,12 @@ pub fn trans_call_inner<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         _ => {}
     }
 
-    if ret_ty == ty::FnDiverging {
+    // A call through a bound such as `FnOnce() -> !` diverges too.
+    let diverges = match ret_ty {
+        ty::FnDiverging => true,
+        ty::FnConverging(ret_ty) => ty::type_is_diverging_output(bcx.tcx(), ret_ty)
+    };
+    if diverges {
         Unreachable(bcx);
     }
 
@@ -10,30 +10,40 @@
 
 
 use back::abi;
-use back::link::mangle_internal_name_by_path_and_seq;
+use back::link::{mangle_internal_name_by_path_and_seq, mangle_internal_name_by_type_and_seq};
 use driver::config::FullDebugInfo;
 use llvm::ValueRef;
 use middle::def;
+use middle::lang_items::CloneTraitLangItem;
 use middle::mem_categorization::Typer;
+use middle::traits;
 use middle::trans::adt;
 use middle::trans::base::*;
 use middle::trans::build::*;
+use middle::trans::callee::{ArgVals, Callee, ExprId, Fn, trans_call_inner};
+use middle::trans::callee::trans_fn_ref_with_substs;
 use middle::trans::cleanup::{CleanupMethods, ScopeId};
 use middle::trans::common::*;
 use middle::trans::datum::{Datum, DatumBlock, Expr, Lvalue, rvalue_scratch_datum};
 use middle::trans::debuginfo;
 use middle::trans::expr;
+use middle::trans::meth;
 use middle::trans::monomorphize::MonoId;
 use middle::trans::type_of::*;
 use middle::trans::type_::Type;
 use middle::ty;
+use middle::ty_fold;
 use middle::subst::{Subst, Substs};
 use util::ppaux::Repr;
 use util::ppaux::ty_to_string;
 
 use arena::TypedArena;
+use std::rc::Rc;
+use syntax::abi::Rust;
 use syntax::ast;
 use syntax::ast_util;
+use syntax::codemap::DUMMY_SP;
+use syntax::parse::token;
 
 // ___Good to know (tm)__________________________________________________
 //
@@ -303,7 +313,6 @@ fn load_environment<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
 fn load_unboxed_closure_environment<'blk, 'tcx>(
                                     bcx: Block<'blk, 'tcx>,
                                     arg_scope_id: ScopeId,
-                                    freevar_mode: ast::CaptureClause,
                                     freevars: &Vec<ty::Freevar>,
                                     closure_id: ast::DefId)
                                     -> Block<'blk, 'tcx> {
@@ -314,8 +323,8 @@ fn load_unboxed_closure_environment<'blk, 'tcx>(
     }
 
     // Special case for small by-value selfs.
-    let self_type = self_type_for_unboxed_closure(bcx.ccx(), closure_id,
-                                                  node_id_type(bcx, closure_id.node));
+    let closure_type = ty_fold::erase_regions(bcx.tcx(), node_id_type(bcx, closure_id.node));
+    let self_type = self_type_for_unboxed_closure(bcx.ccx(), closure_id, closure_type);
     let kind = kind_for_unboxed_closure(bcx.ccx(), closure_id);
     let llenv = if kind == ty::FnOnceUnboxedClosureKind &&
             !arg_is_indirect(bcx.ccx(), self_type) {
@@ -329,11 +338,15 @@ fn load_unboxed_closure_environment<'blk, 'tcx>(
     };
 
     for (i, freevar) in freevars.iter().enumerate() {
+        let def_id = freevar.def.def_id();
+        let freevar_mode = bcx.tcx().upvar_capture_mode(ty::UpvarId {
+            var_id: def_id.node,
+            closure_expr_id: closure_id.node
+        });
         let mut upvar_ptr = GEPi(bcx, llenv, [0, i]);
         if freevar_mode == ast::CaptureByRef {
             upvar_ptr = Load(bcx, upvar_ptr);
         }
-        let def_id = freevar.def.def_id();
         bcx.fcx.llupvars.borrow_mut().insert(def_id.node, upvar_ptr);
 
         if kind == ty::FnOnceUnboxedClosureKind && freevar_mode == ast::CaptureByValue {
@@ -496,7 +509,6 @@ pub fn trans_unboxed_closure<'blk, 'tcx>(
     let freevars: Vec<ty::Freevar> =
         ty::with_freevars(bcx.tcx(), id, |fv| fv.iter().map(|&fv| fv).collect());
     let freevars_ptr = &freevars;
-    let freevar_mode = bcx.tcx().capture_mode(id);
 
     trans_closure(bcx.ccx(),
                   decl,
@@ -512,7 +524,6 @@ pub fn trans_unboxed_closure<'blk, 'tcx>(
                   |bcx, arg_scope| {
                       load_unboxed_closure_environment(bcx,
                                                        arg_scope,
-                                                       freevar_mode,
                                                        freevars_ptr,
                                                        closure_id)
                   });
@@ -528,7 +539,8 @@ pub fn trans_unboxed_closure<'blk, 'tcx>(
         }
     };
 
-    let repr = adt::represent_type(bcx.ccx(), node_id_type(bcx, id));
+    let closure_type = ty_fold::erase_regions(bcx.tcx(), node_id_type(bcx, id));
+    let repr = adt::represent_type(bcx.ccx(), closure_type);
 
     // Create the closure.
     for (i, freevar) in freevars_ptr.iter().enumerate() {
@@ -538,6 +550,10 @@ pub fn trans_unboxed_closure<'blk, 'tcx>(
                                                    dest_addr,
                                                    0,
                                                    i);
+        let freevar_mode = bcx.tcx().upvar_capture_mode(ty::UpvarId {
+            var_id: freevar.def.def_id().node,
+            closure_expr_id: id
+        });
         match freevar_mode {
             ast::CaptureByValue => {
                 bcx = datum.store_to(bcx, upvar_slot_dest);
@@ -657,3 +673,203 @@ pub fn make_closure_from_bare_fn<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
 
     DatumBlock::new(bcx, scratch.to_expr_datum())
 }
+
+/// Returns a bare fn with the signature `fn_ptr_ty` that calls the given
+/// capture-free unboxed closure. Because the closure captures nothing, its
+/// body never reads the environment, so the wrapper passes an undefined one.
+pub fn get_fn_pointer_for_unboxed_closure<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
+                                                       closure_ty: ty::t,
+                                                       fn_ptr_ty: ty::t)
+                                                       -> ValueRef {
+    let ccx = bcx.ccx();
+    let tcx = ccx.tcx();
+
+    debug!("get_fn_pointer_for_unboxed_closure(closure_ty={}, fn_ptr_ty={})",
+           closure_ty.repr(tcx),
+           fn_ptr_ty.repr(tcx));
+
+    let closure_id = match ty::get(closure_ty).sty {
+        ty::ty_unboxed_closure(closure_id, _, _) => closure_id,
+        _ => {
+            ccx.sess().bug(format!("get_fn_pointer_for_unboxed_closure: \
+                                    expected an unboxed closure, got {}",
+                                   closure_ty.repr(tcx)).as_slice());
+        }
+    };
+
+    let llclosure = get_or_create_declaration_if_unboxed_closure(
+        bcx,
+        closure_id,
+        &bcx.fcx.param_substs.substs).unwrap();
+
+    match ccx.unboxed_closure_fn_pointers().borrow().get(&llclosure) {
+        Some(&llfn) => return llfn,
+        None => {}
+    }
+
+    let _icx = push_ctxt("closure::get_fn_pointer_for_unboxed_closure");
+
+    let name = tcx.map.with_path(closure_id.node, |path| {
+        mangle_internal_name_by_path_and_seq(path, "unboxed_closure_as_fn")
+    });
+    let llfn = decl_internal_rust_fn(ccx, fn_ptr_ty, name.as_slice());
+
+    ccx.unboxed_closure_fn_pointers().borrow_mut().insert(llclosure, llfn);
+
+    let output = ty::ty_fn_ret(fn_ptr_ty);
+    let arena = TypedArena::new();
+    let empty_param_substs = param_substs::empty();
+    let fcx = new_fn_ctxt(ccx, llfn, ast::DUMMY_NODE_ID, false, output,
+                          &empty_param_substs, None, &arena);
+    let bcx = init_function(&fcx, true, output);
+
+    let args = create_datums_for_fn_args(&fcx,
+                                         ty::ty_fn_args(fn_ptr_ty).as_slice());
+    let mut llargs = Vec::new();
+    match fcx.llretslotptr.get() {
+        Some(llretptr) => {
+            assert!(!fcx.needs_ret_allocas);
+            llargs.push(llretptr);
+        }
+        None => {}
+    }
+    let self_type = self_type_for_unboxed_closure(ccx, closure_id, closure_ty);
+    llargs.push(C_undef(type_of_explicit_arg(ccx, self_type)));
+    llargs.extend(args.iter().map(|arg| arg.val));
+
+    let retval = Call(bcx, llclosure, llargs.as_slice(), None);
+    match output {
+        ty::FnConverging(output_type) => {
+            if return_type_is_void(ccx, output_type) || fcx.llretslotptr.get().is_some() {
+                RetVoid(bcx);
+            } else {
+                Ret(bcx, retval);
+            }
+        }
+        ty::FnDiverging => {
+            RetVoid(bcx);
+        }
+    }
+
+    // HACK(eddyb) finish_fn cannot be used here, we returned directly.
+    debuginfo::clear_source_location(&fcx);
+    fcx.cleanup();
+
+    llfn
+}
+
+/// Returns a function of type `fn(&C) -> C`, where `C` is the type of the
+/// given unboxed closure, that clones the closure's environment upvar by
+/// upvar. This implements `Clone` for closures that hold their upvars by
+/// value; selection has already checked that each upvar is `Clone`.
+pub fn get_clone_shim_for_unboxed_closure(ccx: &CrateContext,
+                                          closure_id: ast::DefId,
+                                          substs: &Substs)
+                                          -> ValueRef {
+    let tcx = ccx.tcx();
+    let closure_ty = ty::mk_unboxed_closure(tcx, closure_id, ty::ReStatic, substs.clone());
+    let closure_ty = ty_fold::erase_regions(tcx, closure_ty);
+
+    match ccx.unboxed_closure_clone_shims().borrow().get(&closure_ty) {
+        Some(&llfn) => return llfn,
+        None => {}
+    }
+
+    let _icx = push_ctxt("closure::get_clone_shim_for_unboxed_closure");
+
+    debug!("get_clone_shim_for_unboxed_closure(closure_ty={})", closure_ty.repr(tcx));
+
+    let output = ty::FnConverging(closure_ty);
+    let shim_ty = clone_fn_type(tcx, closure_ty);
+    let name = mangle_internal_name_by_type_and_seq(ccx, closure_ty, "clone_shim");
+    let llfn = decl_internal_rust_fn(ccx, shim_ty, name.as_slice());
+
+    ccx.unboxed_closure_clone_shims().borrow_mut().insert(closure_ty, llfn);
+
+    let arena = TypedArena::new();
+    let empty_param_substs = param_substs::empty();
+    let fcx = new_fn_ctxt(ccx, llfn, ast::DUMMY_NODE_ID, false, output,
+                          &empty_param_substs, None, &arena);
+    let mut bcx = init_function(&fcx, false, output);
+
+    // A closure that captures nothing has nothing to clone.
+    match fcx.llretslotptr.get() {
+        Some(_) => {
+            let llself = get_param(fcx.llfn, fcx.arg_pos(0) as u32);
+            let lldest = fcx.get_ret_slot(bcx, output, "ret_slot");
+            let repr = adt::represent_type(ccx, closure_ty);
+            let upvars = ty::unboxed_closure_upvars(tcx, closure_id, substs);
+            for (i, upvar) in upvars.iter().enumerate() {
+                let llsrc = adt::trans_field_ptr(bcx, &*repr, llself, 0, i);
+                let lldest = adt::trans_field_ptr(bcx, &*repr, lldest, 0, i);
+                bcx = clone_upvar(bcx, upvar.ty, llsrc, lldest);
+            }
+            adt::trans_set_discr(bcx, &*repr, lldest, 0);
+        }
+        None => {}
+    }
+
+    finish_fn(&fcx, bcx, output);
+
+    llfn
+}
+
+/// Returns the type `fn(&T) -> T` of `Clone::clone` for `T`.
+fn clone_fn_type(tcx: &ty::ctxt, ty: ty::t) -> ty::t {
+    ty::mk_bare_fn(tcx, ty::BareFnTy {
+        fn_style: ast::NormalFn,
+        abi: Rust,
+        sig: ty::FnSig {
+            binder_id: ast::DUMMY_NODE_ID,
+            inputs: vec![ty::mk_imm_rptr(tcx, ty::ReStatic, ty)],
+            output: ty::FnConverging(ty),
+            variadic: false,
+        },
+    })
+}
+
+/// Clones the upvar of type `ty` at `llsrc` into `lldest`, copying it if
+/// it is `Copy` and calling its `Clone` impl otherwise.
+fn clone_upvar<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
+                           ty: ty::t,
+                           llsrc: ValueRef,
+                           lldest: ValueRef)
+                           -> Block<'blk, 'tcx> {
+    let ccx = bcx.ccx();
+    let tcx = ccx.tcx();
+
+    if !ty::type_moves_by_default(tcx, ty) {
+        memcpy_ty(bcx, lldest, llsrc, ty);
+        return bcx;
+    }
+
+    let clone_trait = langcall(bcx, None, "", CloneTraitLangItem);
+    let trait_ref = Rc::new(ty::TraitRef {
+        def_id: clone_trait,
+        substs: Substs::new_trait(vec![], vec![], vec![], ty),
+        binder_id: ast::DUMMY_NODE_ID
+    });
+    let llfn = match fulfill_obligation(ccx, DUMMY_SP, trait_ref) {
+        traits::VtableImpl(vtable_impl) => {
+            let method_id = meth::method_with_name(ccx,
+                                                   vtable_impl.impl_def_id,
+                                                   token::intern("clone"));
+            trans_fn_ref_with_substs(bcx, method_id, ExprId(0), vtable_impl.substs)
+        }
+        traits::VtableUnboxedClosureClone(closure_id, substs, _) => {
+            get_clone_shim_for_unboxed_closure(ccx, closure_id, &substs)
+        }
+        vtable => {
+            ccx.sess().bug(format!("clone_upvar: unexpected vtable {} for {}",
+                                   vtable.repr(tcx),
+                                   ty.repr(tcx)).as_slice());
+        }
+    };
+
+    trans_call_inner(bcx,
+                     None,
+                     clone_fn_type(tcx, ty),
+                     |bcx, _| Callee { bcx: bcx, data: Fn(llfn) },
+                     ArgVals(&[llsrc]),
+                     Some(expr::SaveIn(lldest))).bcx
+}
@@ -533,6 +533,10 @@ impl<'blk, 'tcx> mc::Typer<'tcx> for BlockS<'blk, 'tcx> {
                     -> ast::CaptureClause {
         self.tcx().capture_modes.borrow().get_copy(&closure_expr_id)
     }
+
+    fn upvar_capture_mode(&self, upvar_id: ty::UpvarId) -> ast::CaptureClause {
+        self.tcx().upvar_capture_mode(upvar_id)
+    }
 }
 
 pub struct Result<'blk, 'tcx: 'blk> {
@@ -775,6 +779,20 @@ pub fn is_null(val: ValueRef) -> bool {
     }
 }
 
+/// Unboxed closure types that key trans tables (`adt_reprs`, the clone
+/// shims) must have been passed through `ty_fold::erase_regions`: otherwise
+/// one closure seen under two regions would get two environment layouts.
+pub fn assert_closure_type_erased(tcx: &ty::ctxt, t: ty::t) {
+    match ty::get(t).sty {
+        ty::ty_unboxed_closure(..) if ty_fold::erase_regions(tcx, t) != t => {
+            tcx.sess.bug(format!("unboxed closure type {} used as a trans \
+                                  table key before its regions were erased",
+                                 t.repr(tcx)).as_slice())
+        }
+        _ => {}
+    }
+}
+
 pub fn monomorphize_type(bcx: &BlockS, t: ty::t) -> ty::t {
     t.subst(bcx.tcx(), &bcx.fcx.param_substs.substs)
 }
@@ -213,6 +213,10 @@ pub fn const_expr(cx: &CrateContext, e: &ast::Expr) -> (ValueRef, ty::t) {
                                 format!("unexpected static function: {}",
                                         store).as_slice())
                 }
+                ty::AdjustUnboxedClosureToFnPointer(_) => {
+                    cx.sess()
+                      .span_bug(e.span, "unexpected unboxed closure in a constant")
+                }
                 ty::AdjustDerefRef(ref adj) => {
                     let mut ty = ety;
                     // Save the last autoderef in case we can avoid it.
@@ -138,6 +138,17 @@ pub struct LocalCrateContext {
     /// Holds the LLVM values for closure IDs.
     unboxed_closure_vals: RefCell<FnvHashMap<MonoId, ValueRef>>,
 
+    /// Cache of bare fn wrappers for capture-free unboxed closures, keyed by
+    /// the LLVM value of the closure.
+    unboxed_closure_fn_pointers: RefCell<FnvHashMap<ValueRef, ValueRef>>,
+
+    /// Cache of shims that implement `Fn` for bare fn types, keyed by the
+    /// bare fn type.
+    fn_pointer_shims: RefCell<FnvHashMap<ty::t, ValueRef>>,
+
+    /// Cache of shims that clone unboxed closures, keyed by the closure type.
+    unboxed_closure_clone_shims: RefCell<FnvHashMap<ty::t, ValueRef>>,
+
     dbg_cx: Option<debuginfo::CrateDebugContext>,
 
     eh_personality: RefCell<Option<ValueRef>>,
@@ -418,6 +429,9 @@ impl LocalCrateContext {
                 opaque_vec_type: Type::from_ref(ptr::null_mut()),
                 builder: BuilderRef_res(llvm::LLVMCreateBuilderInContext(llcx)),
                 unboxed_closure_vals: RefCell::new(FnvHashMap::new()),
+                unboxed_closure_fn_pointers: RefCell::new(FnvHashMap::new()),
+                fn_pointer_shims: RefCell::new(FnvHashMap::new()),
+                unboxed_closure_clone_shims: RefCell::new(FnvHashMap::new()),
                 dbg_cx: dbg_cx,
                 eh_personality: RefCell::new(None),
                 intrinsics: RefCell::new(FnvHashMap::new()),
@@ -683,6 +697,20 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
         &self.local.unboxed_closure_vals
     }
 
+    pub fn unboxed_closure_fn_pointers<'a>(&'a self)
+                                           -> &'a RefCell<FnvHashMap<ValueRef, ValueRef>> {
+        &self.local.unboxed_closure_fn_pointers
+    }
+
+    pub fn fn_pointer_shims<'a>(&'a self) -> &'a RefCell<FnvHashMap<ty::t, ValueRef>> {
+        &self.local.fn_pointer_shims
+    }
+
+    pub fn unboxed_closure_clone_shims<'a>(&'a self)
+                                           -> &'a RefCell<FnvHashMap<ty::t, ValueRef>> {
+        &self.local.unboxed_closure_clone_shims
+    }
+
     pub fn dbg_cx<'a>(&'a self) -> &'a Option<debuginfo::CrateDebugContext> {
         &self.local.dbg_cx
     }
@@ -3395,7 +3395,7 @@ fn populate_scope_map(cx: &CrateContext,
                 walk_expr(cx, &**exp, scope_stack, scope_map);
             }
 
-            ast::PatRange(ref exp1, ref exp2) => {
+            ast::PatRange(ref exp1, ref exp2, _) => {
                 scope_map.insert(pat.id, scope_stack.last().unwrap().scope_metadata);
                 walk_expr(cx, &**exp1, scope_stack, scope_map);
                 walk_expr(cx, &**exp2, scope_stack, scope_map);
@@ -3417,6 +3417,14 @@ fn populate_scope_map(cx: &CrateContext,
                 }
             }
 
+            ast::PatOr(ref alternatives) => {
+                scope_map.insert(pat.id, scope_stack.last().unwrap().scope_metadata);
+
+                for sub_pat in alternatives.iter() {
+                    walk_pattern(cx, &**sub_pat, scope_stack, scope_map);
+                }
+            }
+
             ast::PatMac(_) => {
                 cx.sess().span_bug(pat.span, "debuginfo::populate_scope_map() - \
                                               Found unexpanded macro.");
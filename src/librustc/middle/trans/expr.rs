@@ -62,7 +62,7 @@ use middle::trans::inline;
 use middle::trans::tvec;
 use middle::trans::type_of;
 use middle::ty::{struct_fields, tup_fields};
-use middle::ty::{AdjustDerefRef, AdjustAddEnv, AutoUnsafe};
+use middle::ty::{AdjustDerefRef, AdjustAddEnv, AdjustUnboxedClosureToFnPointer, AutoUnsafe};
 use middle::ty::{AutoPtr};
 use middle::ty;
 use middle::typeck;
@@ -196,6 +196,9 @@ fn apply_adjustments<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         AdjustAddEnv(..) => {
             datum = unpack_datum!(bcx, add_env(bcx, expr, datum));
         }
+        AdjustUnboxedClosureToFnPointer(..) => {
+            datum = unpack_datum!(bcx, unboxed_closure_to_fn_pointer(bcx, expr, datum));
+        }
         AdjustDerefRef(ref adj) => {
             let (autoderefs, use_autoref) = match adj.autoref {
                 // Extracting a value from a box counts as a deref, but if we are
@@ -328,7 +331,8 @@ fn apply_adjustments<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                 let substs = principal.substs.with_self_ty(unsized_ty);
                 let trait_ref =
                     Rc::new(ty::TraitRef { def_id: principal.def_id,
-                                           substs: substs });
+                                           substs: substs,
+                                           binder_id: principal.binder_id });
                 let trait_ref =
                     trait_ref.subst(bcx.tcx(), &bcx.fcx.param_substs.substs);
                 let box_ty = mk_ty(unsized_ty);
@@ -484,6 +488,17 @@ fn apply_adjustments<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         let def = ty::resolve_expr(bcx.tcx(), expr);
         closure::make_closure_from_bare_fn(bcx, closure_ty, def, fn_ptr)
     }
+
+    fn unboxed_closure_to_fn_pointer<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
+                                                 expr: &ast::Expr,
+                                                 datum: Datum<Expr>)
+                                                 -> DatumBlock<'blk, 'tcx, Expr> {
+        // The closure captures nothing, so its value carries no data and
+        // can simply be dropped in favour of a pointer to its body.
+        let fn_ptr_ty = expr_ty_adjusted(bcx, expr);
+        let llfn = closure::get_fn_pointer_for_unboxed_closure(bcx, datum.ty, fn_ptr_ty);
+        DatumBlock::new(bcx, immediate_rvalue(llfn, fn_ptr_ty).to_expr_datum())
+    }
 }
 
 pub fn trans_to_lvalue<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
@@ -1849,7 +1864,7 @@ fn trans_overloaded_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                                                 meth::trans_method_callee(
                                                     bcx,
                                                     method_call,
-                                                    None,
+                                                    Some(callee),
                                                     arg_cleanup_scope)
                                             },
                                             callee::ArgOverloadedCall(all_args),
@@ -22,6 +22,7 @@ use middle::trans::build::*;
 use middle::trans::callee::*;
 use middle::trans::callee;
 use middle::trans::cleanup;
+use middle::trans::closure;
 use middle::trans::common::*;
 use middle::trans::datum::*;
 use middle::trans::expr::{SaveIn, Ignore};
@@ -241,7 +242,8 @@ pub fn trans_static_method_callee(bcx: Block,
                                              Vec::new()));
     debug!("trait_substs={}", trait_substs.repr(bcx.tcx()));
     let trait_ref = Rc::new(ty::TraitRef { def_id: trait_id,
-                                           substs: trait_substs });
+                                           substs: trait_substs,
+                                           binder_id: ast::DUMMY_NODE_ID });
     let vtbl = fulfill_obligation(bcx.ccx(),
                                   DUMMY_SP,
                                   trait_ref);
@@ -299,8 +301,8 @@ pub fn trans_static_method_callee(bcx: Block,
     }
 }
 
-fn method_with_name(ccx: &CrateContext, impl_id: ast::DefId, name: ast::Name)
-                    -> ast::DefId {
+pub fn method_with_name(ccx: &CrateContext, impl_id: ast::DefId, name: ast::Name)
+                        -> ast::DefId {
     match ccx.impl_method_cache().borrow().find_copy(&(impl_id, name)) {
         Some(m) => return m,
         None => {}
@@ -368,6 +370,16 @@ fn trans_monomorphized_callee<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                 data: Fn(llfn),
             }
         }
+        traits::VtableFnPointer(fn_ty) => {
+            let llfn = trans_fn_pointer_shim(bcx.ccx(), fn_ty);
+            Callee { bcx: bcx, data: Fn(llfn) }
+        }
+        traits::VtableUnboxedClosureClone(closure_def_id, substs, _) => {
+            let llfn = closure::get_clone_shim_for_unboxed_closure(bcx.ccx(),
+                                                                   closure_def_id,
+                                                                   &substs);
+            Callee { bcx: bcx, data: Fn(llfn) }
+        }
         _ => {
             bcx.tcx().sess.bug(
                 "vtable_param left in monomorphized function's vtable substs");
@@ -630,6 +642,10 @@ pub fn get_vtable(bcx: Block,
 
                 (vec!(llfn)).into_iter()
             }
+            traits::VtableFnPointer(fn_ty) => {
+                (vec!(trans_fn_pointer_shim(bcx.ccx(), fn_ty))).into_iter()
+            }
+            traits::VtableUnboxedClosureClone(..) |
             traits::VtableParam(..) => {
                 bcx.sess().bug(
                     format!("resolved vtable for {} to bad vtable {} in trans",
@@ -16,6 +16,7 @@ use middle::trans::common::*;
 use middle::trans::foreign;
 use middle::trans::machine;
 use middle::ty;
+use middle::ty_fold;
 use util::ppaux;
 use util::ppaux::Repr;
 
@@ -211,11 +212,16 @@ pub fn sizing_type_of(cx: &CrateContext, t: ty::t) -> Type {
             Type::array(&llty, size)
         }
 
-        ty::ty_tup(..) | ty::ty_enum(..) | ty::ty_unboxed_closure(..) => {
+        ty::ty_tup(..) | ty::ty_enum(..) => {
             let repr = adt::represent_type(cx, t);
             adt::sizing_type_of(cx, &*repr, false)
         }
 
+        ty::ty_unboxed_closure(..) => {
+            let repr = adt::represent_type(cx, ty_fold::erase_regions(cx.tcx(), t));
+            adt::sizing_type_of(cx, &*repr, false)
+        }
+
         ty::ty_struct(..) => {
             if ty::type_is_simd(cx.tcx(), t) {
                 let llet = type_of(cx, ty::simd_type(cx.tcx(), t));
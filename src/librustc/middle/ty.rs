@@ -20,6 +20,7 @@ use middle::dependency_format;
 use middle::lang_items::{FnTraitLangItem, FnMutTraitLangItem};
 use middle::lang_items::{FnOnceTraitLangItem, TyDescStructLangItem};
 use middle::mem_categorization as mc;
+use middle::mem_categorization::Typer;
 use middle::resolve;
 use middle::resolve_lifetime;
 use middle::stability;
@@ -62,7 +63,7 @@ pub const INITIAL_DISCRIMINANT_VALUE: Disr = 0;
 
 // Data types
 
-#[deriving(PartialEq, Eq, Hash)]
+#[deriving(Clone, PartialEq, Eq, Hash)]
 pub struct field {
     pub name: ast::Name,
     pub mt: mt
@@ -274,7 +275,10 @@ pub enum Variance {
 #[deriving(Clone, Show)]
 pub enum AutoAdjustment {
     AdjustAddEnv(ty::TraitStore),
-    AdjustDerefRef(AutoDerefRef)
+    AdjustDerefRef(AutoDerefRef),
+    // A capture-free unboxed closure coerced to a bare fn pointer. The
+    // `ty::t` is the type of the resulting fn pointer.
+    AdjustUnboxedClosureToFnPointer(ty::t),
 }
 
 #[deriving(Clone, PartialEq, Show)]
@@ -484,6 +488,19 @@ pub struct ctxt<'tcx> {
     pub ty_param_defs: RefCell<NodeMap<TypeParameterDef>>,
     pub adjustments: RefCell<NodeMap<AutoAdjustment>>,
     pub normalized_cache: RefCell<FnvHashMap<t, t>>,
+
+    /// Caches the results of `ty_fold::erase_regions` on types with
+    /// regions, and counts the lookups that hit and missed the cache.
+    pub erased_regions_cache: RefCell<FnvHashMap<t, t>>,
+    pub erased_regions_hits: Cell<uint>,
+    pub erased_regions_misses: Cell<uint>,
+
+    /// Calls made to counted folders, gathered under `-Z fold-stats`.
+    pub fold_stats: RefCell<ty_fold::FoldStats>,
+
+    /// The name of the `CountingFolder` whose folder is running, if any.
+    pub fold_stats_folder: Cell<Option<&'static str>>,
+
     pub lang_items: middle::lang_items::LanguageItems,
     /// A mapping of fake provided method def_ids to the default implementation
     pub provided_method_sources: RefCell<DefIdMap<ast::DefId>>,
@@ -508,6 +525,9 @@ pub struct ctxt<'tcx> {
     /// Maps a trait onto a list of impls of that trait.
     pub trait_impls: RefCell<DefIdMap<Rc<RefCell<Vec<ast::DefId>>>>>,
 
+    /// Caches the result of `all_impls_of_trait`.
+    pub trait_impl_infos: RefCell<DefIdMap<Rc<Vec<ImplInfo>>>>,
+
     /// Maps a DefId of a type to a list of its inherent impls.
     /// Contains implementations of methods that are inherent to a type.
     /// Methods in these implementations don't need to be exported.
@@ -566,6 +586,11 @@ pub struct ctxt<'tcx> {
     /// Maps closures to their capture clauses.
     pub capture_modes: RefCell<CaptureModeMap>,
 
+    /// Maps the upvars of unboxed closures not declared `move` to the
+    /// capture mode inferred for them from the closure body. Upvars not in
+    /// this table are captured according to their closure's capture clause.
+    pub upvar_capture_modes: RefCell<UpvarCaptureModeMap>,
+
     /// Maps def IDs to true if and only if they're associated types.
     pub associated_types: RefCell<DefIdMap<bool>>,
 
@@ -640,6 +665,7 @@ pub fn type_has_params(t: t) -> bool {
 }
 pub fn type_has_self(t: t) -> bool { tbox_has_flag(get(t), HAS_SELF) }
 pub fn type_has_ty_infer(t: t) -> bool { tbox_has_flag(get(t), HAS_TY_INFER) }
+pub fn type_has_regions(t: t) -> bool { tbox_has_flag(get(t), HAS_REGIONS) }
 pub fn type_needs_infer(t: t) -> bool {
     tbox_has_flag(get(t), HAS_TY_INFER | HAS_RE_INFER)
 }
@@ -716,7 +742,9 @@ pub enum Region {
 
     // Region bound in a function scope, which will be substituted when the
     // function is called. The first argument must be the `binder_id` of
-    // some enclosing function signature.
+    // some enclosing function signature. The binder is named rather than
+    // counted, so a value keeps its meaning when it is moved under other
+    // binders: there are no binder depths to shift.
     ReLateBound(/* binder_id */ ast::NodeId, BoundRegion),
 
     /// When checking a function body, the types of all arguments and so forth
@@ -978,10 +1006,17 @@ pub struct TyTrait {
     pub bounds: ExistentialBounds
 }
 
+/// A reference to a trait, such as `T : Iterator<int>`. A trait
+/// reference may bind regions of its own, as in `for<'a> Fn(&'a int)`
+/// or `Fn(&int)`: these appear in its substs as `ReLateBound` regions
+/// whose binder is `binder_id`, the node id of the trait reference (see
+/// `replace_late_bound_regions_in_trait_ref()`). A trait reference that
+/// binds no regions has the binder id `DUMMY_NODE_ID`.
 #[deriving(Clone, PartialEq, Eq, Hash, Show)]
 pub struct TraitRef {
     pub def_id: DefId,
     pub substs: Substs,
+    pub binder_id: ast::NodeId,
 }
 
 #[deriving(Clone, PartialEq)]
@@ -1252,7 +1287,7 @@ impl Generics {
 
 impl TraitRef {
     pub fn new(def_id: ast::DefId, substs: Substs) -> TraitRef {
-        TraitRef { def_id: def_id, substs: substs }
+        TraitRef { def_id: def_id, substs: substs, binder_id: ast::DUMMY_NODE_ID }
     }
 
     pub fn self_ty(&self) -> ty::t {
@@ -1522,12 +1557,18 @@ pub fn mk_ctxt<'tcx>(s: Session,
         ty_param_defs: RefCell::new(NodeMap::new()),
         adjustments: RefCell::new(NodeMap::new()),
         normalized_cache: RefCell::new(FnvHashMap::new()),
+        erased_regions_cache: RefCell::new(FnvHashMap::new()),
+        erased_regions_hits: Cell::new(0),
+        erased_regions_misses: Cell::new(0),
+        fold_stats: RefCell::new(FnvHashMap::new()),
+        fold_stats_folder: Cell::new(None),
         lang_items: lang_items,
         provided_method_sources: RefCell::new(DefIdMap::new()),
         struct_fields: RefCell::new(DefIdMap::new()),
         destructor_for_type: RefCell::new(DefIdMap::new()),
         destructors: RefCell::new(DefIdSet::new()),
         trait_impls: RefCell::new(DefIdMap::new()),
+        trait_impl_infos: RefCell::new(DefIdMap::new()),
         inherent_impls: RefCell::new(DefIdMap::new()),
         impl_items: RefCell::new(DefIdMap::new()),
         used_unsafe: RefCell::new(NodeSet::new()),
@@ -1544,6 +1585,7 @@ pub fn mk_ctxt<'tcx>(s: Session,
         transmute_restrictions: RefCell::new(Vec::new()),
         stability: RefCell::new(stability),
         capture_modes: capture_modes,
+        upvar_capture_modes: RefCell::new(FnvHashMap::new()),
         associated_types: RefCell::new(DefIdMap::new()),
         selection_cache: traits::SelectionCache::new(),
         repr_hint_cache: RefCell::new(DefIdMap::new()),
@@ -1783,6 +1825,16 @@ pub fn mk_enum(cx: &ctxt, did: ast::DefId, substs: Substs) -> t {
 
 pub fn mk_uniq(cx: &ctxt, ty: t) -> t { mk_t(cx, ty_uniq(ty)) }
 
+/// The type that stands for a `!` output in the parenthetical notation,
+/// as in `FnOnce() -> !`, where the output must be a type: the empty
+/// `diverging_output` lang item. Without the lang item, `()`.
+pub fn mk_diverging_output(cx: &ctxt) -> t {
+    match cx.lang_items.diverging_output() {
+        Some(did) => mk_enum(cx, did, Substs::empty()),
+        None => mk_nil(),
+    }
+}
+
 pub fn mk_ptr(cx: &ctxt, tm: mt) -> t { mk_t(cx, ty_ptr(tm)) }
 
 pub fn mk_rptr(cx: &ctxt, r: Region, tm: mt) -> t { mk_t(cx, ty_rptr(r, tm)) }
@@ -1899,6 +1951,22 @@ pub fn mk_param_from_def(cx: &ctxt, def: &TypeParameterDef) -> t {
 
 pub fn mk_open(cx: &ctxt, t: t) -> t { mk_t(cx, ty_open(t)) }
 
+/// Returns true if `t` was interned in `cx`, or is one of the primitive
+/// types that `mk_t` shares between all contexts.
+pub fn type_is_interned_in(cx: &ctxt, t: t) -> bool {
+    let t_box = get(t);
+    match t_box.sty {
+        ty_nil | ty_err | ty_bool | ty_int(_) | ty_uint(_) | ty_float(_) | ty_char => true,
+        _ => {
+            let key = intern_key { sty: &t_box.sty };
+            match cx.interner.borrow().get(&key) {
+                Some(interned) => (*interned as *const t_box_) == (t_box as *const t_box_),
+                None => false,
+            }
+        }
+    }
+}
+
 pub fn walk_ty(ty: t, f: |t|) {
     maybe_walk_ty(ty, |t| { f(t); true });
 }
@@ -1944,7 +2012,7 @@ pub fn maybe_walk_ty(ty: t, f: |t| -> bool) {
 
 // Folds types from the bottom up.
 pub fn fold_ty(cx: &ctxt, t0: t, fldop: |t| -> t) -> t {
-    let mut f = ty_fold::BottomUpFolder {tcx: cx, fldop: fldop};
+    let mut f = ty_fold::BottomUpFolder::new(cx, fldop);
     f.fold_ty(t0)
 }
 
@@ -1999,6 +2067,22 @@ pub fn type_is_nil(ty: t) -> bool {
     get(ty).sty == ty_nil
 }
 
+pub fn type_is_diverging_output(cx: &ctxt, ty: t) -> bool {
+    match get(ty).sty {
+        ty_enum(did, _) => cx.lang_items.diverging_output() == Some(did),
+        _ => false
+    }
+}
+
+/// The output of a fn signature as the `Result` parameter of a function
+/// trait: a diverging fn gives the type `mk_diverging_output` gives.
+pub fn fn_output_as_ty(cx: &ctxt, output: FnOutput) -> t {
+    match output {
+        FnConverging(output) => output,
+        FnDiverging => mk_diverging_output(cx)
+    }
+}
+
 pub fn type_is_error(ty: t) -> bool {
     get(ty).flags.intersects(HAS_TY_ERR)
 }
@@ -2140,6 +2224,17 @@ pub fn type_is_scalar(ty: t) -> bool {
     }
 }
 
+/// True for the types that have no component types or regions, such as
+/// `int` or a type parameter. Folding one cannot change it other than by
+/// replacing it as a whole.
+pub fn type_is_leaf(ty: t) -> bool {
+    match get(ty).sty {
+      ty_nil | ty_bool | ty_char | ty_str | ty_int(_) | ty_uint(_) | ty_float(_) |
+      ty_err | ty_infer(_) | ty_param(..) => true,
+      _ => false
+    }
+}
+
 /// Returns true if this type is a floating point type and false otherwise.
 pub fn type_is_floating_point(ty: t) -> bool {
     match get(ty).sty {
@@ -2488,11 +2583,17 @@ pub fn type_contents(cx: &ctxt, ty: t) -> TypeContents {
 
             ty_unboxed_closure(did, r, ref substs) => {
                 // FIXME(#14449): `borrowed_contents` below assumes `&mut`
-                // unboxed closure.
+                // unboxed closure, unless the closure holds its upvars by
+                // value, in which case it is `Copy` if they all are.
                 let upvars = unboxed_closure_upvars(cx, did, substs);
+                let mutbl = if unboxed_closure_captures_by_value(cx, did) {
+                    MutImmutable
+                } else {
+                    MutMutable
+                };
                 TypeContents::union(upvars.as_slice(),
                                     |f| tc_ty(cx, f.ty, cache)) |
-                    borrowed_contents(r, MutMutable)
+                    borrowed_contents(r, mutbl)
             }
 
             ty_tup(ref tys) => {
@@ -2866,7 +2967,7 @@ pub fn is_type_representable(cx: &ctxt, sp: Span, ty: t) -> Representability {
                 let vs = enum_variants(cx, did);
                 let iter = vs.iter()
                     .flat_map(|variant| { variant.args.iter() })
-                    .map(|aty| { aty.subst_spanned(cx, substs, Some(sp)) });
+                    .map(|aty| { aty.subst_for_item(cx, substs, did, Some(sp)) });
 
                 find_nonrepresentable(cx, sp, seen, iter)
             }
@@ -3441,6 +3542,8 @@ pub fn adjust_ty(cx: &ctxt,
                     }
                 }
 
+                AdjustUnboxedClosureToFnPointer(fn_ptr_ty) => fn_ptr_ty,
+
                 AdjustDerefRef(ref adj) => {
                     let mut adjusted_ty = unadjusted_ty;
 
@@ -4375,6 +4478,28 @@ pub fn type_is_empty(cx: &ctxt, t: t) -> bool {
      }
 }
 
+/// Whether no value of type `t` can ever be constructed: an enum none of whose
+/// variants can be constructed, or a struct, tuple or non-empty fixed-length
+/// array with a component that can't be.
+///
+/// Pointers are conservatively assumed to point to something, even when their
+/// referent type is uninhabited, so this never looks through a recursive type.
+pub fn type_is_uninhabited(cx: &ctxt, t: t) -> bool {
+    match get(t).sty {
+        ty_enum(did, ref substs) => {
+            substd_enum_variants(cx, did, substs).iter().all(|variant| {
+                variant.args.iter().any(|&arg| type_is_uninhabited(cx, arg))
+            })
+        }
+        ty_struct(did, ref substs) => {
+            struct_fields(cx, did, substs).iter().any(|f| type_is_uninhabited(cx, f.mt.ty))
+        }
+        ty_tup(ref ts) => ts.iter().any(|&t| type_is_uninhabited(cx, t)),
+        ty_vec(t, Some(n)) => n > 0 && type_is_uninhabited(cx, t),
+        _ => false
+    }
+}
+
 pub fn enum_variants(cx: &ctxt, id: ast::DefId) -> Rc<Vec<Rc<VariantInfo>>> {
     memoized(&cx.enum_var_cache, id, |id: ast::DefId| {
         if ast::LOCAL_CRATE != id.krate {
@@ -4476,7 +4601,7 @@ pub fn bounds_for_trait_ref(tcx: &ctxt,
     let trait_def = lookup_trait_def(tcx, trait_ref.def_id);
     debug!("bounds_for_trait_ref(trait_def={}, trait_ref={})",
            trait_def.repr(tcx), trait_ref.repr(tcx));
-    trait_def.bounds.subst(tcx, &trait_ref.substs)
+    (*trait_def).subst(tcx, &trait_ref.substs).bounds
 }
 
 /// Iterate over attributes of a definition.
@@ -4547,7 +4672,15 @@ pub fn lookup_field_type(tcx: &ctxt,
                          id: DefId,
                          substs: &Substs)
                       -> ty::t {
-    let t = if id.krate == ast::LOCAL_CRATE {
+    lookup_field_type_unsubstituted(tcx, struct_id, id).subst(tcx, substs)
+}
+
+// Look up the declared type of a field, before any substitution.
+fn lookup_field_type_unsubstituted(tcx: &ctxt,
+                                   struct_id: DefId,
+                                   id: DefId)
+                                   -> ty::t {
+    if id.krate == ast::LOCAL_CRATE {
         node_id_to_type(tcx, id.node)
     } else {
         let mut tcache = tcx.tcache.borrow_mut();
@@ -4556,8 +4689,7 @@ pub fn lookup_field_type(tcx: &ctxt,
             Vacant(entry) => entry.set(csearch::get_field_type(tcx, struct_id, id)),
         };
         pty.ty
-    };
-    t.subst(tcx, substs)
+    }
 }
 
 // Look up the list of field names and IDs for a given struct.
@@ -4587,15 +4719,16 @@ pub fn is_tuple_struct(cx: &ctxt, did: ast::DefId) -> bool {
 // this. Takes a list of substs with which to instantiate field types.
 pub fn struct_fields(cx: &ctxt, did: ast::DefId, substs: &Substs)
                      -> Vec<field> {
-    lookup_struct_fields(cx, did).iter().map(|f| {
+    let fields: Vec<field> = lookup_struct_fields(cx, did).iter().map(|f| {
        field {
             name: f.name,
             mt: mt {
-                ty: lookup_field_type(cx, did, f.id, substs),
+                ty: lookup_field_type_unsubstituted(cx, did, f.id),
                 mutbl: MutImmutable
             }
         }
-    }).collect()
+    }).collect();
+    fields.subst_for_item(cx, substs, did, None)
 }
 
 // Returns a list of fields corresponding to the tuple's items. trans uses
@@ -4627,24 +4760,28 @@ pub fn unboxed_closure_upvars(tcx: &ctxt, closure_id: ast::DefId, substs: &Subst
     // This may change if abstract return types of some sort are
     // implemented.
     assert!(closure_id.krate == ast::LOCAL_CRATE);
-    let capture_mode = tcx.capture_modes.borrow().get_copy(&closure_id.node);
     match tcx.freevars.borrow().get(&closure_id.node) {
         None => vec![],
         Some(ref freevars) => {
             freevars.iter().map(|freevar| {
                 let freevar_def_id = freevar.def.def_id();
                 let freevar_ty = node_id_to_type(tcx, freevar_def_id.node);
-                let mut freevar_ty = freevar_ty.subst(tcx, substs);
-                if capture_mode == ast::CaptureByRef {
-                    let borrow = tcx.upvar_borrow_map.borrow().get_copy(&ty::UpvarId {
-                        var_id: freevar_def_id.node,
-                        closure_expr_id: closure_id.node
-                    });
-                    freevar_ty = mk_rptr(tcx, borrow.region, ty::mt {
+                let upvar_id = ty::UpvarId {
+                    var_id: freevar_def_id.node,
+                    closure_expr_id: closure_id.node
+                };
+                let freevar_ty = if tcx.upvar_capture_mode(upvar_id) == ast::CaptureByRef {
+                    // The borrow region may mention the closure's own
+                    // region parameters, so substitute it along with the type.
+                    let borrow = tcx.upvar_borrow_map.borrow().get_copy(&upvar_id);
+                    let (freevar_ty, region) = (freevar_ty, borrow.region).subst(tcx, substs);
+                    mk_rptr(tcx, region, ty::mt {
                         ty: freevar_ty,
                         mutbl: borrow.kind.to_mutbl_lossy()
-                    });
-                }
+                    })
+                } else {
+                    freevar_ty.subst(tcx, substs)
+                };
                 UnboxedClosureUpvar {
                     def: freevar.def,
                     span: freevar.span,
@@ -4655,6 +4792,17 @@ pub fn unboxed_closure_upvars(tcx: &ctxt, closure_id: ast::DefId, substs: &Subst
     }
 }
 
+/// Returns true if the given unboxed closure is declared `move` or
+/// captures nothing, so that its environment holds nothing but upvars
+/// by value. Such a closure is `Copy` or `Clone` whenever all of its upvars
+/// are. Any other closure may hold unique borrows of its upvars, and is
+/// neither.
+pub fn unboxed_closure_captures_by_value(tcx: &ctxt, closure_id: ast::DefId) -> bool {
+    assert!(closure_id.krate == ast::LOCAL_CRATE);
+    tcx.capture_mode(closure_id.node) == ast::CaptureByValue ||
+        with_freevars(tcx, closure_id.node, |freevars| freevars.is_empty())
+}
+
 pub fn is_binopable(cx: &ctxt, ty: t, op: ast::BinOp) -> bool {
     #![allow(non_upper_case_globals)]
     static tycat_other: int = 0;
@@ -4927,6 +5075,56 @@ pub fn record_trait_implementation(tcx: &ctxt,
     tcx.trait_impls.borrow_mut().insert(trait_def_id, Rc::new(RefCell::new(vec!(impl_def_id))));
 }
 
+/// An implementation of a trait, as listed by `all_impls_of_trait`.
+#[deriving(Clone)]
+pub struct ImplInfo {
+    pub def_id: ast::DefId,
+    pub trait_ref: Rc<TraitRef>,
+    /// The self type, in terms of the impl's own type parameters.
+    pub self_ty: t,
+    pub location: ImplLocation,
+    pub generics: Generics,
+}
+
+#[deriving(Clone)]
+pub enum ImplLocation {
+    /// An impl in this crate, at the given span.
+    LocalImpl(Span),
+    /// An impl in the named external crate.
+    ExternalImpl(String),
+}
+
+/// Lists every impl of the given trait known to this crate: its own impls,
+/// followed by those of the external crates. The list is cached, so this
+/// must not be called before coherence has recorded the local impls.
+pub fn all_impls_of_trait(tcx: &ctxt, trait_def_id: ast::DefId) -> Rc<Vec<ImplInfo>> {
+    memoized(&tcx.trait_impl_infos, trait_def_id, |trait_def_id: ast::DefId| {
+        populate_implementations_for_trait_if_necessary(tcx, trait_def_id);
+        let impl_def_ids = match tcx.trait_impls.borrow().get(&trait_def_id) {
+            Some(impls) => impls.borrow().clone(),
+            None => Vec::new(),
+        };
+
+        let (local, external) = impl_def_ids.partition(|id| id.krate == ast::LOCAL_CRATE);
+        let impls = local.into_iter().chain(external.into_iter()).map(|impl_def_id| {
+            let trait_ref = impl_trait_ref(tcx, impl_def_id).unwrap();
+            let location = if impl_def_id.krate == ast::LOCAL_CRATE {
+                LocalImpl(tcx.map.span(impl_def_id.node))
+            } else {
+                ExternalImpl(tcx.sess.cstore.get_crate_data(impl_def_id.krate).name.clone())
+            };
+            ImplInfo {
+                def_id: impl_def_id,
+                self_ty: trait_ref.self_ty(),
+                trait_ref: trait_ref,
+                location: location,
+                generics: lookup_item_type(tcx, impl_def_id).generics,
+            }
+        }).collect();
+        Rc::new(impls)
+    })
+}
+
 /// Populates the type context with all the implementations for the given type
 /// if necessary.
 pub fn populate_implementations_for_type_if_necessary(tcx: &ctxt,
@@ -5272,18 +5470,14 @@ pub fn construct_parameter_environment(
     //
 
     // map T => T
-    let mut types = VecPerParamSpace::empty();
-    for &space in subst::ParamSpace::all().iter() {
-        push_types_from_defs(tcx, &mut types, space,
-                             generics.types.get_slice(space));
-    }
+    let types = generics.types.map_enumerated(|space, index, def| {
+        debug!("construct_parameter_environment(): space={} def={} index={}",
+               space, def.repr(tcx), index);
+        ty::mk_param(tcx, space, index, def.def_id)
+    });
 
     // map bound 'a => free 'a
-    let mut regions = VecPerParamSpace::empty();
-    for &space in subst::ParamSpace::all().iter() {
-        push_region_params(&mut regions, space, free_id,
-                           generics.regions.get_slice(space));
-    }
+    let regions = generics.regions.map(|def| ty::free_region_from_def(free_id, def));
 
     let free_substs = Substs {
         types: types,
@@ -5330,31 +5524,6 @@ pub fn construct_parameter_environment(
         selection_cache: traits::SelectionCache::new(),
     };
 
-    fn push_region_params(regions: &mut VecPerParamSpace<ty::Region>,
-                          space: subst::ParamSpace,
-                          free_id: ast::NodeId,
-                          region_params: &[RegionParameterDef])
-    {
-        for r in region_params.iter() {
-            regions.push(space, ty::free_region_from_def(free_id, r));
-        }
-    }
-
-    fn push_types_from_defs(tcx: &ty::ctxt,
-                            types: &mut subst::VecPerParamSpace<ty::t>,
-                            space: subst::ParamSpace,
-                            defs: &[TypeParameterDef]) {
-        for (i, def) in defs.iter().enumerate() {
-            debug!("construct_parameter_environment(): push_types_from_defs: \
-                    space={} def={} index={}",
-                   space,
-                   def.repr(tcx),
-                   i);
-            let ty = ty::mk_param(tcx, space, i, def.def_id);
-            types.push(space, ty);
-        }
-    }
-
     fn push_bounds_from_defs(tcx: &ty::ctxt,
                              bounds: &mut subst::VecPerParamSpace<ParamBounds>,
                              space: subst::ParamSpace,
@@ -5469,6 +5638,13 @@ impl<'tcx> mc::Typer<'tcx> for ty::ctxt<'tcx> {
         self.capture_modes.borrow().get_copy(&closure_expr_id)
     }
 
+    fn upvar_capture_mode(&self, upvar_id: ty::UpvarId) -> ast::CaptureClause {
+        match self.upvar_capture_modes.borrow().get(&upvar_id) {
+            Some(&mode) => mode,
+            None => self.capture_mode(upvar_id.closure_expr_id),
+        }
+    }
+
     fn unboxed_closures<'a>(&'a self)
                         -> &'a RefCell<DefIdMap<UnboxedClosure>> {
         &self.unboxed_closures
@@ -5558,6 +5734,8 @@ pub type FreevarMap = NodeMap<Vec<Freevar>>;
 
 pub type CaptureModeMap = NodeMap<ast::CaptureClause>;
 
+pub type UpvarCaptureModeMap = FnvHashMap<UpvarId, ast::CaptureClause>;
+
 pub fn with_freevars<T>(tcx: &ty::ctxt, fid: ast::NodeId, f: |&[Freevar]| -> T) -> T {
     match tcx.freevars.borrow().get(&fid) {
         None => f(&[]),
@@ -5568,7 +5746,7 @@ pub fn with_freevars<T>(tcx: &ty::ctxt, fid: ast::NodeId, f: |&[Freevar]| -> T)
 impl AutoAdjustment {
     pub fn is_identity(&self) -> bool {
         match *self {
-            AdjustAddEnv(..) => false,
+            AdjustAddEnv(..) | AdjustUnboxedClosureToFnPointer(..) => false,
             AdjustDerefRef(ref r) => r.is_identity(),
         }
     }
@@ -34,6 +34,11 @@
  * behavior cannot be overriden, but it's much less work to implement.
  * If you ever *do* need an override that doesn't exist, it's not hard
  * to convert the degenerate pattern into the proper thing.
+ *
+ * A folder that can fail implements `FallibleTypeFolder<E>` instead,
+ * whose methods return `Result<T, E>`, and is run with
+ * `T.try_fold_with(F)`. The infallible folds are built on top of the
+ * fallible ones, so both traverse types in exactly the same way.
  */
 
 use middle::subst;
@@ -41,98 +46,975 @@ use middle::subst::VecPerParamSpace;
 use middle::ty;
 use middle::traits;
 use middle::typeck;
+use std::collections::HashMap;
+use std::collections::hash_map::{Occupied, Vacant};
+use std::default::Default;
+use std::hash::{Hash, Hasher};
+use std::mem;
 use std::rc::Rc;
 use syntax::ast;
 use syntax::owned_slice::OwnedSlice;
+use util::nodemap::{FnvHashMap, FnvHashSet, NodeMap, NodeSet};
 use util::ppaux::Repr;
 
 ///////////////////////////////////////////////////////////////////////////
-// Two generic traits
+// Three generic traits
+
+/// The TypeFoldable trait is implemented for every type that can be folded.
+/// Basically, every type that has a corresponding method in TypeFolder.
+///
+/// Only `try_fold_with` is implemented for each type; `fold_with` runs
+/// it with a folder that cannot fail, so the two can never disagree.
+pub trait TypeFoldable {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<Self, E>;
+
+    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Self {
+        infallible(self.try_fold_with(&mut Infallible { folder: folder }))
+    }
+
+    /// Like `try_fold_with`, but gives `None` when the fold leaves `self`
+    /// as it is, so that the caller can keep the value it has instead of
+    /// building an equal one. Only types that can tell cheaply (`ty::t`,
+    /// regions, and vectors of them) ever give `None`.
+    fn try_fold_if_changed<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                           -> Result<Option<Self>, E> {
+        Ok(Some(try!(self.try_fold_with(folder))))
+    }
+
+    /// Like `fold_with`, but takes the folder as a trait object, so that
+    /// callers need not know its type.
+    fn fold_with_dyn<'tcx>(&self, folder: &mut DynTypeFolder<'tcx>) -> Self {
+        self.fold_with(&mut DynShim { folder: folder })
+    }
+}
+
+/// The TypeFolder trait defines the actual *folding*. There is a
+/// method defined for every foldable type. Each of these has a
+/// default implementation that does an "identity" fold. Within each
+/// identity fold, it should invoke `foo.fold_with(self)` to fold each
+/// sub-item.
+pub trait TypeFolder<'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx>;
+
+    /// Returns true if folding `t` cannot change it, judging by the
+    /// flags cached on the interned type; `super_fold_ty` then returns
+    /// `t` without visiting its components.
+    fn skip_ty(&self, _t: ty::t) -> bool {
+        false
+    }
+
+    /// Called by `super_fold_sty` around the components of a fn type:
+    /// its late-bound regions, `ReLateBound(binder_id, _)`, are bound
+    /// within everything folded between the two calls.
+    fn enter_region_binder(&mut self, _binder_id: ast::NodeId) {}
+
+    fn exit_region_binder(&mut self, _binder_id: ast::NodeId) {}
+
+    fn fold_ty(&mut self, t: ty::t) -> ty::t {
+        super_fold_ty(self, t)
+    }
+
+    fn fold_mt(&mut self, t: &ty::mt) -> ty::mt {
+        super_fold_mt(self, t)
+    }
+
+    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> ty::TraitRef {
+        super_fold_trait_ref(self, t)
+    }
+
+    /// Not called by `super_fold_ty` for leaf types (see
+    /// `ty::type_is_leaf`), which have nothing to fold: override
+    /// `fold_ty` to see those.
+    fn fold_sty(&mut self, sty: &ty::sty) -> ty::sty {
+        super_fold_sty(self, sty)
+    }
+
+    fn fold_substs(&mut self,
+                   substs: &subst::Substs)
+                   -> subst::Substs {
+        super_fold_substs(self, substs)
+    }
+
+    fn fold_sig(&mut self,
+                sig: &ty::FnSig)
+                -> ty::FnSig {
+        super_fold_sig(self, sig)
+    }
+
+    /// Folds the return slot of a fn signature; `super_fold_sig` calls
+    /// it for `sig.output` only, never for the argument types.
+    fn fold_output(&mut self,
+                      output: &ty::FnOutput)
+                      -> ty::FnOutput {
+        super_fold_output(self, output)
+    }
+
+    fn fold_bare_fn_ty(&mut self,
+                       fty: &ty::BareFnTy)
+                       -> ty::BareFnTy
+    {
+        super_fold_bare_fn_ty(self, fty)
+    }
+
+    fn fold_closure_ty(&mut self,
+                       fty: &ty::ClosureTy)
+                       -> ty::ClosureTy {
+        super_fold_closure_ty(self, fty)
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        r
+    }
+
+    fn fold_trait_store(&mut self, s: ty::TraitStore) -> ty::TraitStore {
+        super_fold_trait_store(self, s)
+    }
+
+    fn fold_existential_bounds(&mut self, s: ty::ExistentialBounds)
+                               -> ty::ExistentialBounds {
+        super_fold_existential_bounds(self, s)
+    }
+
+    fn fold_autoref(&mut self, ar: &ty::AutoRef) -> ty::AutoRef {
+        super_fold_autoref(self, ar)
+    }
+
+    fn fold_adjustment(&mut self, adj: &ty::AutoAdjustment) -> ty::AutoAdjustment {
+        super_fold_adjustment(self, adj)
+    }
+
+    fn fold_item_substs(&mut self, i: ty::ItemSubsts) -> ty::ItemSubsts {
+        super_fold_item_substs(self, i)
+    }
+
+    fn fold_obligation(&mut self, o: &traits::Obligation) -> traits::Obligation {
+        super_fold_obligation(self, o)
+    }
+
+    fn fold_param_bounds(&mut self, b: &ty::ParamBounds) -> ty::ParamBounds {
+        super_fold_param_bounds(self, b)
+    }
+
+    fn fold_type_parameter_def(&mut self, d: &ty::TypeParameterDef)
+                               -> ty::TypeParameterDef {
+        super_fold_type_parameter_def(self, d)
+    }
+
+    fn fold_generics(&mut self, g: &ty::Generics) -> ty::Generics {
+        super_fold_generics(self, g)
+    }
+
+    fn fold_trait_def(&mut self, d: &ty::TraitDef) -> ty::TraitDef {
+        super_fold_trait_def(self, d)
+    }
+
+    fn fold_method_origin(&mut self, o: &typeck::MethodOrigin) -> typeck::MethodOrigin {
+        super_fold_method_origin(self, o)
+    }
+
+    fn fold_method_callee(&mut self, m: &typeck::MethodCallee) -> typeck::MethodCallee {
+        super_fold_method_callee(self, m)
+    }
+}
+
+/// The FallibleTypeFolder trait is a TypeFolder whose folds may fail
+/// with an error of the folder's choosing, `E`. The first error stops
+/// the fold and is returned from `try_fold_with`. Override `fold_T()`
+/// and invoke `try_super_fold_T()` exactly as with TypeFolder.
+pub trait FallibleTypeFolder<'tcx, E> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx>;
+
+    fn skip_ty(&self, _t: ty::t) -> bool {
+        false
+    }
+
+    fn enter_region_binder(&mut self, _binder_id: ast::NodeId) {}
+
+    fn exit_region_binder(&mut self, _binder_id: ast::NodeId) {}
+
+    fn fold_ty(&mut self, t: ty::t) -> Result<ty::t, E> {
+        try_super_fold_ty(self, t)
+    }
+
+    fn fold_mt(&mut self, t: &ty::mt) -> Result<ty::mt, E> {
+        try_super_fold_mt(self, t)
+    }
+
+    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> Result<ty::TraitRef, E> {
+        try_super_fold_trait_ref(self, t)
+    }
+
+    fn fold_sty(&mut self, sty: &ty::sty) -> Result<ty::sty, E> {
+        try_super_fold_sty(self, sty)
+    }
+
+    fn fold_substs(&mut self,
+                   substs: &subst::Substs)
+                   -> Result<subst::Substs, E> {
+        try_super_fold_substs(self, substs)
+    }
+
+    fn fold_sig(&mut self,
+                sig: &ty::FnSig)
+                -> Result<ty::FnSig, E> {
+        try_super_fold_sig(self, sig)
+    }
+
+    fn fold_output(&mut self,
+                   output: &ty::FnOutput)
+                   -> Result<ty::FnOutput, E> {
+        try_super_fold_output(self, output)
+    }
+
+    fn fold_bare_fn_ty(&mut self,
+                       fty: &ty::BareFnTy)
+                       -> Result<ty::BareFnTy, E>
+    {
+        try_super_fold_bare_fn_ty(self, fty)
+    }
+
+    fn fold_closure_ty(&mut self,
+                       fty: &ty::ClosureTy)
+                       -> Result<ty::ClosureTy, E> {
+        try_super_fold_closure_ty(self, fty)
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> Result<ty::Region, E> {
+        Ok(r)
+    }
+
+    fn fold_trait_store(&mut self, s: ty::TraitStore) -> Result<ty::TraitStore, E> {
+        try_super_fold_trait_store(self, s)
+    }
+
+    fn fold_existential_bounds(&mut self, s: ty::ExistentialBounds)
+                               -> Result<ty::ExistentialBounds, E> {
+        try_super_fold_existential_bounds(self, s)
+    }
+
+    fn fold_autoref(&mut self, ar: &ty::AutoRef) -> Result<ty::AutoRef, E> {
+        try_super_fold_autoref(self, ar)
+    }
+
+    fn fold_adjustment(&mut self, adj: &ty::AutoAdjustment) -> Result<ty::AutoAdjustment, E> {
+        try_super_fold_adjustment(self, adj)
+    }
+
+    fn fold_item_substs(&mut self, i: ty::ItemSubsts) -> Result<ty::ItemSubsts, E> {
+        try_super_fold_item_substs(self, i)
+    }
+
+    fn fold_obligation(&mut self, o: &traits::Obligation) -> Result<traits::Obligation, E> {
+        try_super_fold_obligation(self, o)
+    }
+
+    fn fold_param_bounds(&mut self, b: &ty::ParamBounds) -> Result<ty::ParamBounds, E> {
+        try_super_fold_param_bounds(self, b)
+    }
+
+    fn fold_type_parameter_def(&mut self, d: &ty::TypeParameterDef)
+                               -> Result<ty::TypeParameterDef, E> {
+        try_super_fold_type_parameter_def(self, d)
+    }
+
+    fn fold_generics(&mut self, g: &ty::Generics) -> Result<ty::Generics, E> {
+        try_super_fold_generics(self, g)
+    }
+
+    fn fold_trait_def(&mut self, d: &ty::TraitDef) -> Result<ty::TraitDef, E> {
+        try_super_fold_trait_def(self, d)
+    }
+
+    fn fold_method_origin(&mut self, o: &typeck::MethodOrigin)
+                          -> Result<typeck::MethodOrigin, E> {
+        try_super_fold_method_origin(self, o)
+    }
+
+    fn fold_method_callee(&mut self, m: &typeck::MethodCallee)
+                          -> Result<typeck::MethodCallee, E> {
+        try_super_fold_method_callee(self, m)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// The infallible folds, in terms of the fallible ones.
+//
+// `Infallible` forwards each fold to the wrapped TypeFolder, so that
+// its overrides are still called for every sub-item.
+
+enum NoError {}
+
+fn infallible<T>(r: Result<T, NoError>) -> T {
+    match r {
+        Ok(t) => t,
+        Err(e) => match e {}
+    }
+}
+
+struct Infallible<'a, F: 'a> {
+    folder: &'a mut F,
+}
+
+impl<'a, 'tcx, E, F: TypeFolder<'tcx>> FallibleTypeFolder<'tcx, E> for Infallible<'a, F> {
+    fn tcx<'b>(&'b self) -> &'b ty::ctxt<'tcx> { self.folder.tcx() }
+
+    fn skip_ty(&self, t: ty::t) -> bool {
+        self.folder.skip_ty(t)
+    }
+
+    fn enter_region_binder(&mut self, binder_id: ast::NodeId) {
+        self.folder.enter_region_binder(binder_id)
+    }
+
+    fn exit_region_binder(&mut self, binder_id: ast::NodeId) {
+        self.folder.exit_region_binder(binder_id)
+    }
+
+    fn fold_ty(&mut self, t: ty::t) -> Result<ty::t, E> {
+        Ok(self.folder.fold_ty(t))
+    }
+
+    fn fold_mt(&mut self, t: &ty::mt) -> Result<ty::mt, E> {
+        Ok(self.folder.fold_mt(t))
+    }
+
+    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> Result<ty::TraitRef, E> {
+        Ok(self.folder.fold_trait_ref(t))
+    }
+
+    fn fold_sty(&mut self, sty: &ty::sty) -> Result<ty::sty, E> {
+        Ok(self.folder.fold_sty(sty))
+    }
+
+    fn fold_substs(&mut self, substs: &subst::Substs) -> Result<subst::Substs, E> {
+        Ok(self.folder.fold_substs(substs))
+    }
+
+    fn fold_sig(&mut self, sig: &ty::FnSig) -> Result<ty::FnSig, E> {
+        Ok(self.folder.fold_sig(sig))
+    }
+
+    fn fold_output(&mut self, output: &ty::FnOutput) -> Result<ty::FnOutput, E> {
+        Ok(self.folder.fold_output(output))
+    }
+
+    fn fold_bare_fn_ty(&mut self, fty: &ty::BareFnTy) -> Result<ty::BareFnTy, E> {
+        Ok(self.folder.fold_bare_fn_ty(fty))
+    }
+
+    fn fold_closure_ty(&mut self, fty: &ty::ClosureTy) -> Result<ty::ClosureTy, E> {
+        Ok(self.folder.fold_closure_ty(fty))
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> Result<ty::Region, E> {
+        Ok(self.folder.fold_region(r))
+    }
+
+    fn fold_trait_store(&mut self, s: ty::TraitStore) -> Result<ty::TraitStore, E> {
+        Ok(self.folder.fold_trait_store(s))
+    }
+
+    fn fold_existential_bounds(&mut self, s: ty::ExistentialBounds)
+                               -> Result<ty::ExistentialBounds, E> {
+        Ok(self.folder.fold_existential_bounds(s))
+    }
+
+    fn fold_autoref(&mut self, ar: &ty::AutoRef) -> Result<ty::AutoRef, E> {
+        Ok(self.folder.fold_autoref(ar))
+    }
+
+    fn fold_adjustment(&mut self, adj: &ty::AutoAdjustment)
+                       -> Result<ty::AutoAdjustment, E> {
+        Ok(self.folder.fold_adjustment(adj))
+    }
+
+    fn fold_item_substs(&mut self, i: ty::ItemSubsts) -> Result<ty::ItemSubsts, E> {
+        Ok(self.folder.fold_item_substs(i))
+    }
+
+    fn fold_obligation(&mut self, o: &traits::Obligation)
+                       -> Result<traits::Obligation, E> {
+        Ok(self.folder.fold_obligation(o))
+    }
+
+    fn fold_param_bounds(&mut self, b: &ty::ParamBounds) -> Result<ty::ParamBounds, E> {
+        Ok(self.folder.fold_param_bounds(b))
+    }
+
+    fn fold_type_parameter_def(&mut self, d: &ty::TypeParameterDef)
+                               -> Result<ty::TypeParameterDef, E> {
+        Ok(self.folder.fold_type_parameter_def(d))
+    }
+
+    fn fold_generics(&mut self, g: &ty::Generics) -> Result<ty::Generics, E> {
+        Ok(self.folder.fold_generics(g))
+    }
+
+    fn fold_trait_def(&mut self, d: &ty::TraitDef) -> Result<ty::TraitDef, E> {
+        Ok(self.folder.fold_trait_def(d))
+    }
+
+    fn fold_method_origin(&mut self, o: &typeck::MethodOrigin)
+                          -> Result<typeck::MethodOrigin, E> {
+        Ok(self.folder.fold_method_origin(o))
+    }
+
+    fn fold_method_callee(&mut self, m: &typeck::MethodCallee)
+                          -> Result<typeck::MethodCallee, E> {
+        Ok(self.folder.fold_method_callee(m))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Dynamic folding
+//
+// `TypeFolder` is only ever used as a bound, so every fold is
+// monomorphized for its folder. `DynTypeFolder` can be used as a trait
+// object instead: every `TypeFolder` is one, and `fold_with_dyn` folds
+// through `DynShim`, which forwards each fold back to the object.
+
+/// An object-safe mirror of `TypeFolder`, implemented for every
+/// `TypeFolder`, for code that wants to hold or accept a folder
+/// without knowing its type.
+pub trait DynTypeFolder<'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx>;
+
+    fn skip_ty(&self, t: ty::t) -> bool;
+
+    fn enter_region_binder(&mut self, binder_id: ast::NodeId);
+
+    fn exit_region_binder(&mut self, binder_id: ast::NodeId);
+
+    fn fold_ty(&mut self, t: ty::t) -> ty::t;
+
+    fn fold_mt(&mut self, t: &ty::mt) -> ty::mt;
+
+    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> ty::TraitRef;
+
+    fn fold_sty(&mut self, sty: &ty::sty) -> ty::sty;
+
+    fn fold_substs(&mut self, substs: &subst::Substs) -> subst::Substs;
+
+    fn fold_sig(&mut self, sig: &ty::FnSig) -> ty::FnSig;
+
+    fn fold_output(&mut self, output: &ty::FnOutput) -> ty::FnOutput;
+
+    fn fold_bare_fn_ty(&mut self, fty: &ty::BareFnTy) -> ty::BareFnTy;
+
+    fn fold_closure_ty(&mut self, fty: &ty::ClosureTy) -> ty::ClosureTy;
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region;
+
+    fn fold_trait_store(&mut self, s: ty::TraitStore) -> ty::TraitStore;
+
+    fn fold_existential_bounds(&mut self, s: ty::ExistentialBounds) -> ty::ExistentialBounds;
+
+    fn fold_autoref(&mut self, ar: &ty::AutoRef) -> ty::AutoRef;
+
+    fn fold_adjustment(&mut self, adj: &ty::AutoAdjustment) -> ty::AutoAdjustment;
+
+    fn fold_item_substs(&mut self, i: ty::ItemSubsts) -> ty::ItemSubsts;
+
+    fn fold_obligation(&mut self, o: &traits::Obligation) -> traits::Obligation;
+
+    fn fold_param_bounds(&mut self, b: &ty::ParamBounds) -> ty::ParamBounds;
+
+    fn fold_type_parameter_def(&mut self, d: &ty::TypeParameterDef) -> ty::TypeParameterDef;
+
+    fn fold_generics(&mut self, g: &ty::Generics) -> ty::Generics;
+
+    fn fold_trait_def(&mut self, d: &ty::TraitDef) -> ty::TraitDef;
+
+    fn fold_method_origin(&mut self, o: &typeck::MethodOrigin) -> typeck::MethodOrigin;
+
+    fn fold_method_callee(&mut self, m: &typeck::MethodCallee) -> typeck::MethodCallee;
+}
+
+impl<'tcx, F: TypeFolder<'tcx>> DynTypeFolder<'tcx> for F {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { TypeFolder::tcx(self) }
+
+    fn skip_ty(&self, t: ty::t) -> bool {
+        TypeFolder::skip_ty(self, t)
+    }
+
+    fn enter_region_binder(&mut self, binder_id: ast::NodeId) {
+        TypeFolder::enter_region_binder(self, binder_id)
+    }
+
+    fn exit_region_binder(&mut self, binder_id: ast::NodeId) {
+        TypeFolder::exit_region_binder(self, binder_id)
+    }
+
+    fn fold_ty(&mut self, t: ty::t) -> ty::t {
+        TypeFolder::fold_ty(self, t)
+    }
+
+    fn fold_mt(&mut self, t: &ty::mt) -> ty::mt {
+        TypeFolder::fold_mt(self, t)
+    }
+
+    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> ty::TraitRef {
+        TypeFolder::fold_trait_ref(self, t)
+    }
+
+    fn fold_sty(&mut self, sty: &ty::sty) -> ty::sty {
+        TypeFolder::fold_sty(self, sty)
+    }
+
+    fn fold_substs(&mut self, substs: &subst::Substs) -> subst::Substs {
+        TypeFolder::fold_substs(self, substs)
+    }
+
+    fn fold_sig(&mut self, sig: &ty::FnSig) -> ty::FnSig {
+        TypeFolder::fold_sig(self, sig)
+    }
+
+    fn fold_output(&mut self, output: &ty::FnOutput) -> ty::FnOutput {
+        TypeFolder::fold_output(self, output)
+    }
+
+    fn fold_bare_fn_ty(&mut self, fty: &ty::BareFnTy) -> ty::BareFnTy {
+        TypeFolder::fold_bare_fn_ty(self, fty)
+    }
+
+    fn fold_closure_ty(&mut self, fty: &ty::ClosureTy) -> ty::ClosureTy {
+        TypeFolder::fold_closure_ty(self, fty)
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        TypeFolder::fold_region(self, r)
+    }
+
+    fn fold_trait_store(&mut self, s: ty::TraitStore) -> ty::TraitStore {
+        TypeFolder::fold_trait_store(self, s)
+    }
+
+    fn fold_existential_bounds(&mut self, s: ty::ExistentialBounds) -> ty::ExistentialBounds {
+        TypeFolder::fold_existential_bounds(self, s)
+    }
+
+    fn fold_autoref(&mut self, ar: &ty::AutoRef) -> ty::AutoRef {
+        TypeFolder::fold_autoref(self, ar)
+    }
+
+    fn fold_adjustment(&mut self, adj: &ty::AutoAdjustment) -> ty::AutoAdjustment {
+        TypeFolder::fold_adjustment(self, adj)
+    }
+
+    fn fold_item_substs(&mut self, i: ty::ItemSubsts) -> ty::ItemSubsts {
+        TypeFolder::fold_item_substs(self, i)
+    }
+
+    fn fold_obligation(&mut self, o: &traits::Obligation) -> traits::Obligation {
+        TypeFolder::fold_obligation(self, o)
+    }
+
+    fn fold_param_bounds(&mut self, b: &ty::ParamBounds) -> ty::ParamBounds {
+        TypeFolder::fold_param_bounds(self, b)
+    }
+
+    fn fold_type_parameter_def(&mut self, d: &ty::TypeParameterDef) -> ty::TypeParameterDef {
+        TypeFolder::fold_type_parameter_def(self, d)
+    }
+
+    fn fold_generics(&mut self, g: &ty::Generics) -> ty::Generics {
+        TypeFolder::fold_generics(self, g)
+    }
+
+    fn fold_trait_def(&mut self, d: &ty::TraitDef) -> ty::TraitDef {
+        TypeFolder::fold_trait_def(self, d)
+    }
+
+    fn fold_method_origin(&mut self, o: &typeck::MethodOrigin) -> typeck::MethodOrigin {
+        TypeFolder::fold_method_origin(self, o)
+    }
+
+    fn fold_method_callee(&mut self, m: &typeck::MethodCallee) -> typeck::MethodCallee {
+        TypeFolder::fold_method_callee(self, m)
+    }
+}
+
+struct DynShim<'a, 'tcx: 'a> {
+    folder: &'a mut DynTypeFolder<'tcx>+'a,
+}
+
+impl<'a, 'tcx> TypeFolder<'tcx> for DynShim<'a, 'tcx> {
+    fn tcx<'b>(&'b self) -> &'b ty::ctxt<'tcx> { self.folder.tcx() }
+
+    fn skip_ty(&self, t: ty::t) -> bool {
+        self.folder.skip_ty(t)
+    }
+
+    fn enter_region_binder(&mut self, binder_id: ast::NodeId) {
+        self.folder.enter_region_binder(binder_id)
+    }
+
+    fn exit_region_binder(&mut self, binder_id: ast::NodeId) {
+        self.folder.exit_region_binder(binder_id)
+    }
+
+    fn fold_ty(&mut self, t: ty::t) -> ty::t {
+        self.folder.fold_ty(t)
+    }
+
+    fn fold_mt(&mut self, t: &ty::mt) -> ty::mt {
+        self.folder.fold_mt(t)
+    }
+
+    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> ty::TraitRef {
+        self.folder.fold_trait_ref(t)
+    }
+
+    fn fold_sty(&mut self, sty: &ty::sty) -> ty::sty {
+        self.folder.fold_sty(sty)
+    }
+
+    fn fold_substs(&mut self, substs: &subst::Substs) -> subst::Substs {
+        self.folder.fold_substs(substs)
+    }
+
+    fn fold_sig(&mut self, sig: &ty::FnSig) -> ty::FnSig {
+        self.folder.fold_sig(sig)
+    }
+
+    fn fold_output(&mut self, output: &ty::FnOutput) -> ty::FnOutput {
+        self.folder.fold_output(output)
+    }
+
+    fn fold_bare_fn_ty(&mut self, fty: &ty::BareFnTy) -> ty::BareFnTy {
+        self.folder.fold_bare_fn_ty(fty)
+    }
+
+    fn fold_closure_ty(&mut self, fty: &ty::ClosureTy) -> ty::ClosureTy {
+        self.folder.fold_closure_ty(fty)
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        self.folder.fold_region(r)
+    }
+
+    fn fold_trait_store(&mut self, s: ty::TraitStore) -> ty::TraitStore {
+        self.folder.fold_trait_store(s)
+    }
+
+    fn fold_existential_bounds(&mut self, s: ty::ExistentialBounds) -> ty::ExistentialBounds {
+        self.folder.fold_existential_bounds(s)
+    }
+
+    fn fold_autoref(&mut self, ar: &ty::AutoRef) -> ty::AutoRef {
+        self.folder.fold_autoref(ar)
+    }
+
+    fn fold_adjustment(&mut self, adj: &ty::AutoAdjustment) -> ty::AutoAdjustment {
+        self.folder.fold_adjustment(adj)
+    }
+
+    fn fold_item_substs(&mut self, i: ty::ItemSubsts) -> ty::ItemSubsts {
+        self.folder.fold_item_substs(i)
+    }
+
+    fn fold_obligation(&mut self, o: &traits::Obligation) -> traits::Obligation {
+        self.folder.fold_obligation(o)
+    }
+
+    fn fold_param_bounds(&mut self, b: &ty::ParamBounds) -> ty::ParamBounds {
+        self.folder.fold_param_bounds(b)
+    }
+
+    fn fold_type_parameter_def(&mut self, d: &ty::TypeParameterDef) -> ty::TypeParameterDef {
+        self.folder.fold_type_parameter_def(d)
+    }
+
+    fn fold_generics(&mut self, g: &ty::Generics) -> ty::Generics {
+        self.folder.fold_generics(g)
+    }
+
+    fn fold_trait_def(&mut self, d: &ty::TraitDef) -> ty::TraitDef {
+        self.folder.fold_trait_def(d)
+    }
+
+    fn fold_method_origin(&mut self, o: &typeck::MethodOrigin) -> typeck::MethodOrigin {
+        self.folder.fold_method_origin(o)
+    }
+
+    fn fold_method_callee(&mut self, m: &typeck::MethodCallee) -> typeck::MethodCallee {
+        self.folder.fold_method_callee(m)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Fold statistics
+//
+// With `-Z fold-stats`, substitution and region erasure run their
+// folder inside a `CountingFolder`, which tallies the calls made to
+// each fold method in the type context. The folder's recursion into the
+// components of a value does not go back through the wrapper, so the
+// wrapper names itself in `fold_stats_folder` of the type context while
+// the folder runs, and every fold method called through `TypeFoldable`
+// meanwhile is tallied under that name as well (see `dispatch`).
+// `try_super_fold_ty`, `try_super_fold_substs` and the vector folds
+// also tally, under `super_fold`, how often they could hand back their
+// input rather than build a new value. The tallies are printed and
+// reset by `dump_fold_stats` at the end of each pass.
+
+/// Calls to one method of one folder. `no_ops` counts the calls that
+/// returned their input unchanged, and is only kept for the folds
+/// that are cheap to compare: types, regions and substs.
+#[deriving(Clone, Show)]
+pub struct FoldCount {
+    pub calls: uint,
+    pub no_ops: Option<uint>,
+}
+
+pub type FoldStats = FnvHashMap<(&'static str, &'static str), FoldCount>;
+
+/// Forwards every fold to `folder`, recording each call under `name`,
+/// along with the calls that `folder` makes to itself in recursing into
+/// the components of the value. So `SubstFolder::fold_ty` is counted
+/// once for each type that substitution visits, not only for each type
+/// handed to it from outside. Other folds run from within `folder` are
+/// counted under `name` too.
+pub struct CountingFolder<'a, F: 'a> {
+    folder: &'a mut F,
+    name: &'static str,
+}
+
+impl<'a, F> CountingFolder<'a, F> {
+    pub fn new(name: &'static str, folder: &'a mut F) -> CountingFolder<'a, F> {
+        CountingFolder { folder: folder, name: name }
+    }
+
+    /// Runs `fold` on the wrapped folder and records it as a call of
+    /// `method`, with the calls it makes through `TypeFoldable` recorded
+    /// under this folder's name as well. `no_op` says whether the
+    /// folded value equals the input, for the values that are cheap to
+    /// compare.
+    fn count<'tcx, E, T>(&mut self,
+                         method: &'static str,
+                         fold: |&mut F| -> Result<T, E>,
+                         no_op: |&T| -> Option<bool>)
+                         -> Result<T, E>
+        where F: FallibleTypeFolder<'tcx, E>
+    {
+        let outer = self.folder.tcx().fold_stats_folder.get();
+        self.folder.tcx().fold_stats_folder.set(Some(self.name));
+        let folded = fold(&mut *self.folder);
+        self.folder.tcx().fold_stats_folder.set(outer);
+
+        let no_op = folded.as_ref().ok().and_then(|t| no_op(t));
+        record_fold(self.folder.tcx(), self.name, method, no_op);
+        folded
+    }
+}
+
+/// Calls `fold`, one of the fold methods of `folder`, on a component of
+/// a value: this is how `TypeFoldable` reaches the folder. Inside a
+/// `CountingFolder`, the call is recorded under the counting folder's
+/// name (see `CountingFolder::count`).
+fn dispatch<'tcx, E, F, T>(folder: &mut F,
+                           method: &'static str,
+                           fold: |&mut F| -> Result<T, E>,
+                           no_op: |&T| -> Option<bool>)
+                           -> Result<T, E>
+    where F: FallibleTypeFolder<'tcx, E>
+{
+    let counting = folder.tcx().fold_stats_folder.get();
+    let folded = fold(folder);
+    match counting {
+        Some(name) => {
+            let no_op = folded.as_ref().ok().and_then(|t| no_op(t));
+            record_fold(folder.tcx(), name, method, no_op);
+        }
+        None => {}
+    }
+    folded
+}
+
+fn record_fold(tcx: &ty::ctxt,
+               folder: &'static str,
+               method: &'static str,
+               no_op: Option<bool>) {
+    let mut stats = tcx.fold_stats.borrow_mut();
+    let count = match stats.entry((folder, method)) {
+        Vacant(entry) => entry.set(FoldCount { calls: 0, no_ops: None }),
+        Occupied(entry) => entry.into_mut(),
+    };
+    count.calls += 1;
+    match no_op {
+        Some(no_op) => {
+            let no_ops = count.no_ops.unwrap_or(0);
+            count.no_ops = Some(if no_op { no_ops + 1 } else { no_ops });
+        }
+        None => {}
+    }
+}
+
+/// Prints the fold statistics gathered since the last call under the
+/// heading `pass`, and resets them. Does nothing unless `-Z fold-stats`
+/// was given.
+pub fn dump_fold_stats(tcx: &ty::ctxt, pass: &str) {
+    if !tcx.sess.fold_stats() {
+        return;
+    }
+
+    let stats = mem::replace(&mut *tcx.fold_stats.borrow_mut(), FnvHashMap::new());
+    let mut stats: Vec<((&'static str, &'static str), FoldCount)> =
+        stats.into_iter().collect();
+    stats.sort_by(|&(a, _), &(b, _)| a.cmp(&b));
+
+    println!("--- fold stats: {} ---", pass);
+    for &((folder, method), ref count) in stats.iter() {
+        match count.no_ops {
+            Some(no_ops) => {
+                let perc = no_ops as f64 * 100.0 / count.calls as f64;
+                println!("{}::{}: {} calls, {} no-ops ({:.0f}%)",
+                         folder, method, count.calls, no_ops, perc);
+            }
+            None => {
+                println!("{}::{}: {} calls", folder, method, count.calls);
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx, E, F> FallibleTypeFolder<'tcx, E> for CountingFolder<'a, F>
+    where F: FallibleTypeFolder<'tcx, E>
+{
+    fn tcx<'b>(&'b self) -> &'b ty::ctxt<'tcx> { self.folder.tcx() }
+
+    fn skip_ty(&self, t: ty::t) -> bool {
+        self.folder.skip_ty(t)
+    }
+
+    fn enter_region_binder(&mut self, binder_id: ast::NodeId) {
+        self.folder.enter_region_binder(binder_id)
+    }
+
+    fn exit_region_binder(&mut self, binder_id: ast::NodeId) {
+        self.folder.exit_region_binder(binder_id)
+    }
+
+    fn fold_ty(&mut self, t: ty::t) -> Result<ty::t, E> {
+        self.count("fold_ty", |f| f.fold_ty(t), |u| Some(*u == t))
+    }
+
+    fn fold_mt(&mut self, t: &ty::mt) -> Result<ty::mt, E> {
+        self.count("fold_mt", |f| f.fold_mt(t), |_| None)
+    }
+
+    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> Result<ty::TraitRef, E> {
+        self.count("fold_trait_ref", |f| f.fold_trait_ref(t), |_| None)
+    }
+
+    fn fold_sty(&mut self, sty: &ty::sty) -> Result<ty::sty, E> {
+        self.count("fold_sty", |f| f.fold_sty(sty), |_| None)
+    }
+
+    fn fold_substs(&mut self, substs: &subst::Substs) -> Result<subst::Substs, E> {
+        self.count("fold_substs", |f| f.fold_substs(substs), |u| Some(*u == *substs))
+    }
+
+    fn fold_sig(&mut self, sig: &ty::FnSig) -> Result<ty::FnSig, E> {
+        self.count("fold_sig", |f| f.fold_sig(sig), |_| None)
+    }
 
-/// The TypeFoldable trait is implemented for every type that can be folded.
-/// Basically, every type that has a corresponding method in TypeFolder.
-pub trait TypeFoldable {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Self;
-}
+    fn fold_output(&mut self, output: &ty::FnOutput) -> Result<ty::FnOutput, E> {
+        self.count("fold_output", |f| f.fold_output(output), |_| None)
+    }
 
-/// The TypeFolder trait defines the actual *folding*. There is a
-/// method defined for every foldable type. Each of these has a
-/// default implementation that does an "identity" fold. Within each
-/// identity fold, it should invoke `foo.fold_with(self)` to fold each
-/// sub-item.
-pub trait TypeFolder<'tcx> {
-    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx>;
+    fn fold_bare_fn_ty(&mut self, fty: &ty::BareFnTy) -> Result<ty::BareFnTy, E> {
+        self.count("fold_bare_fn_ty", |f| f.fold_bare_fn_ty(fty), |_| None)
+    }
 
-    fn fold_ty(&mut self, t: ty::t) -> ty::t {
-        super_fold_ty(self, t)
+    fn fold_closure_ty(&mut self, fty: &ty::ClosureTy) -> Result<ty::ClosureTy, E> {
+        self.count("fold_closure_ty", |f| f.fold_closure_ty(fty), |_| None)
     }
 
-    fn fold_mt(&mut self, t: &ty::mt) -> ty::mt {
-        super_fold_mt(self, t)
+    fn fold_region(&mut self, r: ty::Region) -> Result<ty::Region, E> {
+        self.count("fold_region", |f| f.fold_region(r), |u| Some(*u == r))
     }
 
-    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> ty::TraitRef {
-        super_fold_trait_ref(self, t)
+    fn fold_trait_store(&mut self, s: ty::TraitStore) -> Result<ty::TraitStore, E> {
+        self.count("fold_trait_store", |f| f.fold_trait_store(s), |_| None)
     }
 
-    fn fold_sty(&mut self, sty: &ty::sty) -> ty::sty {
-        super_fold_sty(self, sty)
+    fn fold_existential_bounds(&mut self, s: ty::ExistentialBounds)
+                                  -> Result<ty::ExistentialBounds, E> {
+        self.count("fold_existential_bounds", |f| f.fold_existential_bounds(s), |_| None)
     }
 
-    fn fold_substs(&mut self,
-                   substs: &subst::Substs)
-                   -> subst::Substs {
-        super_fold_substs(self, substs)
+    fn fold_autoref(&mut self, ar: &ty::AutoRef) -> Result<ty::AutoRef, E> {
+        self.count("fold_autoref", |f| f.fold_autoref(ar), |_| None)
     }
 
-    fn fold_sig(&mut self,
-                sig: &ty::FnSig)
-                -> ty::FnSig {
-        super_fold_sig(self, sig)
+    fn fold_adjustment(&mut self, adj: &ty::AutoAdjustment) -> Result<ty::AutoAdjustment, E> {
+        self.count("fold_adjustment", |f| f.fold_adjustment(adj), |_| None)
     }
 
-    fn fold_output(&mut self,
-                      output: &ty::FnOutput)
-                      -> ty::FnOutput {
-        super_fold_output(self, output)
+    fn fold_item_substs(&mut self, i: ty::ItemSubsts) -> Result<ty::ItemSubsts, E> {
+        self.count("fold_item_substs", |f| f.fold_item_substs(i.clone()), |_| None)
     }
 
-    fn fold_bare_fn_ty(&mut self,
-                       fty: &ty::BareFnTy)
-                       -> ty::BareFnTy
-    {
-        super_fold_bare_fn_ty(self, fty)
+    fn fold_obligation(&mut self, o: &traits::Obligation) -> Result<traits::Obligation, E> {
+        self.count("fold_obligation", |f| f.fold_obligation(o), |_| None)
     }
 
-    fn fold_closure_ty(&mut self,
-                       fty: &ty::ClosureTy)
-                       -> ty::ClosureTy {
-        super_fold_closure_ty(self, fty)
+    fn fold_param_bounds(&mut self, b: &ty::ParamBounds) -> Result<ty::ParamBounds, E> {
+        self.count("fold_param_bounds", |f| f.fold_param_bounds(b), |_| None)
     }
 
-    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
-        r
+    fn fold_type_parameter_def(&mut self, d: &ty::TypeParameterDef)
+                                  -> Result<ty::TypeParameterDef, E> {
+        self.count("fold_type_parameter_def", |f| f.fold_type_parameter_def(d), |_| None)
     }
 
-    fn fold_trait_store(&mut self, s: ty::TraitStore) -> ty::TraitStore {
-        super_fold_trait_store(self, s)
+    fn fold_generics(&mut self, g: &ty::Generics) -> Result<ty::Generics, E> {
+        self.count("fold_generics", |f| f.fold_generics(g), |_| None)
     }
 
-    fn fold_existential_bounds(&mut self, s: ty::ExistentialBounds)
-                               -> ty::ExistentialBounds {
-        super_fold_existential_bounds(self, s)
+    fn fold_trait_def(&mut self, d: &ty::TraitDef) -> Result<ty::TraitDef, E> {
+        self.count("fold_trait_def", |f| f.fold_trait_def(d), |_| None)
     }
 
-    fn fold_autoref(&mut self, ar: &ty::AutoRef) -> ty::AutoRef {
-        super_fold_autoref(self, ar)
+    fn fold_method_origin(&mut self, o: &typeck::MethodOrigin) -> Result<typeck::MethodOrigin, E> {
+        self.count("fold_method_origin", |f| f.fold_method_origin(o), |_| None)
     }
 
-    fn fold_item_substs(&mut self, i: ty::ItemSubsts) -> ty::ItemSubsts {
-        super_fold_item_substs(self, i)
+    fn fold_method_callee(&mut self, m: &typeck::MethodCallee) -> Result<typeck::MethodCallee, E> {
+        self.count("fold_method_callee", |f| f.fold_method_callee(m), |_| None)
     }
+}
 
-    fn fold_obligation(&mut self, o: &traits::Obligation) -> traits::Obligation {
-        super_fold_obligation(self, o)
+///////////////////////////////////////////////////////////////////////////
+// In-place folds
+//
+// Folding a `Vec` or `VecPerParamSpace` through `TypeFoldable` builds
+// a new one, or a copy of the input when no element changes. A caller
+// that owns the vector and is done with the unfolded value can fold it
+// in place instead, overwriting each element and keeping the allocation.
+
+/// Folds each element of `v` with `folder`, in place.
+pub fn fold_vec_in_place<'tcx, F, T>(folder: &mut F, v: &mut Vec<T>)
+    where F: TypeFolder<'tcx>, T: TypeFoldable
+{
+    fold_slice_in_place(folder, v.as_mut_slice())
+}
+
+/// Folds each element of `v` with `folder`, in place. This is how to
+/// fold the `types` of a `Substs` without rebuilding them.
+pub fn fold_params_in_place<'tcx, F, T>(folder: &mut F, v: &mut VecPerParamSpace<T>)
+    where F: TypeFolder<'tcx>, T: TypeFoldable
+{
+    fold_slice_in_place(folder, v.as_mut_slice())
+}
+
+fn fold_slice_in_place<'tcx, F, T>(folder: &mut F, v: &mut [T])
+    where F: TypeFolder<'tcx>, T: TypeFoldable
+{
+    for elem in v.iter_mut() {
+        let folded = elem.fold_with(folder);
+        *elem = folded;
     }
 }
 
@@ -148,154 +1030,283 @@ pub trait TypeFolder<'tcx> {
 // needed.
 
 impl TypeFoldable for () {
-    fn fold_with<'tcx, F:TypeFolder<'tcx>>(&self, _: &mut F) -> () {
-        ()
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, _: &mut F)
+                     -> Result<(), E> {
+        Ok(())
     }
 }
 
 impl<T:TypeFoldable> TypeFoldable for Option<T> {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Option<T> {
-        self.as_ref().map(|t| t.fold_with(folder))
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<Option<T>, E> {
+        match *self {
+            Some(ref t) => Ok(Some(try!(t.try_fold_with(folder)))),
+            None => Ok(None)
+        }
     }
 }
 
 impl<T:TypeFoldable> TypeFoldable for Rc<T> {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Rc<T> {
-        Rc::new((**self).fold_with(folder))
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<Rc<T>, E> {
+        Ok(Rc::new(try!((**self).try_fold_with(folder))))
+    }
+}
+
+impl<T:TypeFoldable> TypeFoldable for Box<T> {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<Box<T>, E> {
+        Ok(box try!((**self).try_fold_with(folder)))
+    }
+}
+
+impl<T:TypeFoldable+Clone> TypeFoldable for Vec<T> {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<Vec<T>, E> {
+        Ok(try!(self.try_fold_if_changed(folder)).unwrap_or_else(|| self.clone()))
+    }
+
+    fn try_fold_if_changed<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                           -> Result<Option<Vec<T>>, E> {
+        try_fold_slice_if_changed(folder, self.as_slice())
+    }
+}
+
+/// Folds each element of `slice`, and gives a vector of the results
+/// unless every element was left unchanged. No vector is allocated
+/// before the first element that changes.
+fn try_fold_slice_if_changed<'tcx, E, F, T>(folder: &mut F, slice: &[T])
+                                            -> Result<Option<Vec<T>>, E>
+    where F: FallibleTypeFolder<'tcx, E>, T: TypeFoldable + Clone
+{
+    let mut folded: Option<Vec<T>> = None;
+    for (i, t) in slice.iter().enumerate() {
+        match try!(t.try_fold_if_changed(folder)) {
+            Some(new_t) => {
+                if folded.is_none() {
+                    let mut v = Vec::with_capacity(slice.len());
+                    v.push_all(slice.slice_to(i));
+                    folded = Some(v);
+                }
+                folded.as_mut().unwrap().push(new_t);
+            }
+            None => {
+                match folded {
+                    Some(ref mut v) => v.push(t.clone()),
+                    None => {}
+                }
+            }
+        }
+    }
+    record_rebuild(folder.tcx(), "vec", folded.is_some());
+    Ok(folded)
+}
+
+impl<A:TypeFoldable, B:TypeFoldable> TypeFoldable for (A, B) {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<(A, B), E> {
+        let (ref a, ref b) = *self;
+        Ok((try!(a.try_fold_with(folder)), try!(b.try_fold_with(folder))))
+    }
+}
+
+impl<A:TypeFoldable, B:TypeFoldable, C:TypeFoldable> TypeFoldable for (A, B, C) {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<(A, B, C), E> {
+        let (ref a, ref b, ref c) = *self;
+        Ok((try!(a.try_fold_with(folder)),
+            try!(b.try_fold_with(folder)),
+            try!(c.try_fold_with(folder))))
     }
 }
 
-impl<T:TypeFoldable> TypeFoldable for Vec<T> {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Vec<T> {
-        self.iter().map(|t| t.fold_with(folder)).collect()
+impl<K, V, S, H> TypeFoldable for HashMap<K, V, H>
+    where K: Clone + Eq + Hash<S>, V: TypeFoldable, H: Hasher<S> + Default
+{
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<HashMap<K, V, H>, E> {
+        self.iter().map(|(k, v)| {
+            v.try_fold_with(folder).map(|v| (k.clone(), v))
+        }).collect()
     }
 }
 
 impl<T:TypeFoldable> TypeFoldable for OwnedSlice<T> {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> OwnedSlice<T> {
-        self.iter().map(|t| t.fold_with(folder)).collect()
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<OwnedSlice<T>, E> {
+        self.iter().map(|t| t.try_fold_with(folder)).collect()
     }
 }
 
-impl<T:TypeFoldable> TypeFoldable for VecPerParamSpace<T> {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> VecPerParamSpace<T> {
-        self.map(|t| t.fold_with(folder))
+impl<T:TypeFoldable+Clone> TypeFoldable for VecPerParamSpace<T> {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<VecPerParamSpace<T>, E> {
+        Ok(try!(self.try_fold_if_changed(folder)).unwrap_or_else(|| self.clone()))
+    }
+
+    fn try_fold_if_changed<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                           -> Result<Option<VecPerParamSpace<T>>, E> {
+        let folded = try!(try_fold_slice_if_changed(folder, self.as_slice()));
+        Ok(folded.map(|content| self.with_content(content)))
     }
 }
 
 impl TypeFoldable for ty::TraitStore {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::TraitStore {
-        folder.fold_trait_store(*self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::TraitStore, E> {
+        dispatch(folder, "fold_trait_store", |f| f.fold_trait_store(*self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::t {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::t {
-        folder.fold_ty(*self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::t, E> {
+        let folded = try!(dispatch(folder, "fold_ty", |f| f.fold_ty(*self),
+                                   |u| Some(*u == *self)));
+        verify_interned(folder.tcx(), *self, folded);
+        Ok(folded)
+    }
+
+    fn try_fold_if_changed<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                           -> Result<Option<ty::t>, E> {
+        let folded = try!(self.try_fold_with(folder));
+        Ok(if folded == *self { None } else { Some(folded) })
     }
 }
 
 impl TypeFoldable for ty::BareFnTy {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::BareFnTy {
-        folder.fold_bare_fn_ty(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::BareFnTy, E> {
+        dispatch(folder, "fold_bare_fn_ty", |f| f.fold_bare_fn_ty(self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::ClosureTy {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::ClosureTy {
-        folder.fold_closure_ty(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::ClosureTy, E> {
+        dispatch(folder, "fold_closure_ty", |f| f.fold_closure_ty(self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::mt {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::mt {
-        folder.fold_mt(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::mt, E> {
+        dispatch(folder, "fold_mt", |f| f.fold_mt(self), |_| None)
+    }
+}
+
+impl TypeFoldable for ty::field {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::field, E> {
+        Ok(ty::field {
+            name: self.name,
+            mt: try!(self.mt.try_fold_with(folder)),
+        })
     }
 }
 
 impl TypeFoldable for ty::FnOutput {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::FnOutput {
-        folder.fold_output(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::FnOutput, E> {
+        dispatch(folder, "fold_output", |f| f.fold_output(self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::FnSig {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::FnSig {
-        folder.fold_sig(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::FnSig, E> {
+        dispatch(folder, "fold_sig", |f| f.fold_sig(self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::sty {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::sty {
-        folder.fold_sty(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::sty, E> {
+        dispatch(folder, "fold_sty", |f| f.fold_sty(self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::TraitRef {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::TraitRef {
-        folder.fold_trait_ref(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::TraitRef, E> {
+        dispatch(folder, "fold_trait_ref", |f| f.fold_trait_ref(self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::Region {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::Region {
-        folder.fold_region(*self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::Region, E> {
+        dispatch(folder, "fold_region", |f| f.fold_region(*self), |u| Some(*u == *self))
+    }
+
+    fn try_fold_if_changed<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                           -> Result<Option<ty::Region>, E> {
+        let folded = try!(self.try_fold_with(folder));
+        Ok(if folded == *self { None } else { Some(folded) })
     }
 }
 
 impl TypeFoldable for subst::Substs {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> subst::Substs {
-        folder.fold_substs(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<subst::Substs, E> {
+        dispatch(folder, "fold_substs", |f| f.fold_substs(self), |u| Some(*u == *self))
     }
 }
 
 impl TypeFoldable for ty::ItemSubsts {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::ItemSubsts {
-        ty::ItemSubsts {
-            substs: self.substs.fold_with(folder),
-        }
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::ItemSubsts, E> {
+        Ok(ty::ItemSubsts {
+            substs: try!(self.substs.try_fold_with(folder)),
+        })
     }
 }
 
 impl TypeFoldable for ty::AutoRef {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::AutoRef {
-        folder.fold_autoref(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::AutoRef, E> {
+        dispatch(folder, "fold_autoref", |f| f.fold_autoref(self), |_| None)
+    }
+}
+
+impl TypeFoldable for ty::AutoDerefRef {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::AutoDerefRef, E> {
+        Ok(ty::AutoDerefRef {
+            autoderefs: self.autoderefs,
+            autoref: try!(self.autoref.try_fold_with(folder)),
+        })
+    }
+}
+
+impl TypeFoldable for ty::AutoAdjustment {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::AutoAdjustment, E> {
+        dispatch(folder, "fold_adjustment", |f| f.fold_adjustment(self), |_| None)
     }
 }
 
 impl TypeFoldable for typeck::MethodOrigin {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> typeck::MethodOrigin {
-        match *self {
-            typeck::MethodStatic(def_id) => {
-                typeck::MethodStatic(def_id)
-            }
-            typeck::MethodStaticUnboxedClosure(def_id) => {
-                typeck::MethodStaticUnboxedClosure(def_id)
-            }
-            typeck::MethodTypeParam(ref param) => {
-                typeck::MethodTypeParam(typeck::MethodParam {
-                    trait_ref: param.trait_ref.fold_with(folder),
-                    method_num: param.method_num
-                })
-            }
-            typeck::MethodTraitObject(ref object) => {
-                typeck::MethodTraitObject(typeck::MethodObject {
-                    trait_ref: object.trait_ref.fold_with(folder),
-                    object_trait_id: object.object_trait_id,
-                    method_num: object.method_num,
-                    real_index: object.real_index
-                })
-            }
-        }
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<typeck::MethodOrigin, E> {
+        dispatch(folder, "fold_method_origin", |f| f.fold_method_origin(self), |_| None)
+    }
+}
+
+impl TypeFoldable for typeck::MethodCallee {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<typeck::MethodCallee, E> {
+        dispatch(folder, "fold_method_callee", |f| f.fold_method_callee(self), |_| None)
     }
 }
 
 impl TypeFoldable for typeck::vtable_origin {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> typeck::vtable_origin {
-        match *self {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<typeck::vtable_origin, E> {
+        Ok(match *self {
             typeck::vtable_static(def_id, ref substs, ref origins) => {
-                let r_substs = substs.fold_with(folder);
-                let r_origins = origins.fold_with(folder);
+                let r_substs = try!(substs.try_fold_with(folder));
+                let r_origins = try!(origins.try_fold_with(folder));
                 typeck::vtable_static(def_id, r_substs, r_origins)
             }
             typeck::vtable_param(n, b) => {
@@ -307,252 +1318,507 @@ impl TypeFoldable for typeck::vtable_origin {
             typeck::vtable_error => {
                 typeck::vtable_error
             }
-        }
+        })
     }
 }
 
 impl TypeFoldable for ty::BuiltinBounds {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, _folder: &mut F) -> ty::BuiltinBounds {
-        *self
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, _folder: &mut F)
+                     -> Result<ty::BuiltinBounds, E> {
+        Ok(*self)
     }
 }
 
 impl TypeFoldable for ty::ExistentialBounds {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::ExistentialBounds {
-        folder.fold_existential_bounds(*self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::ExistentialBounds, E> {
+        dispatch(folder, "fold_existential_bounds", |f| f.fold_existential_bounds(*self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::ParamBounds {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::ParamBounds {
-        ty::ParamBounds {
-            region_bounds: self.region_bounds.fold_with(folder),
-            builtin_bounds: self.builtin_bounds.fold_with(folder),
-            trait_bounds: self.trait_bounds.fold_with(folder),
-        }
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::ParamBounds, E> {
+        dispatch(folder, "fold_param_bounds", |f| f.fold_param_bounds(self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::TypeParameterDef {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::TypeParameterDef {
-        ty::TypeParameterDef {
-            name: self.name,
-            def_id: self.def_id,
-            space: self.space,
-            index: self.index,
-            associated_with: self.associated_with,
-            bounds: self.bounds.fold_with(folder),
-            default: self.default.fold_with(folder),
-        }
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::TypeParameterDef, E> {
+        dispatch(folder, "fold_type_parameter_def", |f| f.fold_type_parameter_def(self), |_| None)
     }
 }
 
 impl TypeFoldable for ty::RegionParameterDef {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::RegionParameterDef {
-        ty::RegionParameterDef {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::RegionParameterDef, E> {
+        Ok(ty::RegionParameterDef {
             name: self.name,
             def_id: self.def_id,
             space: self.space,
             index: self.index,
-            bounds: self.bounds.fold_with(folder)
-        }
+            bounds: try!(self.bounds.try_fold_with(folder))
+        })
     }
 }
 
 impl TypeFoldable for ty::Generics {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::Generics {
-        ty::Generics {
-            types: self.types.fold_with(folder),
-            regions: self.regions.fold_with(folder),
-        }
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::Generics, E> {
+        dispatch(folder, "fold_generics", |f| f.fold_generics(self), |_| None)
+    }
+}
+
+impl TypeFoldable for ty::TraitDef {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::TraitDef, E> {
+        dispatch(folder, "fold_trait_def", |f| f.fold_trait_def(self), |_| None)
+    }
+}
+
+impl TypeFoldable for ty::Polytype {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::Polytype, E> {
+        Ok(ty::Polytype {
+            generics: try!(self.generics.try_fold_with(folder)),
+            ty: try!(self.ty.try_fold_with(folder)),
+        })
     }
 }
 
 impl TypeFoldable for ty::UnsizeKind {
-    fn fold_with<'tcx, F: TypeFolder<'tcx>>(&self, folder: &mut F) -> ty::UnsizeKind {
-        match *self {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<ty::UnsizeKind, E> {
+        Ok(match *self {
             ty::UnsizeLength(len) => ty::UnsizeLength(len),
-            ty::UnsizeStruct(box ref k, n) => ty::UnsizeStruct(box k.fold_with(folder), n),
+            ty::UnsizeStruct(ref k, n) => ty::UnsizeStruct(try!(k.try_fold_with(folder)), n),
             ty::UnsizeVtable(ty::TyTrait{ref principal, bounds}, self_ty) => {
                 ty::UnsizeVtable(
                     ty::TyTrait {
-                        principal: principal.fold_with(folder),
-                        bounds: bounds.fold_with(folder),
+                        principal: try!(principal.try_fold_with(folder)),
+                        bounds: try!(bounds.try_fold_with(folder)),
                     },
-                    self_ty.fold_with(folder))
+                    try!(self_ty.try_fold_with(folder)))
             }
-        }
+        })
     }
 }
 
 impl TypeFoldable for traits::Obligation {
-    fn fold_with<'tcx, F:TypeFolder<'tcx>>(&self, folder: &mut F) -> traits::Obligation {
-        folder.fold_obligation(self)
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<traits::Obligation, E> {
+        dispatch(folder, "fold_obligation", |f| f.fold_obligation(self), |_| None)
+    }
+}
+
+impl TypeFoldable for traits::ObligationCause {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<traits::ObligationCause, E> {
+        Ok(traits::ObligationCause {
+            span: self.span,
+            code: try!(self.code.try_fold_with(folder)),
+        })
+    }
+}
+
+impl TypeFoldable for traits::ObligationCauseCode {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<traits::ObligationCauseCode, E> {
+        Ok(match *self {
+            traits::ObjectCastObligation(object_ty) => {
+                traits::ObjectCastObligation(try!(object_ty.try_fold_with(folder)))
+            }
+            ref code => code.clone()
+        })
     }
 }
 
 impl<N:TypeFoldable> TypeFoldable for traits::VtableImplData<N> {
-    fn fold_with<'tcx, F:TypeFolder<'tcx>>(&self, folder: &mut F) -> traits::VtableImplData<N> {
-        traits::VtableImplData {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<traits::VtableImplData<N>, E> {
+        Ok(traits::VtableImplData {
             impl_def_id: self.impl_def_id,
-            substs: self.substs.fold_with(folder),
-            nested: self.nested.fold_with(folder),
-        }
+            substs: try!(self.substs.try_fold_with(folder)),
+            nested: try!(self.nested.try_fold_with(folder)),
+        })
+    }
+}
+
+impl<N:TypeFoldable> TypeFoldable for traits::VtableBuiltinData<N> {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<traits::VtableBuiltinData<N>, E> {
+        Ok(traits::VtableBuiltinData {
+            nested: try!(self.nested.try_fold_with(folder)),
+        })
+    }
+}
+
+impl<N:TypeFoldable> TypeFoldable for traits::Vtable<N> {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<traits::Vtable<N>, E> {
+        Ok(match *self {
+            traits::VtableImpl(ref v) => traits::VtableImpl(try!(v.try_fold_with(folder))),
+            traits::VtableUnboxedClosure(d, ref s) => {
+                traits::VtableUnboxedClosure(d, try!(s.try_fold_with(folder)))
+            }
+            traits::VtableFnPointer(ref t) => {
+                traits::VtableFnPointer(try!(t.try_fold_with(folder)))
+            }
+            traits::VtableUnboxedClosureClone(d, ref s, ref n) => {
+                traits::VtableUnboxedClosureClone(d,
+                                                  try!(s.try_fold_with(folder)),
+                                                  try!(n.try_fold_with(folder)))
+            }
+            traits::VtableParam(ref p) => traits::VtableParam(try!(p.try_fold_with(folder))),
+            traits::VtableBuiltin(ref d) => traits::VtableBuiltin(try!(d.try_fold_with(folder))),
+        })
+    }
+}
+
+impl TypeFoldable for traits::VtableParamData {
+    fn try_fold_with<'tcx, E, F: FallibleTypeFolder<'tcx, E>>(&self, folder: &mut F)
+                     -> Result<traits::VtableParamData, E> {
+        Ok(traits::VtableParamData {
+            bound: try!(self.bound.try_fold_with(folder)),
+        })
     }
 }
 
-impl<N:TypeFoldable> TypeFoldable for traits::VtableBuiltinData<N> {
-    fn fold_with<'tcx, F:TypeFolder<'tcx>>(&self, folder: &mut F) -> traits::VtableBuiltinData<N> {
-        traits::VtableBuiltinData {
-            nested: self.nested.fold_with(folder),
-        }
-    }
+///////////////////////////////////////////////////////////////////////////
+// "super" routines: these are the default implementations for TypeFolder.
+//
+// They run the fallible "super" routines below with a folder that
+// cannot fail, so that both kinds of folder traverse types alike.
+
+pub fn super_fold_ty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                t: ty::t)
+                                                -> ty::t {
+    infallible(try_super_fold_ty(&mut Infallible { folder: this }, t))
+}
+
+pub fn super_fold_substs<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                    substs: &subst::Substs)
+                                                    -> subst::Substs {
+    infallible(try_super_fold_substs(&mut Infallible { folder: this }, substs))
+}
+
+pub fn super_fold_sig<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                 sig: &ty::FnSig)
+                                                 -> ty::FnSig {
+    infallible(try_super_fold_sig(&mut Infallible { folder: this }, sig))
+}
+
+pub fn super_fold_output<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                    output: &ty::FnOutput)
+                                                    -> ty::FnOutput {
+    infallible(try_super_fold_output(&mut Infallible { folder: this }, output))
+}
+
+pub fn super_fold_bare_fn_ty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                        fty: &ty::BareFnTy)
+                                                        -> ty::BareFnTy
+{
+    infallible(try_super_fold_bare_fn_ty(&mut Infallible { folder: this }, fty))
+}
+
+pub fn super_fold_closure_ty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                        fty: &ty::ClosureTy)
+                                                        -> ty::ClosureTy
+{
+    infallible(try_super_fold_closure_ty(&mut Infallible { folder: this }, fty))
+}
+
+pub fn super_fold_trait_ref<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                       t: &ty::TraitRef)
+                                                       -> ty::TraitRef {
+    infallible(try_super_fold_trait_ref(&mut Infallible { folder: this }, t))
+}
+
+pub fn super_fold_mt<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                mt: &ty::mt) -> ty::mt {
+    infallible(try_super_fold_mt(&mut Infallible { folder: this }, mt))
+}
+
+pub fn super_fold_sty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                 sty: &ty::sty) -> ty::sty {
+    infallible(try_super_fold_sty(&mut Infallible { folder: this }, sty))
+}
+
+pub fn super_fold_trait_store<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                         trait_store: ty::TraitStore)
+                                                         -> ty::TraitStore {
+    infallible(try_super_fold_trait_store(&mut Infallible { folder: this }, trait_store))
+}
+
+pub fn super_fold_existential_bounds<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                                bounds: ty::ExistentialBounds)
+                                                                -> ty::ExistentialBounds {
+    infallible(try_super_fold_existential_bounds(&mut Infallible { folder: this }, bounds))
+}
+
+pub fn super_fold_autoref<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                     autoref: &ty::AutoRef)
+                                                     -> ty::AutoRef
+{
+    infallible(try_super_fold_autoref(&mut Infallible { folder: this }, autoref))
+}
+
+pub fn super_fold_adjustment<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                        adjustment: &ty::AutoAdjustment)
+                                                        -> ty::AutoAdjustment
+{
+    infallible(try_super_fold_adjustment(&mut Infallible { folder: this }, adjustment))
+}
+
+pub fn super_fold_item_substs<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                         substs: ty::ItemSubsts)
+                                                         -> ty::ItemSubsts
+{
+    infallible(try_super_fold_item_substs(&mut Infallible { folder: this }, substs))
+}
+
+pub fn super_fold_obligation<'tcx, T:TypeFolder<'tcx>>(this: &mut T,
+                                                       obligation: &traits::Obligation)
+                                                       -> traits::Obligation
+{
+    infallible(try_super_fold_obligation(&mut Infallible { folder: this }, obligation))
+}
+
+pub fn super_fold_param_bounds<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                          bounds: &ty::ParamBounds)
+                                                          -> ty::ParamBounds
+{
+    infallible(try_super_fold_param_bounds(&mut Infallible { folder: this }, bounds))
+}
+
+pub fn super_fold_type_parameter_def<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                                def: &ty::TypeParameterDef)
+                                                                -> ty::TypeParameterDef
+{
+    infallible(try_super_fold_type_parameter_def(&mut Infallible { folder: this }, def))
+}
+
+pub fn super_fold_generics<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                      generics: &ty::Generics)
+                                                      -> ty::Generics
+{
+    infallible(try_super_fold_generics(&mut Infallible { folder: this }, generics))
+}
+
+pub fn super_fold_trait_def<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                       def: &ty::TraitDef)
+                                                       -> ty::TraitDef
+{
+    infallible(try_super_fold_trait_def(&mut Infallible { folder: this }, def))
+}
+
+pub fn super_fold_method_origin<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                           origin: &typeck::MethodOrigin)
+                                                           -> typeck::MethodOrigin
+{
+    infallible(try_super_fold_method_origin(&mut Infallible { folder: this }, origin))
+}
+
+pub fn super_fold_method_callee<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
+                                                           callee: &typeck::MethodCallee)
+                                                           -> typeck::MethodCallee
+{
+    infallible(try_super_fold_method_callee(&mut Infallible { folder: this }, callee))
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Fallible "super" routines: these are the default implementations for
+// FallibleTypeFolder.
+//
+// They should invoke `foo.try_fold_with()` to do recursive folding.
+
+pub fn try_super_fold_ty<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(this: &mut T,
+                                                                  t: ty::t)
+                                                                  -> Result<ty::t, E> {
+    // A leaf type has no components, so its `sty` would only be cloned
+    // and then compared equal to itself below.
+    if this.skip_ty(t) || ty::type_is_leaf(t) {
+        verify_interned(this.tcx(), t, t);
+        return Ok(t);
+    }
+
+    let sty = &ty::get(t).sty;
+    let folded = try!(sty.try_fold_with(this));
+
+    // Folders are often the identity on most of a type (e.g. the region
+    // eraser on a type without regions); interning an unchanged `sty`
+    // again would only hash its way back to `t`.
+    let unchanged = folded == *sty;
+    record_rebuild(this.tcx(), "ty", !unchanged);
+    if unchanged {
+        verify_interned(this.tcx(), t, t);
+        return Ok(t);
+    }
+    Ok(ty::mk_t(this.tcx(), folded))
 }
 
-impl<N:TypeFoldable> TypeFoldable for traits::Vtable<N> {
-    fn fold_with<'tcx, F:TypeFolder<'tcx>>(&self, folder: &mut F) -> traits::Vtable<N> {
-        match *self {
-            traits::VtableImpl(ref v) => traits::VtableImpl(v.fold_with(folder)),
-            traits::VtableUnboxedClosure(d, ref s) => {
-                traits::VtableUnboxedClosure(d, s.fold_with(folder))
-            }
-            traits::VtableParam(ref p) => traits::VtableParam(p.fold_with(folder)),
-            traits::VtableBuiltin(ref d) => traits::VtableBuiltin(d.fold_with(folder)),
-        }
+/// Under `-Z fold-stats`, tallies whether a fold had to build a new value
+/// of kind `what` (a type, substs or vector), or could keep its input.
+fn record_rebuild(tcx: &ty::ctxt, what: &'static str, rebuilt: bool) {
+    if tcx.sess.fold_stats() {
+        record_fold(tcx, "super_fold", what, Some(!rebuilt));
     }
 }
 
-impl TypeFoldable for traits::VtableParamData {
-    fn fold_with<'tcx, F:TypeFolder<'tcx>>(&self, folder: &mut F) -> traits::VtableParamData {
-        traits::VtableParamData {
-            bound: self.bound.fold_with(folder),
-        }
+/// Under `-Z verify-fold`, reports a bug if folding `original` gave a
+/// type `folded` that was interned in some other type context, as when
+/// a folder builds types with the wrong `tcx` or hands back a stale one.
+fn verify_interned(tcx: &ty::ctxt, original: ty::t, folded: ty::t) {
+    if tcx.sess.verify_fold() && !ty::type_is_interned_in(tcx, folded) {
+        tcx.sess.bug(format!("folding `{}` gave `{}`, which is not interned in \
+                              this type context",
+                             original.repr(tcx),
+                             folded.repr(tcx)).as_slice());
     }
 }
 
-///////////////////////////////////////////////////////////////////////////
-// "super" routines: these are the default implementations for TypeFolder.
-//
-// They should invoke `foo.fold_with()` to do recursive folding.
-pub fn super_fold_ty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                t: ty::t)
-                                                -> ty::t {
-    let sty = ty::get(t).sty.fold_with(this);
-    ty::mk_t(this.tcx(), sty)
-}
+pub fn try_super_fold_substs<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                             this: &mut T,
+                             substs: &subst::Substs)
+                             -> Result<subst::Substs, E> {
+    // As common as they are, empty substs are not worth rebuilding.
+    if substs.is_empty() {
+        return Ok(substs.clone());
+    }
 
-pub fn super_fold_substs<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                    substs: &subst::Substs)
-                                                    -> subst::Substs {
+    // As for types, the input is handed back when nothing changed, and
+    // neither the regions nor the types are copied unless they changed.
     let regions = match substs.regions {
-        subst::ErasedRegions => {
-            subst::ErasedRegions
-        }
+        subst::ErasedRegions => None,
         subst::NonerasedRegions(ref regions) => {
-            subst::NonerasedRegions(regions.fold_with(this))
+            try!(regions.try_fold_if_changed(this)).map(|r| subst::NonerasedRegions(r))
         }
     };
+    let types = try!(substs.types.try_fold_if_changed(this));
 
-    subst::Substs { regions: regions,
-                    types: substs.types.fold_with(this) }
+    let unchanged = regions.is_none() && types.is_none();
+    record_rebuild(this.tcx(), "substs", !unchanged);
+    if unchanged {
+        return Ok(substs.clone());
+    }
+    Ok(subst::Substs {
+        regions: regions.unwrap_or_else(|| substs.regions.clone()),
+        types: types.unwrap_or_else(|| substs.types.clone()),
+    })
 }
 
-pub fn super_fold_sig<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                 sig: &ty::FnSig)
-                                                 -> ty::FnSig {
-    ty::FnSig { binder_id: sig.binder_id,
-                inputs: sig.inputs.fold_with(this),
-                output: sig.output.fold_with(this),
-                variadic: sig.variadic }
+pub fn try_super_fold_sig<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(this: &mut T,
+                                                                   sig: &ty::FnSig)
+                                                                   -> Result<ty::FnSig, E> {
+    Ok(ty::FnSig { binder_id: sig.binder_id,
+                   inputs: try!(sig.inputs.try_fold_with(this)),
+                   output: try!(sig.output.try_fold_with(this)),
+                   variadic: sig.variadic })
 }
 
-pub fn super_fold_output<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                    output: &ty::FnOutput)
-                                                    -> ty::FnOutput {
-    match *output {
-        ty::FnConverging(ref ty) => ty::FnConverging(ty.fold_with(this)),
+pub fn try_super_fold_output<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                             this: &mut T,
+                             output: &ty::FnOutput)
+                             -> Result<ty::FnOutput, E> {
+    Ok(match *output {
+        ty::FnConverging(ref ty) => ty::FnConverging(try!(ty.try_fold_with(this))),
         ty::FnDiverging => ty::FnDiverging
-    }
+    })
 }
 
-pub fn super_fold_bare_fn_ty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                        fty: &ty::BareFnTy)
-                                                        -> ty::BareFnTy
+pub fn try_super_fold_bare_fn_ty<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                 this: &mut T,
+                                 fty: &ty::BareFnTy)
+                                 -> Result<ty::BareFnTy, E>
 {
-    ty::BareFnTy { sig: fty.sig.fold_with(this),
-                   abi: fty.abi,
-                   fn_style: fty.fn_style }
+    Ok(ty::BareFnTy { sig: try!(fty.sig.try_fold_with(this)),
+                      abi: fty.abi,
+                      fn_style: fty.fn_style })
 }
 
-pub fn super_fold_closure_ty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                        fty: &ty::ClosureTy)
-                                                        -> ty::ClosureTy
+pub fn try_super_fold_closure_ty<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                 this: &mut T,
+                                 fty: &ty::ClosureTy)
+                                 -> Result<ty::ClosureTy, E>
 {
-    ty::ClosureTy {
-        store: fty.store.fold_with(this),
-        sig: fty.sig.fold_with(this),
+    Ok(ty::ClosureTy {
+        store: try!(fty.store.try_fold_with(this)),
+        sig: try!(fty.sig.try_fold_with(this)),
         fn_style: fty.fn_style,
         onceness: fty.onceness,
-        bounds: fty.bounds.fold_with(this),
+        bounds: try!(fty.bounds.try_fold_with(this)),
         abi: fty.abi,
-    }
+    })
 }
-pub fn super_fold_trait_ref<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                       t: &ty::TraitRef)
-                                                       -> ty::TraitRef {
-    ty::TraitRef {
+
+pub fn try_super_fold_trait_ref<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                this: &mut T,
+                                t: &ty::TraitRef)
+                                -> Result<ty::TraitRef, E> {
+    let substs = if t.binder_id == ast::DUMMY_NODE_ID {
+        try!(t.substs.try_fold_with(this))
+    } else {
+        try!(try_fold_within_binder(this, t.binder_id, &t.substs))
+    };
+    Ok(ty::TraitRef {
         def_id: t.def_id,
-        substs: t.substs.fold_with(this),
-    }
+        substs: substs,
+        binder_id: t.binder_id,
+    })
 }
 
-pub fn super_fold_mt<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                mt: &ty::mt) -> ty::mt {
-    ty::mt {ty: mt.ty.fold_with(this),
-            mutbl: mt.mutbl}
+pub fn try_super_fold_mt<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(this: &mut T,
+                                                                  mt: &ty::mt)
+                                                                  -> Result<ty::mt, E> {
+    Ok(ty::mt {ty: try!(mt.ty.try_fold_with(this)),
+               mutbl: mt.mutbl})
 }
 
-pub fn super_fold_sty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                 sty: &ty::sty) -> ty::sty {
-    match *sty {
+pub fn try_super_fold_sty<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(this: &mut T,
+                                                                   sty: &ty::sty)
+                                                                   -> Result<ty::sty, E> {
+    Ok(match *sty {
         ty::ty_uniq(typ) => {
-            ty::ty_uniq(typ.fold_with(this))
+            ty::ty_uniq(try!(typ.try_fold_with(this)))
         }
         ty::ty_ptr(ref tm) => {
-            ty::ty_ptr(tm.fold_with(this))
+            ty::ty_ptr(try!(tm.try_fold_with(this)))
         }
         ty::ty_vec(typ, sz) => {
-            ty::ty_vec(typ.fold_with(this), sz)
+            ty::ty_vec(try!(typ.try_fold_with(this)), sz)
         }
         ty::ty_open(typ) => {
-            ty::ty_open(typ.fold_with(this))
+            ty::ty_open(try!(typ.try_fold_with(this)))
         }
         ty::ty_enum(tid, ref substs) => {
-            ty::ty_enum(tid, substs.fold_with(this))
+            ty::ty_enum(tid, try!(substs.try_fold_with(this)))
         }
         ty::ty_trait(box ty::TyTrait { ref principal, bounds }) => {
             ty::ty_trait(box ty::TyTrait {
-                principal: (*principal).fold_with(this),
-                bounds: bounds.fold_with(this),
+                principal: try!((*principal).try_fold_with(this)),
+                bounds: try!(bounds.try_fold_with(this)),
             })
         }
         ty::ty_tup(ref ts) => {
-            ty::ty_tup(ts.fold_with(this))
+            ty::ty_tup(try!(ts.try_fold_with(this)))
         }
         ty::ty_bare_fn(ref f) => {
-            ty::ty_bare_fn(f.fold_with(this))
+            ty::ty_bare_fn(try!(try_fold_within_binder(this, f.sig.binder_id, f)))
         }
         ty::ty_closure(ref f) => {
-            ty::ty_closure(box f.fold_with(this))
+            ty::ty_closure(try!(try_fold_within_binder(this, f.sig.binder_id, f)))
         }
         ty::ty_rptr(r, ref tm) => {
-            ty::ty_rptr(r.fold_with(this), tm.fold_with(this))
+            ty::ty_rptr(try!(r.try_fold_with(this)), try!(tm.try_fold_with(this)))
         }
         ty::ty_struct(did, ref substs) => {
-            ty::ty_struct(did, substs.fold_with(this))
+            ty::ty_struct(did, try!(substs.try_fold_with(this)))
         }
         ty::ty_unboxed_closure(did, ref region, ref substs) => {
-            ty::ty_unboxed_closure(did, region.fold_with(this), substs.fold_with(this))
+            ty::ty_unboxed_closure(did,
+                                   try!(region.try_fold_with(this)),
+                                   try!(substs.try_fold_with(this)))
         }
         ty::ty_nil | ty::ty_bool | ty::ty_char | ty::ty_str |
         ty::ty_int(_) | ty::ty_uint(_) | ty::ty_float(_) |
@@ -560,65 +1826,191 @@ pub fn super_fold_sty<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
         ty::ty_param(..) => {
             (*sty).clone()
         }
-    }
+    })
 }
 
-pub fn super_fold_trait_store<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                         trait_store: ty::TraitStore)
-                                                         -> ty::TraitStore {
-    match trait_store {
+/// Folds the components of a fn type or trait reference between the
+/// folder's `enter_region_binder` and `exit_region_binder` calls for its
+/// binder. (A signature or substs folded on their own are not within
+/// the binder, so that its late-bound regions can be replaced.)
+fn try_fold_within_binder<'tcx, E, T, U>(this: &mut T,
+                                         binder_id: ast::NodeId,
+                                         value: &U)
+                                         -> Result<U, E>
+    where T: FallibleTypeFolder<'tcx, E>, U: TypeFoldable
+{
+    this.enter_region_binder(binder_id);
+    let result = value.try_fold_with(this);
+    this.exit_region_binder(binder_id);
+    result
+}
+
+pub fn try_super_fold_trait_store<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                  this: &mut T,
+                                  trait_store: ty::TraitStore)
+                                  -> Result<ty::TraitStore, E> {
+    Ok(match trait_store {
         ty::UniqTraitStore => ty::UniqTraitStore,
         ty::RegionTraitStore(r, m) => {
-            ty::RegionTraitStore(r.fold_with(this), m)
+            ty::RegionTraitStore(try!(r.try_fold_with(this)), m)
         }
-    }
+    })
 }
 
-pub fn super_fold_existential_bounds<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                                bounds: ty::ExistentialBounds)
-                                                                -> ty::ExistentialBounds {
-    ty::ExistentialBounds {
-        region_bound: bounds.region_bound.fold_with(this),
+pub fn try_super_fold_existential_bounds<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                         this: &mut T,
+                                         bounds: ty::ExistentialBounds)
+                                         -> Result<ty::ExistentialBounds, E> {
+    Ok(ty::ExistentialBounds {
+        region_bound: try!(bounds.region_bound.try_fold_with(this)),
         builtin_bounds: bounds.builtin_bounds,
-    }
+    })
 }
 
-pub fn super_fold_autoref<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                     autoref: &ty::AutoRef)
-                                                     -> ty::AutoRef
+pub fn try_super_fold_autoref<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                              this: &mut T,
+                              autoref: &ty::AutoRef)
+                              -> Result<ty::AutoRef, E>
 {
-    match *autoref {
-        ty::AutoPtr(r, m, None) => ty::AutoPtr(this.fold_region(r), m, None),
+    Ok(match *autoref {
+        ty::AutoPtr(r, m, None) => ty::AutoPtr(try!(this.fold_region(r)), m, None),
         ty::AutoPtr(r, m, Some(ref a)) => {
-            ty::AutoPtr(this.fold_region(r), m, Some(box super_fold_autoref(this, &**a)))
+            let r = try!(this.fold_region(r));
+            ty::AutoPtr(r, m, Some(try!(a.try_fold_with(this))))
         }
         ty::AutoUnsafe(m, None) => ty::AutoUnsafe(m, None),
-        ty::AutoUnsafe(m, Some(ref a)) => {
-            ty::AutoUnsafe(m, Some(box super_fold_autoref(this, &**a)))
+        ty::AutoUnsafe(m, Some(ref a)) => ty::AutoUnsafe(m, Some(try!(a.try_fold_with(this)))),
+        ty::AutoUnsize(ref k) => ty::AutoUnsize(try!(k.try_fold_with(this))),
+        ty::AutoUnsizeUniq(ref k) => ty::AutoUnsizeUniq(try!(k.try_fold_with(this))),
+    })
+}
+
+pub fn try_super_fold_adjustment<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                 this: &mut T,
+                                 adjustment: &ty::AutoAdjustment)
+                                 -> Result<ty::AutoAdjustment, E>
+{
+    Ok(match *adjustment {
+        ty::AdjustAddEnv(store) => ty::AdjustAddEnv(try!(store.try_fold_with(this))),
+        ty::AdjustDerefRef(ref adj) => ty::AdjustDerefRef(try!(adj.try_fold_with(this))),
+        ty::AdjustUnboxedClosureToFnPointer(fn_ptr_ty) => {
+            ty::AdjustUnboxedClosureToFnPointer(try!(fn_ptr_ty.try_fold_with(this)))
         }
-        ty::AutoUnsize(ref k) => ty::AutoUnsize(k.fold_with(this)),
-        ty::AutoUnsizeUniq(ref k) => ty::AutoUnsizeUniq(k.fold_with(this)),
-    }
+    })
 }
 
-pub fn super_fold_item_substs<'tcx, T: TypeFolder<'tcx>>(this: &mut T,
-                                                         substs: ty::ItemSubsts)
-                                                         -> ty::ItemSubsts
+pub fn try_super_fold_item_substs<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                  this: &mut T,
+                                  substs: ty::ItemSubsts)
+                                  -> Result<ty::ItemSubsts, E>
 {
-    ty::ItemSubsts {
-        substs: substs.substs.fold_with(this),
-    }
+    Ok(ty::ItemSubsts {
+        substs: try!(substs.substs.try_fold_with(this)),
+    })
 }
 
-pub fn super_fold_obligation<'tcx, T:TypeFolder<'tcx>>(this: &mut T,
-                                                       obligation: &traits::Obligation)
-                                                       -> traits::Obligation
+pub fn try_super_fold_obligation<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                 this: &mut T,
+                                 obligation: &traits::Obligation)
+                                 -> Result<traits::Obligation, E>
 {
-    traits::Obligation {
-        cause: obligation.cause,
+    Ok(traits::Obligation {
+        cause: try!(obligation.cause.try_fold_with(this)),
         recursion_depth: obligation.recursion_depth,
-        trait_ref: obligation.trait_ref.fold_with(this),
-    }
+        trait_ref: try!(obligation.trait_ref.try_fold_with(this)),
+    })
+}
+
+pub fn try_super_fold_param_bounds<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                   this: &mut T,
+                                   bounds: &ty::ParamBounds)
+                                   -> Result<ty::ParamBounds, E>
+{
+    Ok(ty::ParamBounds {
+        region_bounds: try!(bounds.region_bounds.try_fold_with(this)),
+        builtin_bounds: try!(bounds.builtin_bounds.try_fold_with(this)),
+        trait_bounds: try!(bounds.trait_bounds.try_fold_with(this)),
+    })
+}
+
+pub fn try_super_fold_type_parameter_def<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                         this: &mut T,
+                                         def: &ty::TypeParameterDef)
+                                         -> Result<ty::TypeParameterDef, E>
+{
+    Ok(ty::TypeParameterDef {
+        name: def.name,
+        def_id: def.def_id,
+        space: def.space,
+        index: def.index,
+        associated_with: def.associated_with,
+        bounds: try!(def.bounds.try_fold_with(this)),
+        default: try!(def.default.try_fold_with(this)),
+    })
+}
+
+pub fn try_super_fold_generics<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                               this: &mut T,
+                               generics: &ty::Generics)
+                               -> Result<ty::Generics, E>
+{
+    Ok(ty::Generics {
+        types: try!(generics.types.try_fold_with(this)),
+        regions: try!(generics.regions.try_fold_with(this)),
+    })
+}
+
+pub fn try_super_fold_trait_def<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                this: &mut T,
+                                def: &ty::TraitDef)
+                                -> Result<ty::TraitDef, E>
+{
+    Ok(ty::TraitDef {
+        generics: try!(def.generics.try_fold_with(this)),
+        bounds: try!(def.bounds.try_fold_with(this)),
+        trait_ref: try!(def.trait_ref.try_fold_with(this)),
+    })
+}
+
+pub fn try_super_fold_method_origin<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                    this: &mut T,
+                                    origin: &typeck::MethodOrigin)
+                                    -> Result<typeck::MethodOrigin, E>
+{
+    Ok(match *origin {
+        typeck::MethodStatic(def_id) => {
+            typeck::MethodStatic(def_id)
+        }
+        typeck::MethodStaticUnboxedClosure(def_id) => {
+            typeck::MethodStaticUnboxedClosure(def_id)
+        }
+        typeck::MethodTypeParam(ref param) => {
+            typeck::MethodTypeParam(typeck::MethodParam {
+                trait_ref: try!(param.trait_ref.try_fold_with(this)),
+                method_num: param.method_num
+            })
+        }
+        typeck::MethodTraitObject(ref object) => {
+            typeck::MethodTraitObject(typeck::MethodObject {
+                trait_ref: try!(object.trait_ref.try_fold_with(this)),
+                object_trait_id: object.object_trait_id,
+                method_num: object.method_num,
+                real_index: object.real_index
+            })
+        }
+    })
+}
+
+pub fn try_super_fold_method_callee<'tcx, E, T: FallibleTypeFolder<'tcx, E>>(
+                                    this: &mut T,
+                                    callee: &typeck::MethodCallee)
+                                    -> Result<typeck::MethodCallee, E>
+{
+    Ok(typeck::MethodCallee {
+        origin: try!(callee.origin.try_fold_with(this)),
+        ty: try!(callee.ty.try_fold_with(this)),
+        substs: try!(callee.substs.try_fold_with(this)),
+    })
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -627,6 +2019,23 @@ pub fn super_fold_obligation<'tcx, T:TypeFolder<'tcx>>(this: &mut T,
 pub struct BottomUpFolder<'a, 'tcx: 'a> {
     pub tcx: &'a ty::ctxt<'tcx>,
     pub fldop: |ty::t|: 'a -> ty::t,
+
+    // Applied to every region; regions are left alone if `None`.
+    pub fldop_r: Option<|ty::Region|: 'a -> ty::Region>,
+}
+
+impl<'a, 'tcx> BottomUpFolder<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>, fld_t: |ty::t|: 'a -> ty::t)
+               -> BottomUpFolder<'a, 'tcx> {
+        BottomUpFolder { tcx: tcx, fldop: fld_t, fldop_r: None }
+    }
+
+    pub fn with_regions(tcx: &'a ty::ctxt<'tcx>,
+                        fld_t: |ty::t|: 'a -> ty::t,
+                        fld_r: |ty::Region|: 'a -> ty::Region)
+                        -> BottomUpFolder<'a, 'tcx> {
+        BottomUpFolder { tcx: tcx, fldop: fld_t, fldop_r: Some(fld_r) }
+    }
 }
 
 impl<'a, 'tcx> TypeFolder<'tcx> for BottomUpFolder<'a, 'tcx> {
@@ -636,6 +2045,40 @@ impl<'a, 'tcx> TypeFolder<'tcx> for BottomUpFolder<'a, 'tcx> {
         let t1 = super_fold_ty(self, ty);
         (self.fldop)(t1)
     }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        match self.fldop_r {
+            Some(ref mut fld_r) => (*fld_r)(r),
+            None => r
+        }
+    }
+}
+
+/// Folds types from the top down: `fldop` sees each type before its
+/// components. If it returns `Some(u)`, the type is replaced by `u` and
+/// its components are not visited; if `None`, the components are
+/// folded as usual.
+pub struct TopDownFolder<'a, 'tcx: 'a> {
+    pub tcx: &'a ty::ctxt<'tcx>,
+    pub fldop: |ty::t|: 'a -> Option<ty::t>,
+}
+
+impl<'a, 'tcx> TopDownFolder<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>, fld_t: |ty::t|: 'a -> Option<ty::t>)
+               -> TopDownFolder<'a, 'tcx> {
+        TopDownFolder { tcx: tcx, fldop: fld_t }
+    }
+}
+
+impl<'a, 'tcx> TypeFolder<'tcx> for TopDownFolder<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn fold_ty(&mut self, ty: ty::t) -> ty::t {
+        match (self.fldop)(ty) {
+            Some(t1) => t1,
+            None => super_fold_ty(self, ty)
+        }
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -651,29 +2094,39 @@ impl<'a, 'tcx> TypeFolder<'tcx> for BottomUpFolder<'a, 'tcx> {
 /// visited by `fld_r`.
 ///
 /// (The distinction between "free" and "bound" is represented by
-/// keeping track of each `FnSig` in the lexical context of the
-/// current position of the fold.)
+/// keeping track of the region binders that enclose the current
+/// position of the fold.)
 pub struct RegionFolder<'a, 'tcx: 'a> {
     tcx: &'a ty::ctxt<'tcx>,
     fld_t: |ty::t|: 'a -> ty::t,
-    fld_r: |ty::Region|: 'a -> ty::Region,
-    within_binder_ids: Vec<ast::NodeId>,
+
+    // Called with each free region and the number of binders that
+    // enclose it.
+    fld_r: |ty::Region, uint|: 'a -> ty::Region,
+
+    // The number of region binders that enclose the current position.
+    binder_depth: uint,
+
+    // Each enclosing binder, mapped to the depth at which it was first
+    // entered. A late-bound region is bound iff its binder is a key.
+    entered_binders: NodeMap<uint>,
 }
 
 impl<'a, 'tcx> RegionFolder<'a, 'tcx> {
     pub fn general(tcx: &'a ty::ctxt<'tcx>,
-                   fld_r: |ty::Region|: 'a -> ty::Region,
+                   fld_r: |ty::Region, uint|: 'a -> ty::Region,
                    fld_t: |ty::t|: 'a -> ty::t)
                    -> RegionFolder<'a, 'tcx> {
         RegionFolder {
             tcx: tcx,
             fld_t: fld_t,
             fld_r: fld_r,
-            within_binder_ids: vec![],
+            binder_depth: 0,
+            entered_binders: NodeMap::new(),
         }
     }
 
-    pub fn regions(tcx: &'a ty::ctxt<'tcx>, fld_r: |ty::Region|: 'a -> ty::Region)
+    pub fn regions(tcx: &'a ty::ctxt<'tcx>, fld_r: |ty::Region, uint|: 'a -> ty::Region)
                    -> RegionFolder<'a, 'tcx> {
         fn noop(t: ty::t) -> ty::t { t }
 
@@ -681,52 +2134,322 @@ impl<'a, 'tcx> RegionFolder<'a, 'tcx> {
             tcx: tcx,
             fld_t: noop,
             fld_r: fld_r,
-            within_binder_ids: vec![],
+            binder_depth: 0,
+            entered_binders: NodeMap::new(),
         }
     }
 }
 
-/// If `ty` has `FnSig` (i.e. closure or fn), return its binder_id;
-/// else None.
-fn opt_binder_id_of_function(t: ty::t) -> Option<ast::NodeId> {
-    match ty::get(t).sty {
-        ty::ty_closure(ref f) => Some(f.sig.binder_id),
-        ty::ty_bare_fn(ref f) => Some(f.sig.binder_id),
-        _                     => None,
-    }
-}
-
 impl<'a, 'tcx> TypeFolder<'tcx> for RegionFolder<'a, 'tcx> {
     fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
 
-    fn fold_ty(&mut self, ty: ty::t) -> ty::t {
-        debug!("RegionFolder.fold_ty({})", ty.repr(self.tcx()));
-        let opt_binder_id = opt_binder_id_of_function(ty);
-        match opt_binder_id {
-            Some(binder_id) => self.within_binder_ids.push(binder_id),
-            None => {}
+    fn enter_region_binder(&mut self, binder_id: ast::NodeId) {
+        self.binder_depth += 1;
+        if !self.entered_binders.contains_key(&binder_id) {
+            self.entered_binders.insert(binder_id, self.binder_depth);
         }
+    }
 
-        let t1 = super_fold_ty(self, ty);
-        let ret = (self.fld_t)(t1);
-
-        if opt_binder_id.is_some() {
-            self.within_binder_ids.pop();
+    fn exit_region_binder(&mut self, binder_id: ast::NodeId) {
+        if self.entered_binders.get(&binder_id) == Some(&self.binder_depth) {
+            self.entered_binders.remove(&binder_id);
         }
+        self.binder_depth -= 1;
+    }
 
-        ret
+    fn fold_ty(&mut self, ty: ty::t) -> ty::t {
+        debug!("RegionFolder.fold_ty({})", ty.repr(self.tcx));
+        let t1 = super_fold_ty(self, ty);
+        (self.fld_t)(t1)
     }
 
     fn fold_region(&mut self, r: ty::Region) -> ty::Region {
         match r {
-            ty::ReLateBound(binder_id, _) if self.within_binder_ids.contains(&binder_id) => {
-                debug!("RegionFolder.fold_region({}) skipped bound region", r.repr(self.tcx()));
+            ty::ReLateBound(binder_id, _) if self.entered_binders.contains_key(&binder_id) => {
+                debug!("RegionFolder.fold_region({}) skipped bound region", r.repr(self.tcx));
                 r
             }
             _ => {
-                debug!("RegionFolder.fold_region({}) folding free region", r.repr(self.tcx()));
-                (self.fld_r)(r)
+                debug!("RegionFolder.fold_region({}) folding free region", r.repr(self.tcx));
+                (self.fld_r)(r, self.binder_depth)
+            }
+        }
+    }
+}
+
+/// Maps `fld_r` over every free region in `value`, passing it the
+/// number of binders that enclose the region within `value`. Regions
+/// bound by fn types and trait references within `value` are left
+/// alone.
+pub fn fold_regions<T:TypeFoldable>(tcx: &ty::ctxt,
+                                    value: &T,
+                                    fld_r: |ty::Region, uint| -> ty::Region)
+                                    -> T {
+    value.fold_with(&mut RegionFolder::regions(tcx, fld_r))
+}
+
+/// Returns the binders of the late-bound regions that escape `value`:
+/// those bound by no fn type or trait reference within `value` itself,
+/// and so by some binder outside of it. A `FnSig` folded on its own
+/// does not bind its own regions, so these count as escaping; fold the
+/// fn type instead to exclude them.
+pub fn escaping_region_binders<T:TypeFoldable>(tcx: &ty::ctxt, value: &T) -> NodeSet {
+    let mut binders = NodeSet::new();
+    fold_regions(tcx, value, |r, _| {
+        match r {
+            ty::ReLateBound(binder_id, _) => { binders.insert(binder_id); }
+            _ => {}
+        }
+        r
+    });
+    binders
+}
+
+/// Returns true if `value` has late-bound regions whose binders lie
+/// outside of it. See `escaping_region_binders`.
+pub fn has_escaping_regions<T:TypeFoldable>(tcx: &ty::ctxt, value: &T) -> bool {
+    !escaping_region_binders(tcx, value).is_empty()
+}
+
+/// Replaces the late-bound regions that escape `value` with free
+/// regions of the scope `scope_id`, as when checking a fn body against
+/// its signature. Regions bound by fn types within `value` are left
+/// alone. Returns the liberated value along with the free region that
+/// each bound region was replaced by.
+pub fn liberate_late_bound_regions<T>(tcx: &ty::ctxt,
+                                      scope_id: ast::NodeId,
+                                      value: &T)
+                                      -> (T, FnvHashMap<ty::BoundRegion, ty::Region>)
+    where T : TypeFoldable + Repr
+{
+    debug!("liberate_late_bound_regions(scope_id={}, value={})",
+           scope_id, value.repr(tcx));
+
+    let mut map = FnvHashMap::new();
+    let new_value = fold_regions(tcx, value, |r, _| {
+        match r {
+            ty::ReLateBound(_, br) => {
+                let free = ty::ReFree(ty::FreeRegion { scope_id: scope_id,
+                                                       bound_region: br });
+                map.insert(br, free);
+                free
+            }
+            _ => r
+        }
+    });
+    debug!("resulting map: {}", map);
+    (new_value, map)
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Region replacer
+//
+// Instantiates the late-bound regions of one binder, as when a fn
+// signature is instantiated at a call site with fresh region variables,
+// or its regions are skolemized to relate it to another signature.
+
+/// Replaces each late-bound region of the binder `binder_id` with the
+/// region that `map_fn` gives for it. `map_fn` is called once for each
+/// distinct bound region; the replacements made are kept in `map`.
+pub struct RegionReplacer<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    binder_id: ast::NodeId,
+    map_fn: |ty::BoundRegion|: 'a -> ty::Region,
+    pub map: FnvHashMap<ty::BoundRegion, ty::Region>,
+}
+
+impl<'a, 'tcx> RegionReplacer<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>,
+               binder_id: ast::NodeId,
+               map_fn: |ty::BoundRegion|: 'a -> ty::Region)
+               -> RegionReplacer<'a, 'tcx> {
+        RegionReplacer {
+            tcx: tcx,
+            binder_id: binder_id,
+            map_fn: map_fn,
+            map: FnvHashMap::new(),
+        }
+    }
+}
+
+impl<'a, 'tcx> TypeFolder<'tcx> for RegionReplacer<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn skip_ty(&self, t: ty::t) -> bool {
+        !ty::type_has_regions(t)
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        match r {
+            ty::ReLateBound(s, br) if s == self.binder_id => {
+                match self.map.entry(br) {
+                    Vacant(entry) => *entry.set((self.map_fn)(br)),
+                    Occupied(entry) => *entry.into_mut(),
+                }
+            }
+            _ => r
+        }
+    }
+}
+
+/// Replaces the late-bound regions of the binder `binder_id` in `value`
+/// (see `RegionReplacer`). Returns the new value along with the region
+/// that each bound region was replaced by.
+pub fn replace_late_bound_regions<T>(tcx: &ty::ctxt,
+                                     binder_id: ast::NodeId,
+                                     value: &T,
+                                     map_fn: |ty::BoundRegion| -> ty::Region)
+                                     -> (T, FnvHashMap<ty::BoundRegion, ty::Region>)
+    where T : TypeFoldable + Repr
+{
+    debug!("replace_late_bound_regions(binder_id={}, value={})",
+           binder_id, value.repr(tcx));
+
+    let mut replacer = RegionReplacer::new(tcx, binder_id, map_fn);
+    let new_value = value.fold_with(&mut replacer);
+    debug!("resulting map: {}", replacer.map);
+    (new_value, replacer.map)
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Inference variable eraser
+//
+// Replaces every type inference variable with `ty_err`, so that passes
+// after typeck never see one, even in types typeck could not resolve.
+
+pub struct InferVarEraser<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+
+    // Called once for each distinct variable that is erased.
+    report: Option<|ty::InferTy|: 'a>,
+    erased: FnvHashSet<ty::InferTy>,
+}
+
+impl<'a, 'tcx> InferVarEraser<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>) -> InferVarEraser<'a, 'tcx> {
+        InferVarEraser { tcx: tcx, report: None, erased: FnvHashSet::new() }
+    }
+
+    pub fn with_report(tcx: &'a ty::ctxt<'tcx>, report: |ty::InferTy|: 'a)
+                       -> InferVarEraser<'a, 'tcx> {
+        InferVarEraser { tcx: tcx, report: Some(report), erased: FnvHashSet::new() }
+    }
+}
+
+pub fn erase_infer_vars<T:TypeFoldable>(tcx: &ty::ctxt, value: T) -> T {
+    let mut eraser = InferVarEraser::new(tcx);
+    value.fold_with(&mut eraser)
+}
+
+impl<'a, 'tcx> TypeFolder<'tcx> for InferVarEraser<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn skip_ty(&self, t: ty::t) -> bool {
+        !ty::type_has_ty_infer(t)
+    }
+
+    fn fold_ty(&mut self, t: ty::t) -> ty::t {
+        match ty::get(t).sty {
+            ty::ty_infer(v) => {
+                if self.erased.insert(v) {
+                    match self.report {
+                        Some(ref mut report) => (*report)(v),
+                        None => {}
+                    }
+                }
+                ty::mk_err()
+            }
+            _ => super_fold_ty(self, t)
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Region anonymizer
+
+/// Rewrites every late-bound region in `value` to `BrAnon` form,
+/// numbered in order of first occurrence, and replaces the binder of
+/// every fn signature (and the binders of trait references) with a
+/// synthetic id for its depth of nesting, so that values which differ
+/// only in the names and binder ids of their late-bound regions become
+/// equal. A region still says how far out its binder is, so that
+/// `fn(fn(&'a int))` binding `'a` on the outer fn differs from the same
+/// type binding it on the inner one. The synthetic ids are not node ids:
+/// use the result only as a cache or equality key, never as a type.
+pub fn anonymize_late_bound_regions<T:TypeFoldable>(tcx: &ty::ctxt, value: &T) -> T {
+    let mut anonymizer = RegionAnonymizer { tcx: tcx,
+                                            binders: Vec::new(),
+                                            anon_regions: FnvHashMap::new() };
+    value.fold_with(&mut anonymizer)
+}
+
+struct RegionAnonymizer<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+
+    // The binders entered so far, outermost first.
+    binders: Vec<ast::NodeId>,
+
+    anon_regions: FnvHashMap<(ast::NodeId, ty::BoundRegion), ty::BoundRegion>,
+}
+
+impl<'a, 'tcx> RegionAnonymizer<'a, 'tcx> {
+    /// The synthetic id of `binder_id`, if it has been entered. Counting
+    /// down from `DUMMY_NODE_ID` keeps the ids clear of real node ids.
+    fn depth_id(&self, binder_id: ast::NodeId) -> Option<ast::NodeId> {
+        self.binders.iter().rposition(|&id| id == binder_id)
+            .map(|depth| ast::DUMMY_NODE_ID - 1 - depth as ast::NodeId)
+    }
+}
+
+impl<'a, 'tcx> TypeFolder<'tcx> for RegionAnonymizer<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn enter_region_binder(&mut self, binder_id: ast::NodeId) {
+        self.binders.push(binder_id);
+    }
+
+    fn exit_region_binder(&mut self, _binder_id: ast::NodeId) {
+        self.binders.pop();
+    }
+
+    fn fold_sig(&mut self, sig: &ty::FnSig) -> ty::FnSig {
+        // A signature folded on its own is not within its binder yet.
+        let binder_id = sig.binder_id;
+        let entered = self.binders.last() == Some(&binder_id);
+        if !entered {
+            TypeFolder::enter_region_binder(self, binder_id);
+        }
+        let depth_id = self.depth_id(binder_id).unwrap();
+        let sig = ty::FnSig { binder_id: depth_id, ..super_fold_sig(self, sig) };
+        if !entered {
+            TypeFolder::exit_region_binder(self, binder_id);
+        }
+        sig
+    }
+
+    fn fold_trait_ref(&mut self, t: &ty::TraitRef) -> ty::TraitRef {
+        let folded = super_fold_trait_ref(self, t);
+        if t.binder_id == ast::DUMMY_NODE_ID {
+            return folded;
+        }
+        // `super_fold_trait_ref` entered the binder just within the
+        // binders entered so far.
+        let depth = self.binders.len();
+        ty::TraitRef { binder_id: ast::DUMMY_NODE_ID - 1 - depth as ast::NodeId, ..folded }
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        match r {
+            ty::ReLateBound(binder_id, br) => {
+                let next = ty::BrAnon(self.anon_regions.len());
+                let anon = match self.anon_regions.entry((binder_id, br)) {
+                    Vacant(entry) => *entry.set(next),
+                    Occupied(entry) => *entry.into_mut(),
+                };
+                // A region bound outside of `value` keeps its binder.
+                ty::ReLateBound(self.depth_id(binder_id).unwrap_or(binder_id), anon)
             }
+            _ => r
         }
     }
 }
@@ -735,23 +2458,87 @@ impl<'a, 'tcx> TypeFolder<'tcx> for RegionFolder<'a, 'tcx> {
 // Region eraser
 //
 // Replaces all free regions with 'static. Useful in trans.
+//
+// Unboxed closure types are erased to the same form that `normalize_ty`
+// gives them, since trans keys its closure tables (environment layouts,
+// LLVM types, clone shims) on that form.
 
 pub struct RegionEraser<'a, 'tcx: 'a> {
     tcx: &'a ty::ctxt<'tcx>,
+
+    // Set once any region has been rewritten.
+    changed: bool,
+}
+
+impl<'a, 'tcx> RegionEraser<'a, 'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>) -> RegionEraser<'a, 'tcx> {
+        RegionEraser { tcx: tcx, changed: false }
+    }
 }
 
 pub fn erase_regions<T:TypeFoldable>(tcx: &ty::ctxt, t: T) -> T {
-    let mut eraser = RegionEraser { tcx: tcx };
-    t.fold_with(&mut eraser)
+    let (t, _) = erase_regions_changed(tcx, t);
+    t
+}
+
+/// As `erase_regions`, but also says whether any region was rewritten,
+/// so that a caller caching the result can skip entries that map a
+/// value to itself.
+pub fn erase_regions_changed<T:TypeFoldable>(tcx: &ty::ctxt, t: T) -> (T, bool) {
+    let mut eraser = RegionEraser::new(tcx);
+    let t = if tcx.sess.fold_stats() {
+        let mut adapter = Infallible { folder: &mut eraser };
+        infallible(t.try_fold_with(&mut CountingFolder::new("RegionEraser", &mut adapter)))
+    } else {
+        t.fold_with(&mut eraser)
+    };
+    (t, eraser.changed)
 }
 
 impl<'a, 'tcx> TypeFolder<'tcx> for RegionEraser<'a, 'tcx> {
     fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
 
+    fn skip_ty(&self, t: ty::t) -> bool {
+        !ty::type_has_regions(t)
+    }
+
+    fn fold_ty(&mut self, t: ty::t) -> ty::t {
+        if !ty::type_has_regions(t) {
+            return t;
+        }
+
+        let tcx = self.tcx;
+        // A cached or normalized type is not folded region by region,
+        // so compare the result instead.
+        match tcx.erased_regions_cache.borrow().find_copy(&t) {
+            Some(u) => {
+                tcx.erased_regions_hits.set(tcx.erased_regions_hits.get() + 1);
+                self.changed |= u != t;
+                return u;
+            }
+            None => {}
+        }
+        tcx.erased_regions_misses.set(tcx.erased_regions_misses.get() + 1);
+
+        let t_erased = match ty::get(t).sty {
+            ty::ty_unboxed_closure(..) => {
+                let u = ty::normalize_ty(tcx, t);
+                self.changed |= u != t;
+                u
+            }
+            _ => super_fold_ty(self, t)
+        };
+        tcx.erased_regions_cache.borrow_mut().insert(t, t_erased);
+        t_erased
+    }
+
     fn fold_region(&mut self, r: ty::Region) -> ty::Region {
         match r {
-            ty::ReLateBound(..) | ty::ReEarlyBound(..) => r,
-            _ => ty::ReStatic
+            ty::ReLateBound(..) | ty::ReEarlyBound(..) | ty::ReStatic => r,
+            _ => {
+                self.changed = true;
+                ty::ReStatic
+            }
         }
     }
 }
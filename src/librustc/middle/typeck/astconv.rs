@@ -55,6 +55,7 @@ use middle::resolve_lifetime as rl;
 use middle::subst::{FnSpace, TypeSpace, AssocSpace, SelfSpace, Subst, Substs};
 use middle::subst::{VecPerParamSpace};
 use middle::ty;
+use middle::ty_fold;
 use middle::typeck::lookup_def_tcx;
 use middle::typeck::infer;
 use middle::typeck::rscope::{UnelidableRscope, RegionScope, SpecificRscope, BindingRscope};
@@ -380,15 +381,40 @@ fn ast_path_substs<'tcx,AC,RS>(
                                          -> (Vec<ty::Region>, Vec<ty::t>)
         where AC: AstConv<'tcx>
     {
+        // Elided regions in the inputs are bound by the trait reference
+        // (or type) that `binder_id` names, just like the elided regions
+        // in the arguments of a fn type; explicit `for<'a>` regions are
+        // bound by the same binder. The output then follows the usual
+        // elision rules.
         let binding_rscope = BindingRscope::new(binder_id);
 
-        let inputs = data.inputs.iter()
-                                .map(|a_t| ast_ty_to_ty(this, &binding_rscope, &**a_t))
-                                .collect();
+        let inputs: Vec<ty::t> = data.inputs.iter()
+                                            .map(|a_t| ast_ty_to_ty(this, &binding_rscope, &**a_t))
+                                            .collect();
+        let input_names: Vec<String> = data.inputs.iter()
+                                                  .map(|a_t| pprust::ty_to_string(&**a_t))
+                                                  .collect();
+        let (implied_output_region,
+             param_lifetimes) = find_implied_output_region(inputs.as_slice(), input_names);
         let input_ty = ty::mk_tup_or_nil(this.tcx(), inputs);
 
+        // An omitted output defaults to `()`. A diverging output is the
+        // empty `DivergingOutput` type, so that a call through the bound is
+        // known not to return.
         let output = match data.output {
-            Some(ref output_ty) => ast_ty_to_ty(this, &binding_rscope, &**output_ty),
+            Some(ref output_ty) => match output_ty.node {
+                ast::TyBot => ty::mk_diverging_output(this.tcx()),
+                ast::TyInfer => {
+                    span_err!(this.tcx().sess, output_ty.span, E0174,
+                              "the output type of the parenthetical notation cannot be \
+                               inferred; write it explicitly, or omit it for `()`");
+                    ty::mk_err()
+                }
+                _ => convert_ty_with_lifetime_elision(this,
+                                                      implied_output_region,
+                                                      param_lifetimes,
+                                                      &**output_ty)
+            },
             None => ty::mk_nil()
         };
 
@@ -447,16 +473,29 @@ pub fn ast_path_to_trait_ref<'tcx,AC,RS>(this: &AC,
                                          where AC: AstConv<'tcx>,
                                                RS: RegionScope {
     let trait_def = this.get_trait_def(trait_def_id);
+    let substs = ast_path_substs(this,
+                                 rscope,
+                                 trait_def_id,
+                                 &trait_def.generics,
+                                 self_ty,
+                                 associated_type,
+                                 path,
+                                 binder_id);
+
+    // Only a trait reference that binds regions of its own, such as
+    // `Fn(&int)`, keeps its binder, so that others compare equal
+    // wherever they were written.
+    let binder_id = if binder_id != ast::DUMMY_NODE_ID &&
+                       ty_fold::escaping_region_binders(this.tcx(), &substs)
+                           .contains(&binder_id) {
+        binder_id
+    } else {
+        ast::DUMMY_NODE_ID
+    };
     ty::TraitRef {
         def_id: trait_def_id,
-        substs: ast_path_substs(this,
-                                rscope,
-                                trait_def_id,
-                                &trait_def.generics,
-                                self_ty,
-                                associated_type,
-                                path,
-                                binder_id)
+        substs: substs,
+        binder_id: binder_id
     }
 }
 
@@ -539,7 +578,17 @@ fn check_path_args(tcx: &ty::ctxt,
                    path: &ast::Path,
                    flags: uint) {
     if (flags & NO_TPS) != 0u {
-        if path.segments.iter().any(|s| s.parameters.has_types()) {
+        let parenthesized = path.segments.iter().any(|s| match s.parameters {
+            ast::ParenthesizedParameters(..) => true,
+            ast::AngleBracketedParameters(..) => false,
+        });
+        if parenthesized {
+            // `T(A) -> B` is sugar for `T<(A,),B>`, which makes no sense
+            // for a type that takes no type parameters.
+            span_err!(tcx.sess, path.span, E0176,
+                "the parenthetical notation can only be used with traits \
+                 and types that take type parameters");
+        } else if path.segments.iter().any(|s| s.parameters.has_types()) {
             span_err!(tcx.sess, path.span, E0109,
                 "type parameters are not allowed on this type");
         }
@@ -789,8 +838,7 @@ fn associated_ty_to_ty<'tcx,AC,RS>(this: &AC,
             debug!("associated_ty_to_ty(type_parameter={} substs={})",
                    type_parameter.repr(this.tcx()),
                    trait_ref.substs.repr(this.tcx()));
-            return *trait_ref.substs.types.get(type_parameter.space,
-                                               type_parameter.index)
+            return trait_ref.substs.type_for_def(type_parameter)
         }
     }
     this.tcx().sess.span_bug(span,
@@ -1176,54 +1224,31 @@ fn ty_of_method_or_bare_fn<'tcx, AC: AstConv<'tcx>>(
     let self_and_input_tys: Vec<ty::t> =
         self_ty.into_iter().chain(input_tys).collect();
 
-    let mut lifetimes_for_params: Vec<(String, Vec<ty::Region>)> = Vec::new();
-
     // Second, if there was exactly one lifetime (either a substitution or a
     // reference) in the arguments, then any anonymous regions in the output
     // have that lifetime.
-    if implied_output_region.is_none() {
-        let mut self_and_input_tys_iter = self_and_input_tys.iter();
-        if self_ty.is_some() {
+    let param_lifetimes = match implied_output_region {
+        Some(_) => Vec::new(),
+        None => {
             // Skip the first argument if `self` is present.
-            drop(self_and_input_tys_iter.next())
-        }
-
-        for (input_type, input_pat) in self_and_input_tys_iter.zip(input_pats.into_iter()) {
-            let mut accumulator = Vec::new();
-            ty::accumulate_lifetimes_in_type(&mut accumulator, *input_type);
-            lifetimes_for_params.push((input_pat, accumulator));
-        }
-
-        if lifetimes_for_params.iter().map(|&(_, ref x)| x.len()).sum() == 1 {
-            implied_output_region =
-                Some(lifetimes_for_params.iter()
-                                         .filter_map(|&(_, ref x)|
-                                            if x.len() == 1 { Some(x[0]) } else { None })
-                                         .next().unwrap());
+            let input_tys = if self_ty.is_some() {
+                self_and_input_tys.slice_from(1)
+            } else {
+                self_and_input_tys.as_slice()
+            };
+            let (region, param_lifetimes) = find_implied_output_region(input_tys, input_pats);
+            implied_output_region = region;
+            param_lifetimes
         }
-    }
-
-    let param_lifetimes: Vec<(String, uint)> = lifetimes_for_params.into_iter()
-                                                                   .map(|(n, v)| (n, v.len()))
-                                                                   .filter(|&(_, l)| l != 0)
-                                                                   .collect();
+    };
 
     let output_ty = match decl.output.node {
         ast::TyBot => ty::FnDiverging,
         ast::TyInfer => ty::FnConverging(this.ty_infer(decl.output.span)),
-        _ => ty::FnConverging(match implied_output_region {
-            Some(implied_output_region) => {
-                let rb = SpecificRscope::new(implied_output_region);
-                ast_ty_to_ty(this, &rb, &*decl.output)
-            }
-            None => {
-                // All regions must be explicitly specified in the output
-                // if the lifetime elision rules do not apply. This saves
-                // the user from potentially-confusing errors.
-                let rb = UnelidableRscope::new(param_lifetimes);
-                ast_ty_to_ty(this, &rb, &*decl.output)
-            }
-        })
+        _ => ty::FnConverging(convert_ty_with_lifetime_elision(this,
+                                                               implied_output_region,
+                                                               param_lifetimes,
+                                                               &*decl.output))
     };
 
     (ty::BareFnTy {
@@ -1238,6 +1263,64 @@ fn ty_of_method_or_bare_fn<'tcx, AC: AstConv<'tcx>>(
     }, explicit_self_category_result)
 }
 
+fn find_implied_output_region(input_tys: &[ty::t], input_names: Vec<String>)
+                              -> (Option<ty::Region>, Vec<(String, uint)>)
+{
+    /*!
+     * Applies the lifetime elision rules to the given inputs: if there
+     * is exactly one lifetime (either a substitution or a reference)
+     * among them, it is the region implied for any anonymous regions in
+     * the output. Also returns, for diagnostics, the name and number of
+     * lifetimes of each input that has any.
+     */
+
+    let mut lifetimes_for_params: Vec<(String, Vec<ty::Region>)> = Vec::new();
+    for (input_type, input_name) in input_tys.iter().zip(input_names.into_iter()) {
+        let mut accumulator = Vec::new();
+        ty::accumulate_lifetimes_in_type(&mut accumulator, *input_type);
+        lifetimes_for_params.push((input_name, accumulator));
+    }
+
+    let implied_output_region =
+        if lifetimes_for_params.iter().map(|&(_, ref x)| x.len()).sum() == 1 {
+            Some(lifetimes_for_params.iter()
+                                     .filter_map(|&(_, ref x)|
+                                        if x.len() == 1 { Some(x[0]) } else { None })
+                                     .next().unwrap())
+        } else {
+            None
+        };
+
+    let param_lifetimes: Vec<(String, uint)> = lifetimes_for_params.into_iter()
+                                                                   .map(|(n, v)| (n, v.len()))
+                                                                   .filter(|&(_, l)| l != 0)
+                                                                   .collect();
+
+    (implied_output_region, param_lifetimes)
+}
+
+fn convert_ty_with_lifetime_elision<'tcx,AC>(this: &AC,
+                                             implied_output_region: Option<ty::Region>,
+                                             param_lifetimes: Vec<(String, uint)>,
+                                             ty: &ast::Ty)
+                                             -> ty::t
+    where AC: AstConv<'tcx>
+{
+    match implied_output_region {
+        Some(implied_output_region) => {
+            let rb = SpecificRscope::new(implied_output_region);
+            ast_ty_to_ty(this, &rb, ty)
+        }
+        None => {
+            // All regions must be explicitly specified in the output
+            // if the lifetime elision rules do not apply. This saves
+            // the user from potentially-confusing errors.
+            let rb = UnelidableRscope::new(param_lifetimes);
+            ast_ty_to_ty(this, &rb, ty)
+        }
+    }
+}
+
 fn determine_explicit_self_category<'tcx, AC: AstConv<'tcx>,
                                     RS:RegionScope>(
                                     this: &AC,
@@ -1413,7 +1496,8 @@ pub fn conv_existential_bounds<'tcx, AC: AstConv<'tcx>, RS:RegionScope>(
         .map(|t|
              Rc::new(ty::TraitRef {
                  def_id: t.def_id,
-                 substs: t.substs.with_self_ty(ty::mk_err()) }))
+                 substs: t.substs.with_self_ty(ty::mk_err()),
+                 binder_id: t.binder_id }))
         .collect();
 
     let region_bound = compute_region_bound(this,
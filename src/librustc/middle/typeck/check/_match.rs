@@ -9,7 +9,7 @@
 // except according to those terms.
 
 use middle::def;
-use middle::pat_util::{PatIdMap, pat_id_map, pat_is_binding, pat_is_const};
+use middle::pat_util::{mod, PatIdMap, pat_id_map, pat_is_binding, pat_is_const};
 use middle::subst::{Subst, Substs};
 use middle::ty;
 use middle::typeck::check::{check_expr, check_expr_has_type, demand, FnCtxt};
@@ -19,6 +19,7 @@ use middle::typeck::require_same_types;
 use util::nodemap::FnvHashMap;
 
 use std::cmp;
+use std::iter::AdditiveIterator;
 use std::collections::hash_map::{Occupied, Vacant};
 use syntax::ast;
 use syntax::ast_util;
@@ -28,6 +29,11 @@ use syntax::print::pprust;
 use syntax::ptr::P;
 
 pub fn check_pat(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t) {
+    check_pat_at(pcx, pat, expected, &RootPat(pat))
+}
+
+/// Checks `pat`, found at `pat_path` in the pattern being checked.
+fn check_pat_at(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t, pat_path: &PatPath) {
     let fcx = pcx.fcx;
     let tcx = pcx.fcx.ccx.tcx;
 
@@ -39,9 +45,9 @@ pub fn check_pat(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t) {
             check_expr(fcx, &**lt);
             let expr_ty = fcx.expr_ty(&**lt);
             fcx.write_ty(pat.id, expr_ty);
-            demand::suptype(fcx, pat.span, expected, expr_ty);
+            demand_pat_suptype(pcx, pat_path, pat.span, expected, expr_ty);
         }
-        ast::PatRange(ref begin, ref end) => {
+        ast::PatRange(ref begin, ref end, range_end) => {
             check_expr(fcx, &**begin);
             check_expr(fcx, &**end);
 
@@ -51,16 +57,22 @@ pub fn check_pat(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t) {
                 tcx, Some(fcx.infcx()), false, pat.span, lhs_ty, rhs_ty,
                 || "mismatched types in range".to_string())
                 && (ty::type_is_numeric(lhs_ty) || ty::type_is_char(rhs_ty)) {
-                match valid_range_bounds(fcx.ccx, &**begin, &**end) {
-                    Some(false) => {
-                        span_err!(tcx.sess, begin.span, E0030,
-                            "lower range bound must be less than upper");
-                    },
-                    None => {
-                        span_err!(tcx.sess, begin.span, E0031,
-                            "mismatched types in range");
-                    },
-                    Some(true) => {}
+                if range_end == ast::RangeExcluded
+                    && !(ty::type_is_integral(lhs_ty) || ty::type_is_char(lhs_ty)) {
+                    span_err!(tcx.sess, pat.span, E0173,
+                        "exclusive range patterns are only allowed on integers and chars");
+                } else {
+                    match valid_range_bounds(fcx.ccx, &**begin, &**end, range_end) {
+                        Some(false) => {
+                            span_err!(tcx.sess, begin.span, E0030,
+                                "lower range bound must be less than upper");
+                        },
+                        None => {
+                            span_err!(tcx.sess, begin.span, E0031,
+                                "mismatched types in range");
+                        },
+                        Some(true) => {}
+                    }
                 }
             } else {
                 span_err!(tcx.sess, begin.span, E0029,
@@ -68,13 +80,13 @@ pub fn check_pat(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t) {
             }
 
             fcx.write_ty(pat.id, lhs_ty);
-            demand::eqtype(fcx, pat.span, expected, lhs_ty);
+            demand_pat_eqtype(pcx, pat_path, pat.span, expected, lhs_ty);
         }
         ast::PatEnum(..) | ast::PatIdent(..) if pat_is_const(&tcx.def_map, pat) => {
             let const_did = tcx.def_map.borrow().get_copy(&pat.id).def_id();
             let const_pty = ty::lookup_item_type(tcx, const_did);
             fcx.write_ty(pat.id, const_pty.ty);
-            demand::suptype(fcx, pat.span, expected, const_pty.ty);
+            demand_pat_suptype(pcx, pat_path, pat.span, expected, const_pty.ty);
         }
         ast::PatIdent(bm, ref path, ref sub) if pat_is_binding(&tcx.def_map, pat) => {
             let typ = fcx.local_ty(pat.span, pat.id);
@@ -87,11 +99,11 @@ pub fn check_pat(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t) {
                     let region_var = fcx.infcx().next_region_var(infer::PatternRegion(pat.span));
                     let mt = ty::mt { ty: expected, mutbl: mutbl };
                     let region_ty = ty::mk_rptr(tcx, region_var, mt);
-                    demand::eqtype(fcx, pat.span, region_ty, typ);
+                    demand_pat_eqtype(pcx, pat_path, pat.span, region_ty, typ);
                 }
                 // otherwise the type of x is the expected type T
                 ast::BindByValue(_) => {
-                    demand::eqtype(fcx, pat.span, expected, typ);
+                    demand_pat_eqtype(pcx, pat_path, pat.span, expected, typ);
                 }
             }
             fcx.write_ty(pat.id, typ);
@@ -103,26 +115,52 @@ pub fn check_pat(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t) {
             }
 
             if let Some(ref p) = *sub {
-                check_pat(pcx, &**p, expected);
+                check_pat_at(pcx, &**p, expected, pat_path);
             }
         }
         ast::PatIdent(_, ref path, _) => {
             let path = ast_util::ident_to_path(path.span, path.node);
-            check_pat_enum(pcx, pat, &path, &Some(vec![]), expected);
+            check_pat_enum(pcx, pat, &path, &Some(vec![]), expected, pat_path);
         }
         ast::PatEnum(ref path, ref subpats) => {
-            check_pat_enum(pcx, pat, path, subpats, expected);
+            check_pat_enum(pcx, pat, path, subpats, expected, pat_path);
         }
         ast::PatStruct(ref path, ref fields, etc) => {
-            check_pat_struct(pcx, pat, path, fields.as_slice(), etc, expected);
+            check_pat_struct(pcx, pat, path, fields.as_slice(), etc, expected, pat_path);
         }
         ast::PatTup(ref elements) => {
-            let element_tys = Vec::from_fn(elements.len(), |_| fcx.infcx().next_ty_var());
-            let pat_ty = ty::mk_tup(tcx, element_tys.clone());
-            fcx.write_ty(pat.id, pat_ty);
-            demand::eqtype(fcx, pat.span, expected, pat_ty);
-            for (element_pat, element_ty) in elements.iter().zip(element_tys.into_iter()) {
-                check_pat(pcx, &**element_pat, element_ty);
+            // Without a `..` the pattern itself fixes the number of
+            // elements; with one, the number comes from the expected type.
+            let explicit = explicit_subpat_count(elements.as_slice());
+            let arity = if explicit == elements.len() {
+                Some(explicit)
+            } else {
+                let expected = structurally_resolved_type(fcx, pat.span, expected);
+                match ty::get(expected).sty {
+                    ty::ty_tup(ref tys) if tys.len() < explicit => {
+                        report_too_many_subpats(tcx, pat.span, explicit, "tuple", tys.len());
+                        None
+                    }
+                    ty::ty_tup(ref tys) => Some(tys.len()),
+                    _ => Some(explicit)
+                }
+            };
+
+            match arity {
+                Some(arity) => {
+                    let element_tys = Vec::from_fn(arity, |_| fcx.infcx().next_ty_var());
+                    let pat_ty = ty::mk_tup(tcx, element_tys.clone());
+                    fcx.write_ty(pat.id, pat_ty);
+                    demand_pat_eqtype(pcx, pat_path, pat.span, expected, pat_ty);
+                    check_tuple_subpats(pcx, elements.as_slice(), element_tys.as_slice(),
+                                        pat_path, None);
+                }
+                None => {
+                    fcx.write_error(pat.id);
+                    for element_pat in elements.iter() {
+                        check_pat_at(pcx, &**element_pat, ty::mk_err(), pat_path);
+                    }
+                }
             }
         }
         ast::PatBox(ref inner) => {
@@ -130,12 +168,12 @@ pub fn check_pat(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t) {
             let uniq_ty = ty::mk_uniq(tcx, inner_ty);
 
             if check_dereferencable(pcx, pat.span, expected, &**inner) {
-                demand::suptype(fcx, pat.span, expected, uniq_ty);
+                demand_pat_suptype(pcx, pat_path, pat.span, expected, uniq_ty);
                 fcx.write_ty(pat.id, uniq_ty);
-                check_pat(pcx, &**inner, inner_ty);
+                check_pat_at(pcx, &**inner, inner_ty, pat_path);
             } else {
                 fcx.write_error(pat.id);
-                check_pat(pcx, &**inner, ty::mk_err());
+                check_pat_at(pcx, &**inner, ty::mk_err(), pat_path);
             }
         }
         ast::PatRegion(ref inner) => {
@@ -153,60 +191,159 @@ pub fn check_pat(pcx: &pat_ctxt, pat: &ast::Pat, expected: ty::t) {
             let rptr_ty = ty::mk_rptr(tcx, region, mt);
 
             if check_dereferencable(pcx, pat.span, expected, &**inner) {
-                demand::suptype(fcx, pat.span, expected, rptr_ty);
+                demand_pat_suptype(pcx, pat_path, pat.span, expected, rptr_ty);
                 fcx.write_ty(pat.id, rptr_ty);
-                check_pat(pcx, &**inner, inner_ty);
+                check_pat_at(pcx, &**inner, inner_ty, pat_path);
             } else {
                 fcx.write_error(pat.id);
-                check_pat(pcx, &**inner, ty::mk_err());
+                check_pat_at(pcx, &**inner, ty::mk_err(), pat_path);
             }
         }
         ast::PatVec(ref before, ref slice, ref after) => {
             let expected_ty = structurally_resolved_type(fcx, pat.span, expected);
-            let inner_ty = fcx.infcx().next_ty_var();
-            let pat_ty = match ty::get(expected_ty).sty {
-                ty::ty_vec(_, Some(size)) => ty::mk_vec(tcx, inner_ty, Some({
-                    let min_len = before.len() + after.len();
-                    match *slice {
+
+            // Reuse the element type of the scrutinee when it is already
+            // known, so that nested vector patterns see a resolved type for
+            // their own scrutinee instead of a fresh type variable.
+            let inner_ty = match ty::get(expected_ty).sty {
+                ty::ty_vec(elem_ty, _) => elem_ty,
+                ty::ty_rptr(_, ty::mt { ty: vec_ty, .. }) => match ty::get(vec_ty).sty {
+                    ty::ty_vec(elem_ty, None) => elem_ty,
+                    _ => fcx.infcx().next_ty_var()
+                },
+                _ => fcx.infcx().next_ty_var()
+            };
+
+            // When the scrutinee is itself a borrowed slice, the subslice
+            // borrows from the same memory, so it shares the scrutinee's
+            // region and mutability rather than getting fresh ones.
+            let borrowed_slice = match ty::get(expected_ty).sty {
+                ty::ty_rptr(region, ty::mt { ty: vec_ty, mutbl }) => {
+                    match ty::get(vec_ty).sty {
+                        ty::ty_vec(_, None) => Some((region, mutbl)),
+                        _ => None
+                    }
+                }
+                _ => None
+            };
+
+            let (pat_ty, slice_region, mutbl) = match ty::get(expected_ty).sty {
+                ty::ty_vec(_, Some(size)) => {
+                    let min_len = before.iter().chain(after.iter())
+                        .map(|elt| pat_util::vec_pat_elem_width(&**elt, inner_ty))
+                        .sum();
+                    let len = match *slice {
+                        Some(_) if min_len > size => {
+                            span_err!(tcx.sess, pat.span, E0168,
+                                "pattern requires at least {} element{} but the array has {}",
+                                min_len, if min_len == 1 {""} else {"s"}, size);
+                            size
+                        }
+                        None if min_len != size => {
+                            span_err!(tcx.sess, pat.span, E0169,
+                                "pattern requires {} element{} but the array has {}",
+                                min_len, if min_len == 1 {""} else {"s"}, size);
+                            size
+                        }
                         Some(_) => cmp::max(min_len, size),
                         None => min_len
-                    }
-                })),
+                    };
+                    (ty::mk_vec(tcx, inner_ty, Some(len)),
+                     fcx.infcx().next_region_var(infer::PatternRegion(pat.span)),
+                     ast::MutImmutable)
+                }
                 _ => {
-                    let region = fcx.infcx().next_region_var(infer::PatternRegion(pat.span));
-                    ty::mk_slice(tcx, region, ty::mt {
-                        ty: inner_ty,
-                        mutbl: ty::deref(expected_ty, true)
-                            .map_or(ast::MutImmutable, |mt| mt.mutbl)
-                    })
+                    let (region, mutbl) = borrowed_slice.unwrap_or_else(|| {
+                        (fcx.infcx().next_region_var(infer::PatternRegion(pat.span)),
+                         ty::deref(expected_ty, true).map_or(ast::MutImmutable, |mt| mt.mutbl))
+                    });
+                    (ty::mk_slice(tcx, region, ty::mt { ty: inner_ty, mutbl: mutbl }),
+                     region,
+                     mutbl)
                 }
             };
 
             fcx.write_ty(pat.id, pat_ty);
-            demand::suptype(fcx, pat.span, expected, pat_ty);
+            demand_pat_suptype(pcx, pat_path, pat.span, expected, pat_ty);
 
-            for elt in before.iter() {
-                check_pat(pcx, &**elt, inner_ty);
+            for (i, elt) in before.iter().enumerate() {
+                check_vec_pat_elt(pcx, &**elt, inner_ty, &SubPat(pat_path, ElementSegment(i)));
             }
             if let Some(ref slice) = *slice {
-                let region = fcx.infcx().next_region_var(infer::PatternRegion(pat.span));
-                let mutbl = ty::deref(expected_ty, true)
-                    .map_or(ast::MutImmutable, |mt| mt.mutbl);
-
-                let slice_ty = ty::mk_slice(tcx, region, ty::mt {
-                    ty: inner_ty,
-                    mutbl: mutbl
-                });
-                check_pat(pcx, &**slice, slice_ty);
+                match slice.node {
+                    ast::PatWild(_) => {}
+                    ast::PatIdent(_, _, None) if pat_is_binding(&tcx.def_map, &**slice) => {}
+                    _ => {
+                        // Only bindings are extracted for the subslice
+                        // position, so anything more structured would be
+                        // silently ignored by exhaustiveness checking and
+                        // trans.
+                        span_err!(tcx.sess, slice.span, E0170,
+                            "subslice patterns must be a binding or `..`");
+                    }
+                }
+                match slice.node {
+                    // `ref rest..` and `ref mut rest..` already borrow from
+                    // the vector being matched, so the binding gets the
+                    // slice type itself rather than a reference to a
+                    // temporary slice.
+                    ast::PatIdent(ast::BindByRef(ref_mutbl), ref path, None)
+                            if pat_is_binding(&tcx.def_map, &**slice) => {
+                        let slice_ty = ty::mk_slice(tcx, slice_region, ty::mt {
+                            ty: inner_ty,
+                            mutbl: ref_mutbl
+                        });
+                        let typ = fcx.local_ty(slice.span, slice.id);
+                        demand::eqtype(fcx, slice.span, slice_ty, typ);
+                        fcx.write_ty(slice.id, typ);
+
+                        let canon_id = pcx.map[path.node];
+                        if canon_id != slice.id {
+                            let ct = fcx.local_ty(slice.span, canon_id);
+                            demand::eqtype(fcx, slice.span, ct, typ);
+                        }
+                    }
+                    _ => {
+                        let slice_ty = ty::mk_slice(tcx, slice_region, ty::mt {
+                            ty: inner_ty,
+                            mutbl: mutbl
+                        });
+                        check_pat_at(pcx, &**slice, slice_ty,
+                                     &SubPat(pat_path, SubsliceSegment(before.len())));
+                    }
+                }
+            }
+            for (i, elt) in after.iter().enumerate() {
+                let index = before.len() + slice.iter().count() + i;
+                check_vec_pat_elt(pcx, &**elt, inner_ty, &SubPat(pat_path, ElementSegment(index)));
             }
-            for elt in after.iter() {
-                check_pat(pcx, &**elt, inner_ty);
+        }
+        ast::PatOr(ref alternatives) => {
+            // Every alternative matches the same value; same-named bindings
+            // are tied to the ones in the first alternative through `pcx.map`.
+            fcx.write_ty(pat.id, expected);
+            for alternative in alternatives.iter() {
+                check_pat_at(pcx, &**alternative, expected, pat_path);
             }
         }
         ast::PatMac(_) => tcx.sess.bug("unexpanded macro")
     }
 }
 
+/// Checks an element of a vector pattern whose elements are of type `elem_ty`.
+/// Among `u8` elements, a byte string literal matches as many elements as it
+/// has bytes, so it is checked as the literal it is rather than as a `u8`.
+fn check_vec_pat_elt(pcx: &pat_ctxt, elt: &ast::Pat, elem_ty: ty::t, pat_path: &PatPath) {
+    let fcx = pcx.fcx;
+    match (pat_util::byte_str_pat_bytes(elt), &elt.node, &ty::get(elem_ty).sty) {
+        (Some(_), &ast::PatLit(ref lt), &ty::ty_uint(ast::TyU8)) => {
+            check_expr(fcx, &**lt);
+            fcx.write_ty(elt.id, fcx.expr_ty(&**lt));
+        }
+        _ => check_pat_at(pcx, elt, elem_ty, pat_path)
+    }
+}
+
 pub fn check_dereferencable(pcx: &pat_ctxt, span: Span, expected: ty::t,
                             inner: &ast::Pat) -> bool {
     let fcx = pcx.fcx;
@@ -290,9 +427,127 @@ pub struct pat_ctxt<'a, 'tcx: 'a> {
     pub map: PatIdMap,
 }
 
+/// Where a pattern sits in the top-level pattern being checked, so that the
+/// type errors about a deeply nested pattern can tell which one it is.
+pub enum PatPath<'a> {
+    /// The top-level pattern itself.
+    RootPat(&'a ast::Pat),
+    /// A sub-pattern of the pattern at the given path.
+    SubPat(&'a PatPath<'a>, PatPathSegment)
+}
+
+pub enum PatPathSegment {
+    /// A named field of a struct or struct variant.
+    FieldSegment(ast::Name),
+    /// A positional field of a tuple, tuple struct or enum variant, along
+    /// with the name of the variant.
+    PositionalSegment(Option<ast::Name>, uint),
+    /// An element of a vector pattern, by its position in the pattern.
+    ElementSegment(uint),
+    /// The subslice of a vector pattern, by its position in the pattern.
+    SubsliceSegment(uint)
+}
+
+impl<'a> PatPath<'a> {
+    fn root(&self) -> &'a ast::Pat {
+        match *self {
+            RootPat(pat) => pat,
+            SubPat(parent, _) => parent.root()
+        }
+    }
+
+    /// Writes the path from the top-level pattern, e.g. `config.retries` or
+    /// `Some.0[1]`.
+    fn push_path_string(&self, s: &mut String) {
+        let segment = match *self {
+            RootPat(_) => return,
+            SubPat(parent, ref segment) => {
+                parent.push_path_string(s);
+                segment
+            }
+        };
+        match *segment {
+            FieldSegment(_) | PositionalSegment(..) if !s.is_empty() => s.push('.'),
+            _ => {}
+        }
+        match *segment {
+            FieldSegment(name) => s.push_str(token::get_name(name).get()),
+            PositionalSegment(Some(variant), i) => {
+                s.push_str(format!("{}.{}", token::get_name(variant), i).as_slice())
+            }
+            PositionalSegment(None, i) => s.push_str(i.to_string().as_slice()),
+            ElementSegment(i) => s.push_str(format!("[{}]", i).as_slice()),
+            SubsliceSegment(i) => s.push_str(format!("[{}..]", i).as_slice())
+        }
+    }
+}
+
+/// A short form of `pat` that shows what kind of pattern it is without
+/// its sub-patterns, e.g. `Settings { .. }`.
+fn pat_outline(pat: &ast::Pat) -> String {
+    match pat.node {
+        ast::PatStruct(ref path, _, _) => {
+            format!("{} {{ .. }}", pprust::path_to_string(path))
+        }
+        ast::PatEnum(ref path, _) => format!("{}(..)", pprust::path_to_string(path)),
+        ast::PatTup(_) => "(..)".to_string(),
+        ast::PatVec(..) => "[..]".to_string(),
+        ast::PatBox(ref inner) => format!("box {}", pat_outline(&**inner)),
+        ast::PatRegion(ref inner) => format!("&{}", pat_outline(&**inner)),
+        ast::PatIdent(_, ref ident, Some(ref inner)) => {
+            format!("{} @ {}", token::get_ident(ident.node), pat_outline(&**inner))
+        }
+        _ => pprust::pat_to_string(pat)
+    }
+}
+
+/// Adds a note on where the pattern at `pat_path` is found, if it's nested
+/// in another one.
+fn note_pat_path(pcx: &pat_ctxt, pat_path: &PatPath) {
+    let kind = match *pat_path {
+        RootPat(_) => return,
+        SubPat(_, FieldSegment(_)) | SubPat(_, PositionalSegment(..)) => "field",
+        SubPat(_, ElementSegment(_)) => "element",
+        SubPat(_, SubsliceSegment(_)) => "subslice"
+    };
+    let mut path = String::new();
+    pat_path.push_path_string(&mut path);
+    let root = pat_path.root();
+    pcx.fcx.ccx.tcx.sess.span_note(root.span,
+        format!("in {} `{}` of pattern `{}`", kind, path, pat_outline(root)).as_slice());
+}
+
+/// Requires the type `actual` of the pattern at `pat_path` to be a subtype of
+/// the type `expected` of the value it matches.
+fn demand_pat_suptype(pcx: &pat_ctxt, pat_path: &PatPath, sp: Span,
+                      expected: ty::t, actual: ty::t) {
+    let fcx = pcx.fcx;
+    demand::suptype_with_fn(fcx, sp, false, expected, actual, |sp, e, a, err| {
+        let err_count = fcx.ccx.tcx.sess.err_count();
+        fcx.report_mismatched_types(sp, e, a, err);
+        if fcx.ccx.tcx.sess.err_count() > err_count {
+            note_pat_path(pcx, pat_path);
+        }
+    })
+}
+
+/// Requires the type `actual` of the pattern at `pat_path` to be the type
+/// `expected`.
+fn demand_pat_eqtype(pcx: &pat_ctxt, pat_path: &PatPath, sp: Span,
+                     expected: ty::t, actual: ty::t) {
+    let fcx = pcx.fcx;
+    demand::eqtype_with_fn(fcx, sp, expected, actual, |sp, e, a, err| {
+        let err_count = fcx.ccx.tcx.sess.err_count();
+        fcx.report_mismatched_types(sp, e, a, err);
+        if fcx.ccx.tcx.sess.err_count() > err_count {
+            note_pat_path(pcx, pat_path);
+        }
+    })
+}
+
 pub fn check_pat_struct(pcx: &pat_ctxt, pat: &ast::Pat,
                         path: &ast::Path, fields: &[Spanned<ast::FieldPat>],
-                        etc: bool, expected: ty::t) {
+                        etc: bool, expected: ty::t, pat_path: &PatPath) {
     let fcx = pcx.fcx;
     let tcx = pcx.fcx.ccx.tcx;
 
@@ -310,7 +565,8 @@ pub fn check_pat_struct(pcx: &pat_ctxt, pat: &ast::Pat,
             fcx.write_error(pat.id);
 
             for field in fields.iter() {
-                check_pat(pcx, &*field.node.pat, ty::mk_err());
+                check_pat_at(pcx, &*field.node.pat, ty::mk_err(),
+                             &SubPat(pat_path, FieldSegment(field.node.ident.name)));
             }
             return;
         }
@@ -320,7 +576,7 @@ pub fn check_pat_struct(pcx: &pat_ctxt, pat: &ast::Pat,
                      def, pat.span, pat.id);
 
     let pat_ty = fcx.node_ty(pat.id);
-    demand::eqtype(fcx, pat.span, expected, pat_ty);
+    demand_pat_eqtype(pcx, pat_path, pat.span, expected, pat_ty);
 
     let item_substs = fcx
         .item_substs()
@@ -330,12 +586,12 @@ pub fn check_pat_struct(pcx: &pat_ctxt, pat: &ast::Pat,
 
     let struct_fields = ty::struct_fields(tcx, variant_def_id, &item_substs);
     check_struct_pat_fields(pcx, pat.span, fields, struct_fields.as_slice(),
-                            variant_def_id, etc);
+                            variant_def_id, etc, pat_path);
 }
 
 pub fn check_pat_enum(pcx: &pat_ctxt, pat: &ast::Pat,
                       path: &ast::Path, subpats: &Option<Vec<P<ast::Pat>>>,
-                      expected: ty::t) {
+                      expected: ty::t, pat_path: &PatPath) {
 
     // Typecheck the path.
     let fcx = pcx.fcx;
@@ -357,19 +613,19 @@ pub fn check_pat_enum(pcx: &pat_ctxt, pat: &ast::Pat,
     instantiate_path(pcx.fcx, path, path_ty, def, pat.span, pat.id);
 
     let pat_ty = fcx.node_ty(pat.id);
-    demand::eqtype(fcx, pat.span, expected, pat_ty);
+    demand_pat_eqtype(pcx, pat_path, pat.span, expected, pat_ty);
 
     let real_path_ty = fcx.node_ty(pat.id);
-    let (arg_tys, kind_name) = match ty::get(real_path_ty).sty {
+    let (arg_tys, kind_name, variant_name) = match ty::get(real_path_ty).sty {
         ty::ty_enum(enum_def_id, ref expected_substs) => {
             let variant = ty::enum_variant_with_id(tcx, enum_def_id, def.def_id());
             (variant.args.iter().map(|t| t.subst(tcx, expected_substs)).collect::<Vec<_>>(),
-                "variant")
+                "variant", Some(variant.name))
         }
         ty::ty_struct(struct_def_id, ref expected_substs) => {
             let struct_fields = ty::struct_fields(tcx, struct_def_id, expected_substs);
             (struct_fields.iter().map(|field| field.mt.ty).collect::<Vec<_>>(),
-                "struct")
+                "struct", None)
         }
         _ => {
             let name = pprust::path_to_string(path);
@@ -379,7 +635,7 @@ pub fn check_pat_enum(pcx: &pat_ctxt, pat: &ast::Pat,
 
             if let Some(ref subpats) = *subpats {
                 for pat in subpats.iter() {
-                    check_pat(pcx, &**pat, ty::mk_err());
+                    check_pat_at(pcx, &**pat, ty::mk_err(), pat_path);
                 }
             }
             return;
@@ -387,43 +643,85 @@ pub fn check_pat_enum(pcx: &pat_ctxt, pat: &ast::Pat,
     };
 
     if let Some(ref subpats) = *subpats {
-        if subpats.len() == arg_tys.len() {
-            for (subpat, arg_ty) in subpats.iter().zip(arg_tys.iter()) {
-                check_pat(pcx, &**subpat, *arg_ty);
-            }
+        let explicit = explicit_subpat_count(subpats.as_slice());
+        let has_dotdot = explicit != subpats.len();
+        if explicit == arg_tys.len() || (has_dotdot && explicit < arg_tys.len()) {
+            check_tuple_subpats(pcx, subpats.as_slice(), arg_tys.as_slice(),
+                                pat_path, variant_name);
         } else if arg_tys.len() == 0 {
             span_err!(tcx.sess, pat.span, E0024,
                       "this pattern has {} field{}, but the corresponding {} has no fields",
-                      subpats.len(), if subpats.len() == 1 {""} else {"s"}, kind_name);
+                      explicit, if explicit == 1 {""} else {"s"}, kind_name);
 
             for pat in subpats.iter() {
-                check_pat(pcx, &**pat, ty::mk_err());
+                check_pat_at(pcx, &**pat, ty::mk_err(), pat_path);
             }
         } else {
-            span_err!(tcx.sess, pat.span, E0023,
-                      "this pattern has {} field{}, but the corresponding {} has {} field{}",
-                      subpats.len(), if subpats.len() == 1 {""} else {"s"},
-                      kind_name,
-                      arg_tys.len(), if arg_tys.len() == 1 {""} else {"s"});
+            if has_dotdot {
+                report_too_many_subpats(tcx, pat.span, explicit, kind_name, arg_tys.len());
+            } else {
+                span_err!(tcx.sess, pat.span, E0023,
+                          "this pattern has {} field{}, but the corresponding {} has {} field{}",
+                          subpats.len(), if subpats.len() == 1 {""} else {"s"},
+                          kind_name,
+                          arg_tys.len(), if arg_tys.len() == 1 {""} else {"s"});
+            }
 
             for pat in subpats.iter() {
-                check_pat(pcx, &**pat, ty::mk_err());
+                check_pat_at(pcx, &**pat, ty::mk_err(), pat_path);
             }
         }
     }
 }
 
+/// The number of sub-patterns of a tuple or tuple-struct pattern, not
+/// counting the `..`.
+fn explicit_subpat_count(subpats: &[P<ast::Pat>]) -> uint {
+    match pat_util::tuple_pat_dotdot_pos(subpats) {
+        Some(_) => subpats.len() - 1,
+        None => subpats.len()
+    }
+}
+
+fn report_too_many_subpats(tcx: &ty::ctxt, span: Span, explicit: uint,
+                           kind_name: &str, arity: uint) {
+    span_err!(tcx.sess, span, E0171,
+              "this pattern has {} field{} besides the `..`, but the corresponding {} \
+               has only {} field{}",
+              explicit, if explicit == 1 {""} else {"s"},
+              kind_name,
+              arity, if arity == 1 {""} else {"s"});
+}
+
+/// Checks the sub-patterns of a tuple or tuple-struct pattern against
+/// the types of the elements. A `..` is given the tuple of the types of
+/// the elements it stands for.
+fn check_tuple_subpats(pcx: &pat_ctxt, subpats: &[P<ast::Pat>], element_tys: &[ty::t],
+                       pat_path: &PatPath, variant_name: Option<ast::Name>) {
+    for &(i, subpat) in pat_util::tuple_pat_elements(subpats, element_tys.len()).iter() {
+        check_pat_at(pcx, &**subpat, element_tys[i],
+                     &SubPat(pat_path, PositionalSegment(variant_name, i)));
+    }
+    if let Some(pos) = pat_util::tuple_pat_dotdot_pos(subpats) {
+        let elided = element_tys.slice(pos, pos + element_tys.len() + 1 - subpats.len());
+        check_pat_at(pcx, &*subpats[pos], ty::mk_tup(pcx.fcx.ccx.tcx, elided.to_vec()),
+                     pat_path);
+    }
+}
+
 /// `path` is the AST path item naming the type of this struct.
 /// `fields` is the field patterns of the struct pattern.
 /// `struct_fields` describes the type of each field of the struct.
 /// `struct_id` is the ID of the struct.
 /// `etc` is true if the pattern said '...' and false otherwise.
+/// `pat_path` is where the struct pattern is found in the pattern being checked.
 pub fn check_struct_pat_fields(pcx: &pat_ctxt,
                                span: Span,
                                fields: &[Spanned<ast::FieldPat>],
                                struct_fields: &[ty::field],
                                struct_id: ast::DefId,
-                               etc: bool) {
+                               etc: bool,
+                               pat_path: &PatPath) {
     let tcx = pcx.fcx.ccx.tcx;
 
     // Index the struct fields' types.
@@ -460,7 +758,8 @@ pub fn check_struct_pat_fields(pcx: &pat_ctxt,
             }
         };
 
-        check_pat(pcx, &*field.pat, field_type);
+        check_pat_at(pcx, &*field.pat, field_type,
+                     &SubPat(pat_path, FieldSegment(field.ident.name)));
     }
 
     // Report an error if not all the fields were specified.
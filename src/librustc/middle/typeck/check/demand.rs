@@ -9,6 +9,7 @@
 // except according to those terms.
 
 
+use middle::def;
 use middle::ty;
 use middle::typeck::check::FnCtxt;
 use middle::typeck::infer;
@@ -50,16 +51,49 @@ pub fn suptype_with_fn(fcx: &FnCtxt,
 }
 
 pub fn eqtype(fcx: &FnCtxt, sp: Span, expected: ty::t, actual: ty::t) {
+    eqtype_with_fn(fcx, sp, expected, actual,
+        |sp, e, a, s| { fcx.report_mismatched_types(sp, e, a, s) })
+}
+
+pub fn eqtype_with_fn(fcx: &FnCtxt,
+                      sp: Span,
+                      expected: ty::t,
+                      actual: ty::t,
+                      handle_err: |Span, ty::t, ty::t, &ty::type_err|) {
     match infer::mk_eqty(fcx.infcx(), false, infer::Misc(sp), actual, expected) {
         Ok(()) => { /* ok */ }
         Err(ref err) => {
-            fcx.report_mismatched_types(sp, expected, actual, err);
+            handle_err(sp, expected, actual, err);
         }
     }
 }
 
+/// Where a coercion takes place, for explaining its adjustment in errors.
+#[deriving(Clone, PartialEq)]
+pub enum CoercionSite {
+    /// The nth argument of a call, counting from 1
+    CoerceArgument(uint),
+    /// The value of a `return`
+    CoerceReturn,
+    /// The initializer of a `let`
+    CoerceAssignment,
+    /// Anywhere else, such as a struct field or an array element
+    CoerceOther,
+}
+
+/// A reference inserted by the adjustment of a coercion, which converted
+/// an expression at `span` to the `target` type expected at `site`.
+#[deriving(Clone)]
+pub struct AdjustmentOrigin {
+    pub site: CoercionSite,
+    pub span: Span,
+    pub mutbl: ast::Mutability,
+    pub target: ty::t,
+}
+
 // Checks that the type `actual` can be coerced to `expected`.
-pub fn coerce(fcx: &FnCtxt, sp: Span, expected: ty::t, expr: &ast::Expr) {
+pub fn coerce(fcx: &FnCtxt, sp: Span, expected: ty::t, expr: &ast::Expr,
+              site: CoercionSite) {
     let expr_ty = fcx.expr_ty(expr);
     debug!("demand::coerce(expected = {}, expr_ty = {})",
            expected.repr(fcx.ccx.tcx),
@@ -71,9 +105,114 @@ pub fn coerce(fcx: &FnCtxt, sp: Span, expected: ty::t, expr: &ast::Expr) {
                      try_resolve_tvar_shallow).unwrap_or(expected)
     } else { expected };
     match fcx.mk_assignty(expr, expr_ty, expected) {
-      result::Ok(()) => { /* ok */ }
+      result::Ok(()) => record_adjustment_origin(fcx, expr, expected, site),
       result::Err(ref err) => {
         fcx.report_mismatched_types(sp, expected, expr_ty, err);
+        note_adjustment_origin(fcx, expr, expr_ty);
+        suggest_borrow(fcx, expected, expr_ty, expr);
       }
     }
 }
+
+// Remembers where the reference inserted by the adjustment of `expr`, if
+// any, came from.
+fn record_adjustment_origin(fcx: &FnCtxt, expr: &ast::Expr, expected: ty::t,
+                            site: CoercionSite) {
+    let mutbl = match fcx.inh.adjustments.borrow().get(&expr.id) {
+        Some(&ty::AdjustDerefRef(ty::AutoDerefRef {
+            autoref: Some(ty::AutoPtr(_, mutbl, _)), ..
+        })) => mutbl,
+        _ => return,
+    };
+    fcx.inh.adjustment_origins.borrow_mut().insert(expr.id, AdjustmentOrigin {
+        site: site,
+        span: expr.span,
+        mutbl: mutbl,
+        target: expected,
+    });
+}
+
+pub fn forward_adjustment_origin(fcx: &FnCtxt, from: ast::NodeId, to: ast::NodeId) {
+    /*!
+     * Records that the value of node `to` is the reference that the
+     * coercion recorded for node `from`, if any, inserted: as for a
+     * local initialized by the coerced expression.
+     */
+
+    let origin = match fcx.inh.adjustment_origins.borrow().get(&from) {
+        Some(origin) => origin.clone(),
+        None => return,
+    };
+    fcx.inh.adjustment_origins.borrow_mut().insert(to, origin);
+}
+
+fn note_adjustment_origin(fcx: &FnCtxt, expr: &ast::Expr, actual: ty::t) {
+    /*!
+     * Called when `expr`, of type `actual`, does not have the type
+     * expected of it. If its value is a reference that a coercion
+     * inserted, either in `expr` itself or in the initializer of the
+     * local that `expr` names, points at that coercion.
+     */
+
+    let id = match expr.node {
+        ast::ExprPath(..) => match fcx.tcx().def_map.borrow().get(&expr.id) {
+            Some(&def::DefLocal(nid)) => nid,
+            _ => expr.id,
+        },
+        _ => expr.id,
+    };
+    let origins = fcx.inh.adjustment_origins.borrow();
+    let origin = match origins.get(&id) {
+        Some(origin) => origin,
+        None => return,
+    };
+
+    // Only a reference of the kind that was inserted is explained.
+    let actual = fcx.infcx().resolve_type_vars_if_possible(actual);
+    match ty::get(actual).sty {
+        ty::ty_rptr(_, mt) if mt.mutbl == origin.mutbl => {}
+        _ => return,
+    }
+
+    let reference = match origin.mutbl {
+        ast::MutMutable => "&mut",
+        ast::MutImmutable => "&",
+    };
+    let site = match origin.site {
+        CoerceArgument(n) => format!(" of argument {}", n),
+        CoerceReturn => " of the return value".to_string(),
+        CoerceAssignment => " of the variable".to_string(),
+        CoerceOther => String::new(),
+    };
+    fcx.tcx().sess.span_note(
+        origin.span,
+        format!("an automatic `{}`-reference was inserted here to match \
+                 the expected type `{}`{}",
+                reference,
+                fcx.infcx().ty_to_string(origin.target),
+                site).as_slice());
+}
+
+// If `expr` would have the expected type once borrowed, as when a value is
+// passed where a reference to it is wanted, suggests adding the `&`.
+fn suggest_borrow(fcx: &FnCtxt, expected: ty::t, expr_ty: ty::t, expr: &ast::Expr) {
+    let mt = match ty::get(expected).sty {
+        ty::ty_rptr(_, mt) => mt,
+        _ => return,
+    };
+    if ty::type_is_error(expected) || ty::type_is_error(expr_ty) ||
+       infer::can_mk_subty(fcx.infcx(), expr_ty, mt.ty).is_err() {
+        return;
+    }
+    let sess = &fcx.tcx().sess;
+    match sess.codemap().span_to_snippet(expr.span) {
+        Some(snippet) => {
+            let borrow = match mt.mutbl {
+                ast::MutMutable => "&mut ",
+                ast::MutImmutable => "&",
+            };
+            sess.span_suggestion(expr.span, "try", format!("{}{}", borrow, snippet));
+        }
+        None => {}
+    }
+}
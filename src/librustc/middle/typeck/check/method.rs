@@ -85,6 +85,7 @@ use middle::subst::{Subst, SelfSpace};
 use middle::traits;
 use middle::ty::*;
 use middle::ty;
+use middle::ty_fold;
 use middle::typeck::astconv::AstConv;
 use middle::typeck::check::{FnCtxt, NoPreference, PreferMutLvalue};
 use middle::typeck::check::{impl_self_ty};
@@ -94,15 +95,14 @@ use middle::typeck::infer;
 use middle::typeck::{MethodCall, MethodCallee};
 use middle::typeck::{MethodOrigin, MethodParam, MethodTypeParam};
 use middle::typeck::{MethodStatic, MethodStaticUnboxedClosure, MethodObject, MethodTraitObject};
-use middle::typeck::check::regionmanip::replace_late_bound_regions;
 use middle::typeck::TypeAndSubsts;
 use middle::typeck::check::vtable;
-use middle::ty_fold::TypeFoldable;
 use util::common::indenter;
 use util::ppaux;
 use util::ppaux::{Repr, UserString};
 
 use std::collections::HashSet;
+use std::mem;
 use std::rc::Rc;
 use syntax::ast::{DefId, MutImmutable, MutMutable};
 use syntax::ast;
@@ -250,9 +250,7 @@ pub fn lookup_in_trait_adjusted<'a, 'tcx>(
     // Substitute the trait parameters into the method type and
     // instantiate late-bound regions to get the actual method type.
     let ref bare_fn_ty = method_ty.fty;
-    let fn_sig = bare_fn_ty.sig.subst(tcx, &trait_ref.substs);
-    let fn_sig = replace_late_bound_regions_with_fresh_var(fcx.infcx(), span,
-                                                           fn_sig.binder_id, &fn_sig);
+    let fn_sig = instantiate_method_sig(fcx.infcx(), span, &*method_ty, &trait_ref.substs);
     let transformed_self_ty = fn_sig.inputs[0];
     let fty = ty::mk_bare_fn(tcx, ty::BareFnTy {
         sig: fn_sig,
@@ -397,21 +395,35 @@ pub fn report_error(fcx: &FnCtxt,
         }
 
         Ambiguity(sources) => {
-            span_err!(fcx.sess(), span, E0034,
-                      "multiple applicable methods in scope");
-
-            report_candidates(fcx, span, method_name, sources);
+            let labels = candidate_labels(fcx, span, method_name, sources);
+            span_err_with_labels!(fcx.sess(), span, labels.as_slice(), E0034,
+                                  "multiple applicable methods in scope");
         }
     }
 
     fn report_candidates(fcx: &FnCtxt,
                          span: Span,
                          method_name: ast::Name,
-                         mut sources: Vec<CandidateSource>) {
+                         sources: Vec<CandidateSource>) {
+        for &(label_span, ref label) in candidate_labels(fcx, span, method_name, sources).iter() {
+            fcx.sess().span_note(label_span, label.as_slice());
+        }
+    }
+
+    fn candidate_labels(fcx: &FnCtxt,
+                        span: Span,
+                        method_name: ast::Name,
+                        mut sources: Vec<CandidateSource>)
+                        -> Vec<(Span, String)> {
+        /*!
+         * Describes where each candidate method is defined, for pointing
+         * at from a method error reported at `span`.
+         */
+
         sources.sort();
         sources.dedup();
 
-        for (idx, source) in sources.iter().enumerate() {
+        sources.iter().enumerate().map(|(idx, source)| {
             match *source {
                 ImplSource(impl_did) => {
                     // Provide the best span we can. Use the method, if local to crate, else
@@ -429,22 +441,22 @@ pub fn report_error(fcx: &FnCtxt,
                                                                      trait_ref.def_id)),
                     };
 
-                    span_note!(fcx.sess(), method_span,
-                               "candidate #{} is defined in an impl{} for the type `{}`",
-                               idx + 1u,
-                               insertion,
-                               impl_ty.user_string(fcx.tcx()));
+                    (method_span,
+                     format!("candidate #{} is defined in an impl{} for the type `{}`",
+                             idx + 1u,
+                             insertion,
+                             impl_ty.user_string(fcx.tcx())))
                 }
                 TraitSource(trait_did) => {
                     let (_, method) = trait_method(fcx.tcx(), trait_did, method_name).unwrap();
                     let method_span = fcx.tcx().map.def_id_span(method.def_id, span);
-                    span_note!(fcx.sess(), method_span,
-                               "candidate #{} is defined in the trait `{}`",
-                               idx + 1u,
-                               ty::item_path_str(fcx.tcx(), trait_did));
+                    (method_span,
+                     format!("candidate #{} is defined in the trait `{}`",
+                             idx + 1u,
+                             ty::item_path_str(fcx.tcx(), trait_did)))
                 }
             }
-        }
+        }).collect()
     }
 }
 
@@ -760,7 +772,8 @@ impl<'a, 'tcx> LookupContext<'a, 'tcx> {
         // argument type like `&Trait`.
         let rcvr_substs = principal.substs.with_self_ty(self_ty);
         let trait_ref = Rc::new(TraitRef { def_id: principal.def_id,
-                                           substs: rcvr_substs.clone() });
+                                           substs: rcvr_substs.clone(),
+                                           binder_id: principal.binder_id });
 
         self.push_inherent_candidates_from_bounds_inner(
             &[trait_ref.clone()],
@@ -1501,23 +1514,18 @@ impl<'a, 'tcx> LookupContext<'a, 'tcx> {
                 self.span,
                 candidate.method_ty.generics.regions.get_slice(subst::FnSpace));
 
-        let all_substs = rcvr_substs.with_method(m_types, m_regions);
+        let mut all_substs = rcvr_substs.with_method(m_types, m_regions);
 
         let ref bare_fn_ty = candidate.method_ty.fty;
 
-        // Compute the method type with type parameters substituted
+        // Compute the method type with the parameters substituted and
+        // late-bound regions replaced with region variables
         debug!("fty={} all_substs={}",
                bare_fn_ty.repr(tcx),
                all_substs.repr(tcx));
 
-        let fn_sig = bare_fn_ty.sig.subst(tcx, &all_substs);
-
-        debug!("after subst, fty={}", fn_sig.repr(tcx));
-
-        // Replace any bound regions that appear in the function
-        // signature with region variables
-        let fn_sig =
-            self.replace_late_bound_regions_with_fresh_var(fn_sig.binder_id, &fn_sig);
+        let fn_sig = instantiate_method_sig(self.fcx.infcx(), self.span,
+                                            &*candidate.method_ty, &all_substs);
         let transformed_self_ty = fn_sig.inputs[0];
         let fty = ty::mk_bare_fn(tcx, ty::BareFnTy {
             sig: fn_sig,
@@ -1555,15 +1563,17 @@ impl<'a, 'tcx> LookupContext<'a, 'tcx> {
         // `err` (just for trait objects) when we generate the
         // obligations.  This causes us to generate the obligation
         // `err:Trait`, and the error type is considered to implement
-        // all traits, so we're all good. Hack hack hack.
+        // all traits, so we're all good. Hack hack hack. The binding is
+        // put back afterwards, rather than overwritten in a copy.
         match candidate.origin {
             MethodTraitObject(..) => {
-                let mut temp_substs = all_substs.clone();
-                temp_substs.types.get_mut_slice(SelfSpace)[0] = ty::mk_err();
+                let self_ty = mem::replace(all_substs.types.get_mut(SelfSpace, 0),
+                                           ty::mk_err());
                 self.fcx.add_obligations_for_parameters(
                     traits::ObligationCause::misc(self.span),
-                    &temp_substs,
+                    &all_substs,
                     &candidate.method_ty.generics);
+                *all_substs.types.get_mut(SelfSpace, 0) = self_ty;
             }
             _ => {
                 self.fcx.add_obligations_for_parameters(
@@ -1795,30 +1805,48 @@ impl<'a, 'tcx> LookupContext<'a, 'tcx> {
     }
 
     fn xform_self_ty(&self, method: &Rc<ty::Method>, substs: &subst::Substs) -> ty::t {
+        // Only the receiver is substituted here: the probe substs have
+        // no entries for the method's own type parameters, which may
+        // appear in the other inputs.
         let xform_self_ty = method.fty.sig.inputs[0].subst(self.tcx(), substs);
-        self.replace_late_bound_regions_with_fresh_var(method.fty.sig.binder_id, &xform_self_ty)
-    }
-
-    fn replace_late_bound_regions_with_fresh_var<T>(&self, binder_id: ast::NodeId, value: &T) -> T
-        where T : TypeFoldable + Repr
-    {
-        replace_late_bound_regions_with_fresh_var(self.fcx.infcx(), self.span, binder_id, value)
+        let (xform_self_ty, _) = ty_fold::replace_late_bound_regions(
+            self.tcx(),
+            method.fty.sig.binder_id,
+            &xform_self_ty,
+            |br| self.fcx.infcx().next_region_var(infer::LateBoundRegion(self.span, br)));
+        xform_self_ty
     }
 }
 
-fn replace_late_bound_regions_with_fresh_var<T>(infcx: &infer::InferCtxt,
-                                                span: Span,
-                                                binder_id: ast::NodeId,
-                                                value: &T)
-                                                -> T
-    where T : TypeFoldable + Repr
+pub fn instantiate_method_sig(infcx: &infer::InferCtxt,
+                              span: Span,
+                              method: &ty::Method,
+                              substs: &subst::Substs)
+                              -> ty::FnSig
 {
-    let (_, value) = replace_late_bound_regions(
+    /*!
+     * Instantiates the signature of `method` for a call at `span`.
+     * `substs` gives the values of all of the method's early-bound
+     * parameters: those of the trait or impl, `Self` (for a trait
+     * method), and the method's own. These are substituted first, and
+     * then each late-bound region of the signature is replaced with a
+     * fresh region variable, leaving a signature that is ready to
+     * unify with the receiver and arguments.
+     *
+     * The order matters: the late-bound regions are found by the
+     * signature's binder, which substitution leaves in place, whereas
+     * the types being substituted in never mention that binder.
+     */
+
+    let fn_sig = method.fty.sig.subst(infcx.tcx, substs);
+    debug!("instantiate_method_sig: after subst, fn_sig={}", fn_sig.repr(infcx.tcx));
+
+    let (fn_sig, _) = ty_fold::replace_late_bound_regions(
         infcx.tcx,
-        binder_id,
-        value,
+        fn_sig.binder_id,
+        &fn_sig,
         |br| infcx.next_region_var(infer::LateBoundRegion(span, br)));
-    value
+    fn_sig
 }
 
 fn trait_method(tcx: &ty::ctxt,
@@ -92,14 +92,16 @@ use middle::ty::{FnSig, VariantInfo};
 use middle::ty::{Polytype};
 use middle::ty::{Disr, ParamTy, ParameterEnvironment};
 use middle::ty;
+use middle::ty_fold;
 use middle::ty_fold::TypeFolder;
 use middle::typeck::astconv::AstConv;
 use middle::typeck::astconv::{ast_region_to_region, ast_ty_to_ty};
 use middle::typeck::astconv;
 use middle::typeck::check::_match::pat_ctxt;
+use middle::typeck::check::demand::{AdjustmentOrigin, CoercionSite, CoerceArgument};
+use middle::typeck::check::demand::{CoerceAssignment, CoerceOther, CoerceReturn};
 use middle::typeck::check::method::{AutoderefReceiver};
-use middle::typeck::check::method::{CheckTraitsAndInherentMethods};
-use middle::typeck::check::regionmanip::replace_late_bound_regions;
+use middle::typeck::check::method::{CheckTraitsAndInherentMethods, CheckTraitsOnly};
 use middle::typeck::CrateCtxt;
 use middle::typeck::infer;
 use middle::typeck::rscope::RegionScope;
@@ -164,8 +166,12 @@ pub struct Inherited<'a, 'tcx: 'a> {
     node_types: RefCell<NodeMap<ty::t>>,
     item_substs: RefCell<NodeMap<ty::ItemSubsts>>,
     adjustments: RefCell<NodeMap<ty::AutoAdjustment>>,
+    // Where each reference inserted by a coercion's adjustment came
+    // from, for explaining it when a later type error involves it
+    adjustment_origins: RefCell<NodeMap<AdjustmentOrigin>>,
     method_map: MethodMap,
     upvar_borrow_map: RefCell<ty::UpvarBorrowMap>,
+    upvar_capture_modes: RefCell<ty::UpvarCaptureModeMap>,
     unboxed_closures: RefCell<DefIdMap<ty::UnboxedClosure>>,
     object_cast_map: ObjectCastMap,
 
@@ -315,6 +321,12 @@ impl<'a, 'tcx> mem_categorization::Typer<'tcx> for FnCtxt<'a, 'tcx> {
                     -> ast::CaptureClause {
         self.ccx.tcx.capture_mode(closure_expr_id)
     }
+    fn upvar_capture_mode(&self, upvar_id: ty::UpvarId) -> ast::CaptureClause {
+        match self.inh.upvar_capture_modes.borrow().get(&upvar_id) {
+            Some(&mode) => mode,
+            None => self.ccx.tcx.capture_mode(upvar_id.closure_expr_id),
+        }
+    }
     fn unboxed_closures<'a>(&'a self) -> &'a RefCell<DefIdMap<ty::UnboxedClosure>> {
         &self.inh.unboxed_closures
     }
@@ -331,9 +343,11 @@ impl<'a, 'tcx> Inherited<'a, 'tcx> {
             node_types: RefCell::new(NodeMap::new()),
             item_substs: RefCell::new(NodeMap::new()),
             adjustments: RefCell::new(NodeMap::new()),
+            adjustment_origins: RefCell::new(NodeMap::new()),
             method_map: RefCell::new(FnvHashMap::new()),
             object_cast_map: RefCell::new(NodeMap::new()),
             upvar_borrow_map: RefCell::new(FnvHashMap::new()),
+            upvar_capture_modes: RefCell::new(FnvHashMap::new()),
             unboxed_closures: RefCell::new(DefIdMap::new()),
             fn_sig_map: RefCell::new(NodeMap::new()),
             region_obligations: RefCell::new(NodeMap::new()),
@@ -525,9 +539,7 @@ fn check_fn<'a, 'tcx>(ccx: &'a CrateCtxt<'a, 'tcx>,
 
     // First, we have to replace any bound regions in the fn type with free ones.
     // The free region references will be bound the node_id of the body block.
-    let (_, fn_sig) = replace_late_bound_regions(tcx, fn_sig.binder_id, fn_sig, |br| {
-        ty::ReFree(ty::FreeRegion {scope_id: body.id, bound_region: br})
-    });
+    let (fn_sig, _) = ty_fold::liberate_late_bound_regions(tcx, body.id, fn_sig);
 
     let arg_tys = fn_sig.inputs.as_slice();
     let ret_ty = fn_sig.output;
@@ -1463,7 +1475,7 @@ fn check_cast(fcx: &FnCtxt,
                 /* this case is allowed */
             }
             _ => {
-                demand::coerce(fcx, e.span, t_1, &*e);
+                demand::coerce(fcx, e.span, t_1, &*e, CoerceOther);
             }
         }
     } else if !(ty::type_is_scalar(t_e) && t_1_is_trivial) {
@@ -1647,7 +1659,8 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
                                        span: Span,
                                        adj: &ty::AutoAdjustment) {
         match *adj {
-            ty::AdjustAddEnv(..) => { }
+            ty::AdjustAddEnv(..) |
+            ty::AdjustUnboxedClosureToFnPointer(..) => { }
             ty::AdjustDerefRef(ref d_r) => {
                 match d_r.autoref {
                     Some(ref a_r) => {
@@ -1897,6 +1910,11 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
                        sub: ty::t,
                        sup: ty::t)
                        -> Result<(), ty::type_err> {
+        match self.coerce_unboxed_closure_to_fn_pointer(expr, sub, sup) {
+            Some(result) => return result,
+            None => {}
+        }
+
         match infer::mk_coercety(self.infcx(),
                                  false,
                                  infer::ExprAssignable(expr.span),
@@ -1911,6 +1929,77 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         }
     }
 
+    fn coerce_unboxed_closure_to_fn_pointer(&self,
+                                            expr: &ast::Expr,
+                                            sub: ty::t,
+                                            sup: ty::t)
+                                            -> Option<Result<(), ty::type_err>> {
+        /*!
+         * Coerces an unboxed closure that captures nothing to a bare
+         * `fn` pointer with the same signature. This lives here rather
+         * than in `infer::coercion` because the signatures of the
+         * closures of this function are only known to the `FnCtxt`.
+         * Returns `None` if this is not such a coercion.
+         */
+
+        let tcx = self.tcx();
+        let (closure_id, substs) = match ty::get(self.infcx().shallow_resolve(sub)).sty {
+            ty::ty_unboxed_closure(closure_id, _, ref substs) => (closure_id, substs.clone()),
+            _ => return None,
+        };
+        match ty::get(self.infcx().shallow_resolve(sup)).sty {
+            ty::ty_bare_fn(..) => {}
+            _ => return None,
+        }
+        let closure_type = match self.inh.unboxed_closures.borrow().get(&closure_id) {
+            Some(unboxed_closure) => unboxed_closure.closure_type.clone(),
+            None => return None,
+        };
+
+        let captured = ty::with_freevars(tcx, closure_id.node, |freevars| {
+            freevars.iter().next().map(|freevar| *freevar)
+        });
+        match captured {
+            Some(freevar) => {
+                span_err!(tcx.sess, expr.span, E0175,
+                          "cannot coerce an unboxed closure that captures variables \
+                           to a fn pointer");
+                span_note!(tcx.sess, freevar.span,
+                           "the closure captures `{}` here",
+                           ty::local_var_name_str(tcx, freevar.def.def_id().node));
+                return Some(Ok(()));
+            }
+            None => {}
+        }
+
+        // The closure takes its arguments as a single tuple; the fn
+        // pointer takes them separately.
+        let mut sig = closure_type.sig.subst(tcx, &substs);
+        sig.inputs = match ty::get(sig.inputs[0]).sty {
+            ty::ty_tup(ref tys) => tys.clone(),
+            _ => Vec::new(),
+        };
+        let fn_ptr_ty = ty::mk_bare_fn(tcx, ty::BareFnTy {
+            fn_style: ast::NormalFn,
+            abi: abi::Rust,
+            sig: sig,
+        });
+
+        match infer::mk_subty(self.infcx(),
+                              false,
+                              infer::ExprAssignable(expr.span),
+                              fn_ptr_ty,
+                              sup) {
+            Ok(()) => {
+                self.write_adjustment(expr.id,
+                                      expr.span,
+                                      ty::AdjustUnboxedClosureToFnPointer(fn_ptr_ty));
+                Some(Ok(()))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+
     pub fn mk_eqty(&self,
                    a_is_expected: bool,
                    origin: infer::TypeOrigin,
@@ -2146,6 +2235,17 @@ fn try_overloaded_call<'a>(fcx: &FnCtxt,
         _ => {}
     }
 
+    // A trait object of one of the function traits (or something that
+    // autoderefs to one, like `Box<FnMut(int) -> int>`) is called
+    // through the object's vtable.
+    match lookup_object_call(fcx, call_expression, callee, callee_type) {
+        Some(method_callee) => {
+            check_overloaded_call_arguments(fcx, call_expression, args, method_callee);
+            return true
+        }
+        None => {}
+    }
+
     // Try the options that are least restrictive on the caller first.
     for &(maybe_function_trait, method_name) in [
         (fcx.tcx().lang_items.fn_trait(), token::intern("call")),
@@ -2167,31 +2267,96 @@ fn try_overloaded_call<'a>(fcx: &FnCtxt,
                 None => continue,
                 Some(method_callee) => method_callee,
             };
-        let method_call = MethodCall::expr(call_expression.id);
-        let output_type = check_method_argument_types(fcx,
-                                                      call_expression.span,
-                                                      method_callee.ty,
-                                                      call_expression,
-                                                      args,
-                                                      DontDerefArgs,
-                                                      TupleArguments);
-        fcx.inh.method_map.borrow_mut().insert(method_call, method_callee);
-        write_call(fcx, call_expression, output_type);
-
-        if !fcx.tcx().sess.features.borrow().overloaded_calls {
-            span_err!(fcx.tcx().sess, call_expression.span, E0056,
-                "overloaded calls are experimental");
-            span_help!(fcx.tcx().sess, call_expression.span,
-                "add `#![feature(overloaded_calls)]` to \
-                the crate attributes to enable");
-        }
-
+        check_overloaded_call_arguments(fcx, call_expression, args, method_callee);
         return true
     }
 
     false
 }
 
+fn lookup_object_call(fcx: &FnCtxt,
+                      call_expression: &ast::Expr,
+                      callee: &ast::Expr,
+                      callee_type: ty::t)
+                      -> Option<MethodCallee> {
+    /*!
+     * If `callee_type` autoderefs to a trait object of one of the
+     * function traits, looks up the method of that trait that a call
+     * resolves to, recording a `MethodTraitObject` origin and the
+     * adjustments of the callee.
+     */
+
+    let tcx = fcx.tcx();
+    let (_, _, method_name) =
+        autoderef(fcx, callee.span, callee_type, None, NoPreference, |t, _| {
+            let def_id = match ty::get(t).sty {
+                ty::ty_trait(box ty::TyTrait { ref principal, .. }) => principal.def_id,
+                _ => return None
+            };
+            if Some(def_id) == tcx.lang_items.fn_trait() {
+                Some(token::intern("call"))
+            } else if Some(def_id) == tcx.lang_items.fn_mut_trait() {
+                Some(token::intern("call_mut"))
+            } else if Some(def_id) == tcx.lang_items.fn_once_trait() {
+                Some(token::intern("call_once"))
+            } else {
+                None
+            }
+        });
+    let method_name = match method_name {
+        Some(method_name) => method_name,
+        None => return None
+    };
+
+    match method::lookup(fcx,
+                         call_expression,
+                         callee,
+                         method_name,
+                         callee_type,
+                         &[],
+                         DontDerefArgs,
+                         CheckTraitsOnly,
+                         AutoderefReceiver) {
+        Ok(method_callee) => Some(method_callee),
+        Err(_) => None
+    }
+}
+
+fn check_overloaded_call_arguments<'a>(fcx: &FnCtxt,
+                                       call_expression: &ast::Expr,
+                                       args: &[&'a P<ast::Expr>],
+                                       method_callee: MethodCallee) {
+    let method_call = MethodCall::expr(call_expression.id);
+    let output_type = check_method_argument_types(fcx,
+                                                  call_expression.span,
+                                                  method_callee.ty,
+                                                  call_expression,
+                                                  args,
+                                                  DontDerefArgs,
+                                                  TupleArguments);
+    fcx.inh.method_map.borrow_mut().insert(method_call, method_callee);
+
+    // A call through a bound such as `FnOnce() -> !` diverges just like
+    // a call to a diverging fn.
+    let output_type = match output_type {
+        ty::FnConverging(output_ty)
+            if ty::type_is_diverging_output(
+                fcx.tcx(), fcx.infcx().resolve_type_vars_if_possible(output_ty)) => {
+            ty::FnDiverging
+        }
+        output_type => output_type
+    };
+    write_call(fcx, call_expression, output_type);
+
+    if !fcx.tcx().sess.features.borrow().overloaded_calls {
+        span_err!(fcx.tcx().sess, call_expression.span, E0056,
+            "overloaded calls are experimental");
+        span_help!(fcx.tcx().sess, call_expression.span,
+            "add `#![feature(overloaded_calls)]` to \
+            the crate attributes to enable");
+    }
+}
+
 fn try_overloaded_deref(fcx: &FnCtxt,
                         span: Span,
                         method_call: Option<MethodCall>,
@@ -2769,7 +2934,7 @@ fn check_argument_types<'a>(fcx: &FnCtxt,
                     DontDerefArgs => {}
                 }
 
-                check_expr_coercable_to_type(fcx, &***arg, formal_ty);
+                check_expr_coercable_to_type(fcx, &***arg, formal_ty, CoerceArgument(i + 1));
             }
         }
     }
@@ -2872,10 +3037,14 @@ fn check_lit(fcx: &FnCtxt,
 
 pub fn valid_range_bounds(ccx: &CrateCtxt,
                           from: &ast::Expr,
-                          to: &ast::Expr)
+                          to: &ast::Expr,
+                          end: ast::RangeEnd)
                        -> Option<bool> {
     match const_eval::compare_lit_exprs(ccx.tcx, from, to) {
-        Some(val) => Some(val <= 0),
+        Some(val) => Some(match end {
+            ast::RangeIncluded => val <= 0,
+            ast::RangeExcluded => val < 0,
+        }),
         None => None
     }
 }
@@ -2890,10 +3059,11 @@ pub fn check_expr_has_type(fcx: &FnCtxt,
 
 fn check_expr_coercable_to_type(fcx: &FnCtxt,
                                 expr: &ast::Expr,
-                                expected: ty::t) {
+                                expected: ty::t,
+                                site: CoercionSite) {
     check_expr_with_unifier(
         fcx, expr, ExpectHasType(expected), NoPreference,
-        || demand::coerce(fcx, expr.span, expected, expr));
+        || demand::coerce(fcx, expr.span, expected, expr, site));
 }
 
 fn check_expr_with_hint(fcx: &FnCtxt, expr: &ast::Expr, expected: ty::t) {
@@ -3055,9 +3225,10 @@ fn check_expr_with_unifier(fcx: &FnCtxt,
 
         // Replace any bound regions that appear in the function
         // signature with region variables
-        let (_, fn_sig) = replace_late_bound_regions(fcx.tcx(), fn_sig.binder_id, fn_sig, |br| {
-            fcx.infcx().next_region_var(infer::LateBoundRegion(call_expr.span, br))
-        });
+        let (fn_sig, _) =
+            ty_fold::replace_late_bound_regions(fcx.tcx(), fn_sig.binder_id, fn_sig, |br| {
+                fcx.infcx().next_region_var(infer::LateBoundRegion(call_expr.span, br))
+            });
 
         // Call the generic checker.
         check_argument_types(fcx,
@@ -3069,6 +3240,24 @@ fn check_expr_with_unifier(fcx: &FnCtxt,
                              fn_sig.variadic,
                              DontTupleArguments);
 
+        // A call that returns the very type an argument was coerced to,
+        // as `fn view<'a>(xs: &'a [u8]) -> &'a [u8]` does, is taken to
+        // pass the reference inserted for that argument through.
+        match fn_sig.output {
+            ty::FnConverging(output) => {
+                for arg in args.iter() {
+                    let forwards = match fcx.inh.adjustment_origins.borrow().get(&arg.id) {
+                        Some(origin) => origin.target == output,
+                        None => false,
+                    };
+                    if forwards {
+                        demand::forward_adjustment_origin(fcx, arg.id, call_expr.id);
+                    }
+                }
+            }
+            ty::FnDiverging => {}
+        }
+
         write_call(fcx, call_expr, fn_sig.output);
     }
 
@@ -3521,8 +3710,8 @@ fn check_expr_with_unifier(fcx: &FnCtxt,
              expected_bounds) = {
             match expected_sty {
                 Some(ty::ty_closure(ref cenv)) => {
-                    let (_, sig) =
-                        replace_late_bound_regions(
+                    let (sig, _) =
+                        ty_fold::replace_late_bound_regions(
                             tcx, cenv.sig.binder_id, &cenv.sig,
                             |_| fcx.inh.infcx.fresh_bound_region(expr.id));
                     let onceness = match (&store, &cenv.store) {
@@ -3791,7 +3980,8 @@ fn check_expr_with_unifier(fcx: &FnCtxt,
             check_expr_coercable_to_type(
                     fcx,
                     &*field.expr,
-                    expected_field_type);
+                    expected_field_type,
+                    CoerceOther);
         }
 
         if error_happened {
@@ -4153,7 +4343,7 @@ fn check_expr_with_unifier(fcx: &FnCtxt,
                                 "`return;` in function returning non-nil");
                         },
                     Some(ref e) => {
-                        check_expr_coercable_to_type(fcx, &**e, result_type);
+                        check_expr_coercable_to_type(fcx, &**e, result_type, CoerceReturn);
                     }
                 }
             }
@@ -4325,7 +4515,7 @@ fn check_expr_with_unifier(fcx: &FnCtxt,
         let typ = match uty {
             Some(uty) => {
                 for e in args.iter() {
-                    check_expr_coercable_to_type(fcx, &**e, uty);
+                    check_expr_coercable_to_type(fcx, &**e, uty, CoerceOther);
                 }
                 uty
             }
@@ -4356,7 +4546,7 @@ fn check_expr_with_unifier(fcx: &FnCtxt,
 
         let (element_ty, t) = match uty {
             Some(uty) => {
-                check_expr_coercable_to_type(fcx, &**element, uty);
+                check_expr_coercable_to_type(fcx, &**element, uty, CoerceOther);
                 (uty, uty)
             }
             None => {
@@ -4397,7 +4587,7 @@ fn check_expr_with_unifier(fcx: &FnCtxt,
             let t = match flds {
                 Some(ref fs) if i < fs.len() => {
                     let ety = fs[i];
-                    check_expr_coercable_to_type(fcx, &**e, ety);
+                    check_expr_coercable_to_type(fcx, &**e, ety, CoerceOther);
                     ety
                 }
                 _ => {
@@ -4692,7 +4882,7 @@ pub fn check_decl_initializer(fcx: &FnCtxt,
                               init: &ast::Expr)
                             {
     let local_ty = fcx.local_ty(init.span, nid);
-    check_expr_coercable_to_type(fcx, init, local_ty)
+    check_expr_coercable_to_type(fcx, init, local_ty, CoerceAssignment)
 }
 
 pub fn check_decl_local(fcx: &FnCtxt, local: &ast::Local)  {
@@ -4704,6 +4894,12 @@ pub fn check_decl_local(fcx: &FnCtxt, local: &ast::Local)  {
     match local.init {
         Some(ref init) => {
             check_decl_initializer(fcx, local.id, &**init);
+            match local.pat.node {
+                ast::PatIdent(_, _, None) => {
+                    demand::forward_adjustment_origin(fcx, init.id, local.pat.id);
+                }
+                _ => {}
+            }
             let init_ty = fcx.expr_ty(&**init);
             if ty::type_is_error(init_ty) {
                 fcx.write_ty(local.id, init_ty);
@@ -4837,7 +5033,7 @@ fn check_block_with_expected(fcx: &FnCtxt,
             }
             let ety = match expected {
                 ExpectHasType(ety) => {
-                    check_expr_coercable_to_type(fcx, &**e, ety);
+                    check_expr_coercable_to_type(fcx, &**e, ety, CoerceOther);
                     ety
                 }
                 _ => {
@@ -4899,7 +5095,7 @@ pub fn check_const_with_ty(fcx: &FnCtxt,
     GatherLocalsVisitor { fcx: fcx }.visit_expr(e);
 
     check_expr_with_hint(fcx, e, declty);
-    demand::coerce(fcx, e.span, declty, e);
+    demand::coerce(fcx, e.span, declty, e, CoerceOther);
     vtable::select_all_fcx_obligations_or_error(fcx);
     regionck::regionck_expr(fcx, e);
     writeback::resolve_type_vars_in_expr(fcx, e);
@@ -5503,11 +5699,21 @@ pub fn instantiate_path(fcx: &FnCtxt,
             substs.types.push(space, tuple_ty);
         }
 
-        let output_ty: Option<ty::t> =
-            data.output.as_ref().map(|ty| fcx.to_ty(&**ty));
-
-        let output_ty =
-            output_ty.unwrap_or(ty::mk_nil());
+        // See `parenthesized_parameters()` in astconv for the treatment
+        // of `!`, `_` and omitted outputs.
+        let output_ty = match data.output {
+            Some(ref output) => match output.node {
+                ast::TyBot => ty::mk_diverging_output(fcx.tcx()),
+                ast::TyInfer => {
+                    span_err!(fcx.tcx().sess, output.span, E0174,
+                              "the output type of the parenthetical notation cannot be \
+                               inferred; write it explicitly, or omit it for `()`");
+                    ty::mk_err()
+                }
+                _ => fcx.to_ty(&**output)
+            },
+            None => ty::mk_nil()
+        };
 
         if type_count >= 2 {
             substs.types.push(space, output_ty);
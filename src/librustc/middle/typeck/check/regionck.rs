@@ -116,10 +116,24 @@ concrete borrow kind like `ty::ImmBorrow`, we return a
 then mean that all later passes would have to check for these figments
 and report an error, and it just seems like more mess in the end.)
 
+### Inferring capture modes for unboxed closures
+
+An unboxed closure that is not declared `move` captures each upvar
+either by reference or by value. Before checking the fn body, we walk
+every such closure, innermost first, and look for uses of its upvars
+that move out of them (or out of something they own). Those upvars are
+captured by value; all others are captured by reference and then go
+through the borrow kind inference above. The result is recorded in
+the `upvar_capture_modes` table. Walking innermost first means that a
+nested closure which itself captures an upvar by value also forces the
+enclosing closure to capture it by value.
+
 */
 
 use middle::def;
+use middle::expr_use_visitor as euv;
 use middle::mem_categorization as mc;
+use middle::mem_categorization::Typer;
 use middle::traits;
 use middle::ty::{ReScope};
 use middle::ty;
@@ -132,7 +146,7 @@ use middle::typeck::infer::resolve_type;
 use middle::typeck::infer;
 use middle::typeck::MethodCall;
 use middle::pat_util;
-use util::nodemap::{DefIdMap, NodeMap, FnvHashMap};
+use util::nodemap::{DefIdMap, NodeMap, NodeSet, FnvHashMap};
 use util::ppaux::{ty_to_string, Repr};
 
 use syntax::ast;
@@ -166,7 +180,9 @@ pub fn regionck_fn(fcx: &FnCtxt, id: ast::NodeId, blk: &ast::Block) {
     let mut rcx = Rcx::new(fcx, blk.id);
     if fcx.err_count_since_creation() == 0 {
         // regionck assumes typeck succeeded
+        infer_capture_modes_in_fn_body(&rcx, blk);
         rcx.visit_fn_body(id, blk);
+        check_closure_kinds_in_fn_body(&rcx, blk);
     }
 
     // Region checking a fn can introduce new trait obligations,
@@ -487,6 +503,13 @@ impl<'fcx, 'tcx> mc::Typer<'tcx> for Rcx<'fcx, 'tcx> {
         self.tcx().capture_modes.borrow().get_copy(&closure_expr_id)
     }
 
+    fn upvar_capture_mode(&self, upvar_id: ty::UpvarId) -> ast::CaptureClause {
+        match self.fcx.inh.upvar_capture_modes.borrow().get(&upvar_id) {
+            Some(&mode) => mode,
+            None => self.capture_mode(upvar_id.closure_expr_id),
+        }
+    }
+
     fn unboxed_closures<'a>(&'a self)
                         -> &'a RefCell<DefIdMap<ty::UnboxedClosure>> {
         &self.fcx.inh.unboxed_closures
@@ -871,15 +894,12 @@ fn check_expr_fn_block(rcx: &mut Rcx,
             });
         }
         ty::ty_unboxed_closure(_, region, _) => {
-            if tcx.capture_modes.borrow().get_copy(&expr.id) == ast::CaptureByRef {
-                ty::with_freevars(tcx, expr.id, |freevars| {
-                    if !freevars.is_empty() {
-                        // Variables being referenced must be constrained and registered
-                        // in the upvar borrow map
-                        constrain_free_variables_in_by_ref_closure(
-                            rcx, region, expr, freevars);
-                    }
-                })
+            let freevars = by_ref_freevars(rcx, expr);
+            if !freevars.is_empty() {
+                // Variables being referenced must be constrained and registered
+                // in the upvar borrow map
+                constrain_free_variables_in_by_ref_closure(
+                    rcx, region, expr, freevars.as_slice());
             }
         }
         _ => { }
@@ -896,15 +916,29 @@ fn check_expr_fn_block(rcx: &mut Rcx,
             })
         }
         ty::ty_unboxed_closure(..) => {
-            if tcx.capture_modes.borrow().get_copy(&expr.id) == ast::CaptureByRef {
-                ty::with_freevars(tcx, expr.id, |freevars| {
-                    propagate_upupvar_borrow_kind(rcx, expr, freevars);
-                });
-            }
+            let freevars = by_ref_freevars(rcx, expr);
+            propagate_upupvar_borrow_kind(rcx, expr, freevars.as_slice());
         }
         _ => {}
     }
 
+    fn by_ref_freevars(rcx: &Rcx, expr: &ast::Expr) -> Vec<ty::Freevar> {
+        /*!
+         * Returns the free variables of the unboxed closure `expr`
+         * that are captured by reference, either because the closure
+         * is not `move` or because capture inference found that the
+         * body only borrows them.
+         */
+
+        ty::with_freevars(rcx.tcx(), expr.id, |freevars| {
+            freevars.iter().filter(|freevar| {
+                let upvar_id = ty::UpvarId { var_id: freevar.def.def_id().node,
+                                             closure_expr_id: expr.id };
+                rcx.upvar_capture_mode(upvar_id) == ast::CaptureByRef
+            }).map(|freevar| *freevar).collect()
+        })
+    }
+
     match ty::get(function_type).sty {
         ty::ty_closure(box ty::ClosureTy {bounds, ..}) => {
             ty::with_freevars(tcx, expr.id, |freevars| {
@@ -1833,6 +1867,362 @@ fn adjust_upvar_borrow_kind_for_unique(rcx: &Rcx, cmt: mc::cmt) {
     }
 }
 
+fn infer_capture_modes_in_fn_body(rcx: &Rcx, body: &ast::Block) {
+    let mut visitor = CaptureModeVisitor { rcx: rcx };
+    visitor.visit_block(body);
+}
+
+struct CaptureModeVisitor<'a, 'fcx: 'a, 'tcx: 'fcx> {
+    rcx: &'a Rcx<'fcx, 'tcx>,
+}
+
+impl<'a, 'fcx, 'tcx, 'v> Visitor<'v> for CaptureModeVisitor<'a, 'fcx, 'tcx> {
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        // Visit nested closures first, see the module comment.
+        visit::walk_expr(self, expr);
+
+        match expr.node {
+            ast::ExprUnboxedFn(ast::CaptureByRef, _, ref decl, ref body) => {
+                infer_capture_modes_for_closure(self.rcx, expr, &**decl, &**body);
+            }
+            _ => {}
+        }
+    }
+
+    // Items nested within the fn body are checked on their own.
+    fn visit_item(&mut self, _: &ast::Item) { }
+}
+
+fn infer_capture_modes_for_closure(rcx: &Rcx,
+                                   expr: &ast::Expr,
+                                   decl: &ast::FnDecl,
+                                   body: &ast::Block) {
+    /*!
+     * Decides, for each upvar of the unboxed closure `expr`, whether
+     * it is captured by value or by reference, and records that in
+     * the `upvar_capture_modes` table.
+     */
+
+    let tcx = rcx.tcx();
+    let freevars: Vec<ty::Freevar> =
+        ty::with_freevars(tcx, expr.id, |fv| fv.iter().map(|&fv| fv).collect());
+    if freevars.is_empty() {
+        return;
+    }
+
+    let upvar_ids: Vec<ty::UpvarId> = freevars.iter().map(|freevar| {
+        ty::UpvarId { var_id: freevar.def.def_id().node,
+                      closure_expr_id: expr.id }
+    }).collect();
+
+    // Categorize the upvars as captured by value while walking the body,
+    // so that mem-categorization does not ask for borrows that have not
+    // been created yet.
+    for upvar_id in upvar_ids.iter() {
+        rcx.fcx.inh.upvar_capture_modes.borrow_mut().insert(*upvar_id, ast::CaptureByValue);
+    }
+
+    let mut finder = UpvarMoveFinder { closure_expr_id: expr.id,
+                                       moved_upvars: NodeSet::new() };
+    {
+        let typer = CaptureModeTyper { rcx: rcx };
+        let mut euv = euv::ExprUseVisitor::new(&mut finder, &typer);
+        euv.walk_fn(decl, body);
+    }
+
+    for upvar_id in upvar_ids.iter() {
+        let mode = if finder.moved_upvars.contains(&upvar_id.var_id) {
+            ast::CaptureByValue
+        } else {
+            ast::CaptureByRef
+        };
+        debug!("infer_capture_modes_for_closure: {} captured {}",
+               upvar_id.repr(tcx), mode);
+        rcx.fcx.inh.upvar_capture_modes.borrow_mut().insert(*upvar_id, mode);
+    }
+}
+
+struct UpvarMoveFinder {
+    closure_expr_id: ast::NodeId,
+    moved_upvars: NodeSet,
+}
+
+impl UpvarMoveFinder {
+    fn note_consume(&mut self, cmt: mc::cmt, mode: euv::ConsumeMode) {
+        match mode {
+            euv::Move(_) => {}
+            euv::Copy => return,
+        }
+
+        // Moving out of anything owned by the upvar requires the closure
+        // to own the upvar.
+        let mut cmt = cmt;
+        loop {
+            let base = match cmt.cat {
+                mc::cat_upvar(ref upvar) => {
+                    if upvar.id.closure_expr_id == self.closure_expr_id {
+                        self.moved_upvars.insert(upvar.id.var_id);
+                    }
+                    return;
+                }
+                mc::cat_deref(ref base, _, _) |
+                mc::cat_interior(ref base, _) |
+                mc::cat_downcast(ref base) => base.clone(),
+                mc::cat_rvalue(..) | mc::cat_static_item | mc::cat_local(..) => return,
+            };
+            cmt = base;
+        }
+    }
+}
+
+impl euv::Delegate for UpvarMoveFinder {
+    fn consume(&mut self,
+               _: ast::NodeId,
+               _: Span,
+               cmt: mc::cmt,
+               mode: euv::ConsumeMode) {
+        self.note_consume(cmt, mode);
+    }
+
+    fn consume_pat(&mut self,
+                   _: &ast::Pat,
+                   cmt: mc::cmt,
+                   mode: euv::ConsumeMode) {
+        self.note_consume(cmt, mode);
+    }
+
+    fn borrow(&mut self,
+              _: ast::NodeId,
+              _: Span,
+              _: mc::cmt,
+              _: ty::Region,
+              _: ty::BorrowKind,
+              _: euv::LoanCause) {
+    }
+
+    fn decl_without_init(&mut self, _: ast::NodeId, _: Span) { }
+
+    fn mutate(&mut self,
+              _: ast::NodeId,
+              _: Span,
+              _: mc::cmt,
+              _: euv::MutateMode) {
+    }
+}
+
+/// The typer used while inferring capture modes. Nested closures have
+/// already had their capture modes inferred, but their upvar borrows are
+/// only created later, by regionck proper. Only moves matter here, so a
+/// placeholder borrow is reported for those.
+struct CaptureModeTyper<'a, 'fcx: 'a, 'tcx: 'fcx> {
+    rcx: &'a Rcx<'fcx, 'tcx>,
+}
+
+impl<'a, 'fcx, 'tcx> mc::Typer<'tcx> for CaptureModeTyper<'a, 'fcx, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> {
+        self.rcx.tcx()
+    }
+
+    fn node_ty(&self, id: ast::NodeId) -> mc::McResult<ty::t> {
+        self.rcx.node_ty(id)
+    }
+
+    fn node_method_ty(&self, method_call: MethodCall) -> Option<ty::t> {
+        self.rcx.node_method_ty(method_call)
+    }
+
+    fn adjustments<'a>(&'a self) -> &'a RefCell<NodeMap<ty::AutoAdjustment>> {
+        self.rcx.adjustments()
+    }
+
+    fn is_method_call(&self, id: ast::NodeId) -> bool {
+        self.rcx.is_method_call(id)
+    }
+
+    fn temporary_scope(&self, id: ast::NodeId) -> Option<ast::NodeId> {
+        self.rcx.temporary_scope(id)
+    }
+
+    fn upvar_borrow(&self, id: ty::UpvarId) -> ty::UpvarBorrow {
+        match self.rcx.fcx.inh.upvar_borrow_map.borrow().get(&id) {
+            Some(&upvar_borrow) => upvar_borrow,
+            None => ty::UpvarBorrow { kind: ty::ImmBorrow, region: ty::ReStatic },
+        }
+    }
+
+    fn capture_mode(&self, closure_expr_id: ast::NodeId)
+                    -> ast::CaptureClause {
+        self.rcx.capture_mode(closure_expr_id)
+    }
+
+    fn upvar_capture_mode(&self, upvar_id: ty::UpvarId) -> ast::CaptureClause {
+        self.rcx.upvar_capture_mode(upvar_id)
+    }
+
+    fn unboxed_closures<'a>(&'a self)
+                        -> &'a RefCell<DefIdMap<ty::UnboxedClosure>> {
+        self.rcx.unboxed_closures()
+    }
+}
+
+fn check_closure_kinds_in_fn_body(rcx: &Rcx, body: &ast::Block) {
+    let mut visitor = ClosureKindVisitor { rcx: rcx };
+    visitor.visit_block(body);
+}
+
+struct ClosureKindVisitor<'a, 'fcx: 'a, 'tcx: 'fcx> {
+    rcx: &'a Rcx<'fcx, 'tcx>,
+}
+
+impl<'a, 'fcx, 'tcx, 'v> Visitor<'v> for ClosureKindVisitor<'a, 'fcx, 'tcx> {
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        match expr.node {
+            ast::ExprUnboxedFn(_, kind, ref decl, ref body)
+                    if kind != ast::FnOnceUnboxedClosureKind => {
+                let mut checker = ClosureKindChecker { tcx: self.rcx.tcx(),
+                                                       closure_expr: expr,
+                                                       kind: kind };
+                let mut euv = euv::ExprUseVisitor::new(&mut checker, self.rcx);
+                euv.walk_fn(&**decl, &**body);
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+
+    // Items nested within the fn body are checked on their own.
+    fn visit_item(&mut self, _: &ast::Item) { }
+}
+
+/// Checks the body of an unboxed closure declared `Fn` or `FnMut` for
+/// uses of its upvars that its declared kind does not permit, so that
+/// these are reported in terms of the declaration rather than as a
+/// borrowck error about the closure environment.
+struct ClosureKindChecker<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    closure_expr: &'a ast::Expr,
+    kind: ast::UnboxedClosureKind,
+}
+
+impl<'a, 'tcx> ClosureKindChecker<'a, 'tcx> {
+    fn upvar_of_this_closure(&self, cmt: mc::cmt, for_move: bool) -> Option<ast::NodeId> {
+        /*!
+         * Returns the variable captured by this closure that `cmt` is
+         * owned by, if any. Derefs through the closure environment are
+         * looked through. Other derefs are only looked through when
+         * mutating, since mutating through an `&mut` requires unique
+         * access to it.
+         */
+
+        let mut cmt = cmt;
+        loop {
+            let base = match cmt.cat {
+                mc::cat_upvar(ref upvar) => {
+                    return if upvar.id.closure_expr_id == self.closure_expr.id {
+                        Some(upvar.id.var_id)
+                    } else {
+                        None
+                    };
+                }
+                mc::cat_deref(ref base, _, _) if cmt.note != mc::NoteNone => base.clone(),
+                mc::cat_deref(ref base, _, mc::OwnedPtr) |
+                mc::cat_deref(ref base, _, mc::BorrowedPtr(ty::MutBorrow, _)) |
+                mc::cat_deref(ref base, _, mc::Implicit(ty::MutBorrow, _)) if !for_move => {
+                    base.clone()
+                }
+                mc::cat_deref(ref base, _, mc::OwnedPtr) => base.clone(),
+                mc::cat_interior(ref base, _) |
+                mc::cat_downcast(ref base) => base.clone(),
+                mc::cat_deref(..) |
+                mc::cat_rvalue(..) |
+                mc::cat_static_item |
+                mc::cat_local(..) => return None,
+            };
+            cmt = base;
+        }
+    }
+
+    fn report_mutation(&self, span: Span, cmt: mc::cmt) {
+        if self.kind != ast::FnUnboxedClosureKind {
+            return;
+        }
+        match self.upvar_of_this_closure(cmt, false) {
+            Some(var_id) => {
+                span_err!(self.tcx.sess, span, E0178,
+                          "closure is declared `Fn` but mutates the captured variable `{}`",
+                          ty::local_var_name_str(self.tcx, var_id));
+                span_note!(self.tcx.sess, self.closure_expr.span,
+                           "the closure is declared `Fn` here; declare it with `&mut:` \
+                            to make it `FnMut`, or with `:` to make it `FnOnce`");
+            }
+            None => {}
+        }
+    }
+
+    fn report_move(&self, span: Span, cmt: mc::cmt, mode: euv::ConsumeMode) {
+        match mode {
+            euv::Move(_) => {}
+            euv::Copy => return,
+        }
+        match self.upvar_of_this_closure(cmt, true) {
+            Some(var_id) => {
+                let kind = match self.kind {
+                    ast::FnUnboxedClosureKind => "Fn",
+                    _ => "FnMut",
+                };
+                span_err!(self.tcx.sess, span, E0179,
+                          "closure is declared `{}` but moves out of the captured \
+                           variable `{}`",
+                          kind, ty::local_var_name_str(self.tcx, var_id));
+                span_note!(self.tcx.sess, self.closure_expr.span,
+                           "the closure is declared `{}` here; declare it with `:` \
+                            to make it `FnOnce`", kind);
+            }
+            None => {}
+        }
+    }
+}
+
+impl<'a, 'tcx> euv::Delegate for ClosureKindChecker<'a, 'tcx> {
+    fn consume(&mut self,
+               _: ast::NodeId,
+               span: Span,
+               cmt: mc::cmt,
+               mode: euv::ConsumeMode) {
+        self.report_move(span, cmt, mode);
+    }
+
+    fn consume_pat(&mut self,
+                   pat: &ast::Pat,
+                   cmt: mc::cmt,
+                   mode: euv::ConsumeMode) {
+        self.report_move(pat.span, cmt, mode);
+    }
+
+    fn borrow(&mut self,
+              _: ast::NodeId,
+              span: Span,
+              cmt: mc::cmt,
+              _: ty::Region,
+              kind: ty::BorrowKind,
+              _: euv::LoanCause) {
+        match kind {
+            ty::ImmBorrow => {}
+            ty::UniqueImmBorrow | ty::MutBorrow => self.report_mutation(span, cmt),
+        }
+    }
+
+    fn decl_without_init(&mut self, _: ast::NodeId, _: Span) { }
+
+    fn mutate(&mut self,
+              _: ast::NodeId,
+              span: Span,
+              cmt: mc::cmt,
+              _: euv::MutateMode) {
+        self.report_mutation(span, cmt);
+    }
+}
+
 fn link_upvar_borrow_kind_for_nested_closures(rcx: &mut Rcx,
                                               inner_upvar_id: ty::UpvarId,
                                               outer_upvar_id: ty::UpvarId) {
@@ -13,7 +13,7 @@
 use middle::subst::{ParamSpace, Subst, Substs};
 use middle::ty;
 use middle::ty_fold;
-use middle::ty_fold::{TypeFolder, TypeFoldable};
+use middle::ty_fold::TypeFoldable;
 
 use syntax::ast;
 
@@ -23,22 +23,30 @@ use util::ppaux::Repr;
 
 // Helper functions related to manipulating region types.
 
-pub fn replace_late_bound_regions<T>(
+pub fn replace_late_bound_regions_in_trait_ref(
     tcx: &ty::ctxt,
-    binder_id: ast::NodeId,
-    value: &T,
+    trait_ref: &ty::TraitRef,
     map_fn: |ty::BoundRegion| -> ty::Region)
-    -> (FnvHashMap<ty::BoundRegion,ty::Region>, T)
-    where T : TypeFoldable + Repr
+    -> (FnvHashMap<ty::BoundRegion,ty::Region>, ty::TraitRef)
 {
-    debug!("replace_late_bound_regions(binder_id={}, value={})",
-           binder_id, value.repr(tcx));
+    /*!
+     * Replaces the late-bound regions that a trait reference binds, as in
+     * `for<'a> Fn(&'a int)` or the elided regions of `Fn(&int)`. These are
+     * the late-bound regions that occur free in the trait reference: any
+     * region bound by a fn type nested inside of it is skipped by the fold.
+     * The trait reference's substs are folded, rather than the trait
+     * reference itself, so that the folder does not enter its binder and
+     * take its regions as bound. The result binds no regions.
+     */
+
+    debug!("replace_late_bound_regions_in_trait_ref(trait_ref={})",
+           trait_ref.repr(tcx));
 
     let mut map = FnvHashMap::new();
-    let new_value = {
-        let mut folder = ty_fold::RegionFolder::regions(tcx, |r| {
+    let new_trait_ref = {
+        let mut folder = ty_fold::RegionFolder::regions(tcx, |r, _| {
             match r {
-                ty::ReLateBound(s, br) if s == binder_id => {
+                ty::ReLateBound(_, br) => {
                     match map.entry(br) {
                         Vacant(entry) => *entry.set(map_fn(br)),
                         Occupied(entry) => *entry.into_mut(),
@@ -47,10 +55,12 @@ pub fn replace_late_bound_regions<T>(
                 _ => r
             }
         });
-        value.fold_with(&mut folder)
+        ty::TraitRef { def_id: trait_ref.def_id,
+                       substs: trait_ref.substs.fold_with(&mut folder),
+                       binder_id: ast::DUMMY_NODE_ID }
     };
     debug!("resulting map: {}", map);
-    (map, new_value)
+    (map, new_trait_ref)
 }
 
 pub enum WfConstraint {
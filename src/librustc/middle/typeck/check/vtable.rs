@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use middle::subst::{SelfSpace, FnSpace};
+use middle::subst::{SelfSpace, FnSpace, TypeSpace, Subst};
 use middle::traits;
 use middle::traits::{SelectionError, OutputTypeParameterMismatch, Overflow, Unimplemented};
 use middle::traits::{Obligation, obligation_for_builtin_bound};
@@ -20,7 +20,11 @@ use middle::typeck::check::{FnCtxt,
 use middle::typeck::infer;
 use std::rc::Rc;
 use syntax::ast;
+use syntax::ast_map;
+use syntax::ast_util::PostExpansionMethod;
+use syntax::codemap;
 use syntax::codemap::Span;
+use syntax::parse::token;
 use util::ppaux::{UserString, Repr, ty_to_string};
 
 pub fn check_object_cast(fcx: &FnCtxt,
@@ -258,7 +262,8 @@ pub fn register_object_cast_obligations(fcx: &FnCtxt,
     // Create the obligation for casting from T to Trait.
     let object_trait_ref =
         Rc::new(ty::TraitRef { def_id: object_trait.principal.def_id,
-                               substs: object_substs });
+                               substs: object_substs,
+                               binder_id: object_trait.principal.binder_id });
     let object_obligation =
         Obligation::new(
             ObligationCause::new(span,
@@ -346,14 +351,30 @@ pub fn report_selection_error(fcx: &FnCtxt,
         }
         Unimplemented => {
             let (trait_ref, self_ty) = resolve_trait_ref(fcx, obligation);
-            if !ty::type_is_error(self_ty) {
-                fcx.tcx().sess.span_err(
-                    obligation.cause.span,
-                    format!(
-                        "the trait `{}` is not implemented for the type `{}`",
-                        trait_ref.user_string(fcx.tcx()),
-                        self_ty.user_string(fcx.tcx())).as_slice());
-                note_obligation_cause(fcx, obligation);
+            if ty::type_is_error(self_ty) {
+                return;
+            }
+            let near_miss = nearly_matching_trait_refs(fcx, obligation, &trait_ref, self_ty)
+                .into_iter()
+                .filter_map(|candidate| {
+                    closure_argument_mismatch(fcx.tcx(), &trait_ref, &*candidate)
+                })
+                .next();
+            match near_miss {
+                Some(mismatch) => {
+                    report_closure_argument_mismatch(fcx, obligation, &mismatch);
+                }
+                None => {
+                    fcx.tcx().sess.span_err(
+                        obligation.cause.span,
+                        format!(
+                            "the trait `{}` is not implemented for the type `{}`",
+                            trait_ref.user_string(fcx.tcx()),
+                            self_ty.user_string(fcx.tcx())).as_slice());
+                    note_obligation_cause(fcx, obligation);
+                    suggest_bound_on_ty_param(fcx, self_ty, &trait_ref);
+                    list_impls_of_trait(fcx, obligation, &trait_ref);
+                }
             }
         }
         OutputTypeParameterMismatch(ref expected_trait_ref, ref e) => {
@@ -361,20 +382,292 @@ pub fn report_selection_error(fcx: &FnCtxt,
                 fcx.infcx().resolve_type_vars_in_trait_ref_if_possible(
                     &**expected_trait_ref);
             let (trait_ref, self_ty) = resolve_trait_ref(fcx, obligation);
-            if !ty::type_is_error(self_ty) {
-                fcx.tcx().sess.span_err(
-                    obligation.cause.span,
-                    format!(
-                        "type mismatch: the type `{}` implements the trait `{}`, \
-                         but the trait `{}` is required ({})",
-                        self_ty.user_string(fcx.tcx()),
-                        expected_trait_ref.user_string(fcx.tcx()),
-                        trait_ref.user_string(fcx.tcx()),
-                        ty::type_err_to_str(fcx.tcx(), e)).as_slice());
-                note_obligation_cause(fcx, obligation);
+            if ty::type_is_error(self_ty) {
+                return;
+            }
+            match closure_argument_mismatch(fcx.tcx(), &trait_ref, &expected_trait_ref) {
+                Some(mismatch) => {
+                    report_closure_argument_mismatch(fcx, obligation, &mismatch);
+                }
+                None => {
+                    fcx.tcx().sess.span_err(
+                        obligation.cause.span,
+                        format!(
+                            "type mismatch: the type `{}` implements the trait `{}`, \
+                             but the trait `{}` is required ({})",
+                            self_ty.user_string(fcx.tcx()),
+                            expected_trait_ref.user_string(fcx.tcx()),
+                            trait_ref.user_string(fcx.tcx()),
+                            ty::type_err_to_str(fcx.tcx(), e)).as_slice());
+                    note_obligation_cause(fcx, obligation);
+                }
+            }
+        }
+    }
+}
+
+// The number of impls that `list_impls_of_trait` lists before summarizing.
+static MAX_LISTED_IMPLS: uint = 4;
+
+fn list_impls_of_trait(fcx: &FnCtxt, obligation: &Obligation, trait_ref: &ty::TraitRef) {
+    /*!
+     * Lists some of the impls of the trait that an unsatisfied
+     * obligation names, to show which types do implement it: those of
+     * this crate at their impl, those of other crates by crate name.
+     */
+
+    let tcx = fcx.tcx();
+    let impls = ty::all_impls_of_trait(tcx, trait_ref.def_id);
+    if impls.is_empty() {
+        return;
+    }
+
+    let span = obligation.cause.span;
+    tcx.sess.span_help(span, "the following implementations were found:");
+    for info in impls.iter().take(MAX_LISTED_IMPLS) {
+        let description = format!("`impl {} for {}`",
+                                  info.trait_ref.user_string(tcx),
+                                  info.self_ty.user_string(tcx));
+        match info.location {
+            ty::LocalImpl(impl_span) => {
+                tcx.sess.span_help(impl_span, description.as_slice());
+            }
+            ty::ExternalImpl(ref krate) => {
+                tcx.sess.span_help(span, format!("{} in crate `{}`",
+                                                 description, krate).as_slice());
             }
         }
     }
+    if impls.len() > MAX_LISTED_IMPLS {
+        tcx.sess.span_help(span, format!("and {} others",
+                                         impls.len() - MAX_LISTED_IMPLS).as_slice());
+    }
+}
+
+/// The way in which the argument tuple of a closure-like trait
+/// reference differs from the one that is required.
+enum ClosureArgumentMismatch {
+    /// The closure takes the first list of arguments, but the second
+    /// is required.
+    ArityMismatch(Vec<ty::t>, Vec<ty::t>),
+
+    /// The argument at the given index has the first type, but the
+    /// second is required. All other arguments agree.
+    ArgumentMismatch(uint, ty::t, ty::t),
+}
+
+fn nearly_matching_trait_refs(fcx: &FnCtxt,
+                              obligation: &Obligation,
+                              trait_ref: &ty::TraitRef,
+                              self_ty: ty::t)
+                              -> Vec<Rc<ty::TraitRef>>
+{
+    /*!
+     * Collects the trait references for `self_ty` that the caller's
+     * bounds and the impls of the obligation's trait provide, with
+     * the impls instantiated so that their self type is `self_ty`.
+     * Bounds come first, as they are the more likely near miss.
+     */
+
+    let tcx = fcx.tcx();
+    let mut candidates: Vec<Rc<ty::TraitRef>> =
+        fcx.inh.param_env.caller_obligations.iter()
+        .map(|o| o.trait_ref.clone())
+        .filter(|t| t.def_id == trait_ref.def_id && t.self_ty() == self_ty)
+        .collect();
+
+    for info in ty::all_impls_of_trait(tcx, trait_ref.def_id).iter() {
+        let infcx = fcx.infcx();
+        let candidate = infcx.probe(|| {
+            let impl_substs = infcx.fresh_substs_for_generics(obligation.cause.span,
+                                                              &info.generics);
+            let impl_trait_ref = info.trait_ref.subst(tcx, &impl_substs);
+            let origin = infer::Misc(obligation.cause.span);
+            match infer::mk_eqty(infcx, false, origin, impl_trait_ref.self_ty(), self_ty) {
+                Ok(()) => {
+                    Some(Rc::new(
+                        infcx.resolve_type_vars_in_trait_ref_if_possible(&*impl_trait_ref)))
+                }
+                Err(_) => None,
+            }
+        });
+        candidates.extend(candidate.into_iter());
+    }
+
+    candidates
+}
+
+fn closure_argument_mismatch(tcx: &ty::ctxt,
+                             required: &ty::TraitRef,
+                             found: &ty::TraitRef)
+                             -> Option<ClosureArgumentMismatch>
+{
+    /*!
+     * Checks whether `found` differs from the `required` trait
+     * reference only in the argument tuple of a closure-like type:
+     * either the argument tuple of an `Fn`-family trait itself, or a
+     * tuple found by descending through a type that differs from the
+     * required one in exactly one type parameter, as for a struct
+     * written with the parenthetical notation.
+     */
+
+    if required.def_id != found.def_id {
+        return None;
+    }
+    single_difference(required.substs.types.get_slice(TypeSpace),
+                      found.substs.types.get_slice(TypeSpace))
+        .and_then(|(_, required, found)| tuple_mismatch(tcx, required, found))
+}
+
+fn single_difference(required: &[ty::t], found: &[ty::t]) -> Option<(uint, ty::t, ty::t)> {
+    if required.len() != found.len() {
+        return None;
+    }
+    let mut differences = required.iter().zip(found.iter()).enumerate()
+        .filter(|&(_, (r, f))| r != f);
+    match (differences.next(), differences.next()) {
+        (Some((i, (&r, &f))), None) => Some((i, r, f)),
+        _ => None,
+    }
+}
+
+fn tuple_mismatch(tcx: &ty::ctxt, required: ty::t, found: ty::t)
+                  -> Option<ClosureArgumentMismatch>
+{
+    match (tuple_elements(required), tuple_elements(found)) {
+        (Some(required_args), Some(found_args)) => {
+            if required_args.len() != found_args.len() {
+                return Some(ArityMismatch(found_args, required_args));
+            }
+            return single_difference(required_args.as_slice(), found_args.as_slice())
+                .map(|(i, required, found)| ArgumentMismatch(i, found, required));
+        }
+        _ => {}
+    }
+
+    match (&ty::get(required).sty, &ty::get(found).sty) {
+        (&ty::ty_struct(required_id, ref required_substs),
+         &ty::ty_struct(found_id, ref found_substs)) |
+        (&ty::ty_enum(required_id, ref required_substs),
+         &ty::ty_enum(found_id, ref found_substs)) if required_id == found_id => {
+            single_difference(required_substs.types.as_slice(),
+                              found_substs.types.as_slice())
+                .and_then(|(_, required, found)| tuple_mismatch(tcx, required, found))
+        }
+        _ => None,
+    }
+}
+
+fn tuple_elements(t: ty::t) -> Option<Vec<ty::t>> {
+    // An empty argument list is `()`, which is not a `ty_tup`.
+    match ty::get(t).sty {
+        ty::ty_nil => Some(Vec::new()),
+        ty::ty_tup(ref elems) => Some(elems.clone()),
+        _ => None,
+    }
+}
+
+fn describe_closure_arguments(tcx: &ty::ctxt, args: &[ty::t]) -> String {
+    match args {
+        [] => "no arguments".to_string(),
+        [arg] => format!("1 argument of type `{}`", arg.user_string(tcx)),
+        _ => {
+            let args: Vec<String> = args.iter()
+                .map(|arg| format!("`{}`", arg.user_string(tcx)))
+                .collect();
+            format!("{} arguments of types {}", args.len(), args.connect(", "))
+        }
+    }
+}
+
+fn report_closure_argument_mismatch(fcx: &FnCtxt,
+                                    obligation: &Obligation,
+                                    mismatch: &ClosureArgumentMismatch) {
+    let tcx = fcx.tcx();
+    match *mismatch {
+        ArityMismatch(ref found, ref required) => {
+            tcx.sess.span_err(
+                obligation.cause.span,
+                format!(
+                    "expected a closure taking {}, but the bound requires {}",
+                    describe_closure_arguments(tcx, found.as_slice()),
+                    describe_closure_arguments(tcx, required.as_slice())).as_slice());
+        }
+        ArgumentMismatch(index, found, required) => {
+            tcx.sess.span_err(
+                obligation.cause.span,
+                format!(
+                    "expected a closure whose argument {} has type `{}`, \
+                     but the bound requires `{}`",
+                    index + 1,
+                    found.user_string(tcx),
+                    required.user_string(tcx)).as_slice());
+        }
+    }
+    note_obligation_cause(fcx, obligation);
+}
+
+fn suggest_bound_on_ty_param(fcx: &FnCtxt, self_ty: ty::t, trait_ref: &ty::TraitRef) {
+    /*!
+     * If `self_ty` is a type parameter declared on the function being
+     * checked, or on an item enclosing it, suggests adding the missing
+     * bound to the parameter's declaration.
+     */
+
+    let def_id = match ty::get(self_ty).sty {
+        ty::ty_param(ty::ParamTy { def_id, .. }) if def_id.krate == ast::LOCAL_CRATE => def_id,
+        _ => return,
+    };
+    let tcx = fcx.tcx();
+    let mut id = fcx.body_id;
+    loop {
+        let generics = match tcx.map.find(id) {
+            Some(ast_map::NodeItem(item)) => match item.node {
+                ast::ItemFn(_, _, _, ref generics, _) |
+                ast::ItemImpl(ref generics, _, _, _) |
+                ast::ItemTrait(ref generics, _, _, _) => Some(generics),
+                _ => None,
+            },
+            Some(ast_map::NodeImplItem(&ast::MethodImplItem(ref m))) => Some(m.pe_generics()),
+            Some(ast_map::NodeTraitItem(&ast::ProvidedMethod(ref m))) => Some(m.pe_generics()),
+            _ => None,
+        };
+        let param = generics.and_then(|generics| {
+            generics.ty_params.iter().find(|param| param.id == def_id.node)
+        });
+        match param {
+            Some(param) => {
+                let bound = trait_ref.user_string(tcx);
+                // Replace the parameter and any bounds it already has, so
+                // that the new one is added at the end of the list.
+                let (span, suggestion) = match param.bounds.as_slice().last() {
+                    None => {
+                        (param.span,
+                         format!("{}: {}", token::get_ident(param.ident), bound))
+                    }
+                    Some(last) => {
+                        let hi = match *last {
+                            ast::TraitTyParamBound(ref poly) => poly.trait_ref.path.span.hi,
+                            ast::RegionTyParamBound(ref lifetime) => lifetime.span.hi,
+                        };
+                        let span = codemap::mk_sp(param.span.lo, hi);
+                        match tcx.sess.codemap().span_to_snippet(span) {
+                            Some(snippet) => (span, format!("{} + {}", snippet, bound)),
+                            None => return,
+                        }
+                    }
+                };
+                tcx.sess.span_suggestion(span, "consider adding a bound:", suggestion);
+                return;
+            }
+            None => {}
+        }
+        let parent = tcx.map.get_parent(id);
+        if parent == id {
+            return;
+        }
+        id = parent;
+    }
 }
 
 pub fn maybe_report_ambiguity(fcx: &FnCtxt, obligation: &Obligation) {
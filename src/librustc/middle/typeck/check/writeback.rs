@@ -15,13 +15,14 @@
 use middle::def;
 use middle::pat_util;
 use middle::ty;
+use middle::ty_fold;
 use middle::ty_fold::{TypeFolder,TypeFoldable};
 use middle::typeck::astconv::AstConv;
 use middle::typeck::check::FnCtxt;
 use middle::typeck::infer::{force_all, resolve_all, resolve_region};
 use middle::typeck::infer::resolve_type;
 use middle::typeck::infer;
-use middle::typeck::{MethodCall, MethodCallee};
+use middle::typeck::MethodCall;
 use middle::typeck::write_substs_to_tcx;
 use middle::typeck::write_ty_to_tcx;
 use util::ppaux::Repr;
@@ -42,6 +43,7 @@ pub fn resolve_type_vars_in_expr(fcx: &FnCtxt, e: &ast::Expr) {
     let mut wbcx = WritebackCx::new(fcx);
     wbcx.visit_expr(e);
     wbcx.visit_upvar_borrow_map();
+    wbcx.visit_upvar_capture_modes();
     wbcx.visit_unboxed_closures();
     wbcx.visit_object_cast_map();
 }
@@ -63,6 +65,7 @@ pub fn resolve_type_vars_in_fn(fcx: &FnCtxt,
         }
     }
     wbcx.visit_upvar_borrow_map();
+    wbcx.visit_upvar_capture_modes();
     wbcx.visit_unboxed_closures();
     wbcx.visit_object_cast_map();
 }
@@ -200,6 +203,19 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
         }
     }
 
+    fn visit_upvar_capture_modes(&self) {
+        if self.fcx.writeback_errors.get() {
+            return;
+        }
+
+        for (upvar_id, &mode) in self.fcx.inh.upvar_capture_modes.borrow().iter() {
+            debug!("Upvar capture mode for {} is {}",
+                   upvar_id.repr(self.tcx()),
+                   mode);
+            self.fcx.tcx().upvar_capture_modes.borrow_mut().insert(*upvar_id, mode);
+        }
+    }
+
     fn visit_unboxed_closures(&self) {
         if self.fcx.writeback_errors.get() {
             return
@@ -270,8 +286,8 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
 
             Some(adjustment) => {
                 let adj_object = ty::adjust_is_object(&adjustment);
-                let resolved_adjustment = match adjustment {
-                    ty::AdjustAddEnv(store) => {
+                match adjustment {
+                    ty::AdjustAddEnv(_) => {
                         // FIXME(eddyb) #2190 Allow only statically resolved
                         // bare functions to coerce to a closure to avoid
                         // constructing (slower) indirect call wrappers.
@@ -288,11 +304,11 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
                                     "consider embedding the function in a closure");
                             }
                         }
-
-                        ty::AdjustAddEnv(self.resolve(&store, reason))
                     }
 
-                    ty::AdjustDerefRef(adj) => {
+                    ty::AdjustUnboxedClosureToFnPointer(_) => {}
+
+                    ty::AdjustDerefRef(ref adj) => {
                         for autoderef in range(0, adj.autoderefs) {
                             let method_call = MethodCall::autoderef(id, autoderef);
                             self.visit_method_map_entry(reason, method_call);
@@ -302,13 +318,10 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
                             let method_call = MethodCall::autoobject(id);
                             self.visit_method_map_entry(reason, method_call);
                         }
-
-                        ty::AdjustDerefRef(ty::AutoDerefRef {
-                            autoderefs: adj.autoderefs,
-                            autoref: self.resolve(&adj.autoref, reason),
-                        })
                     }
-                };
+                }
+
+                let resolved_adjustment = self.resolve(&adjustment, reason);
                 debug!("Adjustments for node {}: {}", id, resolved_adjustment);
                 self.tcx().adjustments.borrow_mut().insert(
                     id, resolved_adjustment);
@@ -325,11 +338,7 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
                 debug!("writeback::resolve_method_map_entry(call={}, entry={})",
                        method_call,
                        method.repr(self.tcx()));
-                let new_method = MethodCallee {
-                    origin: self.resolve(&method.origin, reason),
-                    ty: self.resolve(&method.ty, reason),
-                    substs: self.resolve(&method.substs, reason),
-                };
+                let new_method = self.resolve(&method, reason);
 
                 self.tcx().method_map.borrow_mut().insert(
                     method_call,
@@ -472,8 +481,11 @@ impl<'cx, 'tcx> TypeFolder<'tcx> for Resolver<'cx, 'tcx> {
         match resolve_type(self.infcx, None, t, resolve_all | force_all) {
             Ok(t) => t,
             Err(e) => {
+                // Keep whatever could be resolved, so that only the
+                // unresolved variables become `ty_err`.
                 self.report_error(e);
-                ty::mk_err()
+                let t = self.infcx.resolve_type_vars_if_possible(t);
+                ty_fold::erase_infer_vars(self.tcx, t)
             }
         }
     }
@@ -55,34 +55,28 @@ impl<'cx, 'tcx> OverlapChecker<'cx, 'tcx> {
         debug!("check_for_overlapping_impls_of_trait(trait_def_id={})",
                trait_def_id.repr(self.tcx));
 
-        // FIXME -- it seems like this method actually pushes
-        // duplicate impls onto the list
-        ty::populate_implementations_for_trait_if_necessary(self.tcx,
-                                                            trait_def_id);
-
-        let mut impls = Vec::new();
-        self.push_impls_of_trait(trait_def_id, &mut impls);
-
-        for (i, &impl1_def_id) in impls.iter().enumerate() {
-            if impl1_def_id.krate != ast::LOCAL_CRATE {
-                // we don't need to check impls if both are external;
-                // that's the other crate's job.
-                continue;
+        // The local impls come first, so every pair of impls that
+        // involves a local impl is checked; pairs of external impls are
+        // the other crates' job.
+        let impls = ty::all_impls_of_trait(self.tcx, trait_def_id);
+
+        for (i, impl1) in impls.iter().enumerate() {
+            if impl1.def_id.krate != ast::LOCAL_CRATE {
+                break;
             }
 
-            for &impl2_def_id in impls.slice_from(i+1).iter() {
-                self.check_if_impls_overlap(trait_def_id,
-                                            impl1_def_id,
-                                            impl2_def_id);
+            for impl2 in impls.slice_from(i+1).iter() {
+                self.check_if_impls_overlap(trait_def_id, impl1, impl2);
             }
         }
     }
 
     fn check_if_impls_overlap(&self,
                               trait_def_id: ast::DefId,
-                              impl1_def_id: ast::DefId,
-                              impl2_def_id: ast::DefId)
+                              impl1: &ty::ImplInfo,
+                              impl2: &ty::ImplInfo)
     {
+        let (impl1_def_id, impl2_def_id) = (impl1.def_id, impl2.def_id);
         assert_eq!(impl1_def_id.krate, ast::LOCAL_CRATE);
 
         debug!("check_if_impls_overlap({}, {}, {})",
@@ -99,24 +93,16 @@ impl<'cx, 'tcx> OverlapChecker<'cx, 'tcx> {
                   "conflicting implementations for trait `{}`",
                   ty::item_path_str(self.tcx, trait_def_id));
 
-        if impl2_def_id.krate == ast::LOCAL_CRATE {
-            span_note!(self.tcx.sess, self.span_of_impl(impl2_def_id),
-                       "note conflicting implementation here");
-        } else {
-            let crate_store = &self.tcx.sess.cstore;
-            let cdata = crate_store.get_crate_data(impl2_def_id.krate);
-            span_note!(self.tcx.sess, self.span_of_impl(impl1_def_id),
-                       "conflicting implementation in crate `{}`",
-                       cdata.name);
-        }
-    }
-
-    fn push_impls_of_trait(&self,
-                           trait_def_id: ast::DefId,
-                           out: &mut Vec<ast::DefId>) {
-        match self.tcx.trait_impls.borrow().get(&trait_def_id) {
-            Some(impls) => { out.push_all(impls.borrow().as_slice()); }
-            None => { /* no impls */ }
+        match impl2.location {
+            ty::LocalImpl(span) => {
+                span_note!(self.tcx.sess, span,
+                           "note conflicting implementation here");
+            }
+            ty::ExternalImpl(ref krate) => {
+                span_note!(self.tcx.sess, self.span_of_impl(impl1_def_id),
+                           "conflicting implementation in crate `{}`",
+                           krate);
+            }
         }
     }
 
@@ -1380,7 +1380,8 @@ pub fn trait_def_of_item(ccx: &CrateCtxt, it: &ast::Item) -> Rc<ty::TraitDef> {
         bounds: bounds,
         trait_ref: Rc::new(ty::TraitRef {
             def_id: def_id,
-            substs: substs
+            substs: substs,
+            binder_id: ast::DUMMY_NODE_ID
         })
     });
     tcx.trait_defs.borrow_mut().insert(def_id, trait_def.clone());
@@ -1645,7 +1646,8 @@ fn ty_generics_for_trait(ccx: &CrateCtxt,
 
     let self_trait_ref =
         Rc::new(ty::TraitRef { def_id: local_def(trait_id),
-                               substs: (*substs).clone() });
+                               substs: (*substs).clone(),
+                               binder_id: ast::DUMMY_NODE_ID });
 
     let def = ty::TypeParameterDef {
         space: subst::SelfSpace,
@@ -2125,13 +2127,13 @@ pub fn mk_item_substs(ccx: &CrateCtxt,
                       -> subst::Substs
 {
     let types =
-        ty_generics.types.map(
-            |def| ty::mk_param_from_def(ccx.tcx, def));
+        ty_generics.types.map_enumerated(
+            |space, index, def| ty::mk_param(ccx.tcx, space, index, def.def_id));
 
     let regions =
-        ty_generics.regions.map(
-            |def| ty::ReEarlyBound(def.def_id.node, def.space,
-                                   def.index, def.name));
+        ty_generics.regions.map_enumerated(
+            |space, index, def| ty::ReEarlyBound(def.def_id.node, space,
+                                                 index, def.name));
 
     subst::Substs::new(types, regions)
 }
@@ -303,18 +303,7 @@ pub trait Combine<'tcx> {
                   a: &ty::TraitRef,
                   b: &ty::TraitRef)
                   -> cres<ty::TraitRef> {
-        // Different traits cannot be related
-
-        // - NOTE in the future, expand out subtraits!
-
-        if a.def_id != b.def_id {
-            Err(ty::terr_traits(
-                                expected_found(self, a.def_id, b.def_id)))
-        } else {
-            let substs = try!(self.substs(a.def_id, &a.substs, &b.substs));
-            Ok(ty::TraitRef { def_id: a.def_id,
-                              substs: substs })
-        }
+        super_trait_refs(self, a, b)
     }
 }
 
@@ -334,6 +323,25 @@ pub fn expected_found<'tcx, C: Combine<'tcx>, T>(
     }
 }
 
+pub fn super_trait_refs<'tcx, C: Combine<'tcx>>(this: &C,
+                                                a: &ty::TraitRef,
+                                                b: &ty::TraitRef)
+                                                -> cres<ty::TraitRef> {
+    // Different traits cannot be related
+
+    // - NOTE in the future, expand out subtraits!
+
+    if a.def_id != b.def_id {
+        Err(ty::terr_traits(
+                            expected_found(this, a.def_id, b.def_id)))
+    } else {
+        let substs = try!(this.substs(a.def_id, &a.substs, &b.substs));
+        Ok(ty::TraitRef { def_id: a.def_id,
+                          substs: substs,
+                          binder_id: a.binder_id })
+    }
+}
+
 pub fn super_fn_sigs<'tcx, C: Combine<'tcx>>(this: &C,
                                              a: &ty::FnSig,
                                              b: &ty::FnSig)
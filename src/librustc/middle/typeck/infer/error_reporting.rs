@@ -90,6 +90,7 @@ use syntax::parse::token;
 use syntax::print::pprust;
 use syntax::ptr::P;
 use util::ppaux::bound_region_to_string;
+use util::ppaux::explain_region_and_span;
 use util::ppaux::note_and_explain_region;
 
 // Note: only import UserString, not Repr, since user-facing error
@@ -153,9 +154,15 @@ trait ErrorReportingHelpers {
     fn report_inference_failure(&self,
                                 var_origin: RegionVariableOrigin);
 
+    fn inference_failure_message(&self,
+                                 var_origin: &RegionVariableOrigin) -> String;
+
     fn note_region_origin(&self,
                           origin: &SubregionOrigin);
 
+    fn region_origin_reason(&self,
+                            origin: &SubregionOrigin) -> (codemap::Span, String);
+
     fn give_expl_lifetime_param(&self,
                                 decl: &ast::FnDecl,
                                 fn_style: ast::FnStyle,
@@ -791,23 +798,22 @@ impl<'a, 'tcx> ErrorReporting for InferCtxt<'a, 'tcx> {
                                sub_region: Region,
                                sup_origin: SubregionOrigin,
                                sup_region: Region) {
-        self.report_inference_failure(var_origin);
-
-        note_and_explain_region(
-            self.tcx,
-            "first, the lifetime cannot outlive ",
-            sup_region,
-            "...");
-
-        self.note_region_origin(&sup_origin);
-
-        note_and_explain_region(
-            self.tcx,
-            "but, the lifetime must be valid for ",
-            sub_region,
-            "...");
-
-        self.note_region_origin(&sub_origin);
+        // Point at where each of the two requirements comes from, labeled
+        // with the lifetime it imposes.
+        let (sup_span, sup_reason) = self.region_origin_reason(&sup_origin);
+        let (sub_span, sub_reason) = self.region_origin_reason(&sub_origin);
+        let (sup_description, _) = explain_region_and_span(self.tcx, sup_region);
+        let (sub_description, _) = explain_region_and_span(self.tcx, sub_region);
+        let labels = [
+            (sup_span, format!("first, the lifetime cannot outlive {}{}",
+                               sup_description, sup_reason)),
+            (sub_span, format!("but, the lifetime must be valid for {}{}",
+                               sub_description, sub_reason)),
+        ];
+        self.tcx.sess.span_err_with_labels(
+            var_origin.span(),
+            self.inference_failure_message(&var_origin).as_slice(),
+            labels.as_slice());
     }
 
     fn report_sup_sup_conflict(&self,
@@ -1440,7 +1446,14 @@ impl<'a, 'tcx> ErrorReportingHelpers for InferCtxt<'a, 'tcx> {
 
     fn report_inference_failure(&self,
                                 var_origin: RegionVariableOrigin) {
-        let var_description = match var_origin {
+        self.tcx.sess.span_err(
+            var_origin.span(),
+            self.inference_failure_message(&var_origin).as_slice());
+    }
+
+    fn inference_failure_message(&self,
+                                 var_origin: &RegionVariableOrigin) -> String {
+        let var_description = match *var_origin {
             infer::MiscVariable(_) => "".to_string(),
             infer::PatternRegion(_) => " for pattern".to_string(),
             infer::AddrOfRegion(_) => " for borrow expression".to_string(),
@@ -1469,14 +1482,17 @@ impl<'a, 'tcx> ErrorReportingHelpers for InferCtxt<'a, 'tcx> {
             }
         };
 
-        self.tcx.sess.span_err(
-            var_origin.span(),
-            format!("cannot infer an appropriate lifetime{} \
-                    due to conflicting requirements",
-                    var_description).as_slice());
+        format!("cannot infer an appropriate lifetime{} \
+                 due to conflicting requirements",
+                var_description)
     }
 
     fn note_region_origin(&self, origin: &SubregionOrigin) {
+        let (span, reason) = self.region_origin_reason(origin);
+        self.tcx.sess.span_note(span, reason.as_slice());
+    }
+
+    fn region_origin_reason(&self, origin: &SubregionOrigin) -> (codemap::Span, String) {
         match *origin {
             infer::Subtype(ref trace) => {
                 let desc = match trace.origin {
@@ -1512,158 +1528,135 @@ impl<'a, 'tcx> ErrorReportingHelpers for InferCtxt<'a, 'tcx> {
 
                 match self.values_str(&trace.values) {
                     Some(values_str) => {
-                        self.tcx.sess.span_note(
-                            trace.origin.span(),
-                            format!("...so that {} ({})",
-                                    desc, values_str).as_slice());
+                        (trace.origin.span(),
+                         format!("...so that {} ({})",
+                                 desc, values_str))
                     }
                     None => {
                         // Really should avoid printing this error at
                         // all, since it is derived, but that would
                         // require more refactoring than I feel like
                         // doing right now. - nmatsakis
-                        self.tcx.sess.span_note(
-                            trace.origin.span(),
-                            format!("...so that {}", desc).as_slice());
+                        (trace.origin.span(),
+                         format!("...so that {}", desc))
                     }
                 }
             }
             infer::Reborrow(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that reference does not outlive \
-                    borrowed content");
+                (span,
+                 "...so that reference does not outlive \
+                 borrowed content".to_string())
             }
             infer::ReborrowUpvar(span, ref upvar_id) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!(
-                        "...so that closure can access `{}`",
-                        ty::local_var_name_str(self.tcx, upvar_id.var_id)
-                            .get()
-                            .to_string()).as_slice())
+                (span,
+                 format!(
+                     "...so that closure can access `{}`",
+                     ty::local_var_name_str(self.tcx, upvar_id.var_id)
+                         .get()
+                         .to_string()))
             }
             infer::InfStackClosure(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that closure does not outlive its stack frame");
+                (span,
+                 "...so that closure does not outlive its stack frame".to_string())
             }
             infer::InvokeClosure(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that closure is not invoked outside its lifetime");
+                (span,
+                 "...so that closure is not invoked outside its lifetime".to_string())
             }
             infer::DerefPointer(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that pointer is not dereferenced \
-                    outside its lifetime");
+                (span,
+                 "...so that pointer is not dereferenced \
+                 outside its lifetime".to_string())
             }
             infer::FreeVariable(span, id) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!("...so that captured variable `{}` \
-                            does not outlive the enclosing closure",
-                            ty::local_var_name_str(
-                                self.tcx,
-                                id).get().to_string()).as_slice());
+                (span,
+                 format!("...so that captured variable `{}` \
+                         does not outlive the enclosing closure",
+                         ty::local_var_name_str(
+                             self.tcx,
+                             id).get().to_string()))
             }
             infer::ProcCapture(span, id) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!("...so that captured variable `{}` \
-                            is 'static",
-                            ty::local_var_name_str(
-                                self.tcx,
-                                id).get()).as_slice());
+                (span,
+                 format!("...so that captured variable `{}` \
+                         is 'static",
+                         ty::local_var_name_str(
+                             self.tcx,
+                             id).get()))
             }
             infer::IndexSlice(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that slice is not indexed outside the lifetime");
+                (span,
+                 "...so that slice is not indexed outside the lifetime".to_string())
             }
             infer::RelateObjectBound(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that it can be closed over into an object");
+                (span,
+                 "...so that it can be closed over into an object".to_string())
             }
             infer::RelateProcBound(span, var_node_id, _ty) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!(
-                        "...so that the variable `{}` can be captured \
-                         into a proc",
-                        ty::local_var_name_str(self.tcx,
-                                               var_node_id)).as_slice());
+                (span,
+                 format!(
+                     "...so that the variable `{}` can be captured \
+                      into a proc",
+                     ty::local_var_name_str(self.tcx,
+                                            var_node_id)))
             }
             infer::CallRcvr(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that method receiver is valid for the method call");
+                (span,
+                 "...so that method receiver is valid for the method call".to_string())
             }
             infer::CallArg(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that argument is valid for the call");
+                (span,
+                 "...so that argument is valid for the call".to_string())
             }
             infer::CallReturn(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that return value is valid for the call");
+                (span,
+                 "...so that return value is valid for the call".to_string())
             }
             infer::AddrOf(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that reference is valid \
-                     at the time of borrow");
+                (span,
+                 "...so that reference is valid \
+                  at the time of borrow".to_string())
             }
             infer::AutoBorrow(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that auto-reference is valid \
-                     at the time of borrow");
+                (span,
+                 "...so that auto-reference is valid \
+                  at the time of borrow".to_string())
             }
             infer::ExprTypeIsNotInScope(t, span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!("...so type `{}` of expression is valid during the \
-                             expression",
-                            self.ty_to_string(t)).as_slice());
+                (span,
+                 format!("...so type `{}` of expression is valid during the \
+                          expression",
+                         self.ty_to_string(t)))
             }
             infer::BindingTypeIsNotValidAtDecl(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    "...so that variable is valid at time of its declaration");
+                (span,
+                 "...so that variable is valid at time of its declaration".to_string())
             }
             infer::ReferenceOutlivesReferent(ty, span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!("...so that the reference type `{}` \
-                             does not outlive the data it points at",
-                            self.ty_to_string(ty)).as_slice());
+                (span,
+                 format!("...so that the reference type `{}` \
+                          does not outlive the data it points at",
+                         self.ty_to_string(ty)))
             }
             infer::RelateParamBound(span, param_ty, t) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!("...so that the parameter `{}`, \
-                             when instantiated with `{}`, \
-                             will meet its declared lifetime bounds.",
-                            param_ty.user_string(self.tcx),
-                            self.ty_to_string(t)).as_slice());
+                (span,
+                 format!("...so that the parameter `{}`, \
+                          when instantiated with `{}`, \
+                          will meet its declared lifetime bounds.",
+                         param_ty.user_string(self.tcx),
+                         self.ty_to_string(t)))
             }
             infer::RelateDefaultParamBound(span, t) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!("...so that type parameter \
-                             instantiated with `{}`, \
-                             will meet its declared lifetime bounds.",
-                            self.ty_to_string(t)).as_slice());
+                (span,
+                 format!("...so that type parameter \
+                          instantiated with `{}`, \
+                          will meet its declared lifetime bounds.",
+                         self.ty_to_string(t)))
             }
             infer::RelateRegionParamBound(span) => {
-                self.tcx.sess.span_note(
-                    span,
-                    format!("...so that the declared lifetime parameter bounds \
-                                are satisfied").as_slice());
+                (span,
+                 format!("...so that the declared lifetime parameter bounds \
+                             are satisfied"))
             }
         }
     }
@@ -28,7 +28,6 @@ use middle::ty::{TyVid, IntVid, FloatVid, RegionVid};
 use middle::ty;
 use middle::ty_fold;
 use middle::ty_fold::{TypeFolder, TypeFoldable};
-use middle::typeck::check::regionmanip::replace_late_bound_regions;
 use std::cell::{RefCell};
 use std::rc::Rc;
 use syntax::ast;
@@ -960,8 +959,8 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
                                                     -> (ty::FnSig,
                                                         FnvHashMap<ty::BoundRegion,
                                                                 ty::Region>) {
-        let (map, fn_sig) =
-            replace_late_bound_regions(self.tcx, fsig.binder_id, fsig, |br| {
+        let (fn_sig, map) =
+            ty_fold::replace_late_bound_regions(self.tcx, fsig.binder_id, fsig, |br| {
                 let rvar = self.next_region_var(
                     BoundRegionInFnType(trace.origin.span(), br));
                 debug!("Bound region {} maps to {}",
@@ -977,7 +976,7 @@ pub fn fold_regions_in_sig(tcx: &ty::ctxt,
                            fn_sig: &ty::FnSig,
                            fldr: |r: ty::Region| -> ty::Region)
                            -> ty::FnSig {
-    ty_fold::RegionFolder::regions(tcx, fldr).fold_sig(fn_sig)
+    ty_fold::fold_regions(tcx, fn_sig, |r, _| fldr(r))
 }
 
 impl TypeTrace {
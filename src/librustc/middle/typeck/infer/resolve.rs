@@ -52,6 +52,7 @@ use middle::ty::{FloatVar, FloatVid, IntVar, IntVid, RegionVid, TyVar, TyVid};
 use middle::ty::{IntType, UintType};
 use middle::ty;
 use middle::ty_fold;
+use middle::ty_fold::TypeFoldable;
 use middle::typeck::infer::{fixup_err, fres, InferCtxt};
 use middle::typeck::infer::{unresolved_int_ty,unresolved_float_ty,unresolved_ty};
 use syntax::codemap::Span;
@@ -77,7 +78,6 @@ pub const resolve_and_force_all_but_regions: uint =
 pub struct ResolveState<'a, 'tcx: 'a> {
     infcx: &'a InferCtxt<'a, 'tcx>,
     modes: uint,
-    err: Option<fixup_err>,
     type_depth: uint,
 }
 
@@ -88,21 +88,20 @@ pub fn resolver<'a, 'tcx>(infcx: &'a InferCtxt<'a, 'tcx>,
     ResolveState {
         infcx: infcx,
         modes: modes,
-        err: None,
         type_depth: 0,
     }
 }
 
-impl<'a, 'tcx> ty_fold::TypeFolder<'tcx> for ResolveState<'a, 'tcx> {
+impl<'a, 'tcx> ty_fold::FallibleTypeFolder<'tcx, fixup_err> for ResolveState<'a, 'tcx> {
     fn tcx(&self) -> &ty::ctxt<'tcx> {
         self.infcx.tcx
     }
 
-    fn fold_ty(&mut self, t: ty::t) -> ty::t {
+    fn fold_ty(&mut self, t: ty::t) -> fres<ty::t> {
         self.resolve_type(t)
     }
 
-    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+    fn fold_region(&mut self, r: ty::Region) -> fres<ty::Region> {
         self.resolve_region(r)
     }
 }
@@ -116,50 +115,33 @@ impl<'a, 'tcx> ResolveState<'a, 'tcx> {
                             typ: ty::t)
                             -> fres<ty::t>
     {
-        self.err = None;
-
         debug!("Resolving {} (modes={:x})",
                ty_to_string(self.infcx.tcx, typ),
                self.modes);
 
-        // n.b. This is a hokey mess because the current fold doesn't
-        // allow us to pass back errors in any useful way.
-
-        let rty = self.resolve_type(typ);
-        match self.err {
-            None => {
-                debug!("Resolved {} to {} (modes={:x})",
-                       ty_to_string(self.infcx.tcx, typ),
-                       ty_to_string(self.infcx.tcx, rty),
-                       self.modes);
-                return Ok(rty);
-            }
-            Some(e) => {
-                return Err(e);
-            }
-        }
+        let rty = try!(typ.try_fold_with(self));
+        debug!("Resolved {} to {} (modes={:x})",
+               ty_to_string(self.infcx.tcx, typ),
+               ty_to_string(self.infcx.tcx, rty),
+               self.modes);
+        Ok(rty)
     }
 
     pub fn resolve_region_chk(&mut self,
                               orig: ty::Region)
                               -> fres<ty::Region> {
-        self.err = None;
-        let resolved = self.resolve_region(orig);
-        match self.err {
-          None => Ok(resolved),
-          Some(e) => Err(e)
-        }
+        orig.try_fold_with(self)
     }
 
-    pub fn resolve_type(&mut self, typ: ty::t) -> ty::t {
+    pub fn resolve_type(&mut self, typ: ty::t) -> fres<ty::t> {
         debug!("resolve_type({})", typ.repr(self.infcx.tcx));
 
         if !ty::type_needs_infer(typ) {
-            return typ;
+            return Ok(typ);
         }
 
         if self.type_depth > 0 && !self.should(resolve_nested_tvar) {
-            return typ;
+            return Ok(typ);
         }
 
         match ty::get(typ).sty {
@@ -177,10 +159,10 @@ impl<'a, 'tcx> ResolveState<'a, 'tcx> {
                     // if we are only resolving top-level type
                     // variables, and this is not a top-level type
                     // variable, then shortcircuit for efficiency
-                    typ
+                    Ok(typ)
                 } else {
                     self.type_depth += 1;
-                    let result = ty_fold::super_fold_ty(self, typ);
+                    let result = ty_fold::try_super_fold_ty(self, typ);
                     self.type_depth -= 1;
                     result
                 }
@@ -188,11 +170,11 @@ impl<'a, 'tcx> ResolveState<'a, 'tcx> {
         }
     }
 
-    pub fn resolve_region(&mut self, orig: ty::Region) -> ty::Region {
+    pub fn resolve_region(&mut self, orig: ty::Region) -> fres<ty::Region> {
         debug!("Resolve_region({})", orig.repr(self.infcx.tcx));
         match orig {
-          ty::ReInfer(ty::ReVar(rid)) => self.resolve_region_var(rid),
-          _ => orig
+          ty::ReInfer(ty::ReVar(rid)) => Ok(self.resolve_region_var(rid)),
+          _ => Ok(orig)
         }
     }
 
@@ -203,7 +185,7 @@ impl<'a, 'tcx> ResolveState<'a, 'tcx> {
         self.infcx.region_vars.resolve_var(rid)
     }
 
-    pub fn resolve_ty_var(&mut self, vid: TyVid) -> ty::t {
+    pub fn resolve_ty_var(&mut self, vid: TyVid) -> fres<ty::t> {
         let tcx = self.infcx.tcx;
         let tv = self.infcx.type_variables.borrow();
         match tv.probe(vid) {
@@ -212,50 +194,50 @@ impl<'a, 'tcx> ResolveState<'a, 'tcx> {
             }
             None => {
                 if self.should(force_tvar) {
-                    self.err = Some(unresolved_ty(vid));
+                    return Err(unresolved_ty(vid));
                 }
-                ty::mk_var(tcx, vid)
+                Ok(ty::mk_var(tcx, vid))
             }
         }
     }
 
-    pub fn resolve_int_var(&mut self, vid: IntVid) -> ty::t {
+    pub fn resolve_int_var(&mut self, vid: IntVid) -> fres<ty::t> {
         if !self.should(resolve_ivar) {
-            return ty::mk_int_var(self.infcx.tcx, vid);
+            return Ok(ty::mk_int_var(self.infcx.tcx, vid));
         }
 
         let tcx = self.infcx.tcx;
         let table = &self.infcx.int_unification_table;
         let node = table.borrow_mut().get(tcx, vid);
         match node.value {
-          Some(IntType(t)) => ty::mk_mach_int(t),
-          Some(UintType(t)) => ty::mk_mach_uint(t),
+          Some(IntType(t)) => Ok(ty::mk_mach_int(t)),
+          Some(UintType(t)) => Ok(ty::mk_mach_uint(t)),
           None => {
             if self.should(force_ivar) {
                 // As a last resort, emit an error.
-                self.err = Some(unresolved_int_ty(vid));
+                return Err(unresolved_int_ty(vid));
             }
-            ty::mk_int_var(self.infcx.tcx, vid)
+            Ok(ty::mk_int_var(self.infcx.tcx, vid))
           }
         }
     }
 
-    pub fn resolve_float_var(&mut self, vid: FloatVid) -> ty::t {
+    pub fn resolve_float_var(&mut self, vid: FloatVid) -> fres<ty::t> {
         if !self.should(resolve_fvar) {
-            return ty::mk_float_var(self.infcx.tcx, vid);
+            return Ok(ty::mk_float_var(self.infcx.tcx, vid));
         }
 
         let tcx = self.infcx.tcx;
         let table = &self.infcx.float_unification_table;
         let node = table.borrow_mut().get(tcx, vid);
         match node.value {
-          Some(t) => ty::mk_mach_float(t),
+          Some(t) => Ok(ty::mk_mach_float(t)),
           None => {
             if self.should(force_fvar) {
                 // As a last resort, emit an error.
-                self.err = Some(unresolved_float_ty(vid));
+                return Err(unresolved_float_ty(vid));
             }
-            ty::mk_float_var(self.infcx.tcx, vid)
+            Ok(ty::mk_float_var(self.infcx.tcx, vid))
           }
         }
     }
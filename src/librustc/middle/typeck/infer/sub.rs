@@ -12,16 +12,19 @@
 use middle::ty::{BuiltinBounds};
 use middle::ty;
 use middle::ty::TyVar;
-use middle::typeck::check::regionmanip::replace_late_bound_regions;
+use middle::ty_fold;
+use middle::typeck::check::regionmanip::replace_late_bound_regions_in_trait_ref;
 use middle::typeck::infer::combine::*;
-use middle::typeck::infer::{cres, CresCompare};
+use middle::typeck::infer::{cres, CresCompare, LateBoundRegion};
 use middle::typeck::infer::equate::Equate;
 use middle::typeck::infer::glb::Glb;
 use middle::typeck::infer::InferCtxt;
 use middle::typeck::infer::lub::Lub;
+use middle::typeck::infer::region_inference::RegionMark;
 use middle::typeck::infer::{TypeTrace, Subtype};
 use middle::typeck::infer::type_variable::{SubtypeOf, SupertypeOf};
 use util::common::{indenter};
+use util::nodemap::FnvHashMap;
 use util::ppaux::{bound_region_to_string, Repr};
 
 use syntax::ast::{Onceness, FnStyle, MutImmutable, MutMutable};
@@ -180,8 +183,8 @@ impl<'f, 'tcx> Combine<'tcx> for Sub<'f, 'tcx> {
 
         // Second, we instantiate each bound region in the supertype with a
         // fresh concrete region.
-        let (skol_map, b_sig) = {
-            replace_late_bound_regions(self.fields.infcx.tcx, b.binder_id, b, |br| {
+        let (b_sig, skol_map) = {
+            ty_fold::replace_late_bound_regions(self.fields.infcx.tcx, b.binder_id, b, |br| {
                 let skol = self.fields.infcx.region_vars.new_skolemized(br);
                 debug!("Bound region {} skolemized to {}",
                        bound_region_to_string(self.fields.infcx.tcx, "", false, br),
@@ -198,6 +201,56 @@ impl<'f, 'tcx> Combine<'tcx> for Sub<'f, 'tcx> {
 
         // Presuming type comparison succeeds, we need to check
         // that the skolemized regions do not "leak".
+        try!(self.leak_check(mark, &skol_map));
+
+        return Ok(sig);
+    }
+
+    fn trait_refs(&self, a: &ty::TraitRef, b: &ty::TraitRef) -> cres<ty::TraitRef> {
+        debug!("trait_refs(a={}, b={})",
+               a.repr(self.fields.infcx.tcx), b.repr(self.fields.infcx.tcx));
+        let _indenter = indenter();
+
+        // A trait reference may bind regions of its own, as in
+        // `for<'a> Fn(&'a int) -> &'a int`. These are related just like
+        // the bound regions of fn signatures (see `fn_sigs()` above): the
+        // bound regions of the subtype are instantiated with fresh
+        // variables, those of the supertype are skolemized, and the
+        // skolemized regions must not leak.
+        let mark = self.fields.infcx.region_vars.mark();
+
+        let (_, a_ref) =
+            replace_late_bound_regions_in_trait_ref(self.fields.infcx.tcx, a, |br| {
+                self.fields.infcx.next_region_var(
+                    LateBoundRegion(self.trace().origin.span(), br))
+            });
+
+        let (skol_map, b_ref) =
+            replace_late_bound_regions_in_trait_ref(self.fields.infcx.tcx, b, |br| {
+                self.fields.infcx.region_vars.new_skolemized(br)
+            });
+
+        debug!("a_ref={}", a_ref.repr(self.fields.infcx.tcx));
+        debug!("b_ref={}", b_ref.repr(self.fields.infcx.tcx));
+
+        let trait_ref = try!(super_trait_refs(self, &a_ref, &b_ref));
+
+        try!(self.leak_check(mark, &skol_map));
+
+        return Ok(trait_ref);
+    }
+}
+
+impl<'f, 'tcx> Sub<'f, 'tcx> {
+    fn leak_check(&self,
+                  mark: RegionMark,
+                  skol_map: &FnvHashMap<ty::BoundRegion, ty::Region>)
+                  -> cres<()> {
+        /*!
+         * Checks that each skolemized region in `skol_map` was only
+         * related to itself or to region variables created since `mark`.
+         */
+
         let new_vars =
             self.fields.infcx.region_vars.vars_created_since_mark(mark);
         for (&skol_br, &skol) in skol_map.iter() {
@@ -227,7 +280,6 @@ impl<'f, 'tcx> Combine<'tcx> for Sub<'f, 'tcx> {
             }
         }
 
-        return Ok(sig);
+        Ok(())
     }
 }
-
@@ -27,7 +27,13 @@ use middle::region;
 use middle::resolve;
 use middle::resolve_lifetime;
 use middle::stability;
+use middle::subst;
+use middle::subst::{Subst, Substs, VecPerParamSpace};
+use middle::traits;
 use middle::ty;
+use middle::ty_fold;
+use middle::ty_fold::{TypeFoldable, TypeFolder};
+use middle::typeck;
 use middle::typeck::infer::combine::Combine;
 use middle::typeck::infer;
 use middle::typeck::infer::lub::Lub;
@@ -35,10 +41,14 @@ use middle::typeck::infer::glb::Glb;
 use syntax::codemap;
 use syntax::codemap::{Span, CodeMap, DUMMY_SP};
 use syntax::diagnostic::{Level, RenderSpan, Bug, Fatal, Error, Warning, Note, Help};
-use syntax::{ast, ast_map};
-use util::ppaux::{ty_to_string, UserString};
+use syntax::parse::token;
+use syntax::{abi, ast, ast_map, ast_util};
+use util::nodemap::NodeMap;
+use util::ppaux::{ty_to_string, Repr, UserString};
 
 use arena::TypedArena;
+use std::cmp;
+use std::rc::Rc;
 
 struct Env<'a, 'tcx: 'a> {
     infcx: &'a infer::InferCtxt<'a, 'tcx>,
@@ -51,6 +61,8 @@ struct RH<'a> {
 
 static EMPTY_SOURCE_STR: &'static str = "#![no_std]";
 
+static IMPL_SOURCE_STR: &'static str = "#![no_std] trait Foo {} struct S; impl Foo for S {}";
+
 struct ExpectErrorEmitter {
     messages: Vec<String>
 }
@@ -98,12 +110,19 @@ fn errors(msgs: &[&str]) -> (Box<Emitter+Send>, uint) {
     (box ExpectErrorEmitter { messages: v } as Box<Emitter+Send>, msgs.len())
 }
 
-fn test_env(_test_name: &str,
+fn test_env(test_name: &str,
             source_string: &str,
-            (emitter, expected_err_count): (Box<Emitter+Send>, uint),
+            expected_errors: (Box<Emitter+Send>, uint),
             body: |Env|) {
-    let options =
-        config::basic_options();
+    test_env_with_options(config::basic_options(), test_name, source_string,
+                          expected_errors, body)
+}
+
+fn test_env_with_options(options: config::Options,
+                         _test_name: &str,
+                         source_string: &str,
+                         (emitter, expected_err_count): (Box<Emitter+Send>, uint),
+                         body: |Env|) {
     let codemap =
         CodeMap::new();
     let diagnostic_handler =
@@ -215,6 +234,16 @@ impl<'a, 'tcx> Env<'a, 'tcx> {
         }
     }
 
+    pub fn lookup_impl(&self) -> ast::NodeId {
+        for item in self.infcx.tcx.map.krate().module.items.iter() {
+            match item.node {
+                ast::ItemImpl(..) => return item.id,
+                _ => {}
+            }
+        }
+        panic!("no impl found");
+    }
+
     pub fn make_subtype(&self, a: ty::t, b: ty::t) -> bool {
         match infer::mk_subty(self.infcx, true, infer::Misc(DUMMY_SP), a, b) {
             Ok(_) => true,
@@ -374,6 +403,130 @@ impl<'a, 'tcx> Env<'a, 'tcx> {
     }
 }
 
+/// Counts the types it visits, skipping those without type parameters.
+struct ParamFolder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    visited: uint,
+}
+
+impl<'a, 'tcx> ty_fold::TypeFolder<'tcx> for ParamFolder<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn skip_ty(&self, t: ty::t) -> bool {
+        !ty::type_has_params(t)
+    }
+
+    fn fold_ty(&mut self, t: ty::t) -> ty::t {
+        self.visited += 1;
+        ty_fold::super_fold_ty(self, t)
+    }
+}
+
+/// Records the deepest nesting of region binders it folds through.
+struct BinderDepthFolder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    depth: uint,
+    max_depth: uint,
+}
+
+impl<'a, 'tcx> ty_fold::TypeFolder<'tcx> for BinderDepthFolder<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn enter_region_binder(&mut self, _: ast::NodeId) {
+        self.depth += 1;
+        self.max_depth = cmp::max(self.max_depth, self.depth);
+    }
+
+    fn exit_region_binder(&mut self, _: ast::NodeId) {
+        self.depth -= 1;
+    }
+}
+
+/// Counts the closure types it folds.
+struct ClosureCountFolder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    closures: uint,
+}
+
+impl<'a, 'tcx> ty_fold::TypeFolder<'tcx> for ClosureCountFolder<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn fold_closure_ty(&mut self, fty: &ty::ClosureTy) -> ty::ClosureTy {
+        self.closures += 1;
+        ty_fold::super_fold_closure_ty(self, fty)
+    }
+}
+
+/// Drops the region bounds of every set of parameter bounds it folds.
+struct RegionBoundEraser<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+}
+
+impl<'a, 'tcx> ty_fold::TypeFolder<'tcx> for RegionBoundEraser<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn fold_param_bounds(&mut self, b: &ty::ParamBounds) -> ty::ParamBounds {
+        let b = ty_fold::super_fold_param_bounds(self, b);
+        ty::ParamBounds { region_bounds: Vec::new(), ..b }
+    }
+}
+
+/// Rewrites converging return types to `()`, leaving argument types alone.
+struct ReturnTypeFolder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+}
+
+impl<'a, 'tcx> ty_fold::TypeFolder<'tcx> for ReturnTypeFolder<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn fold_output(&mut self, output: &ty::FnOutput) -> ty::FnOutput {
+        match *output {
+            ty::FnConverging(_) => ty::FnConverging(ty::mk_nil()),
+            ty::FnDiverging => ty::FnDiverging
+        }
+    }
+}
+
+/// Counts the calls made to `fold_sty`.
+struct StyCountFolder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    stys: uint,
+}
+
+impl<'a, 'tcx> ty_fold::TypeFolder<'tcx> for StyCountFolder<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn fold_sty(&mut self, sty: &ty::sty) -> ty::sty {
+        self.stys += 1;
+        ty_fold::super_fold_sty(self, sty)
+    }
+}
+
+/// Counts the types and regions it folds.
+struct FoldCountFolder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    folds: uint,
+}
+
+impl<'a, 'tcx> ty_fold::TypeFolder<'tcx> for FoldCountFolder<'a, 'tcx> {
+    fn tcx<'a>(&'a self) -> &'a ty::ctxt<'tcx> { self.tcx }
+
+    fn fold_ty(&mut self, t: ty::t) -> ty::t {
+        self.folds += 1;
+        ty_fold::super_fold_ty(self, t)
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        self.folds += 1;
+        r
+    }
+}
+
+fn boxed_region_eraser<'a, 'tcx>(tcx: &'a ty::ctxt<'tcx>)
+                                 -> Box<ty_fold::DynTypeFolder<'tcx>+'a> {
+    box ty_fold::RegionEraser::new(tcx) as Box<ty_fold::DynTypeFolder<'tcx>+'a>
+}
+
 #[test]
 fn contravariant_region_ptr_ok() {
     test_env("contravariant_region_ptr", EMPTY_SOURCE_STR, errors([]), |env| {
@@ -516,3 +669,1352 @@ fn glb_bound_static() {
                       env.t_fn(22, [t_rptr_bound1], env.t_int()));
     })
 }
+
+#[test]
+fn vtable_repr_nested() {
+    test_env("vtable_repr_nested", IMPL_SOURCE_STR, errors([]), |env| {
+        let builtin: traits::Vtable<()> = traits::VtableBuiltin(traits::VtableBuiltinData {
+            nested: VecPerParamSpace::new(vec![], vec![()], vec![], vec![])
+        });
+        let vtable = traits::VtableImpl(traits::VtableImplData {
+            impl_def_id: ast_util::local_def(env.lookup_impl()),
+            substs: Substs::empty(),
+            nested: VecPerParamSpace::new(vec![builtin], vec![], vec![], vec![])
+        });
+        let repr = vtable.repr(env.infcx.tcx);
+        assert!(repr.as_slice().starts_with("VtableImpl(impl="), "{}", repr);
+        assert!(repr.contains("S.Foo"), "{}", repr);
+        assert!(repr.contains("VtableBuiltin(nested="), "{}", repr);
+    })
+}
+
+#[test]
+fn erase_regions_unboxed_closure() {
+    test_env("erase_regions_unboxed_closure", EMPTY_SOURCE_STR, errors([]), |env| {
+        // The same closure, instantiated under two different borrow
+        // regions, must erase to a single type so that trans builds one
+        // environment layout for it.
+        let tcx = env.infcx.tcx;
+        let closure_id = ast_util::local_def(0);
+        let closure_under = |id: ast::NodeId| {
+            let substs = Substs::new_type(vec![env.t_rptr_scope(id)], vec![ty::ReScope(id)]);
+            ty::mk_unboxed_closure(tcx, closure_id, ty::ReScope(id), substs)
+        };
+        let t1 = closure_under(1);
+        let t2 = closure_under(2);
+        assert!(t1 != t2);
+
+        let erased = ty_fold::erase_regions(tcx, t1);
+        assert!(erased == ty_fold::erase_regions(tcx, t2), "{}", erased.repr(tcx));
+        assert!(erased == ty::normalize_ty(tcx, t1), "{}", erased.repr(tcx));
+        assert!(erased == ty_fold::erase_regions(tcx, erased), "{}", erased.repr(tcx));
+    })
+}
+
+#[test]
+fn try_subst_out_of_range() {
+    test_env("try_subst_out_of_range", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 1, ast_util::local_def(0));
+        let t = ty::mk_imm_rptr(tcx, ty::ReStatic, param);
+
+        let substs = Substs::new_type(vec![env.t_int()], vec![]);
+        match t.try_subst(tcx, &substs) {
+            Err(subst::SubstError { param: subst::TypeParamOutOfRange(p),
+                                    len: 1,
+                                    root_ty: Some(root_ty),
+                                    item: None }) => {
+                assert_eq!(p.idx, 1);
+                assert!(root_ty == t, "{}", root_ty.repr(tcx));
+            }
+            Err(err) => panic!("unexpected error {}", err.repr(tcx)),
+            Ok(t) => panic!("substituted to {}", t.repr(tcx)),
+        }
+
+        let substs = Substs::new_type(vec![env.t_int(), env.t_int()], vec![]);
+        let expected = ty::mk_imm_rptr(tcx, ty::ReStatic, env.t_int());
+        assert!(t.try_subst(tcx, &substs).ok() == Some(expected));
+    })
+}
+
+#[test]
+fn fold_unchanged_type_is_identical() {
+    test_env("fold_unchanged_type_is_identical", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let boxed = ty::mk_uniq(tcx, ty::mk_vec(tcx, env.t_int(), Some(4)));
+        let t = ty::mk_tup(tcx, vec![env.t_int(), ty::mk_bool(), boxed]);
+
+        let erased = ty_fold::erase_regions(tcx, t);
+        assert!(erased == t, "{} != {}", erased.repr(tcx), t.repr(tcx));
+
+        let mut identity = ty_fold::BottomUpFolder::new(tcx, |t| t);
+        let folded = t.fold_with(&mut identity);
+        assert!(folded == t, "{} != {}", folded.repr(tcx), t.repr(tcx));
+
+        // A type whose only region is already `'static` is unchanged too.
+        let t = ty::mk_tup(tcx, vec![env.t_rptr_static(), boxed]);
+        let erased = ty_fold::erase_regions(tcx, t);
+        assert!(erased == t, "{} != {}", erased.repr(tcx), t.repr(tcx));
+    })
+}
+
+#[test]
+fn fold_unchanged_substs_are_not_rebuilt() {
+    let mut options = config::basic_options();
+    options.debugging_opts |= config::FOLD_STATS;
+    test_env_with_options(options, "fold_unchanged_substs_are_not_rebuilt",
+                          EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let rebuilds = |what: &'static str| {
+            let stats = tcx.fold_stats.borrow();
+            let count = stats.get(&("super_fold", what)).unwrap();
+            count.calls - count.no_ops.unwrap()
+        };
+
+        let substs = Substs::new_type(vec![env.t_int(), ty::mk_bool()], vec![ty::ReStatic]);
+        let t = ty::mk_struct(tcx, ast_util::local_def(100), substs.clone());
+
+        // Neither the struct nor its substs are rebuilt by a fold that
+        // leaves them as they are, nor are the vectors of the substs.
+        let mut identity = ty_fold::BottomUpFolder::new(tcx, |t| t);
+        assert!(t.fold_with(&mut identity) == t);
+        assert!(substs.fold_with(&mut identity) == substs);
+        assert_eq!(rebuilds("ty"), 0);
+        assert_eq!(rebuilds("substs"), 0);
+        assert_eq!(rebuilds("vec"), 0);
+
+        // Turning the `bool` into an `int` rebuilds both, and the types of
+        // the substs, but not their regions.
+        let mut folder = ty_fold::BottomUpFolder::new(tcx, |t| {
+            if t == ty::mk_bool() { ty::mk_int() } else { t }
+        });
+        let folded = t.fold_with(&mut folder);
+        assert!(folded != t, "{}", folded.repr(tcx));
+        assert_eq!(rebuilds("ty"), 1);
+        assert_eq!(rebuilds("substs"), 1);
+        assert_eq!(rebuilds("vec"), 1);
+    })
+}
+
+#[test]
+fn fold_stats_count_the_recursion_of_a_counted_folder() {
+    let mut options = config::basic_options();
+    options.debugging_opts |= config::FOLD_STATS;
+    test_env_with_options(options, "fold_stats_count_the_recursion_of_a_counted_folder",
+                          EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(100));
+        let t = ty::mk_tup(tcx, vec![param, env.t_int(), ty::mk_uniq(tcx, param)]);
+        let substs = Substs::new_type(vec![ty::mk_bool()], vec![]);
+
+        // The one type handed to the folder is counted along with the four
+        // it visits within, of which only the `int` comes back unchanged.
+        let substituted = t.subst(tcx, &substs);
+        let expected = ty::mk_tup(tcx, vec![ty::mk_bool(), env.t_int(),
+                                            ty::mk_uniq(tcx, ty::mk_bool())]);
+        assert!(substituted == expected, "{}", substituted.repr(tcx));
+
+        let stats = tcx.fold_stats.borrow();
+        let count = stats.get(&("SubstFolder", "fold_ty")).unwrap();
+        assert_eq!(count.calls, 5);
+        assert_eq!(count.no_ops, Some(1));
+    })
+}
+
+// The two benchmarks below fold the same substs, once with a folder that
+// leaves them unchanged, which copies nothing but the substs it hands
+// back, and once with one that changes a type, which also builds a new
+// vector of types.
+
+fn bench_substs(env: &Env) -> Substs {
+    let types = vec![env.t_int(), ty::mk_bool(), env.t_rptr_static(), env.t_int()];
+    Substs::new_type(types, vec![ty::ReStatic, ty::ReStatic])
+}
+
+#[bench]
+fn bench_fold_unchanged_substs(b: &mut ::test::Bencher) {
+    test_env("bench_fold_unchanged_substs", EMPTY_SOURCE_STR, errors([]), |env| {
+        let substs = bench_substs(&env);
+        let mut identity = ty_fold::BottomUpFolder::new(env.infcx.tcx, |t| t);
+        b.iter(|| substs.fold_with(&mut identity));
+    })
+}
+
+#[bench]
+fn bench_fold_changed_substs(b: &mut ::test::Bencher) {
+    test_env("bench_fold_changed_substs", EMPTY_SOURCE_STR, errors([]), |env| {
+        let substs = bench_substs(&env);
+        let mut folder = ty_fold::BottomUpFolder::new(env.infcx.tcx, |t| {
+            if t == ty::mk_bool() { ty::mk_int() } else { t }
+        });
+        b.iter(|| substs.fold_with(&mut folder));
+    })
+}
+
+#[test]
+fn fold_skips_types_without_params() {
+    test_env("fold_skips_types_without_params", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(0));
+        let boxed = ty::mk_uniq(tcx, ty::mk_vec(tcx, env.t_int(), Some(4)));
+        let t = ty::mk_tup(tcx, vec![env.t_int(), boxed, param]);
+
+        // The tuple and each of its components are visited, but nothing
+        // inside the box, which has no type parameters.
+        let mut folder = ParamFolder { tcx: tcx, visited: 0 };
+        assert!(t.fold_with(&mut folder) == t);
+        assert_eq!(folder.visited, 4);
+    })
+}
+
+#[test]
+fn region_folder_skips_bound_regions() {
+    test_env("region_folder_skips_bound_regions", EMPTY_SOURCE_STR, errors([]), |env| {
+        // fn(&'a fn(&'b int)), where 'a is bound by the outer fn type
+        // (binder 1) and 'b by the inner one (binder 2).
+        let tcx = env.infcx.tcx;
+        let inner = ty::mk_ctor_fn(tcx, 2, [env.t_rptr_late_bound(2, 0)], ty::mk_nil());
+        let inner_ref = ty::mk_imm_rptr(tcx, ty::ReLateBound(1, ty::BrAnon(0)), inner);
+        let outer = ty::mk_ctor_fn(tcx, 1, [inner_ref], ty::mk_nil());
+
+        // Within the whole type, neither region is free.
+        let mut seen = Vec::new();
+        ty_fold::RegionFolder::regions(tcx, |r, _| { seen.push(r); r }).fold_ty(outer);
+        assert!(seen.is_empty(), "{}", seen.repr(tcx));
+
+        // Within the signature of the outer fn, only 'a is free.
+        let sig = ty::ty_fn_sig(outer);
+        let mut seen = Vec::new();
+        ty_fold::RegionFolder::regions(tcx, |r, _| { seen.push(r); r }).fold_sig(&sig);
+        assert!(seen == vec![ty::ReLateBound(1, ty::BrAnon(0))], "{}", seen.repr(tcx));
+    })
+}
+
+#[test]
+fn fold_counts_region_binders() {
+    test_env("fold_counts_region_binders", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let inner = ty::mk_ctor_fn(tcx, 2, [env.t_rptr_late_bound(2, 0)], ty::mk_nil());
+        let inner_ref = ty::mk_imm_rptr(tcx, ty::ReLateBound(1, ty::BrAnon(0)), inner);
+        let outer = ty::mk_ctor_fn(tcx, 1, [inner_ref], ty::mk_nil());
+
+        let mut folder = BinderDepthFolder { tcx: tcx, depth: 0, max_depth: 0 };
+        outer.fold_with(&mut folder);
+        assert_eq!(folder.max_depth, 2);
+        assert_eq!(folder.depth, 0);
+
+        // A signature folded on its own is not within its own binder.
+        let mut folder = BinderDepthFolder { tcx: tcx, depth: 0, max_depth: 0 };
+        ty::ty_fn_sig(outer).fold_with(&mut folder);
+        assert_eq!(folder.max_depth, 1);
+    })
+}
+
+#[test]
+fn erase_regions_is_memoized() {
+    test_env("erase_regions_is_memoized", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let t = ty::mk_tup(tcx, vec![env.t_rptr_scope(1), env.t_rptr_free(0, 1)]);
+        let expected = ty::mk_tup(tcx, vec![env.t_rptr_static(), env.t_rptr_static()]);
+
+        let misses = tcx.erased_regions_misses.get();
+        assert!(ty_fold::erase_regions(tcx, t) == expected);
+        assert!(tcx.erased_regions_misses.get() > misses);
+
+        let (hits, misses) = (tcx.erased_regions_hits.get(), tcx.erased_regions_misses.get());
+        assert!(ty_fold::erase_regions(tcx, t) == expected);
+        assert_eq!(tcx.erased_regions_hits.get(), hits + 1);
+        assert_eq!(tcx.erased_regions_misses.get(), misses);
+
+        // Types without regions are neither looked up nor cached.
+        let misses = tcx.erased_regions_misses.get();
+        assert!(ty_fold::erase_regions(tcx, env.t_int()) == env.t_int());
+        assert_eq!(tcx.erased_regions_misses.get(), misses);
+    })
+}
+
+#[test]
+fn fold_closure_ty_through_box() {
+    test_env("fold_closure_ty_through_box", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let closure = ty::mk_closure(tcx, ty::ClosureTy {
+            fn_style: ast::NormalFn,
+            onceness: ast::Many,
+            store: ty::UniqTraitStore,
+            bounds: ty::region_existential_bound(ty::ReStatic),
+            sig: ty::ty_fn_sig(ty::mk_ctor_fn(tcx, 1, [env.t_int()], ty::mk_nil())),
+            abi: abi::Rust,
+        });
+
+        let mut folder = ClosureCountFolder { tcx: tcx, closures: 0 };
+        let t = ty::mk_uniq(tcx, closure);
+        assert!(t.fold_with(&mut folder) == t);
+        assert_eq!(folder.closures, 1);
+
+        // A boxed closure type is folded through `fold_closure_ty` too.
+        let boxed = match ty::get(closure).sty {
+            ty::ty_closure(ref f) => f.clone(),
+            _ => panic!("expected a closure type"),
+        };
+        let folded = boxed.fold_with(&mut folder);
+        assert!(folded == boxed);
+        assert_eq!(folder.closures, 2);
+    })
+}
+
+#[test]
+fn erase_regions_in_type_region_pairs() {
+    test_env("erase_regions_in_type_region_pairs", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let pairs = vec![(env.t_rptr_scope(1), ty::ReScope(2)),
+                         (env.t_int(), ty::ReStatic)];
+        let erased = ty_fold::erase_regions(tcx, pairs);
+        assert!(erased == vec![(env.t_rptr_static(), ty::ReStatic),
+                               (env.t_int(), ty::ReStatic)]);
+
+        let triple = (env.t_rptr_scope(1), ty::ReScope(2), env.t_int());
+        assert!(ty_fold::erase_regions(tcx, triple) ==
+                (env.t_rptr_static(), ty::ReStatic, env.t_int()));
+    })
+}
+
+#[test]
+fn fold_node_map_values() {
+    test_env("fold_node_map_values", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let mut map = NodeMap::new();
+        map.insert(1, env.t_int());
+        map.insert(2, ty::mk_tup(tcx, vec![env.t_int(), ty::mk_bool()]));
+
+        let mut folder = ty_fold::BottomUpFolder::new(tcx, |t| {
+            if t == ty::mk_int() { ty::mk_uint() } else { t }
+        });
+        let folded = map.fold_with(&mut folder);
+
+        assert_eq!(folded.len(), 2);
+        assert!(folded[1] == ty::mk_uint());
+        assert!(folded[2] == ty::mk_tup(tcx, vec![ty::mk_uint(), ty::mk_bool()]));
+    })
+}
+
+#[test]
+fn fold_generics_through_param_bounds() {
+    test_env("fold_generics_through_param_bounds", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let def = ty::TypeParameterDef {
+            name: ast::Name(0),
+            def_id: ast_util::local_def(1),
+            space: subst::TypeSpace,
+            index: 0,
+            associated_with: None,
+            bounds: ty::ParamBounds {
+                region_bounds: vec![ty::ReStatic],
+                builtin_bounds: ty::empty_builtin_bounds(),
+                trait_bounds: Vec::new(),
+            },
+            default: Some(env.t_int()),
+        };
+        let mut generics = ty::Generics::empty();
+        generics.types.push(subst::TypeSpace, def);
+
+        let folded = generics.fold_with(&mut RegionBoundEraser { tcx: tcx });
+        let def = folded.types.get(subst::TypeSpace, 0);
+        assert!(def.bounds.region_bounds.is_empty());
+        assert!(def.default == Some(env.t_int()));
+    })
+}
+
+#[test]
+fn fold_output_rewrites_only_return_types() {
+    test_env("fold_output_rewrites_only_return_types", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let sig = ty::FnSig {
+            binder_id: 22,
+            inputs: vec![env.t_int()],
+            output: ty::FnConverging(env.t_int()),
+            variadic: true,
+        };
+
+        let folded = sig.fold_with(&mut ReturnTypeFolder { tcx: tcx });
+        assert_eq!(folded.binder_id, 22);
+        assert!(folded.inputs == vec![env.t_int()]);
+        assert!(folded.output == ty::FnConverging(ty::mk_nil()));
+        assert!(folded.variadic);
+
+        let diverging = ty::FnSig { output: ty::FnDiverging, ..sig };
+        let folded = diverging.fold_with(&mut ReturnTypeFolder { tcx: tcx });
+        assert!(folded.output == ty::FnDiverging);
+    })
+}
+
+#[test]
+fn erase_regions_through_boxed_folder() {
+    test_env("erase_regions_through_boxed_folder", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let t = ty::mk_tup(tcx, vec![env.t_rptr_scope(1),
+                                     env.t_rptr_late_bound(22, 1),
+                                     env.t_int()]);
+
+        let mut folder = boxed_region_eraser(tcx);
+        assert!(t.fold_with_dyn(&mut *folder) == ty_fold::erase_regions(tcx, t));
+    })
+}
+
+#[test]
+fn bottom_up_fold_types_and_regions() {
+    test_env("bottom_up_fold_types_and_regions", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let t = ty::mk_tup(tcx, vec![env.t_rptr_scope(1),
+                                     env.t_rptr_free(0, 1),
+                                     env.t_rptr_late_bound(22, 1)]);
+
+        let mut folder = ty_fold::BottomUpFolder::with_regions(
+            tcx,
+            |t| if t == ty::mk_int() { ty::mk_uint() } else { t },
+            |r| match r {
+                ty::ReLateBound(..) => r,
+                _ => ty::ReStatic
+            });
+        let folded = t.fold_with(&mut folder);
+
+        let late_bound = ty::ReLateBound(22, ty::BrAnon(1));
+        let expected = ty::mk_tup(tcx, vec![ty::mk_imm_rptr(tcx, ty::ReStatic, ty::mk_uint()),
+                                            ty::mk_imm_rptr(tcx, ty::ReStatic, ty::mk_uint()),
+                                            ty::mk_imm_rptr(tcx, late_bound, ty::mk_uint())]);
+        assert!(folded == expected, "{} != {}", folded.repr(tcx), expected.repr(tcx));
+    })
+}
+
+#[test]
+fn top_down_fold_stops_where_closure_fires() {
+    test_env("top_down_fold_stops_where_closure_fires", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(100));
+        let inner = ty::mk_tup(tcx, vec![param, env.t_int()]);
+        let t = ty::mk_tup(tcx, vec![inner, ty::mk_bool()]);
+
+        let mut seen = Vec::new();
+        let folded = {
+            let mut folder = ty_fold::TopDownFolder::new(tcx, |t| {
+                seen.push(t);
+                if t == inner { Some(ty::mk_nil()) } else { None }
+            });
+            t.fold_with(&mut folder)
+        };
+
+        assert!(folded == ty::mk_tup(tcx, vec![ty::mk_nil(), ty::mk_bool()]));
+
+        // Neither component of `inner` is visited once it is replaced.
+        assert!(seen == vec![t, inner, ty::mk_bool()]);
+    })
+}
+
+#[test]
+fn escaping_regions_of_nested_fn_types() {
+    test_env("escaping_regions_of_nested_fn_types", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+
+        // fn(&'a int, fn(&'a int, &'b int)), binding 'a at 10 and 'b at 20
+        let inner = ty::mk_ctor_fn(tcx, 20, [env.t_rptr_late_bound(10, 1),
+                                             env.t_rptr_late_bound(20, 2)],
+                                   ty::mk_nil());
+        let outer = ty::mk_ctor_fn(tcx, 10, [env.t_rptr_late_bound(10, 1), inner],
+                                   ty::mk_nil());
+
+        // The argument of the inner fn refers to the outer binder.
+        let escaping = ty_fold::escaping_region_binders(tcx, &inner);
+        assert_eq!(escaping.len(), 1);
+        assert!(escaping.contains(&10));
+        assert!(!ty_fold::has_escaping_regions(tcx, &outer));
+
+        // Folded on its own, a signature binds none of its regions.
+        let sig = ty::ty_fn_sig(inner);
+        assert_eq!(ty_fold::escaping_region_binders(tcx, &sig).len(), 2);
+        assert!(!ty_fold::has_escaping_regions(tcx, &ty::ty_fn_sig(outer).output));
+
+        let substs = Substs::new_type(vec![inner], vec![ty::ReStatic]);
+        assert!(ty_fold::has_escaping_regions(tcx, &substs));
+
+        let trait_ref = ty::TraitRef { def_id: ast_util::local_def(30),
+                                       substs: Substs::new_type(vec![outer], vec![]),
+                                       binder_id: ast::DUMMY_NODE_ID };
+        assert!(!ty_fold::has_escaping_regions(tcx, &trait_ref));
+    })
+}
+
+#[test]
+fn liberate_late_bound_regions_of_outer_binder_only() {
+    test_env("liberate_late_bound_regions_of_outer_binder_only", EMPTY_SOURCE_STR, errors([]),
+             |env| {
+        let tcx = env.infcx.tcx;
+
+        // fn(&'a int, fn(&'a int, &'b int)), binding 'a at 10 and 'b at 20
+        let inner = ty::mk_ctor_fn(tcx, 20, [env.t_rptr_late_bound(10, 1),
+                                             env.t_rptr_late_bound(20, 2)],
+                                   ty::mk_nil());
+        let outer = ty::mk_ctor_fn(tcx, 10, [env.t_rptr_late_bound(10, 1), inner],
+                                   ty::mk_nil());
+
+        let (sig, map) = ty_fold::liberate_late_bound_regions(tcx, 99,
+                                                              &ty::ty_fn_sig(outer));
+
+        // Only the inner fn's own region is still bound.
+        let liberated_inner = ty::mk_ctor_fn(tcx, 20, [env.t_rptr_free(99, 1),
+                                                       env.t_rptr_late_bound(20, 2)],
+                                             ty::mk_nil());
+        assert!(sig.inputs == vec![env.t_rptr_free(99, 1), liberated_inner]);
+        assert_eq!(sig.binder_id, 10);
+
+        assert_eq!(map.len(), 1);
+        assert!(map[ty::BrAnon(1)] == ty::ReFree(ty::FreeRegion { scope_id: 99,
+                                                                  bound_region: ty::BrAnon(1) }));
+    })
+}
+
+#[test]
+fn anonymize_alpha_equivalent_fn_types() {
+    test_env("anonymize_alpha_equivalent_fn_types", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let named = |binder_id, def| {
+            let br = ty::BrNamed(ast_util::local_def(def), ast::Name(def));
+            ty::mk_imm_rptr(tcx, ty::ReLateBound(binder_id, br), env.t_int())
+        };
+
+        // fn(&'a int, fn(&'b int)) and fn(&'c int, fn(&'d int))
+        let t1 = ty::mk_ctor_fn(tcx, 10, [named(10, 1),
+                                          ty::mk_ctor_fn(tcx, 11, [named(11, 2)],
+                                                         ty::mk_nil())],
+                                ty::mk_nil());
+        let t2 = ty::mk_ctor_fn(tcx, 20, [env.t_rptr_late_bound(20, 7),
+                                          ty::mk_ctor_fn(tcx, 21, [named(21, 3)],
+                                                         ty::mk_nil())],
+                                ty::mk_nil());
+        assert!(t1 != t2);
+
+        let a1 = ty_fold::anonymize_late_bound_regions(tcx, &t1);
+        let a2 = ty_fold::anonymize_late_bound_regions(tcx, &t2);
+        assert!(a1 == a2, "{} != {}", a1.repr(tcx), a2.repr(tcx));
+
+        // fn(&'a int, fn(&'a int)) binds a single region, so it stays distinct.
+        let t3 = ty::mk_ctor_fn(tcx, 30, [named(30, 1),
+                                          ty::mk_ctor_fn(tcx, 31, [named(30, 1)],
+                                                         ty::mk_nil())],
+                                ty::mk_nil());
+        assert!(ty_fold::anonymize_late_bound_regions(tcx, &t3) != a1);
+
+        // fn(fn(&'o int)) with 'o bound by the outer fn, and fn(fn(&'i int))
+        // with 'i bound by the inner one, differ in where the region is bound.
+        let outer = ty::mk_ctor_fn(tcx, 40, [ty::mk_ctor_fn(tcx, 41, [named(40, 1)],
+                                                            ty::mk_nil())],
+                                   ty::mk_nil());
+        let inner = ty::mk_ctor_fn(tcx, 50, [ty::mk_ctor_fn(tcx, 51, [named(51, 1)],
+                                                            ty::mk_nil())],
+                                   ty::mk_nil());
+        let a_outer = ty_fold::anonymize_late_bound_regions(tcx, &outer);
+        let a_inner = ty_fold::anonymize_late_bound_regions(tcx, &inner);
+        assert!(a_outer != a_inner, "{} == {}", a_outer.repr(tcx), a_inner.repr(tcx));
+    })
+}
+
+#[test]
+fn erase_infer_vars_reports_each_variable_once() {
+    test_env("erase_infer_vars_reports_each_variable_once", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let var = env.infcx.next_ty_var();
+        let t = ty::mk_tup(tcx, vec![var, env.t_int(), ty::mk_uniq(tcx, var)]);
+
+        let mut reported = Vec::new();
+        let erased = {
+            let mut eraser = ty_fold::InferVarEraser::with_report(tcx, |v| reported.push(v));
+            t.fold_with(&mut eraser)
+        };
+
+        assert!(erased == ty::mk_tup(tcx, vec![ty::mk_err(), env.t_int(),
+                                               ty::mk_uniq(tcx, ty::mk_err())]));
+        assert_eq!(reported.len(), 1);
+        assert!(ty::mk_infer(tcx, reported[0]) == var);
+    })
+}
+
+#[test]
+fn subst_obligation_cause_object_type() {
+    test_env("subst_obligation_cause_object_type", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(100));
+        let trait_ref = Rc::new(ty::TraitRef {
+            def_id: ast_util::local_def(101),
+            substs: Substs::new_type(vec![param], vec![]),
+            binder_id: ast::DUMMY_NODE_ID,
+        });
+        let cause = traits::ObligationCause::new(DUMMY_SP, traits::ObjectCastObligation(param));
+        let obligation = traits::Obligation::new(cause, trait_ref);
+
+        let substs = Substs::new_type(vec![env.t_int()], vec![]);
+        let obligation = obligation.subst(tcx, &substs);
+        match obligation.cause.code {
+            traits::ObjectCastObligation(object_ty) => assert!(object_ty == env.t_int()),
+            _ => panic!("expected an object cast obligation")
+        }
+    })
+}
+
+#[test]
+fn subst_method_callee() {
+    fn fn_substs(t: ty::t) -> Substs {
+        Substs::new(VecPerParamSpace::new(vec![], vec![], vec![], vec![t]),
+                    VecPerParamSpace::empty())
+    }
+
+    test_env("subst_method_callee", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::FnSpace, 0, ast_util::local_def(100));
+        let callee = typeck::MethodCallee {
+            origin: typeck::MethodTypeParam(typeck::MethodParam {
+                trait_ref: Rc::new(ty::TraitRef {
+                    def_id: ast_util::local_def(101),
+                    substs: Substs::new_trait(vec![], vec![], vec![], param),
+                    binder_id: ast::DUMMY_NODE_ID,
+                }),
+                method_num: 0,
+            }),
+            ty: ty::mk_ctor_fn(tcx, 102, [param], ty::mk_nil()),
+            substs: fn_substs(param),
+        };
+
+        let substs = fn_substs(env.t_int());
+        let callee = callee.subst(tcx, &substs);
+
+        match callee.origin {
+            typeck::MethodTypeParam(ref p) => assert!(p.trait_ref.self_ty() == env.t_int()),
+            _ => panic!("expected a type parameter method origin")
+        }
+        assert!(callee.ty == ty::mk_ctor_fn(tcx, 102, [env.t_int()], ty::mk_nil()));
+        assert!(callee.substs.types.get(subst::FnSpace, 0) == &env.t_int());
+    })
+}
+
+#[test]
+fn subst_unsizing_adjustments() {
+    test_env("subst_unsizing_adjustments", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(100));
+        let vtable = |self_ty| {
+            ty::UnsizeVtable(ty::TyTrait {
+                principal: ty::TraitRef { def_id: ast_util::local_def(101),
+                                          substs: Substs::empty(),
+                                          binder_id: ast::DUMMY_NODE_ID },
+                bounds: ty::region_existential_bound(ty::ReStatic),
+            }, self_ty)
+        };
+        let self_ty_of = |adjustment: &ty::AutoAdjustment| {
+            match *adjustment {
+                ty::AdjustDerefRef(ty::AutoDerefRef {
+                    autoref: Some(ty::AutoUnsizeUniq(ty::UnsizeVtable(_, t))), ..
+                }) |
+                ty::AdjustDerefRef(ty::AutoDerefRef {
+                    autoref: Some(ty::AutoPtr(_, _, Some(box ty::AutoUnsize(
+                        ty::UnsizeStruct(box ty::UnsizeVtable(_, t), _))))), ..
+                }) => t,
+                _ => panic!("unexpected adjustment {}", adjustment)
+            }
+        };
+        let substs = Substs::new_type(vec![env.t_int()], vec![]);
+
+        let uniq = ty::AdjustDerefRef(ty::AutoDerefRef {
+            autoderefs: 0,
+            autoref: Some(ty::AutoUnsizeUniq(vtable(param))),
+        });
+        assert!(self_ty_of(&uniq.subst(tcx, &substs)) == env.t_int());
+
+        let by_ref = ty::AdjustDerefRef(ty::AutoDerefRef {
+            autoderefs: 1,
+            autoref: Some(ty::AutoPtr(ty::ReScope(1), ast::MutImmutable,
+                                      Some(box ty::AutoUnsize(
+                                          ty::UnsizeStruct(box vtable(param), 0))))),
+        });
+        let folded = ty_fold::erase_regions(tcx, by_ref.subst(tcx, &substs));
+        assert!(self_ty_of(&folded) == env.t_int());
+        match folded {
+            ty::AdjustDerefRef(ty::AutoDerefRef {
+                autoderefs: 1, autoref: Some(ty::AutoPtr(ty::ReStatic, _, _))
+            }) => {}
+            _ => panic!("unexpected adjustment {}", folded)
+        }
+    })
+}
+
+#[test]
+fn erase_regions_in_polytype() {
+    test_env("erase_regions_in_polytype", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let def = ty::TypeParameterDef {
+            name: ast::Name(0),
+            def_id: ast_util::local_def(1),
+            space: subst::FnSpace,
+            index: 0,
+            associated_with: None,
+            bounds: ty::ParamBounds {
+                region_bounds: vec![ty::ReScope(5)],
+                builtin_bounds: ty::empty_builtin_bounds(),
+                trait_bounds: Vec::new(),
+            },
+            default: None,
+        };
+        let mut generics = ty::Generics::empty();
+        generics.types.push(subst::FnSpace, def);
+        let pty = ty::Polytype {
+            generics: generics,
+            ty: env.t_fn(22, [env.t_rptr_scope(5)], env.t_int()),
+        };
+
+        let erased = ty_fold::erase_regions(tcx, pty);
+        let def = erased.generics.types.get(subst::FnSpace, 0);
+        assert!(def.bounds.region_bounds == vec![ty::ReStatic]);
+        assert!(erased.ty == env.t_fn(22, [env.t_rptr_static()], env.t_int()),
+                "{}", erased.ty.repr(tcx));
+    })
+}
+
+#[test]
+fn subst_trait_def_bounds_and_trait_ref() {
+    test_env("subst_trait_def_bounds_and_trait_ref", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(100));
+        let trait_ref = |def_id| Rc::new(ty::TraitRef {
+            def_id: ast_util::local_def(def_id),
+            substs: Substs::new_type(vec![param], vec![]),
+            binder_id: ast::DUMMY_NODE_ID,
+        });
+        let trait_def = ty::TraitDef {
+            generics: ty::Generics::empty(),
+            bounds: ty::ParamBounds {
+                region_bounds: Vec::new(),
+                builtin_bounds: ty::empty_builtin_bounds(),
+                trait_bounds: vec![trait_ref(102)],
+            },
+            trait_ref: trait_ref(101),
+        };
+
+        let substs = Substs::new_type(vec![env.t_int()], vec![]);
+        let trait_def = trait_def.subst(tcx, &substs);
+        assert!(*trait_def.trait_ref.substs.types.get(subst::TypeSpace, 0) == env.t_int());
+        let bound = &trait_def.bounds.trait_bounds[0];
+        assert!(*bound.substs.types.get(subst::TypeSpace, 0) == env.t_int());
+    })
+}
+
+#[test]
+fn subst_struct_fields() {
+    test_env("subst_struct_fields", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(100));
+        let region = ty::ReEarlyBound(101, subst::TypeSpace, 0, ast::Name(0));
+        let fields = vec![ty::field {
+            name: ast::Name(1),
+            mt: ty::mt { ty: ty::mk_imm_rptr(tcx, region, param), mutbl: ast::MutMutable },
+        }];
+
+        let substs = Substs::new_type(vec![env.t_int()], vec![ty::ReStatic]);
+        let fields = fields.subst(tcx, &substs);
+        assert_eq!(fields[0].name, ast::Name(1));
+        assert_eq!(fields[0].mt.mutbl, ast::MutMutable);
+        assert!(fields[0].mt.ty == env.t_rptr_static(), "{}", fields[0].mt.ty.repr(tcx));
+    })
+}
+
+#[test]
+fn subst_vtable_res() {
+    test_env("subst_vtable_res", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(100));
+        let nested: typeck::vtable_res =
+            VecPerParamSpace::new(vec![vec![typeck::vtable_static(
+                                      ast_util::local_def(102),
+                                      Substs::new_type(vec![param], vec![]),
+                                      VecPerParamSpace::empty())]],
+                                  vec![], vec![], vec![]);
+        let res: typeck::vtable_res =
+            VecPerParamSpace::new(vec![vec![typeck::vtable_static(
+                                      ast_util::local_def(101),
+                                      Substs::new_type(vec![param], vec![]),
+                                      nested)]],
+                                  vec![], vec![], vec![]);
+
+        let substs = Substs::new_type(vec![env.t_int()], vec![]);
+        let res = res.subst(tcx, &substs);
+        match res.get(subst::TypeSpace, 0)[0] {
+            typeck::vtable_static(_, ref substs, ref nested) => {
+                assert!(*substs.types.get(subst::TypeSpace, 0) == env.t_int());
+                match nested.get(subst::TypeSpace, 0)[0] {
+                    typeck::vtable_static(_, ref substs, _) => {
+                        assert!(*substs.types.get(subst::TypeSpace, 0) == env.t_int());
+                    }
+                    _ => panic!("expected a static vtable")
+                }
+            }
+            _ => panic!("expected a static vtable")
+        }
+    })
+}
+
+#[test]
+fn erase_regions_in_fn_types() {
+    test_env("erase_regions_in_fn_types", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+
+        // fn(&'a int) -> &'a int, with 'a bound by the fn type itself.
+        let t = env.t_fn(1, [env.t_rptr_late_bound(1, 0)], env.t_rptr_late_bound(1, 0));
+        assert!(ty_fold::erase_regions(tcx, t) == t, "{}", t.repr(tcx));
+
+        // fn(&'f int) -> &'s int, with a free and a scope region.
+        let t = env.t_fn(1, [env.t_rptr_free(0, 1)], env.t_rptr_scope(2));
+        let expected = env.t_fn(1, [env.t_rptr_static()], env.t_rptr_static());
+        let erased = ty_fold::erase_regions(tcx, t);
+        assert!(erased == expected, "{}", erased.repr(tcx));
+
+        // A late-bound region whose binder is not in view is kept as well.
+        let t = env.t_rptr_late_bound(9, 0);
+        assert!(ty_fold::erase_regions(tcx, t) == t, "{}", t.repr(tcx));
+    })
+}
+
+#[test]
+fn erase_regions_in_trait_objects() {
+    test_env("erase_regions_in_trait_objects", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let object = |t: ty::t, r: ty::Region| {
+            ty::mk_trait(tcx,
+                         ty::TraitRef { def_id: ast_util::local_def(30),
+                                        substs: Substs::new_type(vec![t], vec![r]),
+                                        binder_id: ast::DUMMY_NODE_ID },
+                         ty::region_existential_bound(r))
+        };
+
+        let t = object(env.t_rptr_scope(1), ty::ReScope(1));
+        let erased = ty_fold::erase_regions(tcx, t);
+        let expected = object(env.t_rptr_static(), ty::ReStatic);
+        assert!(erased == expected, "{}", erased.repr(tcx));
+    })
+}
+
+#[test]
+fn erase_regions_in_nested_closures() {
+    test_env("erase_regions_in_nested_closures", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let closure = |binder_id: ast::NodeId, inputs: &[ty::t], r: ty::Region| {
+            ty::mk_closure(tcx, ty::ClosureTy {
+                fn_style: ast::NormalFn,
+                onceness: ast::Many,
+                store: ty::RegionTraitStore(r, ast::MutMutable),
+                bounds: ty::region_existential_bound(r),
+                sig: ty::ty_fn_sig(ty::mk_ctor_fn(tcx, binder_id, inputs, ty::mk_nil())),
+                abi: abi::Rust,
+            })
+        };
+
+        // |&'a int, |&'b int, &'a int|: 's|: 's, where 'a is bound by the
+        // outer closure and 'b by the inner one.
+        let inner = closure(2, [env.t_rptr_late_bound(2, 0), env.t_rptr_late_bound(1, 0)],
+                            ty::ReScope(3));
+        let outer = closure(1, [env.t_rptr_late_bound(1, 0), inner], ty::ReScope(3));
+
+        let inner_erased = closure(2, [env.t_rptr_late_bound(2, 0),
+                                       env.t_rptr_late_bound(1, 0)],
+                                   ty::ReStatic);
+        let expected = closure(1, [env.t_rptr_late_bound(1, 0), inner_erased], ty::ReStatic);
+        let erased = ty_fold::erase_regions(tcx, outer);
+        assert!(erased == expected, "{}", erased.repr(tcx));
+
+        // Only the scope regions are free: the region folder sees each
+        // closure's store and bound, and none of the late-bound regions.
+        let mut seen = Vec::new();
+        ty_fold::RegionFolder::regions(tcx, |r, _| { seen.push(r); r }).fold_ty(outer);
+        assert_eq!(seen.len(), 4);
+        assert!(seen.iter().all(|r| *r == ty::ReScope(3)), "{}", seen.repr(tcx));
+    })
+}
+
+#[test]
+fn subst_params_of_every_space() {
+    test_env("subst_params_of_every_space", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let param = |space: subst::ParamSpace, idx: uint| {
+            ty::mk_param(tcx, space, idx, ast_util::local_def(100))
+        };
+        let early = ty::ReEarlyBound(101, subst::FnSpace, 0, ast::Name(0));
+        let t = ty::mk_tup(tcx, vec![param(subst::TypeSpace, 0),
+                                     param(subst::SelfSpace, 0),
+                                     param(subst::AssocSpace, 0),
+                                     param(subst::FnSpace, 0),
+                                     ty::mk_imm_rptr(tcx, early, param(subst::FnSpace, 0)),
+                                     env.t_rptr_late_bound(1, 0)]);
+
+        let substs = Substs::new(VecPerParamSpace::new(vec![env.t_int()],
+                                                       vec![ty::mk_bool()],
+                                                       vec![ty::mk_nil()],
+                                                       vec![ty::mk_uint()]),
+                                 VecPerParamSpace::new(vec![], vec![], vec![],
+                                                       vec![ty::ReStatic]));
+        let expected = ty::mk_tup(tcx, vec![env.t_int(),
+                                            ty::mk_bool(),
+                                            ty::mk_nil(),
+                                            ty::mk_uint(),
+                                            ty::mk_imm_rptr(tcx, ty::ReStatic, ty::mk_uint()),
+                                            env.t_rptr_late_bound(1, 0)]);
+        let substituted = t.subst(tcx, &substs);
+        assert!(substituted == expected, "{}", substituted.repr(tcx));
+
+        // Substituting a type that names no parameters gives it back.
+        assert!(expected.subst(tcx, &substs) == expected);
+
+        // So does an empty substitution, on a type with no parameters.
+        assert!(expected.subst(tcx, &Substs::empty()) == expected);
+    })
+}
+
+#[test]
+fn region_folder_skips_trait_ref_bound_regions() {
+    test_env("region_folder_skips_trait_ref_bound_regions", EMPTY_SOURCE_STR, errors([]), |env| {
+        // Box<Foo(&'a int, &'b int) + 's>, where 'a is bound by the trait
+        // reference (binder 40) and 'b by an enclosing fn type (binder 1).
+        let tcx = env.infcx.tcx;
+        let inputs = ty::mk_tup(tcx, vec![env.t_rptr_late_bound(40, 0),
+                                          env.t_rptr_late_bound(1, 0)]);
+        let trait_ref = ty::TraitRef { def_id: ast_util::local_def(30),
+                                       substs: Substs::new_type(vec![inputs], vec![]),
+                                       binder_id: 40 };
+        let object = ty::mk_trait(tcx, trait_ref.clone(),
+                                  ty::region_existential_bound(ty::ReScope(2)));
+
+        let mut seen = Vec::new();
+        ty_fold::RegionFolder::regions(tcx, |r, _| { seen.push(r); r }).fold_ty(object);
+        assert!(seen == vec![ty::ReLateBound(1, ty::BrAnon(0)), ty::ReScope(2)],
+                "{}", seen.repr(tcx));
+
+        let escaping = ty_fold::escaping_region_binders(tcx, &trait_ref);
+        assert!(escaping.contains(&1) && !escaping.contains(&40));
+    })
+}
+
+#[test]
+fn region_folder_enters_nested_trait_ref_binders_once() {
+    test_env("region_folder_enters_nested_trait_ref_binders_once",
+             EMPTY_SOURCE_STR, errors([]), |env| {
+        // Box<Foo(Box<Foo(&'a int, &'b int, &'s int)>)>, where the outer
+        // trait reference (binder 41) binds 'a and the inner one (binder
+        // 40) binds 'b: only 's is free, within both binders.
+        let tcx = env.infcx.tcx;
+        let object = |binder_id: ast::NodeId, inputs: Vec<ty::t>| {
+            let trait_ref = ty::TraitRef {
+                def_id: ast_util::local_def(30),
+                substs: Substs::new_type(vec![ty::mk_tup(tcx, inputs)], vec![]),
+                binder_id: binder_id,
+            };
+            ty::mk_trait(tcx, trait_ref, ty::region_existential_bound(ty::ReStatic))
+        };
+        let inner = object(40, vec![env.t_rptr_late_bound(41, 0),
+                                    env.t_rptr_late_bound(40, 0),
+                                    env.t_rptr_scope(3)]);
+        let outer = object(41, vec![inner]);
+
+        let mut seen = Vec::new();
+        ty_fold::fold_regions(tcx, &outer, |r, depth| { seen.push((r, depth)); r });
+        assert!(seen == vec![(ty::ReScope(3), 2), (ty::ReStatic, 1), (ty::ReStatic, 0)],
+                "{}", seen);
+        assert!(!ty_fold::has_escaping_regions(tcx, &outer));
+        assert!(ty_fold::escaping_region_binders(tcx, &inner).contains(&41));
+    })
+}
+
+#[test]
+fn fold_regions_in_substs_and_trait_refs() {
+    test_env("fold_regions_in_substs_and_trait_refs", EMPTY_SOURCE_STR, errors([]), |env| {
+        // <&'s int, fn(&'a int, &'b int)> with regions ['s], where 'a is
+        // bound by the fn type (binder 1) and 'b by nothing within.
+        let tcx = env.infcx.tcx;
+        let fn_ty = env.t_fn(1, [env.t_rptr_late_bound(1, 0), env.t_rptr_late_bound(2, 0)],
+                             ty::mk_nil());
+        let substs = Substs::new_type(vec![env.t_rptr_scope(3), fn_ty], vec![ty::ReScope(3)]);
+
+        let mut seen = Vec::new();
+        let folded = ty_fold::fold_regions(tcx, &substs, |r, depth| {
+            seen.push((r, depth));
+            ty::ReStatic
+        });
+        assert!(seen == vec![(ty::ReScope(3), 0),
+                             (ty::ReScope(3), 0),
+                             (ty::ReLateBound(2, ty::BrAnon(0)), 1)],
+                "{}", seen);
+        let static_fn = env.t_fn(1, [env.t_rptr_late_bound(1, 0), env.t_rptr_static()],
+                                 ty::mk_nil());
+        assert!(folded == Substs::new_type(vec![env.t_rptr_static(), static_fn],
+                                           vec![ty::ReStatic]));
+
+        // The same substs as those of a trait reference that binds 'b.
+        let trait_ref = ty::TraitRef { def_id: ast_util::local_def(30),
+                                       substs: substs,
+                                       binder_id: 2 };
+        let mut seen = Vec::new();
+        ty_fold::fold_regions(tcx, &trait_ref, |r, depth| { seen.push((r, depth)); r });
+        assert!(seen == vec![(ty::ReScope(3), 1), (ty::ReScope(3), 1)], "{}", seen);
+    })
+}
+
+#[test]
+fn region_folder_reports_binder_depth() {
+    test_env("region_folder_reports_binder_depth", EMPTY_SOURCE_STR, errors([]), |env| {
+        // (&'f int, fn(&'f int, fn(&'x int))), where 'f is free and 'x is
+        // bound by a binder outside of the type: each occurrence is seen
+        // at the depth of the fn types around it.
+        let tcx = env.infcx.tcx;
+        let innermost = env.t_fn(2, [env.t_rptr_late_bound(9, 0)], ty::mk_nil());
+        let inner = env.t_fn(1, [env.t_rptr_free(0, 1), innermost], ty::mk_nil());
+        let t = ty::mk_tup(tcx, vec![env.t_rptr_free(0, 1), inner]);
+
+        let free = ty::ReFree(ty::FreeRegion { scope_id: 0, bound_region: ty::BrAnon(1) });
+        let mut seen = Vec::new();
+        ty_fold::RegionFolder::regions(tcx, |r, depth| { seen.push((r, depth)); r }).fold_ty(t);
+        assert!(seen == vec![(free, 0), (free, 1), (ty::ReLateBound(9, ty::BrAnon(0)), 2)],
+                "{}", seen);
+
+        // Depths are relative to the value being folded: within the
+        // signature of the outer fn, its own binder is not counted.
+        let mut seen = Vec::new();
+        ty_fold::fold_regions(tcx, &ty::ty_fn_sig(inner), |r, depth| {
+            seen.push((r, depth));
+            r
+        });
+        assert!(seen == vec![(free, 0), (ty::ReLateBound(9, ty::BrAnon(0)), 1)], "{}", seen);
+    })
+}
+
+#[test]
+fn verify_fold_rejects_types_of_another_context() {
+    let mut options = config::basic_options();
+    options.debugging_opts |= config::VERIFY_FOLD;
+    test_env_with_options(options, "verify_fold_rejects_types_of_another_context",
+                          EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let t = ty::mk_tup(tcx, vec![env.t_int(), ty::mk_bool()]);
+        assert!(ty::type_is_interned_in(tcx, t));
+
+        // Folding within one context passes the check.
+        let mut identity = ty_fold::BottomUpFolder::new(tcx, |t| t);
+        assert!(t.fold_with(&mut identity) == t);
+
+        // A type from another context is what the check reports, while
+        // the primitives are shared by every context.
+        test_env("other_context", EMPTY_SOURCE_STR, errors([]), |other| {
+            let foreign = ty::mk_tup(other.infcx.tcx, vec![other.t_int(), ty::mk_bool()]);
+            assert!(ty::type_is_interned_in(other.infcx.tcx, foreign));
+            assert!(!ty::type_is_interned_in(tcx, foreign));
+            assert!(ty::type_is_interned_in(tcx, other.t_int()));
+        })
+    })
+}
+
+#[test]
+fn fold_vecs_in_place() {
+    test_env("fold_vecs_in_place", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let mut tys = vec![env.t_rptr_scope(1), env.t_int(), env.t_rptr_free(0, 1)];
+        let expected = ty_fold::erase_regions(tcx, tys.clone());
+        let ptr = tys.as_ptr();
+        ty_fold::fold_vec_in_place(&mut ty_fold::RegionEraser::new(tcx), &mut tys);
+        assert!(tys == expected, "{}", tys.repr(tcx));
+        assert!(tys.as_ptr() == ptr);
+
+        let mut substs = Substs::new_type(vec![env.t_rptr_scope(1), ty::mk_bool()],
+                                          vec![ty::ReStatic]);
+        let expected = ty_fold::erase_regions(tcx, substs.types.clone());
+        let ptr = substs.types.as_slice().as_ptr();
+        ty_fold::fold_params_in_place(&mut ty_fold::RegionEraser::new(tcx), &mut substs.types);
+        assert!(substs.types == expected, "{}", substs.types.repr(tcx));
+        assert!(substs.types.as_slice().as_ptr() == ptr);
+    })
+}
+
+#[test]
+fn erase_regions_reports_change() {
+    test_env("erase_regions_reports_change", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+
+        // A struct type without regions is left alone.
+        let t = ty::mk_struct(tcx, ast_util::local_def(100),
+                              Substs::new_type(vec![env.t_int()], vec![]));
+        let (erased, changed) = ty_fold::erase_regions_changed(tcx, t);
+        assert!(erased == t);
+        assert!(!changed);
+
+        // &'a int becomes &'static int.
+        let t = env.t_rptr_free(0, 1);
+        let (erased, changed) = ty_fold::erase_regions_changed(tcx, t);
+        assert!(erased == env.t_rptr_static(), "{}", erased.repr(tcx));
+        assert!(changed);
+
+        // The second time around the result comes from the cache, and
+        // the change is still reported.
+        let (_, changed) = ty_fold::erase_regions_changed(tcx, t);
+        assert!(changed);
+
+        // &'static int and a late-bound region are already erased.
+        let (_, changed) = ty_fold::erase_regions_changed(tcx, env.t_rptr_static());
+        assert!(!changed);
+        let (_, changed) = ty_fold::erase_regions_changed(tcx, env.t_rptr_late_bound(1, 0));
+        assert!(!changed);
+    })
+}
+
+#[test]
+fn replace_late_bound_regions_of_one_binder() {
+    test_env("replace_late_bound_regions_of_one_binder", EMPTY_SOURCE_STR, errors([]), |env| {
+        // (&'1.0 int, &'1.0 int, &'1.1 int, fn<'2>(&'2.0 int)): only the
+        // regions of binder 1 are replaced, each by one call to `map_fn`.
+        let tcx = env.infcx.tcx;
+        let t = ty::mk_tup(tcx, vec![env.t_rptr_late_bound(1, 0),
+                                     env.t_rptr_late_bound(1, 0),
+                                     env.t_rptr_late_bound(1, 1),
+                                     env.t_fn(2, [env.t_rptr_late_bound(2, 0)], env.t_int())]);
+        let mut calls = 0u;
+        let (replaced, map) = ty_fold::replace_late_bound_regions(tcx, 1, &t, |br| {
+            calls += 1;
+            match br {
+                ty::BrAnon(id) => ty::ReScope(id as ast::NodeId + 10),
+                _ => panic!("unexpected bound region {}", br)
+            }
+        });
+
+        let expected = ty::mk_tup(tcx, vec![env.t_rptr_scope(10),
+                                            env.t_rptr_scope(10),
+                                            env.t_rptr_scope(11),
+                                            env.t_fn(2, [env.t_rptr_late_bound(2, 0)],
+                                                     env.t_int())]);
+        assert!(replaced == expected, "{}", replaced.repr(tcx));
+        assert_eq!(calls, 2);
+        assert_eq!(map.len(), 2);
+        assert!(map.get(&ty::BrAnon(0)) == Some(&ty::ReScope(10)));
+        assert!(map.get(&ty::BrAnon(1)) == Some(&ty::ReScope(11)));
+    })
+}
+
+#[test]
+fn leaf_types_bypass_fold_sty() {
+    test_env("leaf_types_bypass_fold_sty", EMPTY_SOURCE_STR, errors([]), |env| {
+        // (int, Box<bool>, T): only the tuple and the box have components.
+        let tcx = env.infcx.tcx;
+        let param = ty::mk_param(tcx, subst::TypeSpace, 0, ast_util::local_def(100));
+        let t = ty::mk_tup(tcx, vec![env.t_int(), ty::mk_uniq(tcx, ty::mk_bool()), param]);
+        let mut folder = StyCountFolder { tcx: tcx, stys: 0 };
+        assert!(t.fold_with(&mut folder) == t);
+        assert_eq!(folder.stys, 2);
+    })
+}
+
+#[test]
+fn subst_out_of_range_message() {
+    test_env("subst_out_of_range_message", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let def = ty::TypeParameterDef {
+            name: token::intern("T"),
+            def_id: ast_util::local_def(100),
+            space: subst::FnSpace,
+            index: 2,
+            associated_with: None,
+            bounds: ty::ParamBounds {
+                region_bounds: Vec::new(),
+                builtin_bounds: ty::empty_builtin_bounds(),
+                trait_bounds: Vec::new(),
+            },
+            default: None,
+        };
+        tcx.ty_param_defs.borrow_mut().insert(100, def);
+        let param = ty::mk_param(tcx, subst::FnSpace, 2, ast_util::local_def(100));
+        let t = ty::mk_uniq(tcx, param);
+
+        let substs = Substs::new(VecPerParamSpace::new(vec![], vec![], vec![],
+                                                       vec![env.t_int()]),
+                                 VecPerParamSpace::empty());
+        let err = match t.try_subst(tcx, &substs) {
+            Err(err) => err,
+            Ok(t) => panic!("substituted to {}", t.repr(tcx)),
+        };
+        assert_eq!(err.repr(tcx).as_slice(),
+                   "type parameter `T` (index 2 in FnSpace) out of range when substituting \
+                    into `Box<T>` (had 1 FnSpace parameter)");
+
+        // An early-bound region of the type space, with no regions given.
+        let early = ty::ReEarlyBound(101, subst::TypeSpace, 0, token::intern("'a"));
+        let t = ty::mk_imm_rptr(tcx, early, env.t_int());
+        let err = match t.try_subst(tcx, &substs) {
+            Err(err) => err,
+            Ok(t) => panic!("substituted to {}", t.repr(tcx)),
+        };
+        assert_eq!(err.repr(tcx).as_slice(),
+                   "region parameter `'a` (index 0 in TypeSpace) out of range when \
+                    substituting into `&'a int` (had 0 TypeSpace parameters)");
+    })
+}
+
+#[test]
+fn vec_per_param_space_enumerated() {
+    // The self and assoc spaces are empty.
+    let mut v = VecPerParamSpace::new(vec!['a', 'b'], vec![], vec![], vec!['c']);
+    let items: Vec<(subst::ParamSpace, uint, char)> =
+        v.iter_enumerated().map(|(space, index, &c)| (space, index, c)).collect();
+    assert_eq!(items, vec![(subst::TypeSpace, 0, 'a'),
+                           (subst::TypeSpace, 1, 'b'),
+                           (subst::FnSpace, 0, 'c')]);
+
+    let mapped = v.map_enumerated(|space, index, &c| (space, index, c));
+    assert_eq!(mapped.get_slice(subst::TypeSpace),
+               [(subst::TypeSpace, 0, 'a'), (subst::TypeSpace, 1, 'b')].as_slice());
+    assert!(mapped.is_empty_in(subst::SelfSpace));
+    assert!(mapped.is_empty_in(subst::AssocSpace));
+    assert_eq!(*mapped.get(subst::FnSpace, 0), (subst::FnSpace, 0, 'c'));
+
+    *v.get_mut(subst::FnSpace, 0) = 'd';
+    assert_eq!(*v.get(subst::FnSpace, 0), 'd');
+
+    // With every space empty, there is nothing to enumerate.
+    let empty: VecPerParamSpace<char> = VecPerParamSpace::empty();
+    assert!(empty.iter_enumerated().next().is_none());
+    assert!(empty.map_enumerated(|_, _, &c| c) == empty);
+}
+
+#[test]
+fn fold_empty_substs() {
+    test_env("fold_empty_substs", EMPTY_SOURCE_STR, errors([]), |env| {
+        let tcx = env.infcx.tcx;
+        let mut folder = FoldCountFolder { tcx: tcx, folds: 0 };
+
+        // Empty substs, with or without erased regions, come back as
+        // they are without reaching the folder.
+        for substs in [Substs::empty(), Substs::trans_empty()].iter() {
+            assert!(substs.is_empty());
+            assert!(substs.fold_with(&mut folder) == *substs);
+        }
+        assert_eq!(folder.folds, 0);
+
+        // Substs with one type and one region are folded as before.
+        let substs = Substs::new_type(vec![env.t_int()], vec![ty::ReStatic]);
+        assert!(!substs.is_empty());
+        assert!(substs.fold_with(&mut folder) == substs);
+        assert_eq!(folder.folds, 2);
+
+        // Erasing the regions of the substs drops the region values but
+        // not the regions within the types.
+        let substs = Substs::new_type(vec![env.t_rptr_scope(1)], vec![ty::ReScope(2)]);
+        let erased = substs.erase_regions();
+        assert!(erased == Substs::erased(substs.types.clone()), "{}", erased.repr(tcx));
+    })
+}
+
+#[test]
+fn substs_for_defs() {
+    test_env("substs_for_defs", EMPTY_SOURCE_STR, errors([]), |env| {
+        let type_def = |space: subst::ParamSpace, index: uint| ty::TypeParameterDef {
+            name: token::intern("T"),
+            def_id: ast_util::local_def(100),
+            space: space,
+            index: index,
+            associated_with: None,
+            bounds: ty::ParamBounds {
+                region_bounds: Vec::new(),
+                builtin_bounds: ty::empty_builtin_bounds(),
+                trait_bounds: Vec::new(),
+            },
+            default: None,
+        };
+        let region_def = |space: subst::ParamSpace, index: uint| ty::RegionParameterDef {
+            name: token::intern("'a"),
+            def_id: ast_util::local_def(101),
+            space: space,
+            index: index,
+            bounds: Vec::new(),
+        };
+
+        let substs = Substs::new(VecPerParamSpace::new(vec![env.t_int()],
+                                                       vec![ty::mk_bool()],
+                                                       vec![],
+                                                       vec![ty::mk_nil(), ty::mk_uint()]),
+                                 VecPerParamSpace::new(vec![ty::ReScope(1)],
+                                                       vec![],
+                                                       vec![],
+                                                       vec![ty::ReStatic]));
+        assert!(substs.type_for_def(&type_def(subst::TypeSpace, 0)) == env.t_int());
+        assert!(substs.type_for_def(&type_def(subst::SelfSpace, 0)) == ty::mk_bool());
+        assert!(substs.type_for_def(&type_def(subst::FnSpace, 1)) == ty::mk_uint());
+        assert!(substs.opt_type_for_def(&type_def(subst::FnSpace, 2)).is_none());
+        assert!(substs.opt_type_for_def(&type_def(subst::AssocSpace, 0)).is_none());
+
+        assert!(substs.region_for_def(&region_def(subst::TypeSpace, 0)) == ty::ReScope(1));
+        assert!(substs.region_for_def(&region_def(subst::FnSpace, 0)) == ty::ReStatic);
+        assert!(substs.opt_region_for_def(&region_def(subst::SelfSpace, 0)).is_none());
+
+        // With the regions erased, every region parameter is 'static.
+        let erased = substs.erase_regions();
+        assert!(erased.region_for_def(&region_def(subst::TypeSpace, 0)) == ty::ReStatic);
+        assert!(erased.opt_region_for_def(&region_def(subst::SelfSpace, 3)) ==
+                Some(ty::ReStatic));
+    })
+}
+
+#[test]
+#[should_fail]
+fn substs_type_for_missing_def() {
+    let def = ty::TypeParameterDef {
+        name: token::intern("T"),
+        def_id: ast_util::local_def(100),
+        space: subst::FnSpace,
+        index: 0,
+        associated_with: None,
+        bounds: ty::ParamBounds {
+            region_bounds: Vec::new(),
+            builtin_bounds: ty::empty_builtin_bounds(),
+            trait_bounds: Vec::new(),
+        },
+        default: None,
+    };
+    Substs::empty().type_for_def(&def);
+}
+
+#[test]
+fn vec_per_param_space_edit_one_space() {
+    let v = VecPerParamSpace::new(vec![1u, 2], vec![3], vec![4, 5], vec![6, 7, 8]);
+
+    // Truncating and popping the fn space leaves the others alone.
+    let mut w = v.clone();
+    w.truncate(subst::FnSpace, 1);
+    assert_eq!(w.get_slice(subst::FnSpace), [6u].as_slice());
+    assert_eq!(w.pop(subst::FnSpace), Some(6));
+    assert_eq!(w.pop(subst::FnSpace), None);
+    assert_eq!(w.get_slice(subst::TypeSpace), v.get_slice(subst::TypeSpace));
+    assert_eq!(w.get_slice(subst::SelfSpace), v.get_slice(subst::SelfSpace));
+    assert_eq!(w.get_slice(subst::AssocSpace), v.get_slice(subst::AssocSpace));
+
+    // Pushing onto it and truncating back gives the original.
+    w.push(subst::FnSpace, 9);
+    w.truncate(subst::FnSpace, 0);
+    for &x in v.get_slice(subst::FnSpace).iter() {
+        w.push(subst::FnSpace, x);
+    }
+    assert!(w == v);
+
+    // Truncating the type space moves the later spaces down intact.
+    let mut w = v.clone();
+    w.truncate(subst::TypeSpace, 0);
+    assert_eq!(w.len(subst::TypeSpace), 0);
+    assert_eq!(w.get_slice(subst::SelfSpace), [3u].as_slice());
+    assert_eq!(w.get_slice(subst::AssocSpace), [4u, 5].as_slice());
+    assert_eq!(w.get_slice(subst::FnSpace), [6u, 7, 8].as_slice());
+
+    // Retaining within one space, including removing all of it.
+    let mut w = v.clone();
+    w.retain(subst::AssocSpace, |&x| x != 4);
+    w.retain(subst::SelfSpace, |_| false);
+    w.retain(subst::FnSpace, |&x| x % 2 == 0);
+    assert_eq!(w.get_slice(subst::TypeSpace), [1u, 2].as_slice());
+    assert!(w.is_empty_in(subst::SelfSpace));
+    assert_eq!(w.get_slice(subst::AssocSpace), [5u].as_slice());
+    assert_eq!(w.get_slice(subst::FnSpace), [6u, 8].as_slice());
+}
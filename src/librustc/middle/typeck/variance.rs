@@ -863,7 +863,7 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                 self.declared_variance(p.def_id, def_id, TypeParam,
                                        p.space, p.index);
             let variance_i = self.xform(variance, variance_decl);
-            let substs_ty = *substs.types.get(p.space, p.index);
+            let substs_ty = substs.type_for_def(p);
             self.add_constraints_from_ty(substs_ty, variance_i);
         }
 
@@ -872,7 +872,7 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                 self.declared_variance(p.def_id, def_id,
                                        RegionParam, p.space, p.index);
             let variance_i = self.xform(variance, variance_decl);
-            let substs_r = *substs.regions().get(p.space, p.index);
+            let substs_r = substs.region_for_def(p);
             self.add_constraints_from_region(substs_r, variance_i);
         }
     }
@@ -1312,7 +1312,9 @@ impl Clean<Type> for ty::t {
             }
             ty::ty_struct(did, ref substs) |
             ty::ty_enum(did, ref substs) |
-            ty::ty_trait(box ty::TyTrait { principal: ty::TraitRef { def_id: did, ref substs },
+            ty::ty_trait(box ty::TyTrait { principal: ty::TraitRef { def_id: did,
+                                                                     ref substs,
+                                                                     .. },
                                            .. }) => {
                 let fqn = csearch::get_item_path(cx.tcx(), did);
                 let fqn: Vec<String> = fqn.into_iter().map(|i| {
@@ -2075,6 +2077,8 @@ fn name_from_pat(p: &ast::Pat) -> String {
                               which is not allowed in function arguments"),
         PatVec(..) => panic!("tried to get argument name from pat_vec, \
                              which is not allowed in function arguments"),
+        PatOr(..) => panic!("tried to get argument name from PatOr, \
+                            which is not allowed in function arguments"),
         PatMac(..) => {
             warn!("can't document the name of a function argument \
                    produced by a pattern macro");
@@ -444,6 +444,15 @@ pub enum PatWildKind {
     PatWildMulti,
 }
 
+#[deriving(Clone, PartialEq, Eq, Encodable, Decodable, Hash, Show)]
+pub enum RangeEnd {
+    /// The upper bound is part of the range, as in `1...5`
+    RangeIncluded,
+
+    /// The upper bound is not part of the range, as in `1..5`
+    RangeExcluded,
+}
+
 #[deriving(Clone, PartialEq, Eq, Encodable, Decodable, Hash, Show)]
 pub enum Pat_ {
     /// Represents a wildcard pattern (either `_` or `..`)
@@ -466,10 +475,14 @@ pub enum Pat_ {
     PatBox(P<Pat>),
     PatRegion(P<Pat>), // reference pattern
     PatLit(P<Expr>),
-    PatRange(P<Expr>, P<Expr>),
+    PatRange(P<Expr>, P<Expr>, RangeEnd),
     /// [a, b, ..i, y, z] is represented as:
     ///     PatVec(box [a, b], Some(i), box [y, z])
     PatVec(Vec<P<Pat>>, Option<P<Pat>>, Vec<P<Pat>>),
+    /// An or-pattern nested in another pattern, like the `1 | 2` in
+    /// `Some(1 | 2)`. The alternatives at the top of a match arm are
+    /// kept in `Arm::pats` instead.
+    PatOr(Vec<P<Pat>>),
     PatMac(Mac),
 }
 
@@ -603,7 +603,7 @@ pub fn walk_pat(pat: &Pat, it: |&Pat| -> bool) -> bool {
         PatStruct(_, ref fields, _) => {
             fields.iter().all(|field| walk_pat(&*field.node.pat, |p| it(p)))
         }
-        PatEnum(_, Some(ref s)) | PatTup(ref s) => {
+        PatEnum(_, Some(ref s)) | PatTup(ref s) | PatOr(ref s) => {
             s.iter().all(|p| walk_pat(&**p, |p| it(p)))
         }
         PatBox(ref s) | PatRegion(ref s) => {
@@ -615,7 +615,7 @@ pub fn walk_pat(pat: &Pat, it: |&Pat| -> bool) -> bool {
             after.iter().all(|p| walk_pat(&**p, |p| it(p)))
         }
         PatMac(_) => panic!("attempted to analyze unexpanded pattern"),
-        PatWild(_) | PatLit(_) | PatRange(_, _) | PatIdent(_, _, _) |
+        PatWild(_) | PatLit(_) | PatRange(..) | PatIdent(_, _, _) |
         PatEnum(_, _) => {
             true
         }
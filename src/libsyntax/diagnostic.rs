@@ -8,11 +8,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use codemap::{Pos, Span};
+use codemap::{BytePos, Pos, Span};
 use codemap;
 use diagnostics;
 
 use std::cell::{RefCell, Cell};
+use std::collections::HashSet;
 use std::fmt;
 use std::io;
 use std::iter::range;
@@ -23,6 +24,10 @@ use term;
 /// maximum number of lines we will print for each error; arbitrary.
 static MAX_LINES: uint = 6u;
 
+/// maximum number of lines between a labeled span and the primary span of
+/// a diagnostic for the two to be shown in the same snippet; arbitrary.
+static MAX_LABEL_DISTANCE: uint = 4u;
+
 #[deriving(Clone)]
 pub enum RenderSpan {
     /// A FullSpan renders with both with an initial line for the
@@ -33,18 +38,23 @@ pub enum RenderSpan {
     /// A FileLine renders with just a line for the message prefixed
     /// by file:linenum.
     FileLine(Span),
+
+    /// A Suggestion renders with a line for the message, which ends with
+    /// the suggested replacement for the span, followed by the source
+    /// code covered by the span as it reads with the replacement made.
+    Suggestion(Span, String),
 }
 
 impl RenderSpan {
-    fn span(self) -> Span {
-        match self {
-            FullSpan(s) | FileLine(s) => s
+    fn span(&self) -> Span {
+        match *self {
+            FullSpan(s) | FileLine(s) | Suggestion(s, _) => s
         }
     }
     fn is_full_span(&self) -> bool {
         match self {
             &FullSpan(..) => true,
-            &FileLine(..) => false,
+            &FileLine(..) | &Suggestion(..) => false,
         }
     }
 }
@@ -56,11 +66,32 @@ pub enum ColorConfig {
     Never
 }
 
+/// How diagnostics are written out.
+#[deriving(Clone, PartialEq)]
+pub enum ErrorFormat {
+    /// As text for people to read, with snippets of the source.
+    HumanReadable,
+    /// As one JSON object per diagnostic, for tools to read.
+    Json,
+}
+
 pub trait Emitter {
     fn emit(&mut self, cmsp: Option<(&codemap::CodeMap, Span)>,
             msg: &str, code: Option<&str>, lvl: Level);
     fn custom_emit(&mut self, cm: &codemap::CodeMap,
                    sp: RenderSpan, msg: &str, lvl: Level);
+
+    /// Emits a message at `sp` that also points at each of the `labels`
+    /// spans with a short description. By default, the labels are emitted
+    /// as notes following the message.
+    fn emit_with_labels(&mut self, cm: &codemap::CodeMap, sp: Span,
+                        labels: &[(Span, String)], msg: &str,
+                        code: Option<&str>, lvl: Level) {
+        self.emit(Some((cm, sp)), msg, code, lvl);
+        for &(label_sp, ref label) in labels.iter() {
+            self.emit(Some((cm, label_sp)), label.as_slice(), None, Note);
+        }
+    }
 }
 
 /// This structure is used to signify that a task has panicked with a fatal error
@@ -93,6 +124,17 @@ impl SpanHandler {
         self.handler.emit_with_code(Some((&self.cm, sp)), msg, code, Error);
         self.handler.bump_err_count();
     }
+    /// Reports an error at `sp` that also points at each of the `labels`
+    /// spans, described by its label.
+    pub fn span_err_with_labels(&self, sp: Span, msg: &str, labels: &[(Span, String)]) {
+        self.handler.emit_with_labels(&self.cm, sp, labels, msg, None, Error);
+        self.handler.bump_err_count();
+    }
+    pub fn span_err_with_labels_and_code(&self, sp: Span, msg: &str,
+                                         labels: &[(Span, String)], code: &str) {
+        self.handler.emit_with_labels(&self.cm, sp, labels, msg, Some(code), Error);
+        self.handler.bump_err_count();
+    }
     pub fn span_warn(&self, sp: Span, msg: &str) {
         self.handler.emit(Some((&self.cm, sp)), msg, Warning);
     }
@@ -108,6 +150,11 @@ impl SpanHandler {
     pub fn span_help(&self, sp: Span, msg: &str) {
         self.handler.emit(Some((&self.cm, sp)), msg, Help);
     }
+    /// Suggests replacing the code covered by `sp` with `suggestion`,
+    /// printed as `help: msg `suggestion``.
+    pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String) {
+        self.handler.custom_emit(&self.cm, Suggestion(sp, suggestion), msg, Help);
+    }
     pub fn fileline_note(&self, sp: Span, msg: &str) {
         self.handler.custom_emit(&self.cm, FileLine(sp), msg, Note);
     }
@@ -126,9 +173,18 @@ impl SpanHandler {
 /// A handler deals with errors; certain errors
 /// (fatal, bug, unimpl) may cause immediate exit,
 /// others log errors for later reporting.
+///
+/// An error or warning with the same level, span and message as one
+/// already emitted is dropped, together with the notes that follow it,
+/// unless deduplication has been turned off with `set_dedup`.
 pub struct Handler {
     err_count: Cell<uint>,
     emit: RefCell<Box<Emitter + Send>>,
+    dedup: Cell<bool>,
+    emitted: RefCell<HashSet<(Level, Option<(BytePos, BytePos)>, String)>>,
+    // Whether the last error or warning was a dropped duplicate
+    in_duplicate: Cell<bool>,
+    suppressed: Cell<uint>,
 }
 
 impl Handler {
@@ -137,11 +193,42 @@ impl Handler {
         panic!(FatalError);
     }
     pub fn err(&self, msg: &str) {
-        self.emit.borrow_mut().emit(None, msg, None, Error);
+        if !self.is_duplicate(None, msg, Error) {
+            self.emit.borrow_mut().emit(None, msg, None, Error);
+        }
         self.bump_err_count();
     }
+    /// Counts an error that was just emitted. An error dropped as a
+    /// duplicate was already counted the first time round.
     pub fn bump_err_count(&self) {
-        self.err_count.set(self.err_count.get() + 1u);
+        if !self.in_duplicate.get() {
+            self.err_count.set(self.err_count.get() + 1u);
+        }
+    }
+    /// Turns deduplication of diagnostics on or off.
+    pub fn set_dedup(&self, dedup: bool) {
+        self.dedup.set(dedup);
+    }
+    fn is_duplicate(&self, sp: Option<Span>, msg: &str, lvl: Level) -> bool {
+        if !self.dedup.get() {
+            return false;
+        }
+        match lvl {
+            Note | Help => self.in_duplicate.get(),
+            Bug | Fatal => {
+                self.in_duplicate.set(false);
+                false
+            }
+            Error | Warning => {
+                let key = (lvl, sp.map(|sp| (sp.lo, sp.hi)), msg.to_string());
+                let duplicate = !self.emitted.borrow_mut().insert(key);
+                if duplicate {
+                    self.suppressed.set(self.suppressed.get() + 1);
+                }
+                self.in_duplicate.set(duplicate);
+                duplicate
+            }
+        }
     }
     pub fn err_count(&self) -> uint {
         self.err_count.get()
@@ -159,16 +246,38 @@ impl Handler {
                         self.err_count.get());
           }
         }
+        self.note_suppressed();
         self.fatal(s.as_slice());
     }
+    /// Notes how many duplicate diagnostics were dropped since the last
+    /// such note, if any were. Called when aborting on errors and when
+    /// the handler goes away, so that a compilation that succeeds
+    /// reports its dropped warnings too.
+    pub fn note_suppressed(&self) {
+        let suppressed = self.suppressed.get();
+        if suppressed > 0 {
+            // Emitted directly, as the note would otherwise be taken to
+            // belong to the last, possibly dropped, error
+            let note = format!("{} duplicate diagnostic{} suppressed",
+                               suppressed, if suppressed == 1 { "" } else { "s" });
+            self.emit.borrow_mut().emit(None, note.as_slice(), None, Note);
+            self.suppressed.set(0);
+        }
+    }
     pub fn warn(&self, msg: &str) {
-        self.emit.borrow_mut().emit(None, msg, None, Warning);
+        if !self.is_duplicate(None, msg, Warning) {
+            self.emit.borrow_mut().emit(None, msg, None, Warning);
+        }
     }
     pub fn note(&self, msg: &str) {
-        self.emit.borrow_mut().emit(None, msg, None, Note);
+        if !self.is_duplicate(None, msg, Note) {
+            self.emit.borrow_mut().emit(None, msg, None, Note);
+        }
     }
     pub fn help(&self, msg: &str) {
-        self.emit.borrow_mut().emit(None, msg, None, Help);
+        if !self.is_duplicate(None, msg, Help) {
+            self.emit.borrow_mut().emit(None, msg, None, Help);
+        }
     }
     pub fn bug(&self, msg: &str) -> ! {
         self.emit.borrow_mut().emit(None, msg, None, Bug);
@@ -181,18 +290,40 @@ impl Handler {
                 cmsp: Option<(&codemap::CodeMap, Span)>,
                 msg: &str,
                 lvl: Level) {
-        self.emit.borrow_mut().emit(cmsp, msg, None, lvl);
+        if !self.is_duplicate(cmsp.map(|(_, sp)| sp), msg, lvl) {
+            self.emit.borrow_mut().emit(cmsp, msg, None, lvl);
+        }
     }
     pub fn emit_with_code(&self,
                           cmsp: Option<(&codemap::CodeMap, Span)>,
                           msg: &str,
                           code: &str,
                           lvl: Level) {
-        self.emit.borrow_mut().emit(cmsp, msg, Some(code), lvl);
+        if !self.is_duplicate(cmsp.map(|(_, sp)| sp), msg, lvl) {
+            self.emit.borrow_mut().emit(cmsp, msg, Some(code), lvl);
+        }
     }
     pub fn custom_emit(&self, cm: &codemap::CodeMap,
                        sp: RenderSpan, msg: &str, lvl: Level) {
-        self.emit.borrow_mut().custom_emit(cm, sp, msg, lvl);
+        if !self.is_duplicate(Some(sp.span()), msg, lvl) {
+            self.emit.borrow_mut().custom_emit(cm, sp, msg, lvl);
+        }
+    }
+    pub fn emit_with_labels(&self, cm: &codemap::CodeMap, sp: Span,
+                            labels: &[(Span, String)], msg: &str,
+                            code: Option<&str>, lvl: Level) {
+        if !self.is_duplicate(Some(sp), msg, lvl) {
+            self.emit.borrow_mut().emit_with_labels(cm, sp, labels, msg, code, lvl);
+        }
+    }
+}
+
+impl Drop for Handler {
+    fn drop(&mut self) {
+        // The handler lives exactly as long as the compilation, however
+        // that ends, so this is the one place to note the duplicates
+        // dropped since the last abort.
+        self.note_suppressed();
     }
 }
 
@@ -208,14 +339,28 @@ pub fn default_handler(color_config: ColorConfig,
     mk_handler(box EmitterWriter::stderr(color_config, registry))
 }
 
+/// A handler that writes diagnostics to stderr in the given format.
+pub fn handler_for_format(error_format: ErrorFormat,
+                          color_config: ColorConfig,
+                          registry: Option<diagnostics::registry::Registry>) -> Handler {
+    match error_format {
+        HumanReadable => default_handler(color_config, registry),
+        Json => mk_handler(box diagnostics::json::JsonEmitter::stderr()),
+    }
+}
+
 pub fn mk_handler(e: Box<Emitter + Send>) -> Handler {
     Handler {
         err_count: Cell::new(0),
         emit: RefCell::new(e),
+        dedup: Cell::new(true),
+        emitted: RefCell::new(HashSet::new()),
+        in_duplicate: Cell::new(false),
+        suppressed: Cell::new(0),
     }
 }
 
-#[deriving(PartialEq, Clone)]
+#[deriving(PartialEq, Eq, Hash, Clone)]
 pub enum Level {
     Bug,
     Fatal,
@@ -291,20 +436,17 @@ fn print_diagnostic(dst: &mut EmitterWriter, topic: &str, lvl: Level,
         try!(write!(&mut dst.dst, "{} ", topic));
     }
 
+    // A message with a code is printed as `error[E0046]: msg`
+    let level = match code {
+        Some(code) => format!("{}[{}]: ", lvl.to_string(), code),
+        None => format!("{}: ", lvl.to_string()),
+    };
     try!(print_maybe_styled(dst,
-                            format!("{}: ", lvl.to_string()).as_slice(),
+                            level.as_slice(),
                             term::attr::ForegroundColor(lvl.color())));
     try!(print_maybe_styled(dst,
                             format!("{}", msg).as_slice(),
                             term::attr::Bold));
-
-    match code {
-        Some(code) => {
-            let style = term::attr::ForegroundColor(term::color::BRIGHT_MAGENTA);
-            try!(print_maybe_styled(dst, format!(" [{}]", code.clone()).as_slice(), style));
-        }
-        None => ()
-    }
     try!(dst.dst.write_char('\n'));
     Ok(())
 }
@@ -378,6 +520,15 @@ impl Emitter for EmitterWriter {
             Err(e) => panic!("failed to print diagnostics: {}", e),
         }
     }
+
+    fn emit_with_labels(&mut self, cm: &codemap::CodeMap, sp: Span,
+                        labels: &[(Span, String)], msg: &str,
+                        code: Option<&str>, lvl: Level) {
+        match emit_labeled(self, cm, sp, labels, msg, code, lvl) {
+            Ok(()) => {}
+            Err(e) => panic!("failed to print diagnostics: {}", e),
+        }
+    }
 }
 
 fn emit(dst: &mut EmitterWriter, cm: &codemap::CodeMap, rsp: RenderSpan,
@@ -385,28 +536,43 @@ fn emit(dst: &mut EmitterWriter, cm: &codemap::CodeMap, rsp: RenderSpan,
     let sp = rsp.span();
     let ss = cm.span_to_string(sp);
     let lines = cm.span_to_lines(sp);
-    if custom {
-        // we want to tell compiletest/runtest to look at the last line of the
-        // span (since `custom_highlight_lines` displays an arrow to the end of
-        // the span)
-        let span_end = Span { lo: sp.hi, hi: sp.hi, expn_id: sp.expn_id};
-        let ses = cm.span_to_string(span_end);
-        try!(print_diagnostic(dst, ses.as_slice(), lvl, msg, code));
-        if rsp.is_full_span() {
-            try!(custom_highlight_lines(dst, cm, sp, lvl, lines));
+    match rsp {
+        Suggestion(_, ref suggestion) => {
+            // Unlike other custom messages, a suggestion is reported at the
+            // start of its span, which is where the replacement begins.
+            let msg = format!("{} `{}`", msg, suggestion);
+            try!(print_diagnostic(dst, ss.as_slice(), lvl, msg.as_slice(), code));
+            try!(show_code_change(dst, cm, sp, lines, suggestion.as_slice()));
         }
-    } else {
-        try!(print_diagnostic(dst, ss.as_slice(), lvl, msg, code));
-        if rsp.is_full_span() {
-            try!(highlight_lines(dst, cm, sp, lvl, lines));
+        _ if custom => {
+            // we want to tell compiletest/runtest to look at the last line of the
+            // span (since `custom_highlight_lines` displays an arrow to the end of
+            // the span)
+            let span_end = Span { lo: sp.hi, hi: sp.hi, expn_id: sp.expn_id};
+            let ses = cm.span_to_string(span_end);
+            try!(print_diagnostic(dst, ses.as_slice(), lvl, msg, code));
+            if rsp.is_full_span() {
+                try!(custom_highlight_lines(dst, cm, sp, lvl, lines));
+            }
+        }
+        _ => {
+            try!(print_diagnostic(dst, ss.as_slice(), lvl, msg, code));
+            if rsp.is_full_span() {
+                try!(highlight_lines(dst, cm, sp, lvl, lines));
+            }
         }
     }
     try!(print_macro_backtrace(dst, cm, sp));
+    print_explain_help(dst, ss.as_slice(), code)
+}
+
+fn print_explain_help(dst: &mut EmitterWriter, topic: &str,
+                      code: Option<&str>) -> io::IoResult<()> {
     match code {
         Some(code) =>
             match dst.registry.as_ref().and_then(|registry| registry.find_description(code)) {
                 Some(_) => {
-                    try!(print_diagnostic(dst, ss.as_slice(), Help,
+                    try!(print_diagnostic(dst, topic, Help,
                                           format!("pass `--explain {}` to see a detailed \
                                                    explanation", code).as_slice(), None));
                 }
@@ -417,6 +583,109 @@ fn emit(dst: &mut EmitterWriter, cm: &codemap::CodeMap, rsp: RenderSpan,
     Ok(())
 }
 
+/// Prints a message with labeled secondary spans. The labels of spans on
+/// lines close to the primary span are printed under their spans, in one
+/// snippet with it; those further away are referred to by location only,
+/// as notes.
+fn emit_labeled(dst: &mut EmitterWriter, cm: &codemap::CodeMap, sp: Span,
+                labels: &[(Span, String)], msg: &str, code: Option<&str>,
+                lvl: Level) -> io::IoResult<()> {
+    let ss = cm.span_to_string(sp);
+    try!(print_diagnostic(dst, ss.as_slice(), lvl, msg, code));
+
+    let lines = cm.span_to_lines(sp);
+    let (near, far) = if lines.lines.len() == 1 {
+        let line = lines.lines[0];
+        labels.iter().collect::<Vec<&(Span, String)>>().partition(|&&(label_sp, _)| {
+            let label_lines = cm.span_to_lines(label_sp);
+            label_lines.file.name == lines.file.name &&
+                label_lines.lines.len() == 1 &&
+                line_distance(label_lines.lines[0], line) <= MAX_LABEL_DISTANCE
+        })
+    } else {
+        (Vec::new(), labels.iter().collect())
+    };
+    if near.is_empty() {
+        try!(highlight_lines(dst, cm, sp, lvl, lines));
+    } else {
+        let mut marks = vec![(sp, None)];
+        marks.extend(near.into_iter().map(|&(label_sp, ref label)| {
+            (label_sp, Some(label.as_slice()))
+        }));
+        try!(highlight_labeled_lines(dst, cm, &*lines.file, marks, lvl));
+    }
+    for &&(label_sp, ref label) in far.iter() {
+        try!(print_diagnostic(dst, cm.span_to_string(label_sp).as_slice(), Note,
+                              label.as_slice(), None));
+    }
+
+    try!(print_macro_backtrace(dst, cm, sp));
+    print_explain_help(dst, ss.as_slice(), code)
+}
+
+fn line_distance(a: uint, b: uint) -> uint {
+    if a > b { a - b } else { b - a }
+}
+
+/// Prints the lines of a file covered by some single-line spans, each line
+/// followed by a mark under every span on it, and by the span's label if it
+/// has one. The first span is the primary one, marked in the color of
+/// `lvl`. Lines that are skipped between two shown lines are elided.
+fn highlight_labeled_lines(w: &mut EmitterWriter,
+                           cm: &codemap::CodeMap,
+                           fm: &codemap::FileMap,
+                           marks: Vec<(Span, Option<&str>)>,
+                           lvl: Level) -> io::IoResult<()> {
+    let mut marks: Vec<(uint, uint, uint, Option<&str>, bool)> =
+        marks.into_iter().enumerate().map(|(i, (sp, label))| {
+            let lo = cm.lookup_char_pos(sp.lo);
+            let hi = cm.lookup_char_pos(sp.hi);
+            (lo.line - 1, lo.col.to_uint(), hi.col.to_uint(), label, i == 0)
+        }).collect();
+    marks.sort_by(|&(a_line, a_lo, _, _, _), &(b_line, b_lo, _, _, _)| {
+        (a_line, a_lo).cmp(&(b_line, b_lo))
+    });
+
+    let mut last_line = None;
+    for &(line, lo, hi, label, is_primary) in marks.iter() {
+        let prefix = format!("{}:{} ", fm.name, line + 1);
+        if last_line != Some(line) {
+            match last_line {
+                Some(last) if line > last + 1 => {
+                    try!(write!(&mut w.dst, "{0:1$}...\n", "", prefix.len()));
+                }
+                _ => {}
+            }
+            try!(write!(&mut w.dst, "{}{}\n", prefix, fm.get_line(line as int)));
+            last_line = Some(line);
+        }
+
+        // Indent past the prefix, then past the source up to the span,
+        // keeping any tabs in it so that the mark lines up.
+        let mut s = String::from_char(prefix.len(), ' ');
+        for c in fm.get_line(line as int).as_slice().chars().take(lo) {
+            s.push(if c == '\t' { '\t' } else { ' ' });
+        }
+        try!(write!(&mut w.dst, "{}", s));
+        let mut mark = String::from_str("^");
+        for _ in range(lo + 1, hi) {
+            mark.push('~');
+        }
+        match label {
+            Some(label) => {
+                mark.push(' ');
+                mark.push_str(label);
+            }
+            None => {}
+        }
+        let color = if is_primary { lvl.color() } else { Note.color() };
+        try!(print_maybe_styled(w,
+                                format!("{}\n", mark).as_slice(),
+                                term::attr::ForegroundColor(color)));
+    }
+    Ok(())
+}
+
 fn highlight_lines(err: &mut EmitterWriter,
                    cm: &codemap::CodeMap,
                    sp: Span,
@@ -489,6 +758,32 @@ fn highlight_lines(err: &mut EmitterWriter,
     Ok(())
 }
 
+/// Prints the lines covered by `sp` as they would read with `suggestion`
+/// in place of the code the span covers.
+fn show_code_change(w: &mut EmitterWriter,
+                    cm: &codemap::CodeMap,
+                    sp: Span,
+                    lines: codemap::FileLines,
+                    suggestion: &str) -> io::IoResult<()> {
+    let fm = &*lines.file;
+    if lines.lines.is_empty() {
+        return Ok(());
+    }
+    let first = lines.lines[0];
+    let last = lines.lines[lines.lines.len() - 1];
+    let lo = cm.lookup_char_pos(sp.lo);
+    let hi = cm.lookup_char_pos(sp.hi);
+    let before: String = fm.get_line(first as int).as_slice().chars()
+        .take(lo.col.to_uint()).collect();
+    let after: String = fm.get_line(last as int).as_slice().chars()
+        .skip(hi.col.to_uint()).collect();
+    let code = format!("{}{}{}", before, suggestion, after);
+    for (i, line) in code.as_slice().lines().enumerate().take(MAX_LINES) {
+        try!(write!(&mut w.dst, "{}:{} {}\n", fm.name, first + i + 1, line));
+    }
+    Ok(())
+}
+
 /// Here are the differences between this and the normal `highlight_lines`:
 /// `custom_highlight_lines` will always put arrow on the last byte of the
 /// span (instead of the first byte). Also, when the span is too long (more
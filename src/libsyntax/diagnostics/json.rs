@@ -0,0 +1,146 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An emitter that writes each diagnostic as a single line of JSON, for
+//! editors and other tools to read instead of the human-readable text.
+//!
+//! Each line is an object of the form
+//!
+//! ```json
+//! {"message": "...", "code": "E0046", "level": "error",
+//!  "spans": [{"file_name": "foo.rs", "line_start": 1, "line_end": 1,
+//!             "column_start": 5, "column_end": 8, "is_primary": true,
+//!             "label": null, "suggested_replacement": null}]}
+//! ```
+//!
+//! Lines and columns are 1-based, and `column_end` is one past the last
+//! character of the span. `code` is `null` for a diagnostic without a code,
+//! and `spans` is empty for one without a span.
+
+use codemap::{CodeMap, Pos, Span};
+use diagnostic::{Emitter, RenderSpan, FullSpan, FileLine, Suggestion};
+use diagnostic::{Level, Bug, Fatal, Error, Warning, Note, Help};
+
+use serialize::json;
+use std::io;
+
+pub struct JsonEmitter {
+    dst: Box<Writer + Send>,
+}
+
+#[deriving(Encodable)]
+struct Diagnostic {
+    message: String,
+    code: Option<String>,
+    level: &'static str,
+    spans: Vec<DiagnosticSpan>,
+}
+
+#[deriving(Encodable)]
+struct DiagnosticSpan {
+    file_name: String,
+    line_start: uint,
+    line_end: uint,
+    column_start: uint,
+    column_end: uint,
+    is_primary: bool,
+    label: Option<String>,
+    suggested_replacement: Option<String>,
+}
+
+impl JsonEmitter {
+    pub fn stderr() -> JsonEmitter {
+        JsonEmitter::new(box io::stderr())
+    }
+
+    pub fn new(dst: Box<Writer + Send>) -> JsonEmitter {
+        JsonEmitter { dst: dst }
+    }
+
+    fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        // Flush after every diagnostic, so that a tool reading the output
+        // as it is written sees each one as soon as it is reported.
+        let result = self.dst.write_line(json::encode(diagnostic).as_slice())
+            .and_then(|()| self.dst.flush());
+        match result {
+            Ok(()) => {}
+            Err(e) => panic!("failed to print diagnostics: {}", e),
+        }
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(&mut self, cmsp: Option<(&CodeMap, Span)>,
+            msg: &str, code: Option<&str>, lvl: Level) {
+        let spans = match cmsp {
+            Some((cm, sp)) => vec![DiagnosticSpan::from_span(cm, sp, true, None, None)],
+            None => Vec::new(),
+        };
+        self.emit_diagnostic(&Diagnostic::new(msg, code, lvl, spans));
+    }
+
+    fn custom_emit(&mut self, cm: &CodeMap,
+                   sp: RenderSpan, msg: &str, lvl: Level) {
+        let span = match sp {
+            FullSpan(sp) | FileLine(sp) => DiagnosticSpan::from_span(cm, sp, true, None, None),
+            Suggestion(sp, suggestion) => {
+                DiagnosticSpan::from_span(cm, sp, true, None, Some(suggestion))
+            }
+        };
+        self.emit_diagnostic(&Diagnostic::new(msg, None, lvl, vec![span]));
+    }
+
+    fn emit_with_labels(&mut self, cm: &CodeMap, sp: Span,
+                        labels: &[(Span, String)], msg: &str,
+                        code: Option<&str>, lvl: Level) {
+        let mut spans = vec![DiagnosticSpan::from_span(cm, sp, true, None, None)];
+        spans.extend(labels.iter().map(|&(label_sp, ref label)| {
+            DiagnosticSpan::from_span(cm, label_sp, false, Some(label.clone()), None)
+        }));
+        self.emit_diagnostic(&Diagnostic::new(msg, code, lvl, spans));
+    }
+}
+
+impl Diagnostic {
+    fn new(msg: &str, code: Option<&str>, lvl: Level,
+           spans: Vec<DiagnosticSpan>) -> Diagnostic {
+        Diagnostic {
+            message: msg.to_string(),
+            code: code.map(|code| code.to_string()),
+            level: match lvl {
+                Bug => "bug",
+                Fatal | Error => "error",
+                Warning => "warning",
+                Note => "note",
+                Help => "help",
+            },
+            spans: spans,
+        }
+    }
+}
+
+impl DiagnosticSpan {
+    fn from_span(cm: &CodeMap, sp: Span, is_primary: bool,
+                 label: Option<String>,
+                 suggested_replacement: Option<String>) -> DiagnosticSpan {
+        let lo = cm.lookup_char_pos(sp.lo);
+        let hi = cm.lookup_char_pos(sp.hi);
+        DiagnosticSpan {
+            file_name: lo.file.name.clone(),
+            line_start: lo.line,
+            line_end: hi.line,
+            column_start: lo.col.to_uint() + 1,
+            column_end: hi.col.to_uint() + 1,
+            is_primary: is_primary,
+            label: label,
+            suggested_replacement: suggested_replacement,
+        }
+    }
+}
@@ -24,6 +24,15 @@ macro_rules! span_err(
     })
 )
 
+#[macro_export]
+macro_rules! span_err_with_labels(
+    ($session:expr, $span:expr, $labels:expr, $code:ident, $($message:tt)*) => ({
+        __diagnostic_used!($code);
+        $session.span_err_with_labels_and_code($span, format!($($message)*).as_slice(),
+                                               $labels, stringify!($code))
+    })
+)
+
 #[macro_export]
 macro_rules! span_warn(
     ($session:expr, $span:expr, $code:ident, $($message:tt)*) => ({
@@ -1223,14 +1223,15 @@ pub fn noop_fold_pat<T: Folder>(p: P<Pat>, folder: &mut T) -> P<Pat> {
             PatTup(elts) => PatTup(elts.move_map(|x| folder.fold_pat(x))),
             PatBox(inner) => PatBox(folder.fold_pat(inner)),
             PatRegion(inner) => PatRegion(folder.fold_pat(inner)),
-            PatRange(e1, e2) => {
-                PatRange(folder.fold_expr(e1), folder.fold_expr(e2))
+            PatRange(e1, e2, end) => {
+                PatRange(folder.fold_expr(e1), folder.fold_expr(e2), end)
             },
             PatVec(before, slice, after) => {
                 PatVec(before.move_map(|x| folder.fold_pat(x)),
                        slice.map(|x| folder.fold_pat(x)),
                        after.move_map(|x| folder.fold_pat(x)))
             }
+            PatOr(alternatives) => PatOr(alternatives.move_map(|x| folder.fold_pat(x))),
             PatMac(mac) => PatMac(folder.fold_mac(mac))
         },
         span: folder.new_span(span)
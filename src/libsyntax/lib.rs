@@ -42,6 +42,7 @@ pub mod util {
 }
 
 pub mod diagnostics {
+    pub mod json;
     pub mod macros;
     pub mod plugin;
     pub mod registry;
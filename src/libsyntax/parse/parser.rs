@@ -41,7 +41,7 @@ use ast::{MutImmutable, MutMutable, Mac_, MacInvocTT, MatchNormal};
 use ast::{Method, MutTy, BiMul, Mutability};
 use ast::{MethodImplItem, NamedField, UnNeg, NoReturn, UnNot};
 use ast::{Pat, PatEnum, PatIdent, PatLit, PatRange, PatRegion, PatStruct};
-use ast::{PatTup, PatBox, PatWild, PatWildMulti, PatWildSingle};
+use ast::{PatOr, PatTup, PatBox, PatWild, PatWildMulti, PatWildSingle};
 use ast::{PolyTraitRef};
 use ast::{QPath, RequiredMethod};
 use ast::{RetStyle, Return, BiShl, BiShr, Stmt, StmtDecl};
@@ -1808,8 +1808,20 @@ impl<'a> Parser<'a> {
                     seq_sep_trailing_allowed(token::Comma),
                     |p| p.parse_ty(true));
 
+                // As in fn types, the output may be `!`. Unlike in fn
+                // types, the output may not carry `+` bounds of its own:
+                // in `FnMut(A) -> B + 'a`, the bound belongs to the trait.
                 let output_ty = if self.eat(&token::RArrow) {
-                    Some(self.parse_ty(true))
+                    let lo = self.span.lo;
+                    if self.eat(&token::Not) {
+                        Some(P(Ty {
+                            id: ast::DUMMY_NODE_ID,
+                            node: TyBot,
+                            span: mk_sp(lo, self.last_span.hi)
+                        }))
+                    } else {
+                        Some(self.parse_ty(false))
+                    }
                 } else {
                     None
                 };
@@ -3122,6 +3134,52 @@ impl<'a> Parser<'a> {
         };
     }
 
+    /// Parse a sub-pattern that may be made of several alternatives
+    /// separated by '|', as in `Some(1 | 2)`. Only used where the pattern
+    /// is enclosed in delimiters, so that the '|' can't be confused with
+    /// the one separating the patterns of a match arm or closing the
+    /// arguments of a closure.
+    fn parse_pat_alternatives(&mut self) -> P<Pat> {
+        let lo = self.span.lo;
+        let first = self.parse_pat();
+        if self.token != token::BinOp(token::Or) {
+            return first;
+        }
+
+        let mut alternatives = vec!(first);
+        while self.token == token::BinOp(token::Or) {
+            self.bump();
+            alternatives.push(self.parse_pat());
+        }
+        let hi = self.last_span.hi;
+        P(ast::Pat {
+            id: ast::DUMMY_NODE_ID,
+            node: PatOr(alternatives),
+            span: mk_sp(lo, hi)
+        })
+    }
+
+    /// Parse a sub-pattern of a tuple or tuple-struct pattern. This may
+    /// be a `..`, standing for the elements not matched by any other
+    /// sub-pattern, but only once per pattern.
+    fn parse_pat_tuple_elt(&mut self, seen_dotdot: &mut bool) -> P<Pat> {
+        if self.token != token::DotDot {
+            return self.parse_pat_alternatives();
+        }
+
+        let span = self.span;
+        self.bump();
+        if *seen_dotdot {
+            self.span_err(span, "`..` can only be used once per tuple or tuple struct pattern");
+        }
+        *seen_dotdot = true;
+        P(ast::Pat {
+            id: ast::DUMMY_NODE_ID,
+            node: PatWild(PatWildMulti),
+            span: span,
+        })
+    }
+
     fn parse_pat_vec_elements(
         &mut self,
     ) -> (Vec<P<Pat>>, Option<P<Pat>>, Vec<P<Pat>>) {
@@ -3159,7 +3217,7 @@ impl<'a> Parser<'a> {
                 }
             }
 
-            let subpat = self.parse_pat();
+            let subpat = self.parse_pat_alternatives();
             if before_slice && self.token == token::DotDot {
                 self.bump();
                 slice = Some(subpat);
@@ -3202,6 +3260,9 @@ impl<'a> Parser<'a> {
                 break;
             }
 
+            // `Foo { box x }` is shorthand for `Foo { x: box x }`.
+            let is_box = self.eat_keyword(keywords::Box);
+
             let bind_type = if self.eat_keyword(keywords::Mut) {
                 BindByValue(MutMutable)
             } else if self.eat_keyword(keywords::Ref) {
@@ -3213,27 +3274,33 @@ impl<'a> Parser<'a> {
             let fieldname = self.parse_ident();
 
             let (subpat, is_shorthand) = if self.token == token::Colon {
-                match bind_type {
-                    BindByRef(..) | BindByValue(MutMutable) => {
-                        let token_str = self.this_token_to_string();
-                        self.fatal(format!("unexpected `{}`",
-                                           token_str).as_slice())
-                    }
-                    _ => {}
+                if is_box || bind_type != BindByValue(MutImmutable) {
+                    let token_str = self.this_token_to_string();
+                    self.fatal(format!("unexpected `{}`",
+                                       token_str).as_slice())
                 }
 
                 self.bump();
-                let pat = self.parse_pat();
+                let pat = self.parse_pat_alternatives();
                 hi = pat.span.hi;
                 (pat, false)
             } else {
                 hi = self.last_span.hi;
                 let fieldpath = codemap::Spanned{span:self.last_span, node: fieldname};
-                (P(ast::Pat {
+                let binding = P(ast::Pat {
                     id: ast::DUMMY_NODE_ID,
                     node: PatIdent(bind_type, fieldpath, None),
                     span: self.last_span
-                }), true)
+                });
+                if is_box {
+                    (P(ast::Pat {
+                        id: ast::DUMMY_NODE_ID,
+                        node: PatBox(binding),
+                        span: mk_sp(lo, hi)
+                    }), true)
+                } else {
+                    (binding, true)
+                }
             };
             fields.push(codemap::Spanned { span: mk_sp(lo, hi),
                                            node: ast::FieldPat { ident: fieldname,
@@ -3243,6 +3310,22 @@ impl<'a> Parser<'a> {
         return (fields, etc);
     }
 
+    /// Parse the `...` or `..` between the bounds of a range pattern, if
+    /// there is one. A `..` followed by `,` or `]` is instead the end of a
+    /// subslice pattern like `[a, rest.., z]`.
+    fn parse_pat_range_end(&mut self) -> Option<ast::RangeEnd> {
+        let range_end = match self.token {
+            token::DotDotDot => ast::RangeIncluded,
+            token::DotDot => ast::RangeExcluded,
+            _ => return None
+        };
+        if self.look_ahead(1, |t| *t == token::Comma || *t == token::CloseDelim(token::Bracket)) {
+            return None;
+        }
+        self.bump();
+        Some(range_end)
+    }
+
     /// Parse a pattern.
     pub fn parse_pat(&mut self) -> P<Pat> {
         maybe_whole!(self, NtPat);
@@ -3301,15 +3384,27 @@ impl<'a> Parser<'a> {
                 let expr = self.mk_expr(lo, hi, ExprLit(lit));
                 pat = PatLit(expr);
             } else {
-                let mut fields = vec!(self.parse_pat());
+                let mut seen_dotdot = false;
+                let mut fields = vec!(self.parse_pat_tuple_elt(&mut seen_dotdot));
                 if self.look_ahead(1, |t| *t != token::CloseDelim(token::Paren)) {
                     while self.token == token::Comma {
                         self.bump();
                         if self.token == token::CloseDelim(token::Paren) { break; }
-                        fields.push(self.parse_pat());
+                        fields.push(self.parse_pat_tuple_elt(&mut seen_dotdot));
+                    }
+                }
+                if fields.len() == 1 && !seen_dotdot {
+                    let is_or_pat = match fields[0].node {
+                        PatOr(..) => true,
+                        _ => false,
+                    };
+                    if is_or_pat && self.token == token::CloseDelim(token::Paren) {
+                        // `(a | b)` only groups the alternatives
+                        self.bump();
+                        return fields.pop().unwrap();
                     }
+                    self.expect(&token::Comma);
                 }
-                if fields.len() == 1 { self.expect(&token::Comma); }
                 self.expect(&token::CloseDelim(token::Paren));
                 pat = PatTup(fields);
             }
@@ -3348,11 +3443,8 @@ impl<'a> Parser<'a> {
             // These expressions are limited to literals (possibly
             // preceded by unary-minus) or identifiers.
             let val = self.parse_literal_maybe_minus();
-            if (self.token == token::DotDotDot) &&
-                    self.look_ahead(1, |t| {
-                        *t != token::Comma && *t != token::CloseDelim(token::Bracket)
-                    }) {
-                self.bump();
+            let range_end = self.parse_pat_range_end();
+            if range_end.is_some() {
                 let end = if self.token.is_ident() || self.token.is_path() {
                     let path = self.parse_path(LifetimeAndTypesWithColons)
                                    .path;
@@ -3361,7 +3453,7 @@ impl<'a> Parser<'a> {
                 } else {
                     self.parse_literal_maybe_minus()
                 };
-                pat = PatRange(val, end);
+                pat = PatRange(val, end, range_end.unwrap());
             } else {
                 pat = PatLit(val);
             }
@@ -3392,14 +3484,14 @@ impl<'a> Parser<'a> {
                 }
             });
 
-            if self.look_ahead(1, |t| *t == token::DotDotDot) &&
+            if self.look_ahead(1, |t| *t == token::DotDotDot || *t == token::DotDot) &&
                     self.look_ahead(2, |t| {
                         *t != token::Comma && *t != token::CloseDelim(token::Bracket)
                     }) {
                 let start = self.parse_expr_res(RESTRICTION_NO_BAR_OP);
-                self.eat(&token::DotDotDot);
+                let range_end = self.parse_pat_range_end().unwrap();
                 let end = self.parse_expr_res(RESTRICTION_NO_BAR_OP);
-                pat = PatRange(start, end);
+                pat = PatRange(start, end, range_end);
             } else if self.token.is_plain_ident() && !can_be_enum_or_struct {
                 let id = self.parse_ident();
                 let id_span = self.last_span;
@@ -3444,6 +3536,8 @@ impl<'a> Parser<'a> {
                                     token::DotDot => true,
                                     _ => false,
                                 }
+                            }) && self.look_ahead(2, |t| {
+                                *t == token::CloseDelim(token::Paren)
                             });
                             if is_dotdot {
                                 // This is a "top constructor only" pat
@@ -3452,11 +3546,12 @@ impl<'a> Parser<'a> {
                                 self.expect(&token::CloseDelim(token::Paren));
                                 pat = PatEnum(enum_path, None);
                             } else {
+                                let mut seen_dotdot = false;
                                 args = self.parse_enum_variant_seq(
                                     &token::OpenDelim(token::Paren),
                                     &token::CloseDelim(token::Paren),
                                     seq_sep_trailing_allowed(token::Comma),
-                                    |p| p.parse_pat()
+                                    |p| p.parse_pat_tuple_elt(&mut seen_dotdot)
                                 );
                                 pat = PatEnum(enum_path, Some(args));
                             }
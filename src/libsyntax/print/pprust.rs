@@ -1004,10 +1004,10 @@ impl<'a> State<'a> {
     fn print_poly_trait_ref(&mut self, t: &ast::PolyTraitRef) -> IoResult<()> {
         if !t.bound_lifetimes.is_empty() {
             try!(word(&mut self.s, "for<"));
-            for lifetime_def in t.bound_lifetimes.iter() {
-                try!(self.print_lifetime_def(lifetime_def));
-            }
-            try!(word(&mut self.s, ">"));
+            try!(self.commasep(Inconsistent,
+                               t.bound_lifetimes.as_slice(),
+                               |s, lt| s.print_lifetime_def(lt)));
+            try!(self.word_nbsp(">"));
         }
 
         self.print_trait_ref(&t.trait_ref)
@@ -2040,6 +2040,7 @@ impl<'a> State<'a> {
                 match data.output {
                     None => { }
                     Some(ref ty) => {
+                        try!(self.space_if_not_bol());
                         try!(self.word_space("->"));
                         try!(self.print_type(&**ty));
                     }
@@ -2084,7 +2085,7 @@ impl<'a> State<'a> {
                 match *sub {
                     Some(ref p) => {
                         try!(word(&mut self.s, "@"));
-                        try!(self.print_pat(&**p));
+                        try!(self.print_subpat_maybe_paren(&**p));
                     }
                     None => ()
                 }
@@ -2138,17 +2139,20 @@ impl<'a> State<'a> {
             }
             ast::PatBox(ref inner) => {
                 try!(word(&mut self.s, "box "));
-                try!(self.print_pat(&**inner));
+                try!(self.print_subpat_maybe_paren(&**inner));
             }
             ast::PatRegion(ref inner) => {
                 try!(word(&mut self.s, "&"));
-                try!(self.print_pat(&**inner));
+                try!(self.print_subpat_maybe_paren(&**inner));
             }
             ast::PatLit(ref e) => try!(self.print_expr(&**e)),
-            ast::PatRange(ref begin, ref end) => {
+            ast::PatRange(ref begin, ref end, range_end) => {
                 try!(self.print_expr(&**begin));
                 try!(space(&mut self.s));
-                try!(word(&mut self.s, "..."));
+                match range_end {
+                    ast::RangeIncluded => try!(word(&mut self.s, "...")),
+                    ast::RangeExcluded => try!(word(&mut self.s, "..")),
+                }
                 try!(self.print_expr(&**end));
             }
             ast::PatVec(ref before, ref slice, ref after) => {
@@ -2172,11 +2176,36 @@ impl<'a> State<'a> {
                                    |s, p| s.print_pat(&**p)));
                 try!(word(&mut self.s, "]"));
             }
+            ast::PatOr(ref alternatives) => {
+                let mut first = true;
+                for p in alternatives.iter() {
+                    if first {
+                        first = false;
+                    } else {
+                        try!(space(&mut self.s));
+                        try!(self.word_space("|"));
+                    }
+                    try!(self.print_pat(&**p));
+                }
+            }
             ast::PatMac(ref m) => try!(self.print_mac(m)),
         }
         self.ann.post(self, NodePat(pat))
     }
 
+    /// Prints the operand of `@`, `box` or `&`, which binds tighter than
+    /// `|` and so needs parentheses around an or-pattern.
+    fn print_subpat_maybe_paren(&mut self, pat: &ast::Pat) -> IoResult<()> {
+        match pat.node {
+            ast::PatOr(..) => {
+                try!(self.popen());
+                try!(self.print_pat(pat));
+                self.pclose()
+            }
+            _ => self.print_pat(pat)
+        }
+    }
+
     fn print_arm(&mut self, arm: &ast::Arm) -> IoResult<()> {
         // I have no idea why this check is necessary, but here it
         // is :(
@@ -460,6 +460,11 @@ pub fn walk_pat<'v, V: Visitor<'v>>(visitor: &mut V, pattern: &'v Pat) {
                 visitor.visit_pat(&**tuple_element)
             }
         }
+        PatOr(ref alternatives) => {
+            for alternative in alternatives.iter() {
+                visitor.visit_pat(&**alternative)
+            }
+        }
         PatBox(ref subpattern) |
         PatRegion(ref subpattern) => {
             visitor.visit_pat(&**subpattern)
@@ -472,7 +477,7 @@ pub fn walk_pat<'v, V: Visitor<'v>>(visitor: &mut V, pattern: &'v Pat) {
             }
         }
         PatLit(ref expression) => visitor.visit_expr(&**expression),
-        PatRange(ref lower_bound, ref upper_bound) => {
+        PatRange(ref lower_bound, ref upper_bound, _) => {
             visitor.visit_expr(&**lower_bound);
             visitor.visit_expr(&**upper_bound)
         }
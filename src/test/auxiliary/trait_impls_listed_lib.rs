@@ -8,9 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-pub fn main() {
-    match 22i {
-        0 .. 3 => {} //~ ERROR expected `=>`, found `..`
-        _ => {}
-    }
+pub trait Speak {
+    fn speak(&self) {}
 }
+
+impl Speak for uint {}
@@ -11,7 +11,7 @@
 // Tests that a function with a ! annotation always actually fails
 
 fn bad_bang(i: uint) -> ! {
-    return 7u; //~ ERROR `return` in a function declared as diverging [E0166]
+    return 7u; //~ ERROR `return` in a function declared as diverging
 }
 
 fn main() { bad_bang(5u); }
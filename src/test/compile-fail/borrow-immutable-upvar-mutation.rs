@@ -19,23 +19,23 @@ fn main() {
     // By-ref captures
     {
         let mut x = 0u;
-        let _f = |&:| x = 42; //~ ERROR cannot assign
+        let _f = |&:| x = 42; //~ ERROR closure is declared `Fn` but mutates
 
         let mut y = 0u;
-        let _g = |&:| set(&mut y); //~ ERROR cannot borrow
+        let _g = |&:| set(&mut y); //~ ERROR closure is declared `Fn` but mutates
 
         let mut z = 0u;
-        let _h = |&mut:| { set(&mut z); |&:| z = 42; }; //~ ERROR cannot assign
+        let _h = |&mut:| { set(&mut z); |&:| z = 42; }; //~ ERROR `Fn` but mutates
     }
     // By-value captures
     {
         let mut x = 0u;
-        let _f = move |&:| x = 42; //~ ERROR cannot assign
+        let _f = move |&:| x = 42; //~ ERROR closure is declared `Fn` but mutates
 
         let mut y = 0u;
-        let _g = move |&:| set(&mut y); //~ ERROR cannot borrow
+        let _g = move |&:| set(&mut y); //~ ERROR closure is declared `Fn` but mutates
 
         let mut z = 0u;
-        let _h = move |&mut:| { set(&mut z); move |&:| z = 42; }; //~ ERROR cannot assign
+        let _h = move |&mut:| { set(&mut z); move |&:| z = 42; }; //~ ERROR `Fn` but mutates
     }
 }
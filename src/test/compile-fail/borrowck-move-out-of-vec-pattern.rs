@@ -0,0 +1,44 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Moves out of a fixed-length vector through a vector pattern are
+// tracked per element.
+
+fn move_both() {
+    let arr = ["a".to_string(), "b".to_string()];
+    let [s1, s2] = arr; //~ NOTE `arr[0]` moved here
+    drop(s1);
+    drop(s2);
+    let _arr = arr; //~ ERROR use of partially moved value: `arr`
+}
+
+fn move_last_then_bind_it() {
+    let arr = ["a".to_string(), "b".to_string(), "c".to_string()];
+    let [_, _, s] = arr; //~ NOTE `arr[2]` moved here
+    drop(s);
+    let [_, _, ref t] = arr; //~ ERROR use of moved value: `arr[2]`
+}
+
+fn move_then_index() {
+    let arr = ["a".to_string(), "b".to_string()];
+    let [s, _] = arr;
+    drop(s);
+    let _t = arr[1].clone(); //~ ERROR use of moved value: `arr`
+}
+
+fn index_then_bind() {
+    let arr = [box 1i, box 2];
+    let a = arr[0];
+    drop(a);
+    let [_, ref b] = arr; //~ ERROR use of moved value: `arr`
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn a() {
+    let mut v = [1i, 2, 3];
+    let all = match v {
+        [ref mut rest..] => rest
+    };
+    let tail = match v {
+        [_, ref mut rest..] => rest //~ ERROR cannot borrow
+    };
+    all[0] = tail[0];
+}
+
+fn b() {
+    let v = [1i, 2, 3];
+    match v {
+        [_, ref mut rest..] => { //~ ERROR cannot borrow
+            rest[0] = 4;
+        }
+    }
+}
+
+fn c() {
+    let mut v = [1i, 2, 3];
+    match v {
+        [_, ref rest..] => {
+            v[0] = 4; //~ ERROR cannot assign
+            println!("{}", rest[0]);
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Moving out of a box behind a shared reference is not allowed, however
+// deeply the box pattern is nested.
+
+struct Foo {
+    field: Box<String>,
+}
+
+fn main() {
+    let v = vec![box "a".to_string()];
+    match v.as_slice() {
+        [box s] => {} //~ ERROR cannot move out of dereference of `&`-pointer
+        _ => {}
+    }
+
+    let foo = &Foo { field: box "b".to_string() };
+    match *foo {
+        Foo { field: box s } => {} //~ ERROR cannot move out of dereference of `&`-pointer
+    }
+
+    let pair = &(box "c".to_string(), 1i);
+    let &(box s, _) = pair; //~ ERROR cannot move out of dereference of `&`-pointer
+}
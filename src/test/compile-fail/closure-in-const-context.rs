@@ -0,0 +1,24 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Closures are rejected with an explanation in the other places that
+// require a constant expression.
+
+#![feature(unboxed_closures, overloaded_calls)]
+
+enum E {
+    A = (|&: | 1i)(),
+    //~^ ERROR expected constant: closures construct their environment at runtime
+}
+
+fn main() {
+    let _: [int, ..(|&: | 2u)()] = [1, 2];
+    //~^ ERROR expected constant expr for array length: closures construct their environment
+}
@@ -0,0 +1,24 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Closures cannot be used to initialize constants or statics.
+
+#![feature(unboxed_closures, unboxed_closure_sugar)]
+
+const F: &'static Fn(int) -> int = &|&: x: int| x;
+//~^ ERROR closures are not allowed in constant expressions
+
+static mut G: &'static Fn(int) -> int = &|&: x: int| x + 1;
+//~^ ERROR closures are not allowed in constant expressions
+
+const P: proc():Send = proc() {};
+//~^ ERROR closures are not allowed in constant expressions
+
+fn main() {}
@@ -0,0 +1,30 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when a reference inserted by a coercion turns out to be the
+// wrong kind of reference later on, the coercion is pointed out.
+
+fn view<'a>(xs: &'a [u8]) -> &'a [u8] { xs }
+
+fn clear(xs: &mut [u8]) {
+    for x in xs.iter_mut() { *x = 0; }
+}
+
+fn main() {
+    let mut buf = [1u8, 2, 3];
+    let v = view(&mut buf);
+    //~^ NOTE re: automatic `&`-reference was inserted here .*`&.*\[u8\]` of argument 1
+    clear(v); //~ ERROR mismatched types
+
+    let mut other = [4u8, 5];
+    let w: &[u8] = &mut other;
+    //~^ NOTE re: automatic `&`-reference was inserted here .*`&.*\[u8\]` of the variable
+    clear(w); //~ ERROR mismatched types
+}
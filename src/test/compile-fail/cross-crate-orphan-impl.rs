@@ -0,0 +1,30 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:cross_crate_shape.rs
+
+// Tests that a trait from another crate cannot be implemented for a type
+// from another crate, while it can be for a local type.
+
+extern crate cross_crate_shape;
+
+use cross_crate_shape::{Shape, Square};
+
+struct Local;
+
+impl Shape for Vec<Square> { //~ ERROR E0117
+    fn area(&self) -> f64 { 0.0 }
+}
+
+impl Shape for Local {
+    fn area(&self) -> f64 { 0.0 }
+}
+
+fn main() { }
@@ -0,0 +1,29 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:cross_crate_shape.rs
+
+// Tests that a bound declared on a function in another crate is
+// enforced when the function is called from this one.
+
+extern crate cross_crate_shape;
+
+use cross_crate_shape::total_area;
+
+struct Circle {
+    radius: f64,
+}
+
+fn main() {
+    let circles = [Circle { radius: 1.0 }];
+    total_area(circles.as_slice());
+    //~^ ERROR the trait `cross_crate_shape::Shape` is not implemented for the type `Circle`
+    //~| NOTE required by `cross_crate_shape::total_area`
+}
@@ -1,4 +1,4 @@
-// Copyright 2013-2014 The Rust Project Developers. See the COPYRIGHT
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
 // file at the top-level directory of this distribution and at
 // http://rust-lang.org/COPYRIGHT.
 //
@@ -8,29 +8,17 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// compile-flags: --cfg foo
+// deny-unexpected-errors
 
-// check that cfg correctly chooses between the macro impls (see also
-// cfg-macros-notfoo.rs)
+// Test that an error reported at the same span with the same message
+// several times, here once per expansion of `$e`, is reported only once.
 
 #![feature(macro_rules)]
 
-#[cfg(foo)]
-#[macro_escape]
-mod foo {
-    macro_rules! bar {
-        () => { true }
-    }
-}
-
-#[cfg(not(foo))]
-#[macro_escape]
-mod foo {
-    macro_rules! bar {
-        () => { false }
-    }
-}
+macro_rules! thrice(
+    ($e:expr) => ({ $e; $e; $e; })
+)
 
-pub fn main() {
-    assert!(bar!())
+fn main() {
+    thrice!(undefined_name); //~ ERROR unresolved name `undefined_name`
 }
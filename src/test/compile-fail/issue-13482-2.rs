@@ -14,7 +14,7 @@ fn main() {
     let x = [1,2];
     let y = match x {
         [] => None,
-        //~^ ERROR types: expected `[_#0i, ..2]`, found `[_#7t, ..0]`
+        //~^ ERROR re: types: expected `\[_#\d+i, \.\.2\]`, found `\[_#\d+t, \.\.0\]`
         //         (expected array of 2 elements, found array of 0 elements)
         [a,_] => Some(a)
     };
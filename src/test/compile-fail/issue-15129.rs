@@ -20,7 +20,7 @@ pub enum V {
 
 fn main() {
     match (T1(()), V2(true)) {
-    //~^ ERROR non-exhaustive patterns: `(T1(()), V2(_))` not covered
+    //~^ ERROR non-exhaustive patterns: `(T1(()), V2(_))` and `(T2(()), V1(_))` not covered
         (T1(()), V1(i)) => (),
         (T2(()), V2(b)) => ()
     }
@@ -15,7 +15,7 @@ fn main() {
   unsafe {
     let nil: *const u8 = ptr::null();
     let slice: raw::Slice<u8> =
-      Slice { //~ ERROR use of trait `Slice` as a struct constructor [E0159]
+      Slice { //~ ERROR use of trait `Slice` as a struct constructor
         data: nil,
         len:  0,
       };
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// error-pattern: too big for the current architecture
+// error-pattern: re: the type `.*\[.*, \.\.\d+\]` is too big for the current architecture
 
 #[cfg(target_word_size = "64")]
 fn main() {
@@ -8,18 +8,20 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// deny-unexpected-errors
+
 enum A { B, C }
 
 fn main() {
     match (true, false) {
         B => (),
-//~^ ERROR mismatched types: expected `(bool, bool)`, found `A` (expected tuple, found enum A)
+//~^ ERROR[col=9] mismatched types: expected `(bool, bool)`, found `A`
         _ => ()
     }
 
     match (true, false) {
         (true, false, false) => ()
-//~^ ERROR mismatched types: expected `(bool, bool)`, found `(_, _, _)`
+//~^ ERROR[col=9] mismatched types: expected `(bool, bool)`, found `(_, _, _)`
     }
 
     match (true, false) {
@@ -8,6 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// deny-unexpected-errors
+
 // Test the mechanism for warning about possible missing `self` declarations.
 
 trait CtxtFn {
@@ -71,16 +73,16 @@ impl ManyImplTrait for MyInt {}
 
 fn no_param_bound(u: uint, m: MyInt) -> uint {
     u.f8(42) + u.f9(342) + m.fff(42)
-            //~^ ERROR type `uint` does not implement any method in scope named `f9`
-            //~^^ NOTE found defined static methods, maybe a `self` is missing?
-                        //~^^^ ERROR type `MyInt` does not implement any method in scope named `fff`
-                        //~^^^^ NOTE found defined static methods, maybe a `self` is missing?
+            //~^ ERROR[col=18] type `uint` does not implement any method in scope named `f9`
+            //~| NOTE found defined static methods, maybe a `self` is missing?
+            //~| ERROR[col=30] type `MyInt` does not implement any method in scope named `fff`
+            //~| NOTE found defined static methods, maybe a `self` is missing?
 }
 
 fn param_bound<T: ManyImplTrait>(t: T) -> bool {
     t.is_str()
-    //~^ ERROR type `T` does not implement any method in scope named `is_str`
-    //~^^ NOTE found defined static methods, maybe a `self` is missing?
+    //~^ ERROR[col=7] type `T` does not implement any method in scope named `is_str`
+    //~| NOTE found defined static methods, maybe a `self` is missing?
 }
 
 fn main() {
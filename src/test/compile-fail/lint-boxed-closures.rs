@@ -0,0 +1,31 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(boxed_closures)]
+#![allow(dead_code)]
+
+fn apply(f: |int| -> int) -> int { f(1) }
+//~^ ERROR use the unboxed closure trait `FnMut(int) -> int`
+
+fn spawn_it(f: proc(int):Send) { f(1) } //~ ERROR `FnOnce(int) + Send`
+
+struct Holder<'a> {
+    f: |&str|: 'a //~ ERROR use the unboxed closure trait `FnMut(&str)`
+}
+
+#[allow(boxed_closures)]
+fn allowed(f: |int| -> int) -> int { f(2) }
+
+fn main() {
+    let _f: || = || {}; //~ ERROR boxed closure types are deprecated
+    // Closure expressions with inferred types are not linted.
+    let x = 3i;
+    let _ = (|y: int| x + y)(1);
+}
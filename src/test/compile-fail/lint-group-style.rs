@@ -20,7 +20,7 @@ mod test {
 
     #[forbid(bad_style)]
     //~^ NOTE lint level defined here
-    //~^^ NOTE lint level defined here
+    //~| NOTE lint level defined here
     mod bad {
         fn CamelCase() {} //~ ERROR function `CamelCase` should have a snake case name
 
@@ -0,0 +1,28 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+fn consume(s: String) -> bool { s.len() > 0 }
+
+fn main() {
+    let x = Some("hello".to_string());
+    match x {
+        Some(s) if consume(s) => {} //~ ERROR cannot move out of `s` in a pattern guard
+        Some(s) => {}
+        None => {}
+    }
+
+    let mut y = Some("hello".to_string());
+    match y {
+        Some(s) if { y = None; false } => {} //~ ERROR cannot assign in a pattern guard
+        Some(s) => {}
+        None => {}
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// A guard that fails passes control on to the next arm, which must see
+// anything the guard moved as moved.
+
+fn consume(s: String) -> bool { s.len() > 5 }
+
+fn main() {
+    let outer = "hello".to_string();
+    match Some(1i) {
+        Some(n) if consume(outer) => {}
+        Some(n) => { outer.len(); } //~ ERROR use of moved value: `outer`
+        None => {}
+    }
+}
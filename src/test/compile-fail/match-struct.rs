@@ -8,13 +8,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// deny-unexpected-errors
 
 struct S { a: int }
 enum E { C(int) }
 
 fn main() {
     match (S { a: 1 }) {
-        C(_) => (), //~ ERROR mismatched types: expected `S`, found `E`
+        C(_) => (), //~ ERROR[col=9] mismatched types: expected `S`, found `E`
         _ => ()
     }
 }
@@ -0,0 +1,36 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+enum Void {}
+
+fn main() {
+    let r: Result<int, Void> = Ok(1);
+    match r {
+        Ok(x) => {}
+        Err(_) => {} //~ WARNING unreachable pattern
+    }
+    match r {
+        Ok(x) => {}
+        _ => {} //~ WARNING unreachable pattern
+    }
+
+    // References to uninhabited types are conservatively assumed to be
+    // inhabited.
+    let r: Result<int, &Void> = Ok(1);
+    match r { //~ ERROR non-exhaustive patterns: `Err(_)` not covered
+        Ok(x) => {}
+    }
+
+    let r: Result<int, String> = Ok(1);
+    match r { //~ ERROR non-exhaustive patterns: `Err(_)` not covered
+        Ok(x) => {}
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    let x = [1i, 2, 3];
+    match x {
+        [_, _, _, _] => {} //~ ERROR pattern requires 4 elements but the array has 3
+        _ => {}
+    }
+    match x {
+        [_, _] => {} //~ ERROR pattern requires 2 elements but the array has 3
+        _ => {}
+    }
+    match x {
+        [_, _, _, _, rest..] => {}
+        //~^ ERROR pattern requires at least 4 elements but the array has 3
+        _ => {}
+    }
+}
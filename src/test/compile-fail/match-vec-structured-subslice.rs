@@ -1,4 +1,4 @@
-// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
 // file at the top-level directory of this distribution and at
 // http://rust-lang.org/COPYRIGHT.
 //
@@ -9,12 +9,10 @@
 // except according to those terms.
 
 fn main() {
-    let (tx, rx) = channel();
-    let x = Some(rx);
-    tx.send(false);
+    let v = vec![1i, 2, 3];
+    let x: &[int] = v.as_slice();
     match x {
-        Some(z) if z.recv() => { panic!() }, //~ ERROR cannot bind by-move into a pattern guard
-        Some(z) => { assert!(!z.recv()); },
-        None => panic!()
+        [a, [b, c]..] => {} //~ ERROR subslice patterns must be a binding or `..`
+        _ => {}
     }
 }
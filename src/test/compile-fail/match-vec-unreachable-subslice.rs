@@ -0,0 +1,61 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(advanced_slice_patterns)]
+
+fn subsumed(xs: &[int]) {
+    match xs {
+        [x, rest..] => (),
+        [a, b] => (), //~ ERROR unreachable pattern
+        _ => ()
+    }
+
+    match xs {
+        [x, ..] => (),
+        [a, b, c, ..] => (), //~ ERROR unreachable pattern
+        _ => ()
+    }
+
+    match xs {
+        [x, .., y] => (),
+        [a, b, c, .., d, e] => (), //~ ERROR unreachable pattern
+        _ => ()
+    }
+}
+
+fn reordered(xs: &[int]) {
+    match xs {
+        [a, b] => (),
+        [x, rest..] => (),
+        _ => ()
+    }
+
+    match xs {
+        [a, b, c, ..] => (),
+        [x, ..] => (),
+        _ => ()
+    }
+}
+
+fn guarded(xs: &[int]) {
+    match xs {
+        [x, rest..] if x > 0 => (),
+        [a, b] => (),
+        _ => ()
+    }
+
+    match xs {
+        [x, ..] if x > 0 => (),
+        [a, b, c, ..] => (),
+        _ => ()
+    }
+}
+
+fn main() {}
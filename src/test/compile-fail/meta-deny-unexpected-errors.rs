@@ -0,0 +1,21 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// should-fail
+// deny-unexpected-errors
+// error-pattern: mismatched types
+
+// Tests that compiletest rejects a test under `deny-unexpected-errors`
+// when the compiler reports an error that no error pattern accounts for.
+
+fn main() {
+    let _x: int = "not an int";
+    let _y = true + 1i;
+}
@@ -0,0 +1,19 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// should-fail
+
+// Tests that compiletest rejects a test when an expectation stacked onto
+// a line with `//~|` is never reported, even though the one before it is.
+
+fn main() {
+    let _x: int = "not an int"; //~ ERROR mismatched types
+    //~| ERROR this second error is never reported
+}
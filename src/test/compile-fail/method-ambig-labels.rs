@@ -0,0 +1,26 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the candidates of an ambiguous method call are labeled in the
+// snippet of the call when they are close to it, and are referred to by
+// location when they are not.
+
+trait Far { fn go(&self); }
+//~^ NOTE candidate #1 is defined in the trait `Far`
+
+fn unrelated() {}
+
+trait Near { fn go(&self); } //~ LABEL[col=14] candidate #2 is defined in the trait `Near`
+
+fn call<T: Far + Near>(t: T) {
+    t.go(); //~ ERROR multiple applicable methods in scope
+}
+
+fn main() {}
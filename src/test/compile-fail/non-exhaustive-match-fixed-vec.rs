@@ -0,0 +1,27 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    let x = [1i, 2, 3];
+    match x { //~ ERROR non-exhaustive patterns: `[_, _, _]` not covered
+        [1, _, _] => {}
+        [_, 2, ..] => {}
+    }
+
+    // The dynamically-sized case still needs every length to be covered.
+    let v = vec![1i, 2, 3];
+    let s: &[int] = v.as_slice();
+    match s { //~ ERROR non-exhaustive patterns: `[_, _, _, _]` not covered
+        [_, _, _] => {}
+        [_, _] => {}
+        [_] => {}
+        [] => {}
+    }
+}
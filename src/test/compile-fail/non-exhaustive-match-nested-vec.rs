@@ -0,0 +1,24 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    let m = [[true, false], [false, true]];
+    match m { //~ ERROR non-exhaustive patterns: `[[false, _], _]` not covered
+        [[true, _], _] => {}
+    }
+
+    let v = vec![1i, 2];
+    let x: &[int] = v.as_slice();
+    let xss: &[&[int]] = &[x];
+    match xss { //~ ERROR non-exhaustive patterns: `[[]]` and `[[], _]` not covered
+        [] => {}
+        [[_, ..], ..] => {}
+    }
+}
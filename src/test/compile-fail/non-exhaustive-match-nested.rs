@@ -12,7 +12,8 @@ enum t { a(u), b }
 enum u { c, d }
 
 fn match_nested_vecs<'a, T>(l1: Option<&'a [T]>, l2: Result<&'a [T], ()>) -> &'static str {
-    match (l1, l2) { //~ ERROR non-exhaustive patterns: `(Some([]), Err(_))` not covered
+    match (l1, l2) {
+    //~^ ERROR non-exhaustive patterns: `(Some([]), Ok([_]))` and `(Some([]), Err(_))` not covered
         (Some([]), Ok([])) => "Some(empty), Ok(empty)",
         (Some([_, ..]), Ok(_)) | (Some([_, ..]), Err(())) => "Some(non-empty), any",
         (None, Ok([])) | (None, Err(())) | (None, Ok([_])) => "None, Ok(less than one element)",
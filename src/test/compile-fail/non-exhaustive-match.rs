@@ -22,7 +22,7 @@ fn main() {
     match (2i, 3i, 4i) { //~ ERROR non-exhaustive patterns: `(_, _, _)` not covered
       (_, _, 4) => {}
     }
-    match (a, a) { //~ ERROR non-exhaustive patterns: `(a, a)` not covered
+    match (a, a) { //~ ERROR non-exhaustive patterns: `(a, a)` and `(b, b)` not covered
       (a, b) => {}
       (b, a) => {}
     }
@@ -37,7 +37,8 @@ fn main() {
     }
     let vec = vec!(Some(42i), None, Some(21i));
     let vec: &[Option<int>] = vec.as_slice();
-    match vec { //~ ERROR non-exhaustive patterns: `[]` not covered
+    match vec {
+    //~^ ERROR non-exhaustive patterns: `[]`, `[Some(_)]`, `[None, _]` and more not covered
         [Some(..), None, tail..] => {}
         [Some(..), Some(..), tail..] => {}
         [None] => {}
@@ -50,7 +51,8 @@ fn main() {
     }
     let vec = vec!(0.5f32);
     let vec: &[f32] = vec.as_slice();
-    match vec { //~ ERROR non-exhaustive patterns: `[_, _, _, _]` not covered
+    match vec {
+    //~^ ERROR non-exhaustive patterns: `[_]`, `[_, _]`, `[_, _, _]` and more not covered
         [0.1, 0.2, 0.3] => (),
         [0.1, 0.2] => (),
         [0.1] => (),
@@ -33,7 +33,7 @@ fn struct_with_a_nested_enum_and_vector() {
 
 fn enum_with_multiple_missing_variants() {
     match Red {
-    //~^ ERROR non-exhaustive patterns: `Red` not covered
+    //~^ ERROR non-exhaustive patterns: `Red` and `Green` not covered
         CustomRGBA { .. } => ()
     }
 }
@@ -67,6 +67,43 @@ fn vectors_with_nested_enums() {
     }
 }
 
+fn nested_enums(x: Option<Result<int, String>>) {
+    match x {
+    //~^ ERROR non-exhaustive patterns: `Some(Err(_))` not covered
+        None => (),
+        Some(Ok(_)) => ()
+    }
+}
+
+fn struct_with_fields_left_out() {
+    match (Foo { first: true, second: None }) {
+    //~^ ERROR `Foo { first: true, second: Some(_) }` and `Foo { first: false, .. }` not covered
+        Foo { first: true, second: None } => ()
+    }
+}
+
+fn three_missing_cases(x: (bool, Option<bool>)) {
+    match x {
+    //~^ ERROR non-exhaustive patterns: `(true, None)`, `(true, Some(false))` and `(false, _)`
+        (true, Some(true)) => ()
+    }
+}
+
+enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+    Joker
+}
+
+fn more_than_three_missing_cases(s: Suit) {
+    match s {
+    //~^ ERROR non-exhaustive patterns: `Clubs`, `Diamonds`, `Hearts` and more not covered
+        Joker => ()
+    }
+}
+
 fn missing_nil() {
     match ((), false) {
     //~^ ERROR non-exhaustive patterns: `((), false)` not covered
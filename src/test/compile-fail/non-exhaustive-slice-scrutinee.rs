@@ -0,0 +1,25 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    let v = vec![1i, 2, 3];
+    let xs: &[int] = v.as_slice();
+    match xs { //~ ERROR non-exhaustive patterns: `[_, _]` not covered
+        [] => {}
+        [_] => {}
+        [_, _, _, ..] => {}
+    }
+
+    // A wildcard arm or an empty-plus-subslice pair covers every length.
+    match xs {
+        [] => {}
+        [_, ..] => {}
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The note on an object cast names the object type, along with its
+// type parameters.
+
+trait Foo<T> {}
+
+fn main() {
+    let v = vec![1i];
+    let _ = &v as &Foo<uint>;
+    //~^ ERROR the trait `Foo<uint>` is not implemented for the type
+    //~^^ NOTE must be implemented for the cast to the object type `Foo<uint>
+}
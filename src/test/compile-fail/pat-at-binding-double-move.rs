@@ -0,0 +1,39 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn make() -> Option<String> {
+    Some("a".to_string())
+}
+
+fn main() {
+    let x = Some("a".to_string());
+    match x {
+        whole @ Some(inner) => {}
+        //~^ ERROR cannot move into both `whole` and `inner`, which is part of the same value
+        //~^^ NOTE `whole` is bound here
+        None => {}
+    }
+
+    // The matched value needn't have a path of its own.
+    match make() {
+        whole @ Some(inner) => {}
+        //~^ ERROR cannot move into both `whole` and `inner`, which is part of the same value
+        //~^^ NOTE `whole` is bound here
+        None => {}
+    }
+
+    // `inner` would point into the value that was moved into `whole`.
+    match make() {
+        whole @ Some(ref inner) => {}
+        //~^ ERROR cannot borrow `inner` while `whole` takes ownership of the value containing it
+        //~^^ NOTE `whole` is bound here
+        None => {}
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    let r: Result<int, (int, int)> = Ok(1);
+    match Some(r) {
+        Some(Ok(x) | Err((y, _))) => {}
+        //~^ ERROR variable `x` is not bound in all alternatives of this or-pattern
+        //~^^ ERROR variable `y` is not bound in all alternatives of this or-pattern
+        _ => {}
+    }
+    match Some((1i, 2i)) {
+        Some((x, 1) | (ref x, 2)) => {}
+        //~^ ERROR variable `x` is bound with different modes in the alternatives of this or-pattern
+        _ => {}
+    }
+}
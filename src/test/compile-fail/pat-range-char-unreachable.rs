@@ -0,0 +1,32 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+fn main() {
+    match 'c' {
+        'a'...'m' => {}
+        'n'...'z' => {}
+        'c'...'p' => {} //~ ERROR unreachable pattern
+        _ => {}
+    }
+
+    match 'c' {
+        '\0'...'\uD7FF' => {}
+        '\uE000'...'\U0010FFFF' => {}
+        _ => {} //~ ERROR unreachable pattern
+    }
+
+    match 'c' {
+        '\0'..'a' => {}
+        'a' => {}
+        'b'...'\U0010FFFF' => {}
+        'a'..'b' => {} //~ ERROR unreachable pattern
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+fn main() {
+    match 5u {
+        5u..5u => {} //~ ERROR lower range bound must be less than upper
+        6u..1u => {} //~ ERROR lower range bound must be less than upper
+        _ => {}
+    }
+
+    match 1.0f64 {
+        0.0..1.0 => {} //~ ERROR exclusive range patterns are only allowed on integers and chars
+        _ => {}
+    }
+}
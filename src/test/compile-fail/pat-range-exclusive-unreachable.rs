@@ -0,0 +1,25 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+fn main() {
+    match 5u {
+        0u..10u => {}
+        5u...9u => {} //~ ERROR unreachable pattern
+        10u => {}
+        _ => {}
+    }
+
+    match 5u8 {
+        0..128 => {}
+        128...255 => {}
+        _ => {} //~ ERROR unreachable pattern
+    }
+}
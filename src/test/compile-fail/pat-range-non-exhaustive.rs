@@ -0,0 +1,22 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+fn main() {
+    match 5u8 { //~ ERROR non-exhaustive patterns: `_` not covered
+        0...127 => {}
+        128..255 => {}
+    }
+
+    match 5i8 { //~ ERROR non-exhaustive patterns: `_` not covered
+        -128..0 => {}
+        1...127 => {}
+    }
+}
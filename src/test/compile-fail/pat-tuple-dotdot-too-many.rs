@@ -0,0 +1,27 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+struct Pair(int, int);
+
+enum Shape {
+    Line(int, int),
+}
+
+fn main() {
+    let (a, b, .., c) = (1i, 2i);
+    //~^ ERROR 3 fields besides the `..`, but the corresponding tuple has only 2 fields
+    let Pair(d, .., e, f) = Pair(1, 2);
+    //~^ ERROR 3 fields besides the `..`, but the corresponding struct has only 2 fields
+    match Line(1, 2) {
+        Line(.., g, h, i) => {}
+        //~^ ERROR 3 fields besides the `..`, but the corresponding variant has only 2 fields
+    }
+}
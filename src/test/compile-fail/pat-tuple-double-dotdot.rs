@@ -0,0 +1,19 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+struct Five(int, int, int, int, int);
+
+fn main() {
+    let (a, .., b, ..) = (1i, 2i, 3i, 4i, 5i);
+    //~^ ERROR `..` can only be used once per tuple or tuple struct pattern
+    let Five(.., c, ..) = Five(1, 2, 3, 4, 5);
+    //~^ ERROR `..` can only be used once per tuple or tuple struct pattern
+}
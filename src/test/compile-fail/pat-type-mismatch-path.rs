@@ -0,0 +1,48 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Config {
+    retries: uint,
+    name: String
+}
+
+struct Settings {
+    config: Config,
+    verbose: bool
+}
+
+fn main() {
+    let s = Settings {
+        config: Config { retries: 3, name: "x".to_string() },
+        verbose: false
+    };
+    match s {
+        Settings { config: Config { retries: "three", .. }, .. } => {}
+        //~^ ERROR mismatched types: expected `uint`, found `&'static str`
+        //~^^ NOTE in field `config.retries` of pattern `Settings { .. }`
+        _ => {}
+    }
+
+    let v: &[Option<(int, bool)>] = &[];
+    match v {
+        [_, Some((_, 'c')), ..] => {}
+        //~^ ERROR mismatched types: expected `bool`, found `char`
+        //~^^ NOTE in field `[1].Some.0.1` of pattern `[..]`
+        _ => {}
+    }
+
+    match (1i, v) {
+        (_, [Some((_, true)), rest.., None]) => {}
+        (_, [Some(1i), ..]) => {}
+        //~^ ERROR mismatched types: expected `(int, bool)`, found `int`
+        //~^^ NOTE in field `1[0].Some.0` of pattern `(..)`
+        _ => {}
+    }
+}
@@ -16,7 +16,7 @@ enum Option<T> {
 fn main() {
     match &mut Some(1i) {
         ref mut z @ &Some(ref a) => {
-        //~^ ERROR pattern bindings are not allowed after an `@`
+        //~^ ERROR cannot borrow `a` while `z` borrows the value containing it as mutable
             **z = None;
             println!("{}", *a);
         }
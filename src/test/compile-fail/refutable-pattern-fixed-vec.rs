@@ -0,0 +1,20 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn f([0, y]: [int, ..2]) -> int { y } //~ ERROR refutable pattern in function argument
+
+fn main() {
+    let pair = [1i, 2];
+    let [1, y] = pair; //~ ERROR refutable pattern in local binding: `[_, _]` not covered
+
+    let v = vec![1i, 2];
+    let s: &[int] = v.as_slice();
+    let [a, b] = s; //~ ERROR refutable pattern in local binding: `[]` not covered
+}
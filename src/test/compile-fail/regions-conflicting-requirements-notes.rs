@@ -0,0 +1,20 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that the conflicting requirements on an inferred lifetime are
+// labeled where each requirement comes from.
+
+fn to_static<'a>(x: &'a int) -> &'static int {
+    &*x
+    //~^ ERROR cannot infer an appropriate lifetime for borrow expression
+    //~| LABEL re: ^first, the lifetime cannot outlive the lifetime 'a .*so that reference
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that the notes explaining a lifetime mismatch point at the
+// scope that defines the anonymous lifetime involved.
+
+trait T<'a> {
+    fn a(&'a self) -> &'a bool;
+    fn b(&self) {
+    //~^ NOTE the anonymous lifetime #1 defined on the block
+        self.a(); //~ ERROR mismatched types: expected `&'a Self`, found `&Self` (lifetime mismatch)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// revisions: a b
+// should-fail
+
+// Revision `b` is built without `--cfg a`, so it does not report the
+// error that is expected of it; compiletest must reject the test.
+
+#[cfg(a)]
+fn only_in_a() {
+    let x: int = "a"; //[a,b]~ ERROR mismatched types
+}
+
+fn main() {
+    let y: int = "both"; //~ ERROR mismatched types
+}
@@ -0,0 +1,26 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// revisions: a b
+// deny-unexpected-errors
+
+// Check that an error expected only of revision `a` is not required of
+// revision `b`, and that `b` may not report it either.
+
+#![allow(dead_code)]
+
+#[cfg(a)]
+fn only_in_a() {
+    let x: int = "a"; //[a]~ ERROR mismatched types
+}
+
+fn main() {
+    let y: int = "both"; //~ ERROR mismatched types
+}
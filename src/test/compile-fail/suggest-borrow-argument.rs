@@ -0,0 +1,28 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that passing a value where a reference to it is expected suggests
+// borrowing it.
+
+fn takes_ref(_: &int) {}
+
+fn takes_mut(_: &mut Vec<int>) {}
+
+fn main() {
+    let x = 5i;
+    takes_ref(x);
+    //~^ ERROR mismatched types
+    //~| HELP try `&x`
+
+    let mut v = vec![1i];
+    takes_mut(v);
+    //~^ ERROR mismatched types
+    //~| HELP try `&mut v`
+}
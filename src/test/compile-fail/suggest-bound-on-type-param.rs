@@ -0,0 +1,40 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a missing bound on a type parameter is suggested where the
+// parameter is declared, whether on a function or on an impl.
+
+trait Speak {
+    fn speak(&self) -> String;
+}
+
+fn loud<T: Speak>(x: &T) -> String {
+    x.speak()
+}
+
+fn unbounded<T>(x: &T) -> String { //~ HELP consider adding a bound: `T: Speak`
+    loud(x) //~ ERROR the trait `Speak` is not implemented for the type `T`
+}
+
+fn bounded<T: Clone>(x: &T) -> String { //~ HELP consider adding a bound: `T: Clone + Speak`
+    loud(x) //~ ERROR the trait `Speak` is not implemented for the type `T`
+}
+
+struct Wrapper<T> {
+    value: T,
+}
+
+impl<T> Wrapper<T> { //~ HELP consider adding a bound: `T: Speak`
+    fn describe(&self) -> String {
+        loud(&self.value) //~ ERROR the trait `Speak` is not implemented for the type `T`
+    }
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that moving out of a borrowed value in a pattern suggests binding
+// by reference instead.
+
+struct Foo {
+    name: String,
+}
+
+fn main() {
+    let foo = Foo { name: "foo".to_string() };
+    let r = &foo;
+    match *r { //~ ERROR cannot move out of dereference of `&`-pointer
+        Foo { name: n } => drop(n),
+        //~^ NOTE attempting to move value to here
+        //~| HELP to prevent the move, bind by reference with `ref n`
+    }
+}
@@ -12,6 +12,6 @@ trait TraitNotAStruct { }
 
 fn main() {
     TraitNotAStruct{ value: 0 };
-    //~^ ERROR: use of trait `TraitNotAStruct` as a struct constructor [E0159]
+    //~^ ERROR: use of trait `TraitNotAStruct` as a struct constructor
 }
 
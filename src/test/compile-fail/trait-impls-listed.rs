@@ -0,0 +1,30 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:trait_impls_listed_lib.rs
+
+// Check that an unsatisfied trait bound lists the impls of the trait,
+// both those of this crate and those of other crates.
+
+extern crate trait_impls_listed_lib;
+
+use trait_impls_listed_lib::Speak;
+
+struct Local;
+
+impl Speak for Local {} //~ HELP re: impl .*Speak for Local
+
+fn needs<T: Speak>(_: T) {}
+
+fn main() {
+    needs(1i); //~ ERROR is not implemented for the type `int`
+    //~^ HELP the following implementations were found
+    //~| HELP re: impl .*Speak for uint` in crate `trait_impls_listed_lib`
+}
@@ -8,8 +8,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// compile-flags:-Z no-dedup-diagnostics
+
 // Tests that the trait matching code takes lifetime parameters into account.
 // (Issue #15517.)
+//
+// Both lifetimes are mismatched, giving two errors that differ only in the
+// notes that follow them, so deduplication is turned off.
 
 struct Foo<'a,'b> {
     x: &'a int,
@@ -23,7 +28,7 @@ trait Tr {
 impl<'a,'b> Tr for Foo<'a,'b> {
     fn foo(x: Foo<'b,'a>) {
         //~^ ERROR method not compatible with trait
-        //~^^ ERROR method not compatible with trait
+        //~| ERROR method not compatible with trait
     }
 }
 
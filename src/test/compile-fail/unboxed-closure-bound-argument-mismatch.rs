@@ -0,0 +1,45 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that a closure bound which differs from the one required only
+// in the number of arguments, or in the type of a single argument, is
+// reported in terms of those arguments.
+
+#![feature(overloaded_calls)]
+
+fn call_with_char<F: Fn(char)>(f: F) {
+    f('x');
+}
+
+fn call_with_pair<F: Fn(int, uint)>(f: F) {
+    f(1, 2);
+}
+
+fn wrong_argument<F: Fn(int)>(f: F) {
+    call_with_char(f);
+    //~^ ERROR expected a closure whose argument 1 has type `int`, but the bound requires `char`
+}
+
+fn too_many_arguments<F: Fn(int, int)>(f: F) {
+    call_with_char(f);
+    //~^ ERROR expected a closure taking 2 arguments of types `int`, `int`, but the bound
+}
+
+fn too_few_arguments<F: Fn()>(f: F) {
+    call_with_pair(f);
+    //~^ ERROR expected a closure taking no arguments, but the bound requires 2 arguments
+}
+
+fn wrong_second_argument<F: Fn(int, char)>(f: F) {
+    call_with_pair(f);
+    //~^ ERROR expected a closure whose argument 2 has type `char`, but the bound requires `uint`
+}
+
+fn main() { }
@@ -11,31 +11,30 @@
 #![feature(unboxed_closures)]
 
 // Tests that we can't move out of an unboxed closure environment
-// if the upvar is captured by ref or the closure takes self by
-// reference.
+// if the closure takes self by reference.
 
 fn main() {
-    // By-ref cases
+    // Inferred by-value cases
     {
         let x = box 0u;
-        let f = |&:| drop(x); //~ cannot move
+        let f = |&:| drop(x); //~ ERROR closure is declared `Fn` but moves out
     }
     {
         let x = box 0u;
-        let f = |&mut:| drop(x); //~ cannot move
+        let f = |&mut:| drop(x); //~ ERROR closure is declared `FnMut` but moves out
     }
     {
         let x = box 0u;
-        let f = |:| drop(x); //~ cannot move
+        let f = |:| drop(x); // this one is ok
     }
-    // By-value cases
+    // Explicit by-value cases
     {
         let x = box 0u;
-        let f = move |&:| drop(x); //~ cannot move
+        let f = move |&:| drop(x); //~ ERROR closure is declared `Fn` but moves out
     }
     {
         let x = box 0u;
-        let f = move |&mut:| drop(x); //~ cannot move
+        let f = move |&mut:| drop(x); //~ ERROR closure is declared `FnMut` but moves out
     }
     {
         let x = box 0u;
@@ -0,0 +1,89 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the unboxed closure sugar can be used in every position
+// where a trait bound is expected -- bounds lists, where clauses,
+// supertraits, impl generics and object types wherever they appear --
+// and that it is equivalent to the same syntax using angle brackets in
+// each of them.
+
+#![allow(dead_code)]
+
+trait Foo<T,U> { }
+
+trait Eq<X> { }
+impl<X> Eq<X> for X { }
+fn eq<A,B:Eq<A>>() { }
+
+fn angle<F:Foo<(int,),uint>>() { }
+
+// Bounds lists:
+fn bound<F:Foo(int) -> uint>() { angle::<F>() }
+
+// Where clauses:
+fn where_clause<F>() where F : Foo(int) -> uint { angle::<F>() }
+
+// Supertraits:
+trait Sub : Foo(int) -> uint { }
+fn supertrait<F:Sub>() { angle::<F>() }
+
+// Bounds on the generics of inherent impls:
+struct Holder<F> { f: F }
+impl<F:Foo(int) -> uint> Holder<F> {
+    fn impl_bound() { angle::<F>() }
+}
+
+// Object types:
+fn object<'a>() {
+    eq::< &'a Foo<(int,),uint>,         &'a Foo(int) -> uint          >();
+    eq::< Box<Foo<(int,uint),()>>,      Box<Foo(int,uint)>            >();
+}
+
+// Object types in struct fields:
+struct Fields<'a> {
+    by_ref: &'a Foo(int) -> uint,
+    boxed: Box<Foo(int,uint)>,
+}
+fn fields<'a>(f: Fields<'a>) {
+    let _: &'a Foo<(int,),uint> = f.by_ref;
+    let _: Box<Foo<(int,uint),()>> = f.boxed;
+}
+
+// Object types in type aliases:
+type Alias<'a> = &'a Foo(int) -> uint;
+fn alias<'a>() {
+    eq::< &'a Foo<(int,),uint>,         Alias<'a>                     >();
+}
+
+// Object types in fn pointer types:
+fn fn_pointer<'a>() {
+    eq::< fn(&'a Foo<(int,),uint>),     fn(&'a Foo(int) -> uint)      >();
+    eq::< fn() -> Box<Foo<(),()>>,      fn() -> Box<Foo()>            >();
+}
+
+// Errors expected:
+fn where_clause_mismatch<F>() where F : Foo(int) -> int { angle::<F>() }
+//~^ ERROR not implemented
+
+trait SubMismatch : Foo(uint) -> uint { }
+fn supertrait_mismatch<F:SubMismatch>() { angle::<F>() }
+//~^ ERROR not implemented
+
+impl<F:Foo(int) -> int> Holder<F> {
+    fn impl_bound_mismatch() { angle::<F>() }
+    //~^ ERROR not implemented
+}
+
+fn alias_mismatch<'a>() {
+    eq::< &'a Foo<(int,),int>,          Alias<'a>                     >();
+    //~^ ERROR not implemented
+}
+
+fn main() { }
@@ -8,17 +8,24 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// deny-unexpected-errors
+
 // Test that the unboxed closure sugar can be used with an arbitrary
 // struct type and that it is equivalent to the same syntax using
-// angle brackets. This test covers only simple types and in
-// particular doesn't test bound regions.
+// angle brackets.
 
 #![allow(dead_code)]
 
+use std::ops::DivergingOutput;
+
 struct Foo<T,U> {
     t: T, u: U
 }
 
+struct Bar<'a> {
+    x: &'a int
+}
+
 trait Eq<X> { }
 impl<X> Eq<X> for X { }
 fn eq<A,B:Eq<A>>() { }
@@ -31,9 +38,29 @@ fn test<'a,'b>() {
     eq::< Foo<(int,uint),uint>,         Foo(int,uint) -> uint         >();
     eq::< Foo<(&'a int,&'b uint),uint>, Foo(&'a int,&'b uint) -> uint >();
 
+    // An elided region in the output refers to the sole region of the
+    // inputs, as with fn types:
+    eq::< Foo<(&'a int,),&'a int>,      Foo(&'a int) -> &int          >();
+    eq::< Foo<(&'a int,uint),&'a uint>, Foo(&'a int,uint) -> &uint    >();
+    eq::< Foo<(Bar<'a>,),&'a int>,      Foo(Bar<'a>) -> &int          >();
+
+    // An omitted output is `()`, and `!` is the empty `DivergingOutput`:
+    eq::< Foo<(int,),()>,               Foo(int) -> ()                >();
+    eq::< Foo<(int,),DivergingOutput>,  Foo(int) -> !                 >();
+
     // Errors expected:
     eq::< Foo<(),()>,                   Foo(char)                     >();
+    //~^ ERROR expected a closure taking 1 argument of type `char`, but the bound requires no
+    eq::< Foo<(int,),()>,               Foo()                         >();
+    //~^ ERROR expected a closure taking no arguments, but the bound requires 1 argument of
+    eq::< Foo<(int,uint),()>,           Foo(int,char)                 >();
+    //~^ ERROR expected a closure whose argument 2 has type `char`, but the bound requires `uint`
+    eq::< Foo<(int,),uint>,             Foo(int) -> !                 >();
+    //~^ ERROR not implemented
+    eq::< Foo(int),                     Foo(int) -> !                 >();
     //~^ ERROR not implemented
+    eq::< Foo<(int,),uint>,             Foo(int) -> _                 >();
+    //~^ ERROR the output type of the parenthetical notation cannot be inferred
 }
 
 fn main() { }
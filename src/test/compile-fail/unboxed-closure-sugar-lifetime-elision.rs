@@ -0,0 +1,28 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the lifetime elision rules of fn types apply to the output
+// of the unboxed closure sugar.
+
+#![feature(unboxed_closures)]
+
+fn f<F: Fn(&int, &uint) -> &int>(_: F) { }
+//~^ ERROR missing lifetime specifier
+//~^^ HELP the signature does not say whether it is borrowed from `&int` or `&uint`
+
+fn g<F>(_: F) where F: for<'a> Fn(&'a int, &int) -> &int { }
+//~^ ERROR missing lifetime specifier
+//~^^ HELP the signature does not say whether it is borrowed from `&'a int` or `&int`
+
+fn h<F: Fn() -> &int>(_: F) { }
+//~^ ERROR missing lifetime specifier
+//~^^ HELP there is no value for it to be borrowed from
+
+fn main() { }
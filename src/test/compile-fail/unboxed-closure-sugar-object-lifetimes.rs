@@ -0,0 +1,36 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that a closure stored in an object type written with the closure
+// sugar and a `+ 'a` bound must not capture anything shorter-lived than
+// `'a`.
+
+#![feature(unboxed_closures, overloaded_calls)]
+
+struct Event {
+    code: uint,
+}
+
+type Filter<'a> = Box<Fn(&Event) -> bool + 'a>;
+
+struct Registry<'a> {
+    filters: Vec<Filter<'a>>,
+}
+
+fn register<'a>(r: &mut Registry<'a>) {
+    let limit = 3u;
+    r.filters.push(box |&: e: &Event| e.code < limit);
+    //~^ ERROR cannot infer an appropriate lifetime
+}
+
+fn main() {
+    let mut r = Registry { filters: Vec::new() };
+    register(&mut r);
+}
@@ -0,0 +1,21 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the unboxed closure sugar is rejected with a targeted error
+// when it is applied to a struct in supertrait position.
+
+struct Bar<A,R> {
+    a: A, r: R
+}
+
+trait Baz : Bar(int) -> int { }
+//~^ ERROR `Bar` is not a trait; parenthesized parameters may only be used with a trait
+
+fn main() { }
@@ -0,0 +1,20 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that calling a boxed `FnOnce` closure consumes it.
+
+#![feature(overloaded_calls, unboxed_closures)]
+
+fn main() {
+    let s = "once".to_string();
+    let f: Box<FnOnce() -> String> = box move |:| s;
+    f();
+    f(); //~ ERROR use of moved value: `f`
+}
@@ -0,0 +1,26 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that calling a trait object of a function trait checks the
+// arguments against the trait's signature.
+
+#![feature(unboxed_closures, overloaded_calls)]
+
+use std::ops::Fn;
+
+fn main() {
+    let f: Box<Fn(int)> = box |&: _: int| { };
+    f();
+    //~^ ERROR this function takes 1 parameter but 0 parameters were supplied
+    f(1, 2);
+    //~^ ERROR this function takes 1 parameter but 2 parameters were supplied
+    f("one");
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,33 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that an unboxed closure is only `Clone` if it holds its upvars by
+// value and all of them are `Clone`.
+
+#![feature(unboxed_closures)]
+
+struct NotClone;
+
+fn dup<F: Clone>(f: &F) -> (F, F) {
+    (f.clone(), f.clone())
+}
+
+fn main() {
+    let token = NotClone;
+    let f = move |&:| { let _ = &token; }; //~ ERROR not implemented for the type `NotClone`
+    //~^ NOTE the closure that captures `token` requires that all captured variables
+    dup(&f);
+
+    // A closure that captures by reference may hold unique borrows, so it
+    // is never `Clone`.
+    let x = 3i;
+    let g = |&:| x + 1;
+    dup(&g); //~ ERROR the trait `core::clone::Clone` is not implemented
+}
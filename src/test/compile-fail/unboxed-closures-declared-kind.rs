@@ -0,0 +1,47 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// deny-unexpected-errors
+
+#![feature(unboxed_closures)]
+
+// Tests that uses of captured variables which conflict with the
+// declared kind of an unboxed closure are reported at the offending
+// expression, with a note pointing back at the declaration.
+
+fn set(x: &mut uint) { *x = 5; }
+
+fn main() {
+    let mut count = 0u;
+    let _f = |&:| {
+    //~^ NOTE the closure is declared `Fn` here
+        count += 1; //~ ERROR closure is declared `Fn` but mutates the captured variable `count`
+    };
+
+    let mut total = 0u;
+    let _g = |&:| {
+    //~^ NOTE declare it with `&mut:` to make it `FnMut`, or with `:` to make it `FnOnce`
+        set(&mut total); //~ ERROR closure is declared `Fn` but mutates the captured variable
+    };
+
+    let names = vec!["a".to_string()];
+    let _h = |&mut:| {
+    //~^ NOTE the closure is declared `FnMut` here; declare it with `:` to make it `FnOnce`
+        let taken = names; //~ ERROR closure is declared `FnMut` but moves out of the captured
+        taken.len()
+    };
+
+    // Reading a capture is fine for every kind, as is mutating one
+    // from an `FnMut` closure.
+    let reads = 0u;
+    let _i = |&:| reads + 1;
+    let mut hits = 0u;
+    let _j = |&mut:| hits += 1;
+}
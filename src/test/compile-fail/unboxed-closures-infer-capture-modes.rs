@@ -0,0 +1,32 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that capture inference moves upvars that an unboxed closure
+// consumes and borrows the ones it only reads or mutates.
+
+#![feature(overloaded_calls, unboxed_closures)]
+
+fn main() {
+    let v = vec![1i];
+    let consume = |:| drop(v);
+    //~^ NOTE `v` moved into closure environment here
+    println!("{}", v); //~ ERROR use of moved value: `v`
+    consume();
+
+    let mut x = 0i;
+    let read = |&:| x + 1;
+    x = 2; //~ ERROR cannot assign to `x` because it is borrowed
+    read();
+
+    let mut y = 0i;
+    let mut write = |&mut:| y += 1;
+    let _z = y; //~ ERROR cannot use `y` because it was mutably borrowed
+    write();
+}
@@ -0,0 +1,22 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that a `move` unboxed closure moves its captured variables, even
+// when its body only reads them.
+
+#![feature(overloaded_calls, unboxed_closures)]
+
+fn main() {
+    let s = "hello".to_string();
+    let f = move |&:| s.len();
+    //~^ NOTE `s` moved into closure environment here because it has type
+    println!("{}", s); //~ ERROR use of moved value: `s`
+    f();
+}
@@ -0,0 +1,27 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that a closure which borrows an upvar from the environment of
+// the closure enclosing it cannot outlive the body of that closure.
+
+#![feature(unboxed_closures, overloaded_calls)]
+
+fn escape<'a>(out: &mut Vec<Box<Fn<(), uint> + 'a>>) {
+    let v = vec![1u, 2, 3];
+    let mut outer = move |&mut:| {
+        out.push(box |&:| v.len()); //~ ERROR cannot infer an appropriate lifetime
+    };
+    outer();
+}
+
+fn main() {
+    let mut fs = Vec::new();
+    escape(&mut fs);
+}
@@ -0,0 +1,24 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that only unboxed closures which capture nothing can be coerced to
+// bare `fn` pointers.
+
+#![feature(unboxed_closures)]
+
+fn main() {
+    let y = 1i;
+    let f: fn(int) -> int = |&: x: int| x + y;
+    //~^ ERROR cannot coerce an unboxed closure that captures variables to a fn pointer
+    //~^^ NOTE the closure captures `y` here
+    let table: [fn(int) -> int, ..1] = [|&: x: int| x * y];
+    //~^ ERROR cannot coerce an unboxed closure that captures variables to a fn pointer
+    //~^^ NOTE the closure captures `y` here
+}
@@ -0,0 +1,30 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that only safe Rust fns implement the `Fn` traits.
+
+#![feature(overloaded_calls, unboxed_closure_sugar)]
+
+use std::ops::Fn;
+
+unsafe fn unsafe_square(x: int) -> int { x * x }
+
+extern "C" fn c_square(x: int) -> int { x * x }
+
+fn call_it<F: Fn(int) -> int>(f: F, x: int) -> int {
+    f(x)
+}
+
+fn main() {
+    let f: unsafe fn(int) -> int = unsafe_square;
+    call_it(f, 2); //~ ERROR not implemented
+    let g: extern "C" fn(int) -> int = c_square;
+    call_it(g, 2); //~ ERROR not implemented
+}
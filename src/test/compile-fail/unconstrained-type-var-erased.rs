@@ -0,0 +1,22 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// An unconstrained type variable is reported once, and later passes see
+// `ty_err` in its place rather than the variable itself. The integer
+// variable is not the subject of any obligation, so it is writeback that
+// reports it, and the array around it is kept.
+
+fn main() {
+    let x = [1, 2, 3];
+    //~^ ERROR cannot determine a type for this local variable: cannot determine the type of this
+    let y = x.as_slice();
+    let n = y.len();
+    let z = x;
+}
@@ -13,7 +13,7 @@ enum Foo<T> { FooSome(T), FooNone }
 fn bar<T: Sized>() { }
 fn foo<Sized? T>() { bar::<Foo<T>>() }
 //~^ ERROR the trait `core::kinds::Sized` is not implemented
-//~^^ ERROR the trait `core::kinds::Sized` is not implemented
+//~| ERROR the trait `core::kinds::Sized` is not implemented
 //
 // One error is for T being provided to Foo<T>, the other is
 // for Foo<T> being provided to bar.
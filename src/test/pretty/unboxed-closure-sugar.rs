@@ -0,0 +1,41 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the parenthetical `Fn(A) -> B` sugar pretty-prints as
+// written, in bounds, where clauses, object types and paths, and
+// that the output converges.
+
+#![feature(unboxed_closures)]
+#![allow(dead_code)]
+
+struct Foo<T,U> {
+    t: T, u: U
+}
+
+trait Eq<X> { }
+impl<X> Eq<X> for X { }
+fn eq<A,B:Eq<A>>() { }
+
+fn paths<'a,'b>() {
+    eq::< Foo<(),()>,                   Foo()                         >();
+    eq::< Foo<(int,uint),uint>,         Foo(int,uint) -> uint         >();
+    eq::< Foo<(&'a int,&'b uint),uint>, Foo(&'a int,&'b uint) -> uint >();
+    eq::< Foo<(int,),()>,               Foo(int) -> !                 >();
+}
+
+fn bound<F: Fn(int) -> int>(f: &F) -> int { 0 }
+
+fn where_bound<F>(f: &F) -> int where F: FnMut(int, int) -> int { 0 }
+
+fn higher_ranked<F: for<'a, 'b> Fn(&'a int, &'b int) -> &'a int>(f: F) { }
+
+fn object<'a>(f: Box<FnOnce(int) -> int + 'a>) { }
+
+fn main() { }
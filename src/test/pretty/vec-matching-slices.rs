@@ -0,0 +1,61 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that subslice patterns, both bare `..` and `rest..` bindings in
+// every position, pretty-print as surface syntax and converge.
+
+#![feature(advanced_slice_patterns)]
+
+fn first_and_rest(x: &[int]) -> int {
+    match x {
+        [] => 0,
+        [a, rest..] => a + rest.len() as int,
+    }
+}
+
+fn rest_and_last(x: &[int]) -> int {
+    match x {
+        [] => 0,
+        [rest.., a] => a + rest.len() as int,
+    }
+}
+
+fn middle(x: &[int]) -> uint {
+    match x {
+        [_, mid.., _] => mid.len(),
+        [ref whole..] => whole.len(),
+    }
+}
+
+fn prefix(x: &[int]) -> int {
+    match x {
+        [1, 2, ..] => 1,
+        [.., 3] => 2,
+        [..] => 3,
+    }
+}
+
+fn nested(x: &[&[int]]) -> int {
+    match x {
+        [[a, ..], [.., b], ..] => a + b,
+        _ => 0,
+    }
+}
+
+pub fn main() {
+    let v = [1i, 2, 3];
+    assert_eq!(first_and_rest(v.as_slice()), 3);
+    assert_eq!(rest_and_last(v.as_slice()), 5);
+    assert_eq!(middle(v.as_slice()), 1);
+    assert_eq!(prefix(v.as_slice()), 1);
+    let a: &[int] = &[4];
+    let b: &[int] = &[5, 6];
+    assert_eq!(nested(&[a, b]), 10);
+}
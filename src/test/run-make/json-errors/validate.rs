@@ -0,0 +1,88 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Checks the JSON that rustc writes for errors.rs under
+// `--error-format=json`: every line must be a well-formed diagnostic, and
+// the two errors in errors.rs must be reported where they are, along with
+// the suggestion to borrow the argument.
+
+extern crate serialize;
+
+use serialize::json;
+use serialize::json::Json;
+use std::io::{BufferedReader, File};
+use std::os;
+
+fn check_span(span: &Json) {
+    assert!(span.find("file_name").unwrap().is_string());
+    let line_start = span.find("line_start").unwrap().as_u64().unwrap();
+    let line_end = span.find("line_end").unwrap().as_u64().unwrap();
+    assert!(line_start <= line_end);
+    assert!(span.find("column_start").unwrap().as_u64().unwrap() >= 1);
+    assert!(span.find("column_end").unwrap().as_u64().unwrap() >= 1);
+    assert!(span.find("is_primary").unwrap().is_boolean());
+    for key in ["label", "suggested_replacement"].iter() {
+        let value = span.find(*key).unwrap();
+        assert!(value.is_string() || value.is_null());
+    }
+}
+
+fn check_diagnostic(diagnostic: &Json) {
+    assert!(diagnostic.find("message").unwrap().is_string());
+    let code = diagnostic.find("code").unwrap();
+    assert!(code.is_string() || code.is_null());
+    let level = diagnostic.find("level").unwrap().as_string().unwrap();
+    assert!(["error", "warning", "note", "help"].iter().any(|l| *l == level));
+    for span in diagnostic.find("spans").unwrap().as_list().unwrap().iter() {
+        check_span(span);
+    }
+}
+
+fn primary_line(diagnostic: &Json) -> Option<u64> {
+    diagnostic.find("spans").unwrap().as_list().unwrap().iter()
+        .find(|span| span.find("is_primary").unwrap().as_boolean().unwrap())
+        .map(|span| span.find("line_start").unwrap().as_u64().unwrap())
+}
+
+fn main() {
+    let path = Path::new(os::args()[1].as_slice());
+    let mut reader = BufferedReader::new(File::open(&path).unwrap());
+    let diagnostics: Vec<Json> = reader.lines().map(|line| {
+        json::from_str(line.unwrap().as_slice()).unwrap()
+    }).collect();
+
+    for diagnostic in diagnostics.iter() {
+        check_diagnostic(diagnostic);
+    }
+
+    let errors: Vec<&Json> = diagnostics.iter().filter(|diagnostic| {
+        diagnostic.find("level").unwrap().as_string() == Some("error") &&
+            primary_line(*diagnostic).is_some()
+    }).collect();
+    assert_eq!(errors.len(), 2);
+
+    let missing = errors.iter().find(|error| {
+        error.find("code").unwrap().as_string() == Some("E0046")
+    }).unwrap();
+    assert_eq!(primary_line(*missing), Some(17));
+
+    let mismatch = errors.iter().find(|error| {
+        error.find("message").unwrap().as_string().unwrap().starts_with("mismatched types")
+    }).unwrap();
+    assert_eq!(primary_line(*mismatch), Some(22));
+
+    // The replacement has quotes and backslashes in it, which have to
+    // survive being escaped.
+    let suggestion = diagnostics.iter()
+        .flat_map(|diagnostic| diagnostic.find("spans").unwrap().as_list().unwrap().iter())
+        .filter_map(|span| span.find("suggested_replacement").unwrap().as_string())
+        .next();
+    assert_eq!(suggestion, Some(r#"&"say \"hi\"".to_string()"#));
+}
@@ -0,0 +1,74 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Box patterns nested inside array, slice, tuple and struct patterns.
+
+struct Pair {
+    first: Box<String>,
+    second: Box<int>,
+}
+
+struct Named {
+    name: Box<String>,
+    age: Box<uint>,
+}
+
+fn sum_slice(v: &[Box<int>]) -> int {
+    match v {
+        [] => 0,
+        [box a] => a,
+        [box a, box ref b, ..] => a + *b,
+    }
+}
+
+pub fn main() {
+    let arr = [box 1i, box 2];
+    match arr {
+        [box a, box b] => assert_eq!(a + b, 3),
+    }
+
+    let strings = [box "a".to_string(), box "b".to_string()];
+    let [box first, box second] = strings;
+    assert_eq!(first.as_slice(), "a");
+    assert_eq!(second.as_slice(), "b");
+
+    assert_eq!(sum_slice(&[]), 0);
+    assert_eq!(sum_slice(&[box 5]), 5);
+    assert_eq!(sum_slice(&[box 5, box 6, box 7]), 11);
+
+    let tuple = (box 3i, box "three".to_string());
+    match tuple {
+        (box 4, _) => panic!("matched the wrong number"),
+        (box n, box ref s) => {
+            assert_eq!(n, 3);
+            assert_eq!(s.as_slice(), "three");
+        }
+    }
+
+    let pair = Pair { first: box "first".to_string(), second: box 2 };
+    match pair {
+        Pair { second: box 1, .. } => panic!("matched the wrong number"),
+        Pair { first: box ref s, second: box n } => {
+            assert_eq!(s.as_slice(), "first");
+            assert_eq!(n, 2);
+        }
+    }
+    let Pair { first: box s, .. } = pair;
+    assert_eq!(s.as_slice(), "first");
+
+    let mut named = Named { name: box "anne".to_string(), age: box 30 };
+    match named {
+        Named { box ref mut age, .. } => *age += 1,
+    }
+    let Named { box name, box age } = named;
+    assert_eq!(name.as_slice(), "anne");
+    assert_eq!(age, 31);
+}
@@ -8,10 +8,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// compile-flags:
+// revisions: foo notfoo
 
-// check that cfg correctly chooses between the macro impls (see also
-// cfg-macros-foo.rs)
+// check that cfg correctly chooses between the macro impls
 
 #![feature(macro_rules)]
 
@@ -32,5 +31,5 @@ mod foo {
 }
 
 pub fn main() {
-    assert!(!bar!())
+    assert_eq!(bar!(), cfg!(foo))
 }
@@ -0,0 +1,28 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// check-stdout
+
+// Tests the formatting of floating point numbers, which is checked against
+// the expected standard output.
+
+use std::f64;
+
+pub fn main() {
+    println!("{}", 1.5f64);
+    println!("{}", 0.5f32);
+    println!("{}", 10000000000f64);
+    println!("{:.2}", 3.14159f64);
+    println!("{:8.3}|", 1.0f64);
+    println!("{:<8.1}|", -2.5f64);
+    println!("{}", f64::NAN);
+    println!("{}", f64::INFINITY);
+    println!("{}", f64::NEG_INFINITY);
+}
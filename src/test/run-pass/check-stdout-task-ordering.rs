@@ -0,0 +1,51 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// check-stdout
+
+// Tests that output from several tasks appears in the order that their
+// synchronization imposes, along with the destructors run as each task
+// finishes, by checking it against the expected standard output.
+
+struct Noisy {
+    name: &'static str,
+}
+
+impl Drop for Noisy {
+    fn drop(&mut self) {
+        println!("dropping {}", self.name);
+    }
+}
+
+pub fn main() {
+    let _outer = Noisy { name: "main's guard" };
+    println!("main: start");
+
+    for i in range(0u, 3) {
+        let (tx, rx) = channel();
+        spawn(proc() {
+            let _guard = Noisy { name: "child's guard" };
+            println!("child {}: running", i);
+            tx.send(());
+        });
+        rx.recv();
+        // The child may still be running its destructor, so wait until
+        // its end of the channel hangs up.
+        assert!(rx.recv_opt().is_err());
+        println!("main: child {} done", i);
+    }
+
+    {
+        let _first = Noisy { name: "first" };
+        let _second = Noisy { name: "second" };
+        println!("main: leaving scope");
+    }
+    println!("main: end");
+}
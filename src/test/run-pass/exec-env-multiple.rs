@@ -0,0 +1,22 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// exec-env:TEST_EXEC_ENV_FIRST=1
+// exec-env:TEST_EXEC_ENV_SECOND=key=value
+
+// Check that every `exec-env` directive is applied when running the test,
+// and that a value is taken to be everything after the first `=`.
+
+use std::os;
+
+pub fn main() {
+    assert_eq!(os::getenv("TEST_EXEC_ENV_FIRST"), Some("1".to_string()));
+    assert_eq!(os::getenv("TEST_EXEC_ENV_SECOND"), Some("key=value".to_string()));
+}
@@ -0,0 +1,48 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The unboxed closure spellings are not caught by the boxed closure lint,
+// and neither are closure types produced by macro expansion.
+
+#![feature(unboxed_closures, unboxed_closure_sugar, overloaded_calls, macro_rules)]
+#![deny(boxed_closures)]
+
+macro_rules! apply_boxed {
+    ($x:expr) => ({
+        fn call(f: |int| -> int, x: int) -> int { f(x) }
+        call(|y| y + 1, $x)
+    })
+}
+
+struct Holder<F: Fn(&str) -> uint> {
+    f: F
+}
+
+fn apply<F: Fn(int) -> int>(f: F) -> int { f(1) }
+
+fn apply_mut(f: &mut FnMut(int) -> int) -> int { f.call_mut((2,)) }
+
+fn spawn_it(f: Box<FnOnce<(int,), int> + Send>) -> int { f.call_once((3,)) }
+
+fn main() {
+    assert_eq!(apply(|&: x: int| x * 2), 2);
+    assert_eq!(apply_mut(&mut |&mut: x: int| x * 3), 6);
+    assert_eq!(spawn_it(box |: x: int| x * 4), 12);
+
+    let len = |&: s: &str| s.len();
+    let h = Holder { f: len };
+    assert_eq!((h.f)("four"), 4);
+
+    // Closure expressions whose types are inferred are never linted.
+    let x = 5i;
+    assert_eq!((|y: int| x + y)(1), 6);
+
+    assert_eq!(apply_boxed!(1), 2);
+}
@@ -0,0 +1,53 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Bindings are only seen by reference while the guard of their arm runs, so
+// an arm whose guard fails leaves the matched value intact for the next arms.
+
+fn describe(x: Option<String>) -> String {
+    match x {
+        Some(s) if s.len() > 5 => format!("long {}", s),
+        Some(s) if s.len() > 2 => format!("short {}", s),
+        Some(s) => s,
+        None => "none".to_string()
+    }
+}
+
+fn first_even(v: Vec<Box<int>>) -> Option<Box<int>> {
+    let mut iter = v.into_iter();
+    loop {
+        match iter.next() {
+            Some(b) if *b % 2 == 0 => return Some(b),
+            Some(_) => {}
+            None => return None
+        }
+    }
+}
+
+pub fn main() {
+    assert_eq!(describe(Some("abcdefg".to_string())).as_slice(), "long abcdefg");
+    assert_eq!(describe(Some("abcd".to_string())).as_slice(), "short abcd");
+    assert_eq!(describe(Some("ab".to_string())).as_slice(), "ab");
+    assert_eq!(describe(None).as_slice(), "none");
+
+    assert_eq!(first_even(vec![box 1, box 3, box 4, box 6]), Some(box 4));
+    assert_eq!(first_even(vec![box 1, box 3]), None);
+
+    let pair = (box 1i, "two".to_string());
+    match pair {
+        (a, b) if *a == 2 => panic!("{} {}", a, b),
+        (a, b) if b.as_slice() == "three" => panic!("{}", a),
+        (a, b) => {
+            assert_eq!(*a, 1);
+            assert_eq!(b.as_slice(), "two");
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(if_let)]
+
+// Variants with an argument of an uninhabited type can't be constructed, so
+// a match doesn't need to cover them.
+
+enum Void {}
+
+struct Wrapper {
+    void: Void,
+    n: int,
+}
+
+enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+fn unwrap(r: Result<int, Void>) -> int {
+    match r {
+        Ok(x) => x
+    }
+}
+
+fn left(e: Either<int, (int, Wrapper)>) -> int {
+    match e {
+        Left(n) => n
+    }
+}
+
+fn nested(o: Option<Result<String, Void>>) -> uint {
+    match o {
+        Some(Ok(s)) => s.len(),
+        None => 0
+    }
+}
+
+#[allow(dead_code)]
+fn absurd(v: Void) -> int {
+    match v {}
+}
+
+#[allow(dead_code)]
+fn absurd_struct(w: Wrapper) -> int {
+    match w {}
+}
+
+pub fn main() {
+    assert_eq!(unwrap(Ok(3)), 3);
+    assert_eq!(left(Left(4)), 4);
+    assert_eq!(nested(Some(Ok("five".to_string()))), 4);
+    assert_eq!(nested(None), 0);
+
+    let r: Result<int, Void> = Ok(6);
+    let Ok(x) = r;
+    assert_eq!(x, 6);
+
+    let r: Result<int, Void> = Ok(7);
+    if let Ok(y) = r {
+        assert_eq!(y, 7);
+    } else {
+        panic!();
+    }
+}
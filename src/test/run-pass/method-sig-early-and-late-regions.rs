@@ -0,0 +1,60 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test method calls whose signature mentions `Self`, early-bound
+// lifetimes (from the trait, and from a method bound) and late-bound
+// lifetimes all at once, through an impl and through a type parameter.
+
+trait Chooser<'a> {
+    fn choose<'b>(&'b self, long: &'a int, short: &'b int) -> (Self, &'a int);
+
+    fn keep<'c, T: 'c>(&self, x: &'c T, _y: &int) -> (Self, &'c T);
+}
+
+#[deriving(PartialEq, Show)]
+struct Flip(bool);
+
+impl<'a> Chooser<'a> for Flip {
+    fn choose<'b>(&'b self, long: &'a int, short: &'b int) -> (Flip, &'a int) {
+        let Flip(b) = *self;
+        assert!(*short > 0);
+        (Flip(!b), long)
+    }
+
+    fn keep<'c, T: 'c>(&self, x: &'c T, _y: &int) -> (Flip, &'c T) {
+        let Flip(b) = *self;
+        (Flip(b), x)
+    }
+}
+
+fn through_param<'a, C: Chooser<'a>>(c: &C, long: &'a int) -> (C, &'a int) {
+    let short = 1i;
+    c.choose(long, &short)
+}
+
+pub fn main() {
+    let long = 22i;
+    let flip = Flip(true);
+    {
+        let short = 3i;
+        let (flipped, l) = flip.choose(&long, &short);
+        assert_eq!(flipped, Flip(false));
+        assert_eq!(*l, 22);
+    }
+
+    let (flipped, l) = through_param(&flip, &long);
+    assert_eq!(flipped, Flip(false));
+    assert_eq!(*l, 22);
+
+    let s = "kept".to_string();
+    let (same, k) = flip.keep(&s, &long);
+    assert_eq!(same, Flip(true));
+    assert_eq!(k.as_slice(), "kept");
+}
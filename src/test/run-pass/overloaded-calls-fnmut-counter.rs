@@ -0,0 +1,37 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that a struct implementing `FnMut` can be called with call
+// syntax repeatedly, with each call updating its state.
+
+#![feature(overloaded_calls)]
+
+use std::ops::FnMut;
+
+struct Counter {
+    count: int,
+}
+
+impl FnMut<(int,), int> for Counter {
+    extern "rust-call" fn call_mut(&mut self, (step,): (int,)) -> int {
+        self.count += step;
+        self.count
+    }
+}
+
+fn main() {
+    let mut counter = Counter { count: 0 };
+    let mut totals = Vec::new();
+    for i in range(1i, 5) {
+        totals.push(counter(i));
+    }
+    assert_eq!(totals, vec![1, 3, 6, 10]);
+    assert_eq!(counter.count, 10);
+}
@@ -0,0 +1,40 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that a struct implementing `Fn` satisfies an `F: Fn(int) -> int`
+// bound, and that the bounded parameter can be called with call syntax.
+
+#![feature(overloaded_calls, unboxed_closure_sugar)]
+
+use std::ops::Fn;
+
+struct Scale {
+    factor: int,
+}
+
+impl Fn<(int,), int> for Scale {
+    extern "rust-call" fn call(&self, (x,): (int,)) -> int {
+        x * self.factor
+    }
+}
+
+fn apply_twice<F: Fn(int) -> int>(f: F, x: int) -> int {
+    f(f(x))
+}
+
+fn sum_mapped<F: Fn(int) -> int>(f: &F, xs: &[int]) -> int {
+    xs.iter().fold(0, |acc, &x| acc + (*f)(x))
+}
+
+fn main() {
+    let triple = Scale { factor: 3 };
+    assert_eq!(sum_mapped(&triple, &[1, 2, 3]), 18);
+    assert_eq!(apply_twice(triple, 2), 18);
+}
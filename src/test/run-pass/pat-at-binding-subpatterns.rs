@@ -0,0 +1,62 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn describe(x: Option<int>) -> (Option<int>, int) {
+    match x {
+        whole @ Some(ref inner) => (whole, *inner),
+        None => (None, 0),
+    }
+}
+
+fn first_two(v: [int, ..2]) -> ([int, ..2], int) {
+    match v {
+        arr @ [first, _] => (arr, first),
+    }
+}
+
+fn head(v: &[String]) -> uint {
+    match v {
+        all @ [ref first, ..] => all.len() + first.len(),
+        [] => 0,
+    }
+}
+
+pub fn main() {
+    assert_eq!(describe(Some(3)), (Some(3), 3));
+    assert_eq!(describe(None), (None, 0));
+
+    assert_eq!(first_two([1, 2]), ([1, 2], 1));
+
+    let v = vec!["ab".to_string(), "c".to_string()];
+    assert_eq!(head(v.as_slice()), 4);
+
+    // The whole value is moved while a copy of one of its fields is
+    // bound next to it.
+    match ("hello".to_string(), 5u) {
+        pair @ (_, n) => {
+            assert_eq!(pair.ref0().as_slice(), "hello");
+            assert_eq!(n, 5);
+        }
+    }
+    let pair @ (_, n) = ("world".to_string(), 6u);
+    assert_eq!(pair.ref0().as_slice(), "world");
+    assert_eq!(n, 6);
+
+    let mut opt = Some("x".to_string());
+    match opt {
+        ref whole @ Some(ref s) => {
+            assert!(whole.is_some());
+            assert_eq!(s.as_slice(), "x");
+        }
+        None => panic!(),
+    }
+    opt = None;
+    assert!(opt.is_none());
+}
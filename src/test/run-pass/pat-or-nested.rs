@@ -0,0 +1,83 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+enum Shape {
+    Circle(f64),
+    Square(f64),
+    Rect(f64, f64),
+}
+
+fn classify(x: Option<int>) -> &'static str {
+    match x {
+        Some(1 | 2) => "small",
+        Some(3 | 4 | 5) => "medium",
+        Some(_) => "large",
+        None => "none",
+    }
+}
+
+fn side(s: &Shape) -> f64 {
+    match *s {
+        Circle(x) | Square(x) => x,
+        Rect(x, 1.0 | 2.0) => x,
+        Rect(_, y) => y,
+    }
+}
+
+fn first_or_last(v: &[int]) -> int {
+    match v {
+        [0 | 1, x, ..] => x,
+        [.., x, 9 | 10] => x,
+        [x, ..] => x,
+        [] => -1,
+    }
+}
+
+fn unwrap_either(r: Option<Result<int, int>>) -> int {
+    match r {
+        Some(Ok(x) | Err(x)) => x,
+        None => 0,
+    }
+}
+
+fn pair(p: (bool, Option<int>)) -> int {
+    match p {
+        (true | false, Some(x @ (1 | 2))) => x * 10,
+        (true, Some(_) | None) => 1,
+        (false, _) => 2,
+    }
+}
+
+pub fn main() {
+    assert_eq!(classify(Some(1)), "small");
+    assert_eq!(classify(Some(2)), "small");
+    assert_eq!(classify(Some(4)), "medium");
+    assert_eq!(classify(Some(6)), "large");
+    assert_eq!(classify(None), "none");
+
+    assert_eq!(side(&Circle(1.0)), 1.0);
+    assert_eq!(side(&Square(2.0)), 2.0);
+    assert_eq!(side(&Rect(3.0, 2.0)), 3.0);
+    assert_eq!(side(&Rect(3.0, 4.0)), 4.0);
+
+    assert_eq!(first_or_last(&[1, 7, 8]), 7);
+    assert_eq!(first_or_last(&[5, 7, 10]), 7);
+    assert_eq!(first_or_last(&[5, 7, 8]), 5);
+    assert_eq!(first_or_last(&[]), -1);
+
+    assert_eq!(unwrap_either(Some(Ok(3))), 3);
+    assert_eq!(unwrap_either(Some(Err(4))), 4);
+    assert_eq!(unwrap_either(None), 0);
+
+    assert_eq!(pair((false, Some(2))), 20);
+    assert_eq!(pair((true, Some(3))), 1);
+    assert_eq!(pair((true, None)), 1);
+    assert_eq!(pair((false, None)), 2);
+}
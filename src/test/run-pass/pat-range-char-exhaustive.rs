@@ -0,0 +1,53 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Char ranges are checked over the Unicode scalar values, so a set of ranges
+// covering them is exhaustive without a wildcard, whether or not a range spans
+// the surrogate code points.
+
+fn plane(c: char) -> uint {
+    match c {
+        '\0'...'\uFFFF' => 0,
+        '\U00010000'..'\U00020000' => 1,
+        '\U00020000'...'\U0010FFFF' => 2
+    }
+}
+
+fn around_surrogates(c: char) -> bool {
+    match c {
+        '\0'..'\uD000' => false,
+        '\uD000'...'\uE000' => true,
+        '\uE001'...'\U0010FFFF' => false
+    }
+}
+
+fn split_at_surrogates(c: char) -> bool {
+    match c {
+        '\0'...'\uD7FF' => true,
+        '\uE000'...'\U0010FFFF' => false
+    }
+}
+
+pub fn main() {
+    assert_eq!(plane('a'), 0);
+    assert_eq!(plane('\uFFFF'), 0);
+    assert_eq!(plane('\U00010000'), 1);
+    assert_eq!(plane('\U0001FFFF'), 1);
+    assert_eq!(plane('\U0010FFFF'), 2);
+
+    assert!(!around_surrogates('a'));
+    assert!(around_surrogates('\uD7FF'));
+    assert!(around_surrogates('\uE000'));
+    assert!(!around_surrogates('\uE001'));
+
+    assert!(split_at_surrogates('\uD7FF'));
+    assert!(!split_at_surrogates('\uE000'));
+}
@@ -0,0 +1,54 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+fn classify(x: u8) -> uint {
+    match x {
+        0..10 => 0,
+        10...127 => 1,
+        128..255 => 2,
+        255 => 3
+    }
+}
+
+fn sign(x: i8) -> int {
+    match x {
+        -128..0 => -1,
+        0 => 0,
+        1...127 => 1
+    }
+}
+
+pub fn main() {
+    assert_eq!(classify(0), 0);
+    assert_eq!(classify(9), 0);
+    assert_eq!(classify(10), 1);
+    assert_eq!(classify(127), 1);
+    assert_eq!(classify(128), 2);
+    assert_eq!(classify(254), 2);
+    assert_eq!(classify(255), 3);
+
+    assert_eq!(sign(-128), -1);
+    assert_eq!(sign(-1), -1);
+    assert_eq!(sign(0), 0);
+    assert_eq!(sign(127), 1);
+
+    match 'c' {
+        'a'..'c' => panic!("exclusive range matched its upper bound"),
+        'c'..'z' => {}
+        _ => panic!("should match char range")
+    }
+
+    let halves = match 200u8 {
+        0...127 => "low",
+        128...255 => "high"
+    };
+    assert_eq!(halves, "high");
+}
@@ -0,0 +1,70 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// A single `..` may stand for any number of elements, at any position in
+// a tuple or tuple-struct pattern.
+
+struct Five(int, int, int, int, int);
+
+enum Shape {
+    Triangle(int, int, int),
+    Square(int, int, int, int),
+}
+
+fn ends((first, .., last): (int, int, int, int, int)) -> (int, int) {
+    (first, last)
+}
+
+fn corners(shape: Shape) -> int {
+    match shape {
+        Triangle(a, ..) => a,
+        Square(.., d) => d,
+    }
+}
+
+fn main() {
+    let five_tuple = (1i, 2i, 3i, 4i, 5i);
+
+    let (first, .., last) = five_tuple;
+    assert_eq!((first, last), (1, 5));
+    let (.., fourth, fifth) = five_tuple;
+    assert_eq!((fourth, fifth), (4, 5));
+    let (first, second, ..) = five_tuple;
+    assert_eq!((first, second), (1, 2));
+    let (a, b, c, d, e, ..) = five_tuple;
+    assert_eq!((a, b, c, d, e), five_tuple);
+    let (..) = five_tuple;
+    assert_eq!(ends(five_tuple), (1, 5));
+
+    let five = Five(1, 2, 3, 4, 5);
+    let Five(first, .., last) = five;
+    assert_eq!((first, last), (1, 5));
+    let Five(.., ref fifth) = five;
+    assert_eq!(*fifth, 5);
+    let Five(ref first, ref second, ..) = five;
+    assert_eq!((*first, *second), (1, 2));
+
+    let described = match (true, 'a', 0u, "x", false) {
+        (false, ..) => "starts false",
+        (.., false) => "ends false",
+        (true, .., true) => "true at both ends",
+    };
+    assert_eq!(described, "ends false");
+
+    match five {
+        Five(1, .., 4) => panic!(),
+        Five(.., 3, 4, 5) => {}
+        Five(..) => panic!(),
+    }
+
+    assert_eq!(corners(Triangle(1, 2, 3)), 1);
+    assert_eq!(corners(Square(1, 2, 3, 4)), 4);
+}
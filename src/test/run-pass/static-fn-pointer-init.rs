@@ -0,0 +1,28 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Statics and constants of fn pointer type may still be initialized with fn items.
+
+fn double(x: int) -> int { x * 2 }
+
+static F: fn(int) -> int = double;
+const G: fn(int) -> int = double;
+
+struct Ops {
+    op: fn(int) -> int,
+}
+
+static OPS: Ops = Ops { op: double };
+
+fn main() {
+    assert_eq!(F(2), 4);
+    assert_eq!(G(3), 6);
+    assert_eq!((OPS.op)(4), 8);
+}
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// no-parallel
 // ignore-macos osx really doesn't like cycling through large numbers of
 //              sockets as calls to connect() will start returning EADDRNOTAVAIL
 //              quite quickly and it takes a few seconds for the sockets to get
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// no-parallel
 // ignore-linux see joyent/libuv#1189
 // ignore-android needs extra network permissions
 // exec-env:RUST_LOG=debug
@@ -0,0 +1,85 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests the closure sugar in object types that carry a lifetime bound,
+// inside type aliases and struct fields with lifetime parameters.
+
+#![feature(unboxed_closures, overloaded_calls)]
+
+struct Event {
+    code: uint,
+}
+
+type Callback<'a> = Box<FnMut(&Event) + 'a>;
+type Filter<'a> = Box<Fn(&Event) -> bool + 'a>;
+type Handler<'a> = Box<FnMut(&'a Event) -> uint + 'a>;
+
+struct Dispatcher<'a> {
+    callbacks: Vec<Callback<'a>>,
+    filters: Vec<Filter<'a>>,
+    fallback: Option<Box<Fn(uint) -> uint + 'a>>,
+}
+
+impl<'a> Dispatcher<'a> {
+    fn new() -> Dispatcher<'a> {
+        Dispatcher { callbacks: Vec::new(), filters: Vec::new(), fallback: None }
+    }
+
+    fn set_fallback(&mut self, f: Box<Fn(uint) -> uint + 'a>) {
+        self.fallback = Some(f);
+    }
+
+    fn dispatch(&mut self, e: &Event) -> uint {
+        if self.filters.iter().all(|f| f(e)) {
+            for cb in self.callbacks.iter_mut() {
+                cb(e);
+            }
+            self.callbacks.len()
+        } else {
+            match self.fallback {
+                Some(ref f) => f(e.code),
+                None => 0,
+            }
+        }
+    }
+}
+
+fn make_handler<'a>(total: &'a mut uint) -> Handler<'a> {
+    box move |&mut: e: &'a Event| { *total += e.code; *total }
+}
+
+fn run_handler<'a>(h: &mut Handler<'a>, e: &'a Event) -> uint {
+    h(e)
+}
+
+pub fn main() {
+    let mut seen = Vec::new();
+    let limit = 10u;
+    {
+        let mut d = Dispatcher::new();
+        d.callbacks.push(box |&mut: e: &Event| seen.push(e.code));
+        d.filters.push(box |&: e: &Event| e.code < limit);
+        d.set_fallback(box |&: code: uint| code - limit);
+
+        assert_eq!(d.dispatch(&Event { code: 1 }), 1);
+        assert_eq!(d.dispatch(&Event { code: 2 }), 1);
+        assert_eq!(d.dispatch(&Event { code: 15 }), 5);
+    }
+    assert_eq!(seen, vec![1, 2]);
+
+    let e = Event { code: 7 };
+    let mut total = 0u;
+    {
+        let mut h = make_handler(&mut total);
+        assert_eq!(run_handler(&mut h, &e), 7);
+        assert_eq!(run_handler(&mut h, &e), 14);
+    }
+    assert_eq!(total, 14);
+}
@@ -0,0 +1,49 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that boxed `FnOnce` closures can be called with call syntax,
+// which consumes the box.
+
+#![feature(overloaded_calls, unboxed_closures)]
+
+fn run_all(tasks: Vec<Box<FnOnce(uint) -> String>>) -> Vec<String> {
+    let mut results = Vec::new();
+    for (i, task) in tasks.into_iter().enumerate() {
+        results.push(task(i));
+    }
+    results
+}
+
+fn main() {
+    let greeting = "hello".to_string();
+    let numbers = vec![1u, 2, 3];
+
+    let mut tasks: Vec<Box<FnOnce(uint) -> String>> = Vec::new();
+    tasks.push(box move |: i: uint| format!("{} {}", i, greeting));
+    tasks.push(box move |: i: uint| {
+        let numbers = numbers;
+        format!("{} {}", i, numbers.into_iter().fold(0, |a, b| a + b))
+    });
+    tasks.push(box |: i: uint| i.to_string());
+
+    let results = run_all(tasks);
+    assert_eq!(results, vec!["0 hello".to_string(),
+                             "1 6".to_string(),
+                             "2".to_string()]);
+
+    // A boxed `FnOnce` can be sent to another task and run there.
+    let name = "task".to_string();
+    let task: Box<FnOnce<(), uint> + Send> = box move |:| name.len();
+    let (tx, rx) = channel();
+    spawn(proc() {
+        tx.send(task());
+    });
+    assert_eq!(rx.recv(), 4);
+}
@@ -0,0 +1,58 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that trait objects of the function traits can be called with
+// the ordinary call syntax.
+
+#![feature(unboxed_closures, overloaded_calls)]
+
+use std::ops::{Fn, FnMut};
+
+struct Double;
+
+impl Fn<(int,),int> for Double {
+    extern "rust-call" fn call(&self, (x,): (int,)) -> int {
+        x * 2
+    }
+}
+
+fn make_adder(x: int) -> Box<Fn(int) -> int> {
+    box move |&: y: int| -> int { x + y }
+}
+
+fn make_counter() -> Box<FnMut() -> int> {
+    let mut count = 0;
+    box move |&mut:| -> int { count += 1; count }
+}
+
+fn apply_all(fs: &Vec<Box<Fn(int) -> int>>, x: int) -> Vec<int> {
+    fs.iter().map(|f| f(x)).collect()
+}
+
+fn call_ref(f: &Fn(int, int) -> int) -> int {
+    f(3, 4)
+}
+
+pub fn main() {
+    let mut fs: Vec<Box<Fn(int) -> int>> = Vec::new();
+    fs.push(make_adder(1));
+    fs.push(make_adder(10));
+    fs.push(box Double);
+    fs.push(box |&: x: int| -> int { x * x });
+    assert_eq!(apply_all(&fs, 5), vec![6, 15, 10, 25]);
+    assert_eq!(fs[1](7), 17);
+
+    let mut counter = make_counter();
+    assert_eq!(counter(), 1);
+    assert_eq!(counter(), 2);
+
+    let add = |&: x: int, y: int| -> int { x + y };
+    assert_eq!(call_ref(&add), 7);
+}
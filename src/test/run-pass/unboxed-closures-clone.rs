@@ -0,0 +1,61 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that unboxed closures which hold their upvars by value are `Copy`
+// or `Clone` when their upvars are, and that clones have independent state.
+
+#![feature(overloaded_calls, unboxed_closures)]
+
+fn dup<F: Clone>(f: &F) -> (F, F) {
+    (f.clone(), f.clone())
+}
+
+fn main() {
+    // A `move` closure capturing only `Copy` values is itself `Copy`.
+    let base = 10i;
+    let add = move |&: x: int| x + base;
+    let add_copy = add;
+    assert_eq!(add(1), 11);
+    assert_eq!(add_copy(2), 12);
+
+    // A clone starts from the state at the time of cloning and then
+    // evolves independently.
+    let mut count = 0i;
+    let mut counter = move |&mut:| { count += 1; count };
+    assert_eq!(counter(), 1);
+    let mut other = counter.clone();
+    assert_eq!(counter(), 2);
+    assert_eq!(other(), 2);
+    assert_eq!(other(), 3);
+    assert_eq!(counter(), 3);
+
+    // Upvars that are `Clone` but not `Copy` are cloned member-wise.
+    let mut v = vec![1i, 2, 3];
+    let mut push = move |&mut: x: int| { v.push(x); v.len() };
+    let (mut first, mut second) = dup(&push);
+    assert_eq!(push(4), 4);
+    assert_eq!(push(5), 5);
+    assert_eq!(first(6), 4);
+    assert_eq!(second(7), 4);
+    assert_eq!(second(8), 5);
+
+    // A closure capturing a clonable closure is clonable too.
+    let names = vec!["a".to_string(), "b".to_string()];
+    let count_names = move |&:| names.len();
+    let outer = move |&: extra: uint| count_names() + extra;
+    let outer_clone = outer.clone();
+    assert_eq!(outer(1), 3);
+    assert_eq!(outer_clone(2), 4);
+
+    // A closure that captures nothing is `Copy`.
+    let unit = |&:| 7i;
+    let unit_copy = unit;
+    assert_eq!(unit() + unit_copy(), 14);
+}
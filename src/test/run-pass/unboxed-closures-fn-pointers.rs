@@ -0,0 +1,76 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that bare fn types, both fn items and fn pointers, implement the
+// `Fn` traits and can be passed to APIs bounded by them.
+
+#![feature(overloaded_calls, unboxed_closure_sugar)]
+
+use std::ops::{Fn, FnMut, FnOnce};
+
+fn square(x: int) -> int { x * x }
+
+fn answer() -> int { 42 }
+
+fn first<'a>(x: &'a int, _: &'a int) -> &'a int { x }
+
+fn deref(x: &int) -> int { *x }
+
+fn call_it<F: Fn(int) -> int>(f: &F, x: int) -> int {
+    (*f)(x)
+}
+
+fn call_it_mut<F: FnMut(int) -> int>(f: &mut F, x: int) -> int {
+    (*f)(x)
+}
+
+fn call_it_once<F: FnOnce(int) -> int>(f: F, x: int) -> int {
+    f(x)
+}
+
+fn call_nullary<F: Fn() -> int>(f: F) -> int {
+    f()
+}
+
+// Higher-ranked bounds are satisfied by higher-ranked fn types.
+fn call_deref<F>(f: F) -> int where F: for<'a> Fn(&'a int) -> int {
+    let x = 3;
+    let y = 4;
+    f(&x) + f(&y)
+}
+
+fn call_first<F>(f: F) -> int where F: for<'a> Fn(&'a int, &'a int) -> &'a int {
+    let x = 5;
+    let y = 6;
+    *f(&x, &y)
+}
+
+fn main() {
+    // fn items
+    assert_eq!(call_it(&square, 3), 9);
+    assert_eq!(call_it_mut(&mut square, 4), 16);
+    assert_eq!(call_it_once(square, 5), 25);
+    assert_eq!(call_nullary(answer), 42);
+    assert_eq!(call_deref(deref), 7);
+    assert_eq!(call_first(first), 5);
+
+    // fn pointers
+    let mut f: fn(int) -> int = square;
+    assert_eq!(call_it(&f, 6), 36);
+    assert_eq!(call_it_mut(&mut f, 7), 49);
+    assert_eq!(call_it_once(f, 8), 64);
+
+    let g: fn(&int) -> int = deref;
+    assert_eq!(call_deref(g), 7);
+
+    // fn pointers as trait objects
+    let h: &Fn(int) -> int = &f;
+    assert_eq!(h(9), 81);
+}
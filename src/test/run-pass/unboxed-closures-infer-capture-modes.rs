@@ -0,0 +1,63 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that unboxed closures not declared `move` capture each upvar by
+// reference or by value depending on how the body uses it.
+
+#![feature(overloaded_calls, unboxed_closures)]
+
+fn count_matching<F: Fn(int) -> bool>(xs: &[int], f: F) -> uint {
+    xs.iter().filter(|&&x| f(x)).count()
+}
+
+fn main() {
+    // A read-only capture borrows, so the original stays usable.
+    let name = "closure".to_string();
+    let len = |&:| name.len();
+    assert_eq!(len(), 7);
+    assert_eq!(name.as_slice(), "closure");
+
+    let threshold = 2i;
+    assert_eq!(count_matching(&[1, 2, 3, 4], |&: x: int| x > threshold), 2);
+    assert_eq!(threshold, 2);
+
+    // A mutating capture borrows uniquely, and the mutation is visible
+    // once the closure is gone.
+    let mut count = 0u;
+    {
+        let mut bump = |&mut:| count += 1;
+        bump();
+        bump();
+    }
+    assert_eq!(count, 2);
+
+    // A consumed capture is moved into the closure, so the closure can
+    // be sent to another task.
+    let words = vec!["a".to_string(), "b".to_string()];
+    let join = |:| {
+        let words = words;
+        words.concat()
+    };
+    let (tx, rx) = channel();
+    spawn(proc() {
+        tx.send(join());
+    });
+    assert_eq!(rx.recv().as_slice(), "ab");
+
+    // Only the consumed upvar is moved; the other one is still borrowed.
+    let owned = box 5i;
+    let shared = 6i;
+    let take = |:| {
+        let owned = owned;
+        *owned + shared
+    };
+    assert_eq!(take(), 11);
+    assert_eq!(shared, 6);
+}
@@ -0,0 +1,38 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that a `move` unboxed closure captures its upvars by value, so that
+// a closure which only reads owned data can still be sent to another task.
+
+#![feature(overloaded_calls, unboxed_closures)]
+
+fn main() {
+    let greeting = "hello".to_string();
+    let numbers = vec![1i, 2, 3];
+    let describe = move |&:| {
+        format!("{} {}", greeting, numbers.iter().fold(0, |a, &b| a + b))
+    };
+
+    let (tx, rx) = channel();
+    spawn(proc() {
+        tx.send(describe());
+    });
+    assert_eq!(rx.recv().as_slice(), "hello 6");
+
+    let owned = box 10i;
+    let (tx, rx) = channel();
+    let add = move |&: x: int| *owned + x;
+    spawn(proc() {
+        tx.send(add(5));
+        tx.send(add(7));
+    });
+    assert_eq!(rx.recv(), 15);
+    assert_eq!(rx.recv(), 17);
+}
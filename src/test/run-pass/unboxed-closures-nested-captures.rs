@@ -0,0 +1,120 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests closures nested inside unboxed closures that use variables the
+// enclosing closure captured. The inner closure captures from the
+// enclosing closure's environment, by reference or by value, and every
+// level sees the same variable.
+
+#![feature(unboxed_closures, overloaded_calls)]
+
+fn two_levels_by_ref() {
+    let x = 3i;
+    let outer = |&:| {
+        let inner = |&:| x + 1;
+        inner() + x
+    };
+    assert_eq!(outer(), 7);
+    assert_eq!(outer(), 7);
+    assert_eq!(x, 3);
+}
+
+fn two_levels_mutating() {
+    let mut count = 0i;
+    {
+        let mut outer = |&mut:| {
+            let mut inner = |&mut:| count += 1;
+            inner();
+            inner();
+        };
+        outer();
+        outer();
+    }
+    assert_eq!(count, 4);
+}
+
+fn by_value_outer_by_ref_inner() {
+    let v = vec![1i, 2, 3];
+    let outer = move |&:| {
+        let inner = |&:| v.len();
+        inner() + v.len()
+    };
+    assert_eq!(outer(), 6);
+    assert_eq!(outer(), 6);
+}
+
+fn by_ref_outer_by_value_inner() {
+    let n = 5i;
+    let outer = |&:| {
+        let inner = move |&:| n * 2;
+        inner()
+    };
+    assert_eq!(outer(), 10);
+    assert_eq!(n, 5);
+}
+
+fn moved_through_two_levels() {
+    let v = vec![1i, 2, 3];
+    let outer = move |:| {
+        let inner = move |:| v;
+        inner()
+    };
+    assert_eq!(outer(), vec![1i, 2, 3]);
+}
+
+fn three_levels_mutating() {
+    let mut total = 0i;
+    let step = 2i;
+    {
+        let mut outer = |&mut:| {
+            let mut middle = |&mut:| {
+                let mut inner = |&mut:| total += step;
+                inner();
+                inner();
+            };
+            middle();
+        };
+        outer();
+        outer();
+    }
+    assert_eq!(total, 8);
+    assert_eq!(step, 2);
+}
+
+fn three_levels_mixed() {
+    let prefix = "n".to_string();
+    let mut names = Vec::new();
+    {
+        let mut outer = |&mut:| {
+            for i in range(0u, 2) {
+                // `make` owns its copy of `i`, and `inner` borrows it
+                // from `make`'s environment.
+                let make = move |&:| {
+                    let inner = |&:| i * 10;
+                    inner() + 1
+                };
+                names.push(format!("{}{}", prefix, make()));
+            }
+        };
+        outer();
+    }
+    assert_eq!(names, vec!["n1".to_string(), "n11".to_string()]);
+    assert_eq!(prefix.as_slice(), "n");
+}
+
+fn main() {
+    two_levels_by_ref();
+    two_levels_mutating();
+    by_value_outer_by_ref_inner();
+    by_ref_outer_by_value_inner();
+    moved_through_two_levels();
+    three_levels_mutating();
+    three_levels_mixed();
+}
@@ -0,0 +1,48 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that closures can be called through bounds written with the
+// unboxed closure sugar whose regions are bound by the bound itself,
+// either elided or introduced with `for<'a>`.
+
+#![feature(overloaded_calls, unboxed_closures)]
+
+use std::ops::Fn;
+
+struct Identity;
+
+impl<'a> Fn<(&'a int,), &'a int> for Identity {
+    extern "rust-call" fn call(&self, (x,): (&'a int,)) -> &'a int {
+        x
+    }
+}
+
+fn call_elided<F: Fn(&int) -> &int>(f: F) -> int {
+    let x = 3;
+    *f(&x)
+}
+
+fn call_explicit<F>(f: F) -> int where F: for<'a> Fn(&'a int) -> &'a int {
+    let x = 4;
+    let y = 5;
+    *f(&x) + *f(&y)
+}
+
+fn call_twice<F: Fn(&int) -> int>(f: F) -> int {
+    let x = 6;
+    let y = 7;
+    f(&x) + f(&y)
+}
+
+fn main() {
+    assert_eq!(call_elided(Identity), 3);
+    assert_eq!(call_explicit(Identity), 9);
+    assert_eq!(call_twice(|&: x: &int| *x * 2), 26);
+}
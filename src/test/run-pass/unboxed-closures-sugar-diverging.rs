@@ -0,0 +1,30 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a bound written with the unboxed closure sugar may have a
+// `!` output, that a diverging closure satisfies it, and that a call
+// through it diverges, so it can stand in for a value of any type.
+
+#![feature(unboxed_closures, overloaded_calls)]
+
+use std::ops::FnOnce;
+use std::task;
+
+fn call_diverging<F: FnOnce() -> !>(f: F) -> int {
+    f()
+}
+
+fn main() {
+    let result = task::try(proc() {
+        let x: int = call_diverging(|:| -> ! { panic!("diverged") });
+        println!("{}", x);
+    });
+    assert!(result.is_err());
+}
@@ -0,0 +1,37 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that unboxed closures which capture nothing can be coerced to
+// bare `fn` pointers.
+
+#![feature(unboxed_closures)]
+
+fn apply(f: fn(int) -> int, x: int) -> int {
+    f(x)
+}
+
+fn make_table() -> [fn(int) -> int, ..2] {
+    [|&: x: int| x + 1, |&: x: int| x * 2]
+}
+
+pub fn main() {
+    let table: [fn(int) -> int, ..2] = [|&: x: int| x + 1, |&: x: int| x * 2];
+    assert_eq!(table[0](5), 6);
+    assert_eq!(table[1](5), 10);
+
+    let other = make_table();
+    assert_eq!(other[0](1) + other[1](1), 4);
+
+    assert_eq!(apply(|&mut: x: int| x - 3, 10), 7);
+    assert_eq!(apply(|: x: int| -x, 10), -10);
+
+    let unit: fn() -> String = |&:| "unit".to_string();
+    assert_eq!(unit().as_slice(), "unit");
+}
@@ -0,0 +1,44 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Matches over fixed-length arrays only need to cover that one length.
+
+fn describe(xs: [int, ..3]) -> &'static str {
+    match xs {
+        [0, _, _] => "leading zero",
+        [_, 0, _] => "middle zero",
+        [_, _, 0] => "trailing zero",
+        [_, _, _] => "no zeros",
+    }
+}
+
+fn head(xs: [int, ..3]) -> int {
+    match xs {
+        [first, ..] => first
+    }
+}
+
+fn split(xs: [bool, ..2]) -> uint {
+    match xs {
+        [true, true] => 3,
+        [true, false] => 2,
+        [false, true] => 1,
+        [false, false] => 0,
+    }
+}
+
+pub fn main() {
+    assert_eq!(describe([0, 1, 2]), "leading zero");
+    assert_eq!(describe([1, 0, 2]), "middle zero");
+    assert_eq!(describe([1, 2, 0]), "trailing zero");
+    assert_eq!(describe([1, 2, 3]), "no zeros");
+    assert_eq!(head([7, 8, 9]), 7);
+    assert_eq!(split([true, false]), 2);
+}
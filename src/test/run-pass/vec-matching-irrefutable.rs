@@ -0,0 +1,39 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Patterns that cover every value of a fixed-length array are irrefutable,
+// so they can be used in `let` and in function arguments.
+
+fn norm([x, y, z]: [f64, ..3]) -> f64 {
+    (x * x + y * y + z * z).sqrt()
+}
+
+fn first_and_count([first, rest..]: [int, ..4]) -> (int, uint) {
+    (first, rest.len())
+}
+
+pub fn main() {
+    let coords = [3.0f64, 4.0, 0.0];
+    let [x, y, z] = coords;
+    assert_eq!(x, 3.0);
+    assert_eq!(y, 4.0);
+    assert_eq!(z, 0.0);
+    assert_eq!(norm(coords), 5.0);
+
+    let [head, tail..] = [1i, 2, 3];
+    assert_eq!(head, 1);
+    assert_eq!(tail, [2i, 3].as_slice());
+
+    let [ref a, ref b] = ["a".to_string(), "b".to_string()];
+    assert_eq!(a.as_slice(), "a");
+    assert_eq!(b.as_slice(), "b");
+
+    assert_eq!(first_and_count([5, 6, 7, 8]), (5, 3));
+}
@@ -0,0 +1,57 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(advanced_slice_patterns)]
+
+// Slice patterns of different lengths are dispatched on the length of the
+// slice; make sure the arms, and their guards, are still tried in order.
+
+use std::cell::RefCell;
+
+fn guard(log: &RefCell<Vec<uint>>, arm: uint, result: bool) -> bool {
+    log.borrow_mut().push(arm);
+    result
+}
+
+fn classify(xs: &[int], log: &RefCell<Vec<uint>>) -> uint {
+    match xs {
+        [a, rest..] if guard(log, 0, a == 0) => rest.len() * 10,
+        [] => 1,
+        [a] if guard(log, 2, a > 5) => 2,
+        [a, b] if guard(log, 3, a == b) => 3,
+        [a, ..] if guard(log, 4, a < 0) => 4,
+        [_, b, c] => (b + c) as uint,
+        [a.., b, c] if guard(log, 6, b == c) => a.len() * 100,
+        [_, rest.., z] if guard(log, 7, z > 0) => rest.len() * 1000,
+        _ => 8
+    }
+}
+
+fn check(xs: &[int], expected: uint, expected_guards: &[uint]) {
+    let log = RefCell::new(vec![]);
+    assert_eq!(classify(xs, &log), expected);
+    assert_eq!(log.borrow().as_slice(), expected_guards);
+}
+
+fn main() {
+    check(&[], 1, &[]);
+    check(&[0], 0, &[0]);
+    check(&[6], 2, &[0, 2]);
+    check(&[1], 8, &[0, 2, 4]);
+    check(&[-1], 4, &[0, 2, 4]);
+    check(&[0, 0, 0, 0], 30, &[0]);
+    check(&[1, 1], 3, &[0, 3]);
+    check(&[1, 2], 0, &[0, 3, 4, 6, 7]);
+    check(&[-1, 2], 4, &[0, 3, 4]);
+    check(&[1, 2, 3], 5, &[0, 4]);
+    check(&[1, 2, 3, 3], 200, &[0, 4, 6]);
+    check(&[1, 2, 3, 4, 5], 3000, &[0, 4, 6, 7]);
+    check(&[1, 2, 3, 4, -5], 8, &[0, 4, 6, 7]);
+}
@@ -0,0 +1,51 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(advanced_slice_patterns)]
+
+fn fixed() {
+    let m = [[1i, 2], [3, 4]];
+    match m {
+        [[a, ..], [b, c]] => {
+            assert_eq!(a, 1);
+            assert_eq!(b, 3);
+            assert_eq!(c, 4);
+        }
+    }
+
+    let rows = [[1i, 2], [3, 4], [5, 6]];
+    match rows {
+        [[0, _], ..] => panic!(),
+        [[a, ..], [b, c], rest..] => {
+            assert_eq!(a, 1);
+            assert_eq!(b + c, 7);
+            assert_eq!(rest.len(), 1);
+            assert_eq!(rest[0][1], 6);
+        }
+    }
+}
+
+fn slices(xss: &[&[int]]) -> int {
+    match xss {
+        [] => 0,
+        [[], rest..] => slices(rest),
+        [[x, ..], rest..] => x + slices(rest),
+    }
+}
+
+pub fn main() {
+    fixed();
+
+    let a: &[int] = &[1, 2];
+    let b: &[int] = &[];
+    let c: &[int] = &[10];
+    assert_eq!(slices(&[a, b, c]), 11);
+    assert_eq!(slices(&[b, b]), 0);
+}
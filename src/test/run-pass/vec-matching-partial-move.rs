@@ -0,0 +1,42 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Each element of a fixed-length vector matched by value is moved on
+// its own, so the remaining elements can still be used.
+
+fn main() {
+    let arr = ["a".to_string(), "b".to_string()];
+    let [first, _] = arr;
+    assert_eq!(first.as_slice(), "a");
+    {
+        let [_, ref second] = arr;
+        assert_eq!(second.as_slice(), "b");
+    }
+    let [_, second] = arr;
+    assert_eq!(second.as_slice(), "b");
+
+    let arr = ["a".to_string(), "b".to_string(), "c".to_string()];
+    {
+        let [ref a, ref b, ref c] = arr;
+        assert_eq!(a.as_slice(), "a");
+        assert_eq!(b.as_slice(), "b");
+        assert_eq!(c.as_slice(), "c");
+    }
+    match arr {
+        [x, _, z] => {
+            assert_eq!(x.as_slice(), "a");
+            assert_eq!(z.as_slice(), "c");
+        }
+    }
+    match arr {
+        [_, ref y, _] => assert_eq!(y.as_slice(), "b")
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(advanced_slice_patterns)]
+
+fn a() {
+    let names = ["a".to_string(), "b".to_string(), "c".to_string()];
+    match names {
+        [ref first, ref rest..] => {
+            let rest: &[String] = rest;
+            assert_eq!(first.as_slice(), "a");
+            assert_eq!(rest.len(), 2);
+            assert_eq!(rest[1].as_slice(), "c");
+        }
+    }
+    match names {
+        [ref init.., ref last] => {
+            assert_eq!(init.len(), 2);
+            assert_eq!(last.as_slice(), "c");
+        }
+    }
+    // Nothing was moved out of `names`.
+    assert_eq!(names[0].as_slice(), "a");
+}
+
+fn b() {
+    let mut names = ["a".to_string(), "b".to_string(), "c".to_string()];
+    match names {
+        [ref mut first, _, _] => first.push_str("1")
+    }
+    match names {
+        [_, ref mut rest..] => {
+            let rest: &mut [String] = rest;
+            rest[1].push_str("3");
+        }
+    }
+    assert_eq!(names[0].as_slice(), "a1");
+    assert_eq!(names[1].as_slice(), "b");
+    assert_eq!(names[2].as_slice(), "c3");
+}
+
+fn c() {
+    let mut v = vec![1i, 2, 3];
+    match v.as_mut_slice() {
+        [_, ref mut tail..] => {
+            for x in tail.iter_mut() {
+                *x *= 10;
+            }
+        }
+        _ => {}
+    }
+    assert_eq!(v, vec![1i, 20, 30]);
+}
+
+pub fn main() {
+    a();
+    b();
+    c();
+}
@@ -0,0 +1,52 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn classify(xs: &[int]) -> uint {
+    match xs {
+        [] => 0,
+        [_] => 1,
+        [_, _, ..] => 2
+    }
+}
+
+fn sum_first_two(xs: &[int]) -> int {
+    match xs {
+        [] => 0,
+        [a] => a,
+        [a, b, rest..] => {
+            assert_eq!(rest.len(), xs.len() - 2);
+            a + b
+        }
+    }
+}
+
+fn bump_first(xs: &mut [int]) {
+    match xs {
+        [] => {}
+        [ref mut first, ..] => *first += 1
+    }
+}
+
+pub fn main() {
+    let empty: &[int] = &[];
+    assert_eq!(classify(empty), 0);
+    assert_eq!(classify(&[1]), 1);
+    assert_eq!(classify(&[1, 2]), 2);
+    assert_eq!(classify(&[1, 2, 3, 4]), 2);
+
+    assert_eq!(sum_first_two(empty), 0);
+    assert_eq!(sum_first_two(&[5]), 5);
+    assert_eq!(sum_first_two(&[5, 6]), 11);
+    assert_eq!(sum_first_two(&[5, 6, 7]), 11);
+
+    let mut v = [1i, 2, 3];
+    bump_first(&mut v);
+    assert_eq!(v.as_slice(), [2i, 2, 3].as_slice());
+}
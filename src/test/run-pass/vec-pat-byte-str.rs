@@ -0,0 +1,73 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Byte string literals in vector patterns over `u8`s match one byte each.
+
+#[deriving(PartialEq, Show)]
+enum Method {
+    Get,
+    Post,
+    Other,
+}
+
+fn method(line: &[u8]) -> (Method, &[u8]) {
+    match line {
+        [b"GET ", rest..] => (Get, rest),
+        [b'P', b"OST", b' ', rest..] => (Post, rest),
+        rest => (Other, rest)
+    }
+}
+
+fn minor_version(line: &[u8]) -> Option<u8> {
+    match line {
+        [.., b" HTTP/1.", v] => Some(v - b'0'),
+        _ => None
+    }
+}
+
+fn is_ab(arr: [u8, ..3]) -> bool {
+    match arr {
+        [b"ab", _] => true,
+        [_, _, _] => false
+    }
+}
+
+fn first_word(words: &[&[u8]]) -> bool {
+    match words {
+        // Here the elements are byte strings themselves.
+        [b"hello", ..] => true,
+        _ => false
+    }
+}
+
+pub fn main() {
+    let line = b"GET /index.html HTTP/1.1";
+    let (m, rest) = method(line);
+    assert_eq!(m, Get);
+    assert_eq!(rest, b"/index.html HTTP/1.1");
+    assert_eq!(minor_version(rest), Some(1));
+
+    let (m, rest) = method(b"POST /form HTTP/1.0");
+    assert_eq!(m, Post);
+    assert_eq!(rest, b"/form HTTP/1.0");
+    assert_eq!(minor_version(rest), Some(0));
+
+    let (m, rest) = method(b"GE");
+    assert_eq!(m, Other);
+    assert_eq!(rest, b"GE");
+    assert_eq!(minor_version(rest), None);
+
+    assert!(is_ab([b'a', b'b', b'c']));
+    assert!(!is_ab([b'a', b'c', b'b']));
+
+    assert!(first_word(&[b"hello", b"world"]));
+    assert!(!first_word(&[b"world"]));
+}